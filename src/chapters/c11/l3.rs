@@ -0,0 +1,123 @@
+//! Chapter 11, Lesson 3: Maneuvering Reentry Vehicles
+//!
+//! A strategic reentry vehicle enters at 300 kft and 20,000 ft/s on a
+//! 25 deg dive. The L1 constant ballistic coefficient is compared with one
+//! that falls as the drag coefficient rises toward the transonic peak, and
+//! with lifting vehicles that pull up into a shallow glide, hold a 60 deg
+//! bank or spiral about their mean path. Peak deceleration, peak
+//! stagnation-point heating and total heat load are reported for each.
+//! Output: 7 columns [CASE, T, RT1K, RT2K, RT3K, ATG, QDOT]
+
+use crate::plotting::{PlotConfig, Series, line_plot};
+use crate::save_data;
+use crate::utils::reentry::{BallisticCoefficient, Maneuver, ReentryTrajectory, ReentryVehicle};
+use crate::utils::rk2::Vec3;
+
+pub struct Results {
+    pub cases: Vec<Case>,
+}
+
+pub struct Case {
+    pub label: &'static str,
+    pub trajectory: ReentryTrajectory,
+}
+
+/// Run the C11L3 simulation
+pub fn run() -> Results {
+    let h: f64 = 0.01;
+    let beta: f64 = 1500.0;
+    let vt_init: f64 = 20000.0;
+    let gamtdeg: f64 = 25.0;
+    let alt_init: f64 = 300000.0;
+    let xld: f64 = 0.5;
+
+    let r0 = Vec3::new(0.0, alt_init, 0.0);
+    let v0 = Vec3::new(vt_init * (gamtdeg / 57.3).cos(), -vt_init * (gamtdeg / 57.3).sin(), 0.0);
+
+    let ballistic = ReentryVehicle::ballistic(beta);
+    let vehicles = [
+        ("Constant beta", ballistic.clone()),
+        ("Beta with Mach", ballistic.clone().with_beta(BallisticCoefficient::mach_table(
+            &[0.8, 1.2, 3.0, 6.0, 20.0],
+            &[0.8 * beta, 0.6 * beta, 0.75 * beta, 0.9 * beta, beta],
+        ))),
+        ("Pull-up, L/D 0.5", ballistic.clone()
+            .with_lift(xld, Maneuver::PullUp { start_alt: 150000.0, gamma: -5.0 / 57.3 })),
+        ("60 deg bank, L/D 0.5", ballistic.clone().with_lift(xld, Maneuver::Bank(60.0 / 57.3))),
+        ("Spiral, L/D 0.5", ballistic.clone()
+            .with_lift(xld, Maneuver::Spiral { start_alt: 150000.0, rate: 1.0 })),
+    ];
+
+    let cases = vehicles.into_iter().map(|(label, vehicle)| Case {
+        label,
+        trajectory: vehicle.fly(r0, v0, h, 0.1),
+    }).collect();
+
+    Results { cases }
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+
+    let mut columns: [Vec<f64>; 7] = Default::default();
+    let mut series = Vec::new();
+    let colors = [
+        plotters::prelude::BLUE,
+        plotters::prelude::RED,
+        plotters::prelude::GREEN,
+        plotters::prelude::BLACK,
+        plotters::prelude::MAGENTA,
+    ];
+    for (i, case) in results.cases.iter().enumerate() {
+        let traj = &case.trajectory;
+        for k in 0..traj.time.len() {
+            let r = traj.position[k];
+            let row = [(i + 1) as f64, traj.time[k], r.x / 1000.0, r.y / 1000.0, r.z / 1000.0, traj.load_g[k], traj.heat_rate[k]];
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(value);
+            }
+        }
+        let rt1k = traj.position.iter().map(|r| r.x / 1000.0).collect();
+        let rt2k = traj.position.iter().map(|r| r.y / 1000.0).collect();
+        series.push(Series::new(rt1k, rt2k).with_label(case.label).with_color(colors[i % colors.len()]));
+    }
+
+    let data_file = format!("{}/c11l3_datfil.txt", output_dir);
+    save_data(&data_file, &columns)?;
+
+    let plot_file = format!("{}/c11l3_trajectory.png", output_dir);
+    let config = PlotConfig::new("Maneuvering Reentry Vehicles")
+        .with_labels("Downrange (Kft)", "Altitude (Kft)");
+    line_plot(&plot_file, &config, &series).ok();
+
+    println!("C11L3: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    for case in &results.cases {
+        let traj = &case.trajectory;
+        let end = traj.position.last().unwrap();
+        println!("  {:<22} impact {:6.1} s at {:6.1} Kft down, {:6.1} Kft cross, V = {:5.0} ft/s  peak {:5.1} G  {:6.0} BTU/ft^2/s  load {:7.0} BTU/ft^2",
+            case.label, traj.time.last().unwrap(), end.x / 1000.0, end.z / 1000.0,
+            traj.velocity.last().unwrap().magnitude(),
+            traj.peak_load_g(), traj.peak_heat_rate(), traj.total_heat_load());
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c11l3_maneuvers() {
+        let results = run();
+        let end = |i: usize| *results.cases[i].trajectory.position.last().unwrap();
+        // Drag rising as the vehicle slows costs impact speed
+        let speed = |i: usize| results.cases[i].trajectory.velocity.last().unwrap().magnitude();
+        assert!(speed(1) < speed(0));
+        // Pulling up extends the range; the bank carries it crossrange
+        assert!(end(2).x > end(0).x);
+        assert!(end(3).z > 10000.0);
+        assert!(end(0).z == 0.0);
+    }
+}
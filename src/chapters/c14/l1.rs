@@ -5,9 +5,10 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::filters::fading_memory::Gains;
+use crate::utils::noise::{NoiseSource, White};
 use rand::prelude::*;
 use rand::SeedableRng;
-use rand_distr::StandardNormal;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -24,6 +25,13 @@ pub fn run() -> Results {
 
 /// Run with optional seed for reproducibility
 pub fn run_with_seed(seed: Option<u64>) -> Results {
+    let signoise: f64 = 0.001;
+    run_with_noise(seed, &mut White::new(signoise))
+}
+
+/// Run with an arbitrary noise model on the measured target angle; the
+/// filter coasts through blind range
+pub fn run_with_noise(seed: Option<u64>, noise: &mut dyn NoiseSource) -> Results {
     let vm: f64 = 3000.0;
     let vt: f64 = 1000.0;
     let xnt: f64 = 0.0;
@@ -35,8 +43,6 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
     let xnp: f64 = 3.0;
     let beta: f64 = 0.8;
     let ts: f64 = 0.1;
-    let signoise: f64 = 0.001;
-    let noise: bool = true;
 
     let mut rng: Box<dyn RngCore> = match seed {
         Some(s) => Box::new(rand::rngs::StdRng::seed_from_u64(s)),
@@ -53,8 +59,9 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
     let mut vt2 = vt * betat.sin();
     let he = hedeg / 57.3;
 
-    let gfilter = 1.0 - beta * beta;
-    let hfilter = (1.0 - beta) * (1.0 - beta);
+    let gains = Gains::fading_memory(2, beta);
+    let gfilter = gains.g;
+    let hfilter = gains.h;
 
     let mut xlamh: f64 = 0.0;
     let mut xlamdh: f64 = 0.0;
@@ -168,18 +175,14 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
         if s >= ts - 1e-5 {
             s = 0.0;
 
-            let thettnoise = if noise {
-                signoise * rng.sample::<f64, _>(StandardNormal)
-            } else {
-                0.0
-            };
+            let rt = (rt1 * rt1 + rt2 * rt2).sqrt();
+            let rm = (rm1 * rm1 + rm2 * rm2).sqrt();
+
+            let thettnoise = noise.sample(ts, rtm, rng.as_mut());
 
             let thettm = thett + thettnoise;
             let thetmm = thetm;
 
-            let rt = (rt1 * rt1 + rt2 * rt2).sqrt();
-            let rm = (rm1 * rm1 + rm2 * rm2).sqrt();
-
             let rt1m = rt * thettm.cos();
             let rt2m = rt * thettm.sin();
             let rm1m = rm * thetmm.cos();
@@ -187,7 +190,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
 
             let xlamm = (rt2m - rm2m).atan2(rt1m - rm1m);
 
-            let res = xlamm - (xlamh + ts * xlamdh);
+            let res = if noise.is_blind(rtm) { 0.0 } else { xlamm - (xlamh + ts * xlamdh) };
             xlamh = gfilter * res + xlamh + ts * xlamdh;
             xlamdh += hfilter * res / ts;
 
@@ -259,4 +262,29 @@ mod tests {
         let min_rtm = results.rtm.iter().cloned().fold(f64::MAX, f64::min);
         assert!(min_rtm < 100.0);  // Within 100 ft miss
     }
+
+    #[test]
+    fn test_c14l1_glint_and_blind_range() {
+        use crate::utils::noise::{BlindRange, Glint, NoiseSum, Thermal};
+        let blind = 3000.0;
+        let mut seen = NoiseSum::new()
+            .with(Thermal::range_squared(0.0005, 30000.0))
+            .with(Glint::new(50.0, 0.1));
+        let mut masked = NoiseSum::new()
+            .with(Thermal::range_squared(0.0005, 30000.0))
+            .with(BlindRange::new(Glint::new(50.0, 0.1), blind));
+        let open = run_with_noise(Some(12345), &mut seen);
+        let results = run_with_noise(Some(12345), &mut masked);
+        assert!(results.xlamdh.iter().all(|x| x.is_finite()));
+
+        // Inside blind range the residual is zero, so the filter coasts on a
+        // constant LOS rate estimate
+        let coasting: Vec<usize> = (1..results.time.len()).filter(|&k| results.rtm[k] < blind).collect();
+        assert!(coasting.len() >= 5);
+        for k in coasting {
+            assert_eq!(results.xlamdh[k], results.xlamdh[k - 1]);
+        }
+        let miss = |r: &Results| *r.rtm.last().unwrap();
+        assert!((miss(&results) - miss(&open)).abs() > 1e-3, "miss {} vs {}", miss(&results), miss(&open));
+    }
 }
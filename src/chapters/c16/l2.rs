@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::rocket::{StageDesign, ThrustSpec, Vehicle};
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -27,23 +28,15 @@ pub fn run() -> Results {
     let amax2: f64 = 10.0;
     let gamdeg: f64 = 85.0;
 
-    // Stage 2 calculations
-    let top2 = wpay * ((delv2 / (xisp2 * 32.2)).exp() - 1.0);
-    let bot2 = 1.0 / xmf2 - ((1.0 - xmf2) / xmf2) * (delv2 / (xisp2 * 32.2)).exp();
-    let wp2 = top2 / bot2;
-    let ws2 = wp2 * (1.0 - xmf2) / xmf2;
-    let wtot2 = wp2 + ws2 + wpay;
-    let trst2 = amax2 * (wpay + ws2);
-    let tb2 = xisp2 * wp2 / trst2;
-
-    // Stage 1 calculations
-    let top1 = wtot2 * ((delv1 / (xisp1 * 32.2)).exp() - 1.0);
-    let bot1 = 1.0 / xmf1 - ((1.0 - xmf1) / xmf1) * (delv1 / (xisp1 * 32.2)).exp();
-    let wp1 = top1 / bot1;
-    let ws1 = wp1 * (1.0 - xmf1) / xmf1;
-    let wtot = wp1 + ws1 + wtot2;
-    let trst1 = amax1 * (wtot2 + ws1);
-    let tb1 = xisp1 * wp1 / trst1;
+    let vehicle = Vehicle::size(wpay, &[
+        StageDesign::new(xisp1, xmf1, ThrustSpec::MaxAcceleration(amax1)),
+        StageDesign::new(xisp2, xmf2, ThrustSpec::MaxAcceleration(amax2)),
+    ], &[delv1, delv2]).expect("stages deliver the velocity increment");
+    let (stage1, stage2) = (vehicle.stages[0], vehicle.stages[1]);
+    let (wp1, trst1, tb1) = (stage1.propellant, stage1.thrust, stage1.burn_time);
+    let (wp2, trst2, tb2) = (stage2.propellant, stage2.thrust, stage2.burn_time);
+    let wtot = vehicle.liftoff_weight();
+    let wtot2 = vehicle.ignition_weight(1);
 
     let h: f64 = 0.01;
     let a: f64 = 2.0926e7;
@@ -0,0 +1,111 @@
+//! Chapter 16, Lesson 3: Multi-Stage Sizing
+//!
+//! Liftoff weight of the L1 two-stage rocket as the velocity increment is
+//! split between the stages, the split that minimizes it, and a three-stage
+//! vehicle of the same technology sized for the same 20,000 ft/s. Both
+//! optimized vehicles are flown on an L2-style gravity turn to compare burnout
+//! conditions.
+//! Output: 2 columns [STAGE 1 FRACTION OF DELV, LIFTOFF WEIGHT (LB)]
+
+use crate::plotting::{PlotConfig, Series, line_plot};
+use crate::save_data;
+use crate::utils::rocket::{StageDesign, ThrustSpec, Trajectory, Vehicle};
+use crate::utils::rk2::Vec3;
+use crate::utils::steering::GravityTurn;
+
+pub struct Results {
+    pub fraction: Vec<f64>,
+    pub weight: Vec<f64>,
+    pub two_stage: Vehicle,
+    pub three_stage: Vehicle,
+    pub two_stage_flight: Trajectory,
+    pub three_stage_flight: Trajectory,
+}
+
+/// Run the C16L3 simulation
+pub fn run() -> Results {
+    let wpay: f64 = 100.0;
+    let delv: f64 = 20000.0;
+    let gamdeg: f64 = 89.0;
+    let a: f64 = 2.0926e7;
+    let design = StageDesign::new(250.0, 0.85, ThrustSpec::MaxAcceleration(10.0));
+
+    let mut fraction = Vec::new();
+    let mut weight = Vec::new();
+    for k in 5..=95 {
+        let f = k as f64 / 100.0;
+        if let Some(v) = Vehicle::size(wpay, &[design, design], &[f * delv, (1.0 - f) * delv]) {
+            fraction.push(f);
+            weight.push(v.liftoff_weight());
+        }
+    }
+
+    let two_stage = Vehicle::optimal(wpay, &[design; 2], delv).expect("two stages reach DELV");
+    let three_stage = Vehicle::optimal(wpay, &[design; 3], delv).expect("three stages reach DELV");
+
+    // L2 launch: unit velocity at GAMDEG from the local horizontal, then
+    // thrust along the velocity vector. The optimized stages lift off with
+    // less thrust than L2's, so the turn starts nearer the vertical.
+    let ang: f64 = 90.0 / 57.3;
+    let r0 = Vec3::new(a * ang.cos(), a * ang.sin(), 0.0);
+    let v0 = Vec3::new((1.5708 - gamdeg / 57.3 + ang).cos(), (1.5708 - gamdeg / 57.3 + ang).sin(), 0.0);
+    let two_stage_flight = two_stage.fly(r0, v0, 0.01, 10.0, 5000.0, &mut GravityTurn::new());
+    let three_stage_flight = three_stage.fly(r0, v0, 0.01, 10.0, 5000.0, &mut GravityTurn::new());
+
+    Results { fraction, weight, two_stage, three_stage, two_stage_flight, three_stage_flight }
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+
+    let data_file = format!("{}/c16l3_datfil.txt", output_dir);
+    save_data(&data_file, &[
+        results.fraction.clone(),
+        results.weight.clone(),
+    ])?;
+
+    let plot_file = format!("{}/c16l3_weight.png", output_dir);
+    let config = PlotConfig::new("Two-Stage Liftoff Weight vs Velocity Split")
+        .with_labels("Stage 1 Fraction of Delta V", "Liftoff Weight (Lb)");
+    let series = vec![
+        Series::new(results.fraction.clone(), results.weight.clone())
+            .with_color(plotters::prelude::BLUE),
+    ];
+    line_plot(&plot_file, &config, &series).ok();
+
+    println!("C16L3: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    for (label, vehicle, flight) in [
+        ("Two-stage", &results.two_stage, &results.two_stage_flight),
+        ("Three-stage", &results.three_stage, &results.three_stage_flight),
+    ] {
+        let split: Vec<String> = (0..vehicle.stages.len())
+            .map(|i| format!("{:.0}", vehicle.stage_delta_v(i)))
+            .collect();
+        println!("  {} optimum: liftoff weight = {:.1} lb, delta V split = [{}] ft/s",
+            label, vehicle.liftoff_weight(), split.join(", "));
+        if let Some((tbo, _, vbo)) = flight.burnout {
+            println!("    burnout at {:.1} s, {:.0} ft/s", tbo, vbo.magnitude());
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c16l3_staging() {
+        let results = run();
+        let best = results.weight.iter().cloned().fold(f64::MAX, f64::min);
+        assert!(results.two_stage.liftoff_weight() <= best + 1e-6);
+        // Identical stages share the velocity evenly
+        assert!((results.two_stage.stage_delta_v(0) - 10000.0).abs() < 1e-6);
+        // A third stage of the same technology saves weight
+        assert!(results.three_stage.liftoff_weight() < results.two_stage.liftoff_weight());
+        assert!(results.two_stage_flight.burnout.is_some());
+        assert!(results.three_stage_flight.burnout.is_some());
+    }
+}
@@ -4,4 +4,5 @@
 
 pub mod l1;
 pub mod l2;
+pub mod l3;
 
@@ -7,11 +7,16 @@
 //! MATLAB BUG: Calls `lambertpz` but the function file is `lamberpz.m` and the
 //! function is defined as `lambert` not `lambertpz`.
 
+use crate::utils::constants::{EARTH_RADIUS_FT, GM_FT};
+use crate::utils::elements::OrbitalElements;
+use crate::utils::kepler::StateVector;
 use crate::utils::lambert2d::lambert2d;
 
 pub struct Results {
     pub vrxm: f64,
     pub vrym: f64,
+    /// Elements of the transfer trajectory at launch
+    pub elements: OrbitalElements,
 }
 
 /// Run the C17L2 simulation
@@ -23,7 +28,7 @@ pub fn run() -> Results {
     let tf: f64 = 1000.0;
     let pi: f64 = std::f64::consts::PI;
     let degrad = 360.0 / (2.0 * pi);
-    let a: f64 = 2.0926e7;
+    let a: f64 = EARTH_RADIUS_FT;
 
     let altt = altnmt * 6076.0;
     let altm = altnmm * 6076.0;
@@ -39,9 +44,12 @@ pub fn run() -> Results {
     println!("VRXM = {:.6e}", result.vrx);
     println!("VRYM = {:.6e}", result.vry);
 
+    let elements = OrbitalElements::from_state(&StateVector::new(xm, ym, 0.0, result.vrx, result.vry, 0.0), GM_FT);
+
     Results {
         vrxm: result.vrx,
         vrym: result.vry,
+        elements,
     }
 }
 
@@ -54,6 +62,10 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
     println!("C17L2: Simulation finished");
     println!("  VRXM = {:.6e}", results.vrxm);
     println!("  VRYM = {:.6e}", results.vrym);
+    if let Some(ra) = results.elements.apogee_radius() {
+        println!("  Apogee altitude (Nmi) = {:.1}", (ra - EARTH_RADIUS_FT) / 6076.0);
+    }
+    println!("  Energy (ft^2/s^2) = {:.6e}", results.elements.energy());
 
     Ok(results)
 }
@@ -66,4 +78,17 @@ mod tests {
     fn test_c17l2_runs() {
         let _results = run();
     }
+
+    #[test]
+    fn test_c17l2_apogee_and_period() {
+        // Values from the listing constants before EARTH_RADIUS_FT replaced them
+        let elements = run().elements;
+        let ra = elements.apogee_radius().unwrap();
+        let period = elements.period().unwrap();
+        assert!((ra / 2.2777217160e7 - 1.0).abs() < 1e-9, "apogee radius {}", ra);
+        assert!((period / 3.0196207438e3 - 1.0).abs() < 1e-9, "period {}", period);
+        // The transfer comes back down to the surface after the flight time
+        let landing = elements.propagate(1000.0).radius();
+        assert!((landing - EARTH_RADIUS_FT).abs() < 100.0, "landing radius {}", landing);
+    }
 }
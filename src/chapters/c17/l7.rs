@@ -0,0 +1,150 @@
+//! Chapter 17, Lesson 7: Boost Steering Comparison
+//!
+//! The L3 two-stage rocket is flown from 30 deg to a target at 45 deg
+//! longitude under five steering laws: a gravity turn after a vertical
+//! rise, linear tangent steering, a tabulated pitch program, L3's Lambert
+//! guidance and L4's velocity-to-be-gained guidance with energy management.
+//! The open-loop laws fly wherever their program takes them; the guided
+//! ones cut off on the trajectory that reaches the target at TF.
+//! Output: 4 columns [LAW, T, DISTNM, ALTNM]
+
+use crate::plotting::{PlotConfig, Series, line_plot};
+use crate::save_data;
+use crate::utils::rk2::Vec3;
+use crate::utils::rocket::{StageDesign, ThrustSpec, Trajectory, Vehicle};
+use crate::utils::steering::{
+    GravityTurn, LambertSteering, LinearTangent, PitchProgram, SteeringLaw, VelocityToBeGained,
+};
+
+pub struct Results {
+    pub flights: Vec<Flight>,
+}
+
+pub struct Flight {
+    pub label: &'static str,
+    pub trajectory: Trajectory,
+    /// Time, speed (ft/s) and flight path angle (deg) at the end of boost
+    pub end_of_boost: (f64, f64, f64),
+    /// Downrange distance at impact (Nmi)
+    pub range_nm: f64,
+    /// Distance from the target at impact (Nmi)
+    pub miss_nm: f64,
+}
+
+/// Run the C17L7 simulation
+pub fn run() -> Results {
+    let wpay: f64 = 100.0;
+    let delv: f64 = 20000.0;
+    let design = StageDesign::new(300.0, 0.90, ThrustSpec::MaxAcceleration(20.0));
+    let vehicle = Vehicle::size(wpay, &[design, design], &[0.3333 * delv, 0.6667 * delv])
+        .expect("stages reach DELV");
+    let tb = vehicle.burnout_time();
+
+    let a: f64 = 2.0926e7;
+    let ang: f64 = 30.0 / 57.3;
+    let xlongt: f64 = 45.0 / 57.3;
+    let tf: f64 = 500.0;
+    let h: f64 = 0.01;
+    let r0 = Vec3::new(a * ang.cos(), a * ang.sin(), 0.0);
+    let target = Vec3::new(a * xlongt.cos(), a * xlongt.sin(), 0.0);
+    let v0 = r0.normalize();
+
+    let laws: Vec<(&'static str, Box<dyn SteeringLaw>)> = vec![
+        ("Gravity turn", Box::new(GravityTurn::with_kick(5.0, 85.0 / 57.3, &r0, &target))),
+        ("Linear tangent", Box::new(LinearTangent::between(85.0 / 57.3, 15.0 / 57.3, tb, &r0, &target))),
+        ("Pitch program", Box::new(PitchProgram::new(
+            vec![0.0, 5.0, 20.0, 40.0, tb],
+            [90.0, 90.0, 60.0, 35.0, 20.0].iter().map(|d| d / 57.3).collect(),
+            &r0, &target,
+        ))),
+        ("Lambert (L3)", Box::new(LambertSteering::new(target, tf))),
+        ("VTG, energy managed (L4)", Box::new(VelocityToBeGained::new(target, tf, vehicle.delta_v()))),
+    ];
+
+    let flights = laws.into_iter().map(|(label, mut law)| {
+        let trajectory = vehicle.fly(r0, v0, h, 10.0, 5000.0, law.as_mut());
+        let (t, r, v) = trajectory.cutoff.or(trajectory.burnout).expect("boost ends above ground");
+        let gamdeg = 90.0 - 57.3 * (r.dot(&v) / (r.magnitude() * v.magnitude())).acos();
+        let impact = *trajectory.position.last().unwrap();
+        Flight {
+            label,
+            end_of_boost: (t, v.magnitude(), gamdeg),
+            range_nm: ground_distance_nm(&r0, &impact, a),
+            miss_nm: ground_distance_nm(&target, &impact, a),
+            trajectory,
+        }
+    }).collect();
+
+    Results { flights }
+}
+
+/// Great-circle distance between the ground points below two positions
+fn ground_distance_nm(r1: &Vec3, r2: &Vec3, a: f64) -> f64 {
+    let cbeta = r1.dot(r2) / (r1.magnitude() * r2.magnitude());
+    a * cbeta.clamp(-1.0, 1.0).acos() / 6076.0
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+    let a: f64 = 2.0926e7;
+
+    let mut law = Vec::new();
+    let mut time = Vec::new();
+    let mut distnm = Vec::new();
+    let mut altnm = Vec::new();
+    let mut series = Vec::new();
+    let colors = [
+        plotters::prelude::BLUE,
+        plotters::prelude::RED,
+        plotters::prelude::GREEN,
+        plotters::prelude::BLACK,
+        plotters::prelude::MAGENTA,
+    ];
+    for (i, flight) in results.flights.iter().enumerate() {
+        let r0 = flight.trajectory.position[0];
+        let dist: Vec<f64> = flight.trajectory.position.iter().map(|r| ground_distance_nm(&r0, r, a)).collect();
+        let alt: Vec<f64> = flight.trajectory.position.iter().map(|r| (r.magnitude() - a) / 6076.0).collect();
+        law.extend(std::iter::repeat_n((i + 1) as f64, dist.len()));
+        time.extend(&flight.trajectory.time);
+        distnm.extend(&dist);
+        altnm.extend(&alt);
+        series.push(Series::new(dist, alt).with_label(flight.label).with_color(colors[i % colors.len()]));
+    }
+
+    let data_file = format!("{}/c17l7_datfil.txt", output_dir);
+    save_data(&data_file, &[law, time, distnm, altnm])?;
+
+    let plot_file = format!("{}/c17l7_trajectory.png", output_dir);
+    let config = PlotConfig::new("Boost Steering Comparison")
+        .with_labels("Downrange (Nmi)", "Altitude (Nmi)");
+    line_plot(&plot_file, &config, &series).ok();
+
+    println!("C17L7: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    for flight in &results.flights {
+        let (t, v, gamdeg) = flight.end_of_boost;
+        println!("  {:<26} boost ends {:6.1} s  V = {:7.0} ft/s  gamma = {:5.1} deg  range = {:7.1} Nmi  miss = {:7.1} Nmi",
+            flight.label, t, v, gamdeg, flight.range_nm, flight.miss_nm);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c17l7_steering_laws() {
+        let results = run();
+        assert_eq!(results.flights.len(), 5);
+        // Both guided laws hit the target
+        for flight in &results.flights[3..] {
+            assert!(flight.miss_nm < 1.0, "{} misses by {} Nmi", flight.label, flight.miss_nm);
+        }
+        // Lambert guidance cuts off early; energy management burns it all
+        let tb_lambert = results.flights[3].end_of_boost.0;
+        let tb_vtg = results.flights[4].end_of_boost.0;
+        assert!(tb_lambert < tb_vtg, "{} {}", tb_lambert, tb_vtg);
+    }
+}
@@ -8,4 +8,5 @@ pub mod l3;
 pub mod l4;
 pub mod l5;
 pub mod l6;
+pub mod l7;
 
@@ -4,6 +4,8 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::seeker::{Seeker, SeekerConfig, SeekerInput};
+use crate::utils::targets::{Step, TargetManeuver};
 
 /// Simulation results
 pub struct Results {
@@ -18,12 +20,23 @@ pub struct Results {
 
 /// Run the C2L1 simulation
 pub fn run() -> Results {
+    run_with_seeker(None)
+}
+
+/// Run with guidance driven by a seeker model instead of the true LOS rate
+pub fn run_with_seeker(seeker_config: Option<SeekerConfig>) -> Results {
+    // XNT=0: non-maneuvering target
+    run_with_target(&Step::new(0.0), seeker_config)
+}
+
+/// Run against an arbitrary target maneuver, turning the target heading at
+/// the maneuver's acceleration
+pub fn run_with_target(target: &dyn TargetManeuver, seeker_config: Option<SeekerConfig>) -> Results {
     let vm: f64 = 3000.0;     // Missile velocity (ft/s)
     let vt: f64 = 1000.0;     // Target velocity (ft/s)
-    let xnt: f64 = 0.0;       // Target acceleration
     let he_deg: f64 = -20.0;  // Heading error (degrees)
     let xnp: f64 = 4.0;       // Navigation ratio
-    let beta: f64 = 0.0;      // Target heading angle
+    let mut beta: f64 = 0.0;  // Target heading angle
 
     // Initial positions
     let mut rm1: f64 = 0.0;
@@ -58,6 +71,7 @@ pub fn run() -> Results {
     let mut vc = -(rtm1 * vtm1 + rtm2 * vtm2) / rtm;
 
     let mut xnc: f64;
+    let mut seeker = seeker_config.map(|c| Seeker::new(c, xlam, thet + he));
 
     // Results storage
     let mut array_t = Vec::new();
@@ -89,13 +103,16 @@ pub fn run() -> Results {
         vtm2 = vt2 - vm2;
         vc = -(rtm1 * vtm1 + rtm2 * vtm2) / rtm;
         let xlam = rtm2.atan2(rtm1);
-        let xlamd = (rtm1 * vtm2 - rtm2 * vtm1) / (rtm * rtm);
+        let xlamd = match &seeker {
+            Some(sk) => sk.los_rate,
+            None => (rtm1 * vtm2 - rtm2 * vtm1) / (rtm * rtm),
+        };
         xnc = xnp * vc * xlamd;
         let am1 = -xnc * xlam.sin();
         let am2 = xnc * xlam.cos();
         vt1 = -vt * beta.cos();
         vt2 = vt * beta.sin();
-        let betad = xnt / vt;
+        let betad = target.accel(t) / vt;
 
         // Euler step
         let beta_new = beta + h * betad;
@@ -115,16 +132,19 @@ pub fn run() -> Results {
         vtm2 = vt2 - vm2;
         vc = -(rtm1 * vtm1 + rtm2 * vtm2) / rtm;
         let xlam = rtm2.atan2(rtm1);
-        let xlamd = (rtm1 * vtm2 - rtm2 * vtm1) / (rtm * rtm);
+        let xlamd = match &seeker {
+            Some(sk) => sk.los_rate,
+            None => (rtm1 * vtm2 - rtm2 * vtm1) / (rtm * rtm),
+        };
         xnc = xnp * vc * xlamd;
         let am1 = -xnc * xlam.sin();
         let am2 = xnc * xlam.cos();
         let vt1_new = -vt * beta_new.cos();
         let vt2_new = vt * beta_new.sin();
-        let betad = xnt / vt;
+        let betad = target.accel(t) / vt;
 
         // RK2 averaging
-        let _beta = 0.5 * (beta_old + beta_new + h * betad);
+        beta = 0.5 * (beta_old + beta_new + h * betad);
         rt1 = 0.5 * (rt1_old + rt1 + h * vt1_new);
         rt2 = 0.5 * (rt2_old + rt2 + h * vt2_new);
         rm1 = 0.5 * (rm1_old + rm1 + h * vm1);
@@ -132,6 +152,16 @@ pub fn run() -> Results {
         vm1 = 0.5 * (vm1_old + vm1 + h * am1);
         vm2 = 0.5 * (vm2_old + vm2 + h * am2);
 
+        if let Some(sk) = seeker.as_mut() {
+            let (r1, r2) = (rt1 - rm1, rt2 - rm2);
+            sk.update(t, h, &SeekerInput {
+                los: r2.atan2(r1),
+                range: (r1 * r1 + r2 * r2).sqrt(),
+                body_att: vm2.atan2(vm1),
+                body_rate: xnc / vm,
+            });
+        }
+
         s += h;
 
         // Store data at sampling interval
@@ -148,6 +178,11 @@ pub fn run() -> Results {
     }
 
     println!("Final miss distance (RTM): {:.2} ft", rtm);
+    if let Some(sk) = &seeker {
+        for e in sk.events() {
+            println!("  Seeker {:?} at t = {:.2} s", e.kind, e.t);
+        }
+    }
 
     Results {
         time: array_t,
@@ -230,4 +265,26 @@ mod tests {
         // Should achieve close intercept with PN guidance (within 100 ft at closest approach)
         assert!(min_rtm < 500.0, "Closest approach should be small: {}", min_rtm);
     }
+
+    #[test]
+    fn test_c2l1_seeker() {
+        let min_rtm = |r: &Results| r.rtm.iter().cloned().fold(f64::INFINITY, f64::min);
+        let tracked = run_with_seeker(Some(SeekerConfig::default()));
+        assert!(min_rtm(&tracked) < 500.0);
+
+        // A 10 deg gimbal cannot hold the 20 deg heading-error look angle
+        let limited = run_with_seeker(Some(SeekerConfig { gimbal_limit: Some(10.0 / 57.3), ..Default::default() }));
+        assert!(min_rtm(&limited) > 10.0 * min_rtm(&tracked));
+    }
+
+    #[test]
+    fn test_c2l1_maneuvering_target() {
+        let min_rtm = |r: &Results| r.rtm.iter().cloned().fold(f64::INFINITY, f64::min);
+        let straight = run();
+        let turning = run_with_target(&Step::new(96.6), None);
+        // A 3 g target turns away from its straight-line track
+        let n = turning.rt2.len().min(straight.rt2.len());
+        assert!((turning.rt2[n - 1] - straight.rt2[n - 1]).abs() > 100.0);
+        assert!(min_rtm(&turning) < 500.0);
+    }
 }
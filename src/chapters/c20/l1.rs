@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::targets::{Jink, TargetManeuver};
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -17,9 +18,21 @@ pub struct Results {
 
 /// Run the C20L1 simulation
 pub fn run() -> Results {
-    let xnp: f64 = 3.0;
     let displace: f64 = 200.0;   // Target displacement (ft)
     let thom: f64 = 1.0;         // Time to home at jink
+    // Head-on from 20,000 ft, so the intercept time is the initial range over
+    // VM + VT; the small margin fires the jink on the step where TGO reaches
+    // THOM, as the listing's TGO <= THOM test does
+    let rtm: f64 = 20000.0;
+    let vc: f64 = 3000.0 + 1000.0;
+    run_with_target(&Jink::new(displace, rtm / vc - thom - 0.00001))
+}
+
+/// Run against an arbitrary target maneuver, applying its displacement to
+/// the target position and its acceleration normal to the initial line of
+/// sight
+pub fn run_with_target(target: &dyn TargetManeuver) -> Results {
+    let xnp: f64 = 3.0;
     let vm: f64 = 3000.0;
     let vt: f64 = 1000.0;
 
@@ -29,9 +42,9 @@ pub fn run() -> Results {
     let mut rt1: f64 = 20000.0;
     let mut rt2: f64 = 1000.0;
 
-    let mut qswitch = false;
+    let mut offset: f64 = 0.0;
     let vt1 = -vt;
-    let vt2: f64 = 0.0;
+    let mut vt2: f64 = 0.0;
 
     let mut t: f64 = 0.0;
     let mut s: f64 = 0.0;
@@ -63,10 +76,11 @@ pub fn run() -> Results {
             h = 0.00005;
         }
 
-        // Target jink at specified time-to-go
-        if tgo <= thom && !qswitch {
-            qswitch = true;
-            rt2 += displace;
+        // Target jink
+        let jink = target.offset(t);
+        if jink != offset {
+            rt2 += jink - offset;
+            offset = jink;
         }
 
         let rt1old = rt1;
@@ -75,6 +89,7 @@ pub fn run() -> Results {
         let rm2old = rm2;
         let vm1old = vm1;
         let vm2old = vm2;
+        let vt2old = vt2;
 
         // First derivative evaluation
         rtm1 = rt1 - rm1;
@@ -98,6 +113,7 @@ pub fn run() -> Results {
         rm2 += h * vm2;
         vm1 += h * am1;
         vm2 += h * am2;
+        vt2 += h * target.accel(t);
         t += h;
 
         // Second derivative for RK2
@@ -122,6 +138,7 @@ pub fn run() -> Results {
         rm2 = 0.5 * (rm2old + rm2 + h * vm2);
         vm1 = 0.5 * (vm1old + vm1 + h * am1);
         vm2 = 0.5 * (vm2old + vm2 + h * am2);
+        vt2 = 0.5 * (vt2old + vt2 + h * target.accel(t));
 
         s += h;
 
@@ -208,4 +225,16 @@ mod tests {
             assert!(final_rtm < 300.0);
         }
     }
+
+    #[test]
+    fn test_c20l1_accelerating_target() {
+        use crate::utils::targets::Step;
+        // 3 g from launch moves the target about 1200 ft over the 5 s flight
+        let results = run_with_target(&Step::new(96.6));
+        let n = results.rt2.len();
+        assert!(results.rt2[n - 1] > 2000.0, "target y {}", results.rt2[n - 1]);
+        let final_rtm = ((results.rt1[n - 1] - results.rm1[n - 1]).powi(2)
+            + (results.rt2[n - 1] - results.rm2[n - 1]).powi(2)).sqrt();
+        assert!(final_rtm < 300.0);
+    }
 }
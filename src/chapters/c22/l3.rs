@@ -5,17 +5,26 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::{Actuator, ActuatorDynamics};
+use crate::utils::lti::TransferFunction;
+use crate::utils::root_locus::{damping, closed_loop_poles, gain_range, plot_root_locus, root_locus};
 
 pub struct Results {
     pub freq: Vec<f64>,    // Frequency (rad/sec)
     pub gain: Vec<f64>,    // Gain (dB)
     pub phase: Vec<f64>,   // Phase (deg)
+    pub open_loop: TransferFunction,  // Open loop per unit rate gyro gain KR
+    pub kr: f64,           // Design rate gyro gain
 }
 
 /// Run the C22L3 simulation
 pub fn run() -> Results {
-    let zact: f64 = 0.7;
-    let wact: f64 = 150.0;
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Frequency response with an arbitrary fin actuator; only its linear
+/// dynamics enter the loop
+pub fn run_with_actuator(actuator: Actuator) -> Results {
     let k3: f64 = -1.89;
     let ta: f64 = 0.457;
     let zaf: f64 = 0.058;
@@ -31,13 +40,18 @@ pub fn run() -> Results {
 
         let xmag1 = (1.0 + (w * ta).powi(2)).sqrt();
         let xmag2 = ((1.0 - (w / waf).powi(2)).powi(2) + (2.0 * zaf * w / waf).powi(2)).sqrt();
-        let xmag3 = ((1.0 - (w / wact).powi(2)).powi(2) + (2.0 * zact * w / wact).powi(2)).sqrt();
+        let (xmag3, phase3) = match actuator.dynamics {
+            ActuatorDynamics::SecondOrder { wact, zact } => (
+                ((1.0 - (w / wact).powi(2)).powi(2) + (2.0 * zact * w / wact).powi(2)).sqrt(),
+                57.3 * (2.0 * zact * w / wact).atan2(1.0 - (w / wact).powi(2)),
+            ),
+            ActuatorDynamics::FirstOrder { tau } => ((1.0 + (w * tau).powi(2)).sqrt(), 57.3 * (w * tau).atan2(1.0)),
+        };
 
         let gain = 20.0 * (-k3 * kr * xmag1 / (xmag2 * xmag3)).log10();
 
         let phase1 = 57.3 * (w * ta).atan2(1.0);
         let phase2 = 57.3 * (2.0 * zaf * w / waf).atan2(1.0 - (w / waf).powi(2));
-        let phase3 = 57.3 * (2.0 * zact * w / wact).atan2(1.0 - (w / wact).powi(2));
 
         let phase = phase1 - phase2 - phase3;
 
@@ -46,10 +60,18 @@ pub fn run() -> Results {
         array_phase.push(phase);
     }
 
+    // Same loop as a transfer function for root locus over KR
+    let open_loop = TransferFunction::gain(-k3)
+        .series(&TransferFunction::lead(ta))
+        .series(&TransferFunction::second_order(zaf, waf))
+        .series(&actuator.tf());
+
     Results {
         freq: array_w,
         gain: array_gain,
         phase: array_phase,
+        open_loop,
+        kr,
     }
 }
 
@@ -85,8 +107,15 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
 
     line_plot(&plot_file2, &config2, &series2).ok();
 
+    let plot_file3 = format!("{}/c22l3_rlocus.png", output_dir);
+    let locus = root_locus(&results.open_loop, &gain_range(10.0 * results.kr, 400));
+    plot_root_locus(&plot_file3, "Rate Gyro Root Locus (KR)", &results.open_loop, &locus, results.kr).ok();
+
     println!("C22L3: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    for d in damping(&closed_loop_poles(&results.open_loop, results.kr)).iter().filter(|d| d.pole.im >= 0.0) {
+        println!("  Closed-loop pole {:.2}{:+.2}j: wn = {:.2} rad/s, zeta = {:.3}", d.pole.re, d.pole.im, d.wn, d.zeta);
+    }
 
     Ok(results)
 }
@@ -101,4 +130,11 @@ mod tests {
         assert!(!results.freq.is_empty());
         assert_eq!(results.freq.len(), 159);
     }
+
+    #[test]
+    fn test_c22l3_design_gain_stable() {
+        let results = run();
+        let poles = closed_loop_poles(&results.open_loop, results.kr);
+        assert!(poles.iter().all(|p| p.re < 0.0));
+    }
 }
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -14,6 +15,11 @@ pub struct Results {
 
 /// Run the C22L4 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     let delay: f64 = 0.015;
     let vm: f64 = 3000.0;
     let xncg: f64 = 10.0;  // Commanded acceleration (G)
@@ -33,8 +39,6 @@ pub fn run() -> Results {
     let xhl: f64 = 19.5;
     let wgt: f64 = 1000.0;
     let xkr: f64 = 0.1;
-    let wact: f64 = 150.0;
-    let zact: f64 = 0.7;
 
     let rho = if alt < 30000.0 {
         0.002378 * (-alt / 30000.0).exp()
@@ -127,30 +131,33 @@ pub fn run() -> Results {
 
         // First derivative evaluation
         let _delcp = xkr * (xkdc * xncg + thd);
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
         let _xnl = xk1 * (e - edd / (wz * wz));
         thd = xk3 * (e + ta * ed);
 
         // Euler step
         e += h * ed;
         ed += h * edd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
         t += h;
 
         // Second derivative for RK2
         let delcp2 = xkr * (xkdc * xncg + thd);
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
         xnl = xk1 * (e - edd / (wz * wz));
         thd = xk3 * (e + ta * ed);
 
         // RK2 averaging
         e = 0.5 * (eold + e + h * ed);
         ed = 0.5 * (edold + ed + h * edd);
-        del = 0.5 * (delold + del + h * deld);
+        del = 0.5 * (delold + del + h * delrate);
         deld = 0.5 * (deldold + deld + h * deldd);
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
 
         // Store delayed command (use delcp2 from after Euler step for consistency with MATLAB)
         z[i] = delcp2;
@@ -219,4 +226,12 @@ mod tests {
         let results = run();
         assert!(!results.time.is_empty());
     }
+
+    #[test]
+    fn test_c22l4_rate_limit_slows_response() {
+        let linear = run();
+        let limited = run_with_actuator(Actuator::second_order(150.0, 0.7).with_rate_limit(0.5));
+        let n = linear.time.len() / 10;
+        assert!(limited.xnl[n].abs() < linear.xnl[n].abs());
+    }
 }
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub freq: Vec<f64>,    // Frequency (rad/sec)
@@ -14,8 +15,11 @@ pub struct Results {
 
 /// Run the C22L5 simulation
 pub fn run() -> Results {
-    let zact: f64 = 0.7;
-    let wact: f64 = 150.0;
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     let k3: f64 = -1.89;
     let ta: f64 = 0.457;
     let zaf: f64 = 0.058;
@@ -31,6 +35,7 @@ pub fn run() -> Results {
     for i in 2..=160 {
         let w = 10.0_f64.powf(0.025 * (i as f64) - 1.0);
         let period = 2.0 * pi / w;
+        actuator.reset();
 
         let mut t: f64 = 0.0;
         let mut s: f64 = 0.0;
@@ -59,8 +64,8 @@ pub fn run() -> Results {
 
             // First derivative evaluation
             let x = -(w * t).sin();
-            let deldd = wact * wact * (x - del - 2.0 * zact * deld / wact);
-            let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+            let (delrate, deldd) = actuator.derivatives(x, del, deld);
+            let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
             let y = kr * k3 * (e + ta * ed);
             let pd = y * (w * t).sin();
             let qd = y * (w * t).cos();
@@ -68,16 +73,17 @@ pub fn run() -> Results {
             // Euler step
             e += h * ed;
             ed += h * edd;
-            del += h * deld;
+            del += h * delrate;
             deld += h * deldd;
+            actuator.limit(&mut del, &mut deld);
             p += h * pd;
             q += h * qd;
             t += h;
 
             // Second derivative for RK2
             let x = -(w * t).sin();
-            let deldd = wact * wact * (x - del - 2.0 * zact * deld / wact);
-            let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+            let (delrate, deldd) = actuator.derivatives(x, del, deld);
+            let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
             let y = kr * k3 * (e + ta * ed);
             let pd = y * (w * t).sin();
             let qd = y * (w * t).cos();
@@ -85,8 +91,10 @@ pub fn run() -> Results {
             // RK2 averaging
             e = 0.5 * (eold + e + h * ed);
             ed = 0.5 * (edold + ed + h * edd);
-            del = 0.5 * (delold + del + h * deld);
+            del = 0.5 * (delold + del + h * delrate);
             deld = 0.5 * (deldold + deld + h * deldd);
+            actuator.limit(&mut del, &mut deld);
+            actuator.latch(del);
             p = 0.5 * (pold + p + h * pd);
             q = 0.5 * (qold + q + h * qd);
 
@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -13,6 +14,11 @@ pub struct Results {
 
 /// Run the C23L1 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     // Missile parameters
     let fr: f64 = 3.0;
     let diam: f64 = 1.0;
@@ -26,8 +32,6 @@ pub fn run() -> Results {
     let xn: f64 = 4.0;
     let xcg: f64 = 10.0;
     let xhl: f64 = 19.5;
-    let wact: f64 = 150.0;
-    let zact: f64 = 0.7;
     let tf: f64 = 1.0;
     let vm: f64 = 3000.0;
     let xncg: f64 = 10.0;
@@ -131,32 +135,35 @@ pub fn run() -> Results {
         // First derivative evaluation
         let thd = xk3 * (e + ta * ed);
         let delc = xkr * (x + thd);
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
         let xnl = xk1 * (e - edd / (wz * wz));
         let xd = wi * (thd + xka * (xnl - xncg * xkdc));
 
         // Euler step
         e += h * ed;
         ed += h * edd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
         x += h * xd;
         t += h;
 
         // Second derivative for RK2
         let thd = xk3 * (e + ta * ed);
         let delc = xkr * (x + thd);
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
         let xnl = xk1 * (e - edd / (wz * wz));
         let xd = wi * (thd + xka * (xnl - xncg * xkdc));
 
         // RK2 averaging
         e = 0.5 * (eold + e + h * ed);
         ed = 0.5 * (edold + ed + h * edd);
-        del = 0.5 * (delold + del + h * deld);
+        del = 0.5 * (delold + del + h * delrate);
         deld = 0.5 * (deldold + deld + h * deldd);
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
         x = 0.5 * (xold + x + h * xd);
 
         s += h;
@@ -4,15 +4,25 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::{Actuator, ActuatorDynamics};
+use crate::utils::lti::TransferFunction;
+use crate::utils::root_locus::{damping, closed_loop_poles, gain_range, plot_root_locus, root_locus};
 
 pub struct Results {
     pub w: Vec<f64>,
     pub gain: Vec<f64>,
     pub phase: Vec<f64>,
+    pub open_loop: TransferFunction,  // Open loop at the design gain (K = 1)
 }
 
 /// Run the C23L2 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Frequency response with an arbitrary fin actuator; only its linear
+/// dynamics enter the loop
+pub fn run_with_actuator(actuator: Actuator) -> Results {
     // Missile parameters
     let fr: f64 = 3.0;
     let diam: f64 = 1.0;
@@ -26,8 +36,6 @@ pub fn run() -> Results {
     let xn: f64 = 4.0;
     let xcg: f64 = 10.0;
     let xhl: f64 = 19.5;
-    let wact: f64 = 150.0;
-    let zact: f64 = 0.7;
     let vm: f64 = 3000.0;
     let xncg: f64 = 10.0;
     let wcr: f64 = 50.0;
@@ -119,11 +127,16 @@ pub fn run() -> Results {
         let xmagtop = -xk0 * ((1.0 - (w / w0).powi(2)).powi(2) + (2.0 * z0 * w / w0).powi(2)).sqrt();
         let xmagbot = w * ((1.0 - (w / waf).powi(2)).powi(2) + (2.0 * zaf * w / waf).powi(2)).sqrt();
         let xmag = xmagtop / xmagbot;
-        let xmagact = 1.0 / ((1.0 - w * w / (wact * wact)).powi(2) + (2.0 * zact * w / wact).powi(2)).sqrt();
+        let (xmagact, phaseact) = match actuator.dynamics {
+            ActuatorDynamics::SecondOrder { wact, zact } => (
+                1.0 / ((1.0 - w * w / (wact * wact)).powi(2) + (2.0 * zact * w / wact).powi(2)).sqrt(),
+                (2.0 * zact * w / wact).atan2(1.0 - w * w / (wact * wact)),
+            ),
+            ActuatorDynamics::FirstOrder { tau } => (1.0 / (1.0 + (w * tau).powi(2)).sqrt(), (w * tau).atan2(1.0)),
+        };
 
         let phasetop = (2.0 * z0 * w / w0).atan2(1.0 - (w / w0).powi(2));
         let phasebot = (2.0 * zaf * w / waf).atan2(1.0 - (w / waf).powi(2));
-        let phaseact = (2.0 * zact * w / wact).atan2(1.0 - w * w / (wact * wact));
 
         let gain = 20.0 * (xmag * xmagact).log10();
         let phase = -90.0 + 57.3 * (phasetop - phasebot - phaseact);
@@ -133,10 +146,18 @@ pub fn run() -> Results {
         array_phase.push(phase);
     }
 
+    // Same open loop as a transfer function for root locus over a gain multiplier
+    let open_loop = TransferFunction::gain(-xk0)
+        .series(&TransferFunction::new(&[1.0 / (w0 * w0), 2.0 * z0 / w0, 1.0], &[1.0]))
+        .series(&TransferFunction::integrator())
+        .series(&TransferFunction::second_order(zaf, waf))
+        .series(&actuator.tf());
+
     Results {
         w: array_w,
         gain: array_gain,
         phase: array_phase,
+        open_loop,
     }
 }
 
@@ -174,8 +195,16 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
 
     line_plot(&plot_file2, &config2, &series2).ok();
 
+    // Root locus over a multiplier on the design loop gain
+    let plot_file3 = format!("{}/c23l2_rlocus.png", output_dir);
+    let locus = root_locus(&results.open_loop, &gain_range(5.0, 400));
+    plot_root_locus(&plot_file3, "Three-Loop Autopilot Root Locus", &results.open_loop, &locus, 1.0).ok();
+
     println!("C23L2: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    for d in damping(&closed_loop_poles(&results.open_loop, 1.0)).iter().filter(|d| d.pole.im >= 0.0) {
+        println!("  Closed-loop pole {:.2}{:+.2}j: wn = {:.2} rad/s, zeta = {:.3}", d.pole.re, d.pole.im, d.wn, d.zeta);
+    }
 
     Ok(results)
 }
@@ -189,4 +218,15 @@ mod tests {
         let results = run();
         assert_eq!(results.w.len(), 159);
     }
+
+    #[test]
+    fn test_c23l2_open_loop_matches_bode() {
+        for actuator in [Actuator::second_order(150.0, 0.7), Actuator::first_order(0.01)] {
+            let results = run_with_actuator(actuator);
+            let data = crate::utils::lti::bode(&results.open_loop, &results.w);
+            for i in 0..results.w.len() {
+                assert!((data.gain_db[i] - results.gain[i]).abs() < 1e-6);
+            }
+        }
+    }
 }
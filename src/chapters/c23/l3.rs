@@ -4,6 +4,9 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::{Actuator, ActuatorDynamics};
+use crate::utils::lti::TransferFunction;
+use crate::utils::radome::{ParasiticLoop, RadomeBoundary, boundary_map, slope_specification};
 
 pub struct Results {
     pub r: Vec<f64>,
@@ -12,10 +15,20 @@ pub struct Results {
     pub xmgl: Vec<f64>,
     pub xmudnt: Vec<f64>,
     pub rms: Vec<f64>,
+    /// Parasitic loop with the lesson's filter, autopilot and turning rate time constant
+    pub parasitic: ParasiticLoop,
+    /// Stable radome slope interval at the lesson flight condition
+    pub boundary: RadomeBoundary,
 }
 
 /// Run the C23L3 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model; only its linear dynamics
+/// enter the adjoint
+pub fn run_with_actuator(actuator: Actuator) -> Results {
     // Missile parameters
     let fr: f64 = 3.0;
     let diam: f64 = 1.0;
@@ -29,8 +42,6 @@ pub fn run() -> Results {
     let xn: f64 = 4.0;
     let xcg: f64 = 10.0;
     let xhl: f64 = 19.5;
-    let wact: f64 = 150.0;
-    let zact: f64 = 0.7;
     let tf: f64 = 5.0;
     let vm: f64 = 3000.0;
     let xncg: f64 = 10.0;
@@ -123,6 +134,15 @@ pub fn run() -> Results {
     let xkr = _xk / (xka * wi);
     let xkdc = 1.0 + 1845.0 / (xka * vm);
 
+    // Actuator DEL' = A DEL + B DELC on the states (DEL, DELD); the adjoint
+    // carries them as (X9, X8). A first-order lag leaves DELD and X8 at zero.
+    let (act_a, act_b) = match actuator.dynamics {
+        ActuatorDynamics::SecondOrder { wact, zact } => {
+            ([[0.0, 1.0], [-wact * wact, -2.0 * zact * wact]], [0.0, wact * wact])
+        }
+        ActuatorDynamics::FirstOrder { tau } => ([[-1.0 / tau, 0.0], [0.0, 0.0]], [1.0 / tau, 0.0]),
+    };
+
     let h: f64 = 0.0001;
 
     let mut array_r = Vec::new();
@@ -192,10 +212,10 @@ pub fn run() -> Results {
             let x5d = -y1pz + r_val * y1pz;
             let y2pz = -xka * wi * x7;
             let x6d = -x6 / t2 + xnp * vc * xkdc * y2pz / 32.2;
-            let x7d = xkr * wact * wact * x8;
-            let x8d = x9 - 2.0 * zact * wact * x8;
+            let x7d = xkr * (act_b[1] * x8 + act_b[0] * x9);
+            let x8d = act_a[0][1] * x9 + act_a[1][1] * x8;
             let y4pz = xk1 * (-32.2 * x2 - y2pz);
-            let x9d = -wact * wact * x8 + x10 * waf * waf - waf * waf * y4pz / (wz * wz);
+            let x9d = act_a[1][0] * x8 + act_a[0][0] * x9 + x10 * waf * waf - waf * waf * y4pz / (wz * wz);
             let y3pz = xk3 * (x7d + wi * x7 + (x4 - x5) / 57.3);
             let x10d = -2.0 * zaf * waf * (x10 - y4pz / (wz * wz)) + x11 + ta * y3pz;
             let x11d = -waf * waf * (x10 - y4pz / (wz * wz)) + y4pz + y3pz;
@@ -232,10 +252,10 @@ pub fn run() -> Results {
             let x5d2 = -y1pz2 + r_val * y1pz2;
             let y2pz2 = -xka * wi * x7;
             let x6d2 = -x6 / t2 + xnp * vc * xkdc * y2pz2 / 32.2;
-            let x7d2 = xkr * wact * wact * x8;
-            let x8d2 = x9 - 2.0 * zact * wact * x8;
+            let x7d2 = xkr * (act_b[1] * x8 + act_b[0] * x9);
+            let x8d2 = act_a[0][1] * x9 + act_a[1][1] * x8;
             let y4pz2 = xk1 * (-32.2 * x2 - y2pz2);
-            let x9d2 = -wact * wact * x8 + x10 * waf * waf - waf * waf * y4pz2 / (wz * wz);
+            let x9d2 = act_a[1][0] * x8 + act_a[0][0] * x9 + x10 * waf * waf - waf * waf * y4pz2 / (wz * wz);
             let y3pz2 = xk3 * (x7d2 + wi * x7 + (x4 - x5) / 57.3);
             let x10d2 = -2.0 * zaf * waf * (x10 - y4pz2 / (wz * wz)) + x11 + ta * y3pz2;
             let x11d2 = -waf * waf * (x10 - y4pz2 / (wz * wz)) + y4pz2 + y3pz2;
@@ -280,6 +300,13 @@ pub fn run() -> Results {
         r_val += 0.01;
     }
 
+    // Guidance filter lags in series with a single-lag flight control system
+    let guidance = TransferFunction::lag(t1)
+        .series(&TransferFunction::lag(t2))
+        .series(&TransferFunction::lag(tau));
+    let parasitic = ParasiticLoop { xnp, vc, vm, ta, guidance };
+    let boundary = parasitic.stability_boundary(0.5);
+
     Results {
         r: array_r,
         xmfn: array_xmfn,
@@ -287,6 +314,8 @@ pub fn run() -> Results {
         xmgl: array_xmgl,
         xmudnt: array_xmudnt,
         rms: array_rms,
+        parasitic,
+        boundary,
     }
 }
 
@@ -329,6 +358,18 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
 
     println!("C23L3: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    let bound = |r: Option<f64>| r.map_or("unbounded".to_string(), |r| format!("{:.4}", r));
+    println!(
+        "  Parasitic loop stable for R in [{}, {}] (Ta = {:.2} s)",
+        bound(results.boundary.r_min),
+        bound(results.boundary.r_max),
+        results.parasitic.ta
+    );
+    let map = boundary_map(&results.parasitic, &[0.0, 25000.0, 50000.0], &[3.0, 4.0, 5.0], 0.5);
+    for p in &map {
+        println!("    alt {:>6.0} ft  N' {:.0}: |R| < {:.4}", p.alt, p.xnp, p.boundary.allowable(0.5));
+    }
+    println!("  Radome slope spec (safety factor 2): |R| < {:.4}", slope_specification(&map, 0.5, 2.0));
 
     Ok(results)
 }
@@ -342,4 +383,26 @@ mod tests {
         let results = run();
         assert_eq!(results.r.len(), 13);
     }
+
+    #[test]
+    fn test_c23l3_first_order_actuator() {
+        // A lag with the same time constant as 2 ZACT / WACT barely moves
+        // the miss; a slow actuator degrades it
+        let base = run();
+        let lag = run_with_actuator(Actuator::first_order(2.0 * 0.7 / 150.0));
+        let slow = run_with_actuator(Actuator::first_order(0.1));
+        for k in 0..base.rms.len() {
+            assert!((lag.rms[k] - base.rms[k]).abs() < 0.01 * base.rms[k]);
+            assert!(slow.rms[k] > base.rms[k]);
+        }
+    }
+
+    #[test]
+    fn test_c23l3_parasitic_boundary() {
+        let results = run();
+        let r_min = results.boundary.r_min.expect("negative slopes destabilize the loop");
+        assert!(r_min < 0.0 && r_min > -0.5);
+        let high = results.parasitic.at_altitude(50000.0).stability_boundary(0.5);
+        assert!(high.allowable(0.5) < results.boundary.allowable(0.5));
+    }
 }
@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -13,6 +14,11 @@ pub struct Results {
 
 /// Run the C23L4 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     // Missile parameters
     let scale: f64 = 1.0;
     let fr: f64 = 3.0;
@@ -27,8 +33,6 @@ pub fn run() -> Results {
     let xn: f64 = 4.0;
     let xcg: f64 = 10.0;
     let xhl: f64 = 19.5;
-    let wact: f64 = 150.0;
-    let zact: f64 = 0.7;
     let tf: f64 = 1.0;
     let vm: f64 = 3000.0;
     let xncg: f64 = 10.0;
@@ -133,7 +137,7 @@ pub fn run() -> Results {
         let xold = x;
 
         // First derivative evaluation
-        let delr = del / 57.3;
+        let delr = actuator.output(del) / 57.3;
         let cn = 2.0 * alf + 1.5 * splan * alf * alf / sref
             + 8.0 * swing * alf / (b * sref)
             + 8.0 * stail * (alf + delr) / (b * sref);
@@ -147,19 +151,20 @@ pub fn run() -> Results {
         let thddeg = thd * 57.3;
         let xnlg = xnl / 32.2;
         let delc = xkr * (x + thddeg);
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
         let xd = wi * (thddeg + xka * (xnlg - xncg * xkdc));
 
         // Euler step
         thd += h * thdd;
         alf += h * alfd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
         x += h * xd;
         t += h;
 
         // Second derivative for RK2
-        let delr = del / 57.3;
+        let delr = actuator.output(del) / 57.3;
         let cn = 2.0 * alf + 1.5 * splan * alf * alf / sref
             + 8.0 * swing * alf / (b * sref)
             + 8.0 * stail * (alf + delr) / (b * sref);
@@ -173,14 +178,16 @@ pub fn run() -> Results {
         let thddeg = thd * 57.3;
         let xnlg = xnl / 32.2;
         let delc = xkr * (x + thddeg);
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
         let xd = wi * (thddeg + xka * (xnlg - xncg * xkdc));
 
         // RK2 averaging
         thd = 0.5 * (thdold + thd + h * thdd);
         alf = 0.5 * (alfold + alf + h * alfd);
-        del = 0.5 * (delold + del + h * deld);
+        del = 0.5 * (delold + del + h * delrate);
         deld = 0.5 * (deldold + deld + h * deldd);
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
         x = 0.5 * (xold + x + h * xd);
 
         s += h;
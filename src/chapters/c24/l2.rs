@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -15,6 +16,11 @@ pub struct Results {
 
 /// Run the C24L2 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     // Parameters
     let xnc: f64 = 322.0;
     let xncg: f64 = xnc / 32.2;
@@ -25,8 +31,6 @@ pub fn run() -> Results {
     let wcr: f64 = 50.0;
     let zeta: f64 = 0.7;
     let tau: f64 = 0.3;
-    let wact: f64 = 150.0;
-    let zact: f64 = 0.7;
     let slope: f64 = 1.5;
     let diam: f64 = 1.0;
     let fr: f64 = 3.0;
@@ -142,12 +146,13 @@ pub fn run() -> Results {
         let delnldold = delnld;
 
         // First derivative evaluation
+        let delf = actuator.output(delnl);
         let cn = 2.0 * alf + 1.5 * splan * alf * alf / sref
             + 8.0 * swing * alf / (b * sref)
-            + 8.0 * stail * (alf + delnl / 57.3) / (b * sref);
+            + 8.0 * stail * (alf + delf / 57.3) / (b * sref);
         let cm = 2.0 * alf * tmp4 + 1.5 * splan * alf * alf * tmp3 / sref
             + 8.0 * swing * alf * tmp1 / (b * sref)
-            + 8.0 * stail * (alf + delnl / 57.3) * tmp2 / (b * sref);
+            + 8.0 * stail * (alf + delf / 57.3) * tmp2 / (b * sref);
 
         let thdd = q * sref * diam * cm / xiyy;
         let thddeg = thd * 57.3;
@@ -157,23 +162,25 @@ pub fn run() -> Results {
         let xnanl = xnlg;
         let xxd = wi * (thddeg + xka * (xnanl - xncg * xkdc));
         let delcnl = xkr * (xx + thddeg);
-        let delnldd = wact * wact * (delcnl - delnl - 2.0 * zact * delnld / wact);
+        let (delnlrate, delnldd) = actuator.derivatives(delcnl, delnl, delnld);
 
         // Euler step
         thd += h * thdd;
         alf += h * alfd;
         xx += h * xxd;
-        delnl += h * delnld;
+        delnl += h * delnlrate;
         delnld += h * delnldd;
+        actuator.limit(&mut delnl, &mut delnld);
         t += h;
 
         // Second derivative for RK2
+        let delf = actuator.output(delnl);
         let cn = 2.0 * alf + 1.5 * splan * alf * alf / sref
             + 8.0 * swing * alf / (b * sref)
-            + 8.0 * stail * (alf + delnl / 57.3) / (b * sref);
+            + 8.0 * stail * (alf + delf / 57.3) / (b * sref);
         let cm = 2.0 * alf * tmp4 + 1.5 * splan * alf * alf * tmp3 / sref
             + 8.0 * swing * alf * tmp1 / (b * sref)
-            + 8.0 * stail * (alf + delnl / 57.3) * tmp2 / (b * sref);
+            + 8.0 * stail * (alf + delf / 57.3) * tmp2 / (b * sref);
 
         let thdd = q * sref * diam * cm / xiyy;
         let thddeg = thd * 57.3;
@@ -183,14 +190,16 @@ pub fn run() -> Results {
         let xnanl = xnlg;
         let xxd = wi * (thddeg + xka * (xnanl - xncg * xkdc));
         let delcnl = xkr * (xx + thddeg);
-        let delnldd = wact * wact * (delcnl - delnl - 2.0 * zact * delnld / wact);
+        let (delnlrate, delnldd) = actuator.derivatives(delcnl, delnl, delnld);
 
         // RK2 averaging
         thd = 0.5 * (thdold + thd + h * thdd);
         alf = 0.5 * (alfold + alf + h * alfd);
         xx = 0.5 * (xxold + xx + h * xxd);
-        delnl = 0.5 * (delnlold + delnl + h * delnld);
+        delnl = 0.5 * (delnlold + delnl + h * delnlrate);
         delnld = 0.5 * (delnldold + delnld + h * delnldd);
+        actuator.limit(&mut delnl, &mut delnld);
+        actuator.latch(delnl);
 
         if s >= ts - 0.00001 {
             s = 0.0;
@@ -280,4 +289,15 @@ mod tests {
         let results = run();
         assert!(!results.time.is_empty());
     }
+
+    #[test]
+    fn test_c24l2_fin_rate_limit() {
+        // Fin deflection is in degrees; the linear fin peaks near 15 deg/s
+        let linear = run();
+        let limited = run_with_actuator(Actuator::second_order(150.0, 0.7).with_rate_limit(10.0));
+        let dt = limited.time[1] - limited.time[0];
+        let peak_rate = |r: &Results| r.delnl.windows(2).map(|w| (w[1] - w[0]).abs() / dt).fold(0.0, f64::max);
+        assert!(peak_rate(&linear) > 10.0);
+        assert!(peak_rate(&limited) <= 10.0 + 1e-6);
+    }
 }
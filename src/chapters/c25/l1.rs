@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -14,6 +15,11 @@ pub struct Results {
 
 /// Run the C25L1 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(100.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     let ts: f64 = 0.001;
     let xk3: f64 = -0.362;
     let ta: f64 = 0.831;
@@ -25,8 +31,6 @@ pub fn run() -> Results {
     let w1: f64 = 259.0;
     let tf: f64 = 1.0;
     let delc: f64 = 1.0;
-    let wact: f64 = 100.0;
-    let zact: f64 = 0.7;
 
     let mut t: f64 = 0.0;
     let mut s: f64 = 0.0;
@@ -54,9 +58,9 @@ pub fn run() -> Results {
         let deldold = deld;
 
         // First derivative evaluation
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = (del - e - b11 * ed) / b12;
-        let e1ddd = w1 * w1 * (deld - e1d - 2.0 * z1 * e1dd / w1);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = (actuator.output(del) - e - b11 * ed) / b12;
+        let e1ddd = w1 * w1 * (delrate - e1d - 2.0 * z1 * e1dd / w1);
         let thdrb = xk3 * (e + ta * ed);
         let thdfb = xkfb * (e1d + e1ddd / (wzfb * wzfb));
         let _thdtot = thdrb + thdfb;
@@ -67,14 +71,15 @@ pub fn run() -> Results {
         e1 += h * e1d;
         e1d += h * e1dd;
         e1dd += h * e1ddd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
         t += h;
 
         // Second derivative for RK2
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = (del - e - b11 * ed) / b12;
-        let e1ddd = w1 * w1 * (deld - e1d - 2.0 * z1 * e1dd / w1);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = (actuator.output(del) - e - b11 * ed) / b12;
+        let e1ddd = w1 * w1 * (delrate - e1d - 2.0 * z1 * e1dd / w1);
         let thdrb = xk3 * (e + ta * ed);
         let thdfb = xkfb * (e1d + e1ddd / (wzfb * wzfb));
         let thdtot = thdrb + thdfb;
@@ -85,8 +90,10 @@ pub fn run() -> Results {
         e1 = 0.5 * (e1old + e1 + h * e1d);
         e1d = 0.5 * (e1dold + e1d + h * e1dd);
         e1dd = 0.5 * (e1ddold + e1dd + h * e1ddd);
-        del = 0.5 * (delold + del + h * deld);
+        del = 0.5 * (delold + del + h * delrate);
         deld = 0.5 * (deldold + deld + h * deldd);
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
 
         s += h;
         if s >= ts - 0.00001 {
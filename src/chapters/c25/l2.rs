@@ -4,14 +4,25 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
+use crate::utils::lti::TransferFunction;
+use crate::utils::root_locus::{damping, closed_loop_poles, gain_range, plot_root_locus, root_locus};
 
 pub struct Results {
     pub time: Vec<f64>,
     pub thdtot: Vec<f64>,  // Total body rate
+    pub open_loop: TransferFunction,  // Rate loop per unit XKR, rigid plus flexible body
+    pub xkr: f64,          // Design rate gyro gain
 }
 
 /// Run the C25L2 simulation
 pub fn run() -> Results {
+    // WACT=100.; then overwritten to 400
+    run_with_actuator(Actuator::second_order(400.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     let ts: f64 = 0.001;
     let xk3: f64 = -0.362;
     let ta: f64 = 0.831;
@@ -27,9 +38,6 @@ pub fn run() -> Results {
     let _zb: f64 = 0.5;
     let tf: f64 = 1.0;
     let xin: f64 = 1.0;
-    // WACT=100.; then overwritten to 400
-    let wact: f64 = 400.0;
-    let zact: f64 = 0.7;
     // XKR=.1 then .2 then .3
     let xkr: f64 = 0.3;
     let xk: f64 = (1.0 - xkr * xk3) / (xkr * xk3);
@@ -61,9 +69,9 @@ pub fn run() -> Results {
         let deldold = deld;
 
         // First derivative evaluation
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = (del - e - b11 * ed) / b12;
-        let e1ddd = w1 * w1 * (deld - e1d - 2.0 * z1 * e1dd / w1);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = (actuator.output(del) - e - b11 * ed) / b12;
+        let e1ddd = w1 * w1 * (delrate - e1d - 2.0 * z1 * e1dd / w1);
         let thdrb = xk3 * (e + ta * ed);
         let thdfb = xkfb * (e1d + e1ddd / (wzfb * wzfb));
         let thdtot = thdrb + fb * thdfb;
@@ -75,14 +83,15 @@ pub fn run() -> Results {
         e1 += h * e1d;
         e1d += h * e1dd;
         e1dd += h * e1ddd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
         t += h;
 
         // Second derivative for RK2
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = (del - e - b11 * ed) / b12;
-        let e1ddd = w1 * w1 * (deld - e1d - 2.0 * z1 * e1dd / w1);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = (actuator.output(del) - e - b11 * ed) / b12;
+        let e1ddd = w1 * w1 * (delrate - e1d - 2.0 * z1 * e1dd / w1);
         let thdrb = xk3 * (e + ta * ed);
         let thdfb = xkfb * (e1d + e1ddd / (wzfb * wzfb));
         let thdtot = thdrb + fb * thdfb;
@@ -94,8 +103,10 @@ pub fn run() -> Results {
         e1 = 0.5 * (e1old + e1 + h * e1d);
         e1d = 0.5 * (e1dold + e1d + h * e1dd);
         e1dd = 0.5 * (e1ddold + e1dd + h * e1ddd);
-        del = 0.5 * (delold + del + h * deld);
+        del = 0.5 * (delold + del + h * delrate);
         deld = 0.5 * (deldold + deld + h * deldd);
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
 
         s += h;
         if s >= ts - 0.00001 {
@@ -105,9 +116,18 @@ pub fn run() -> Results {
         }
     }
 
+    // Rate loop with the flexible mode included, as seen by the gyro:
+    // DELC = XKR*THDTOT gives 1 + XKR*L(s) = 0 with L = -ACT*(RIGID + FLEX)
+    let act = actuator.tf();
+    let rigid = TransferFunction::new(&[xk3 * ta, xk3], &[b12, b11, 1.0]);
+    let flex = TransferFunction::new(&[xkfb / (wzfb * wzfb), 0.0, xkfb, 0.0], &[1.0 / (w1 * w1), 2.0 * z1 / w1, 1.0]);
+    let open_loop = act.series(&rigid.parallel(&flex)).scale(-1.0);
+
     Results {
         time: array_t,
         thdtot: array_thdtot,
+        open_loop,
+        xkr,
     }
 }
 
@@ -132,8 +152,15 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
 
     line_plot(&plot_file, &config, &series).ok();
 
+    let plot_file2 = format!("{}/c25l2_rlocus.png", output_dir);
+    let locus = root_locus(&results.open_loop, &gain_range(3.0 * results.xkr, 400));
+    plot_root_locus(&plot_file2, "Flexible Body Rate Loop Root Locus (KR)", &results.open_loop, &locus, results.xkr).ok();
+
     println!("C25L2: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    for d in damping(&closed_loop_poles(&results.open_loop, results.xkr)).iter().filter(|d| d.pole.im >= 0.0) {
+        println!("  Closed-loop pole {:.2}{:+.2}j: wn = {:.2} rad/s, zeta = {:.3}", d.pole.re, d.pole.im, d.wn, d.zeta);
+    }
 
     Ok(results)
 }
@@ -1,17 +1,30 @@
 //! Chapter 25, Lesson 3: Flexible Body Effects with Two Modes and Notch Filters
 //!
-//! Simulates flexible body with two modes and optional notch filters.
+//! Simulates flexible body with two modes and optional notch filters. The
+//! notches are placed on the bending modes by `utils::flex`, which also
+//! reports the rigid-body phase loss and flex-mode gain stabilization of the
+//! rate loop.
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
+use crate::utils::flex::{design_notches, BendingMode, FlexibleAirframe, NotchDesign, NotchReport, RateLoop};
 
 pub struct Results {
     pub time: Vec<f64>,
     pub thdtot: Vec<f64>,  // Total body rate
+    /// Frequency-domain summary of the notched rate loop
+    pub notch_report: NotchReport,
 }
 
 /// Run the C25L3 simulation
 pub fn run() -> Results {
+    // WACT=100.; then overwritten to 400
+    run_with_actuator(Actuator::second_order(400.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     let ts: f64 = 0.001;
     let xk3: f64 = -0.362;
     let ta: f64 = 0.831;
@@ -25,21 +38,25 @@ pub fn run() -> Results {
     let wzfb2: f64 = 255.0;
     let z2: f64 = 0.022;
     let w2: f64 = 649.0;
-    let wt: f64 = w1;
-    let zt: f64 = z1;
-    let wt2: f64 = w2;
-    let zt2: f64 = z2;
-    let wb: f64 = w1;
-    let zb: f64 = 0.5;
-    let wb2: f64 = w2;
-    let zb2: f64 = 0.5;
     let tf: f64 = 1.0;
     let xin: f64 = 1.0;
-    // WACT=100.; then overwritten to 400
-    let wact: f64 = 400.0;
-    let zact: f64 = 0.7;
     // XKR=.1 then .3
     let xkr: f64 = 0.3;
+
+    // WT=W1, ZT=Z1, WB=W1, ZB=.5 and likewise for the second mode
+    let airframe = FlexibleAirframe::new(xk3, ta, b11, b12)
+        .with_mode(BendingMode::new(w1, z1, xkfb, wzfb))
+        .with_mode(BendingMode::new(w2, z2, xkfb2, wzfb2));
+    let rate_loop = RateLoop { airframe, actuator: actuator.tf(), kr: xkr };
+    let notch_report = design_notches(&rate_loop, &NotchDesign::default());
+    let (wt, zt, wb, zb) = {
+        let n = &notch_report.notches[0];
+        (n.wt, n.zt, n.wb, n.zb)
+    };
+    let (wt2, zt2, wb2, zb2) = {
+        let n = &notch_report.notches[1];
+        (n.wt, n.zt, n.wb, n.zb)
+    };
     // NOTCH=3 (no filters), NOTCH=1 (first mode filter), NOTCH=2 (both filters)
     let notch: i32 = 2;
     let xk: f64 = (1.0 - xkr * xk3) / (xkr * xk3);
@@ -88,10 +105,10 @@ pub fn run() -> Results {
         let e4dold = e4d;
 
         // First derivative evaluation
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = (del - e - b11 * ed) / b12;
-        let e1ddd = w1 * w1 * (deld - e1d - 2.0 * z1 * e1dd / w1);
-        let e3ddd = w2 * w2 * (deld - e3d - 2.0 * z2 * e3dd / w2);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = (actuator.output(del) - e - b11 * ed) / b12;
+        let e1ddd = w1 * w1 * (delrate - e1d - 2.0 * z1 * e1dd / w1);
+        let e3ddd = w2 * w2 * (delrate - e3d - 2.0 * z2 * e3dd / w2);
         let thdrb = xk3 * (e + ta * ed);
         let thdfb = xkfb * (e1d + e1ddd / (wzfb * wzfb));
         let thdfb2 = xkfb2 * (e3d + e3ddd / (wzfb2 * wzfb2));
@@ -116,8 +133,9 @@ pub fn run() -> Results {
         e1 += h * e1d;
         e1d += h * e1dd;
         e1dd += h * e1ddd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
         e2 += h * e2d;
         e2d += h * e2dd;
         e3 += h * e3d;
@@ -128,10 +146,10 @@ pub fn run() -> Results {
         t += h;
 
         // Second derivative for RK2
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let edd = (del - e - b11 * ed) / b12;
-        let e1ddd = w1 * w1 * (deld - e1d - 2.0 * z1 * e1dd / w1);
-        let e3ddd = w2 * w2 * (deld - e3d - 2.0 * z2 * e3dd / w2);
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let edd = (actuator.output(del) - e - b11 * ed) / b12;
+        let e1ddd = w1 * w1 * (delrate - e1d - 2.0 * z1 * e1dd / w1);
+        let e3ddd = w2 * w2 * (delrate - e3d - 2.0 * z2 * e3dd / w2);
         let thdrb = xk3 * (e + ta * ed);
         let thdfb = xkfb * (e1d + e1ddd / (wzfb * wzfb));
         let thdfb2 = xkfb2 * (e3d + e3ddd / (wzfb2 * wzfb2));
@@ -156,8 +174,10 @@ pub fn run() -> Results {
         e1 = 0.5 * (e1old + e1 + h * e1d);
         e1d = 0.5 * (e1dold + e1d + h * e1dd);
         e1dd = 0.5 * (e1ddold + e1dd + h * e1ddd);
-        del = 0.5 * (delold + del + h * deld);
+        del = 0.5 * (delold + del + h * delrate);
         deld = 0.5 * (deldold + deld + h * deldd);
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
         e2 = 0.5 * (e2old + e2 + h * e2d);
         e2d = 0.5 * (e2dold + e2d + h * e2dd);
         e3 = 0.5 * (e3old + e3 + h * e3d);
@@ -177,6 +197,7 @@ pub fn run() -> Results {
     Results {
         time: array_t,
         thdtot: array_thdtot,
+        notch_report,
     }
 }
 
@@ -203,6 +224,14 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
 
     println!("C25L3: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    let report = &results.notch_report;
+    println!("  Rigid-body phase loss from notches (deg) = {:.2}", report.rigid_phase_loss_deg);
+    for mode in &report.modes {
+        println!(
+            "  Mode {:.0} rad/s: peak loop gain {:.1} dB -> {:.1} dB, gain stabilized: {}",
+            mode.w, mode.peak_gain_db_unfiltered, mode.peak_gain_db_filtered, mode.gain_stabilized
+        );
+    }
 
     Ok(results)
 }
@@ -216,4 +245,15 @@ mod tests {
         let results = run();
         assert!(!results.time.is_empty());
     }
+
+    #[test]
+    fn test_c25l3_notches_gain_stabilize() {
+        let results = run();
+        let report = &results.notch_report;
+        assert_eq!(report.notches.len(), 2);
+        assert!(report.modes.iter().all(|m| m.gain_stabilized));
+        assert!(report.rigid_phase_loss_deg < 5.0);
+        // The notched loop stays bounded over the flight
+        assert!(results.thdtot.iter().all(|x| x.is_finite() && x.abs() < 20.0));
+    }
 }
@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -14,10 +15,13 @@ pub struct Results {
 
 /// Run the C26L2 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(100.0, 0.65))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     // QACT=1; then QACT=0
     let qact: i32 = 0;
-    let wact: f64 = 100.0;
-    let zact: f64 = 0.65;
     let xkd: f64 = 9000.0;
     let wrr: f64 = 2.0;
     let c1: f64 = 3.0;
@@ -45,21 +49,23 @@ pub fn run() -> Results {
 
         // First derivative evaluation
         let delc = -c1 * phi - c2 * phid;
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let e = if qact == 1 { del } else { delc };
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let e = if qact == 1 { actuator.output(del) } else { delc };
         let phidd = xkd * e - wrr * phid;
 
         // Euler step
         phi += h * phid;
         phid += h * phidd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
         t += h;
 
         // Second derivative for RK2
         let delc = -c1 * phi - c2 * phid;
-        let _deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let e = if qact == 1 { del } else { delc };
+        let (_delrate, _deldd) = actuator.derivatives(delc, del, deld);
+        let e = if qact == 1 { actuator.output(del) } else { delc };
         let phidd = xkd * e - wrr * phid;
 
         // RK2 averaging
@@ -5,10 +5,14 @@
 //!
 //! MATLAB BUG: Line 41 uses `C=GT*S` but `GT` is never defined. It should be `G'*S`
 //! (G transpose times S). This implementation correctly computes G'*S.
+//! The gains settle to the steady-state LQR solution of the same problem.
 //! Output: 5 columns [T, C1, C2, C3, C4]
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::{Actuator, ActuatorDynamics};
+use crate::utils::matrix::{diag, Matrix};
+use crate::utils::riccati::lqr;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -16,14 +20,20 @@ pub struct Results {
     pub c2: Vec<f64>,
     pub c3: Vec<f64>,
     pub c4: Vec<f64>,
+    /// Infinite-horizon gains from the algebraic Riccati equation
+    pub steady_state: Vec<f64>,
 }
 
 const ORDER: usize = 4;
 
 /// Run the C26L3 simulation
 pub fn run() -> Results {
-    let wact: f64 = 100.0;
-    let zact: f64 = 0.65;
+    run_with_actuator(Actuator::second_order(100.0, 0.65))
+}
+
+/// Design the gains for an arbitrary fin actuator; only its linear
+/// dynamics enter the Riccati equation
+pub fn run_with_actuator(actuator: Actuator) -> Results {
     let kdel: f64 = 9000.0;
     let wr: f64 = 2.0;
     let delcmx: f64 = 30.0;
@@ -36,13 +46,22 @@ pub fn run() -> Results {
     let mut f = [[0.0; ORDER]; ORDER];
     let mut s = [[0.0; ORDER]; ORDER];
 
-    g[3][0] = wact * wact;
     f[0][1] = 1.0;
     f[1][1] = -wr;
     f[1][2] = kdel;
-    f[2][3] = 1.0;
-    f[3][2] = -wact * wact;
-    f[3][3] = -2.0 * zact * wact;
+    // Fin states DEL and DELD; a first-order lag leaves DELD at zero
+    match actuator.dynamics {
+        ActuatorDynamics::SecondOrder { wact, zact } => {
+            g[3][0] = wact * wact;
+            f[2][3] = 1.0;
+            f[3][2] = -wact * wact;
+            f[3][3] = -2.0 * zact * wact;
+        }
+        ActuatorDynamics::FirstOrder { tau } => {
+            g[2][0] = 1.0 / tau;
+            f[2][2] = -1.0 / tau;
+        }
+    }
     a[0][0] = (delcmx / phimx).powi(2);
     a[1][1] = (delcmx / phidmx).powi(2);
 
@@ -93,12 +112,17 @@ pub fn run() -> Results {
         }
     }
 
+    let to_matrix = |m: &[[f64; ORDER]]| -> Matrix { m.iter().map(|row| row.to_vec()).collect() };
+    let b: Matrix = g.iter().map(|row| row.to_vec()).collect();
+    let (k, _) = lqr(&to_matrix(&f), &b, &to_matrix(&a), &diag(&[1.0])).expect("roll autopilot is stabilizable");
+
     Results {
         time: array_t,
         c1: array_c1,
         c2: array_c2,
         c3: array_c3,
         c4: array_c4,
+        steady_state: k[0].clone(),
     }
 }
 
@@ -203,8 +227,10 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
 
     line_plot(&plot_file, &config, &series).ok();
 
+    let k = &results.steady_state;
     println!("C26L3: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    println!("  Steady-state gains: C1 = {:.4}, C2 = {:.5}, C3 = {:.4}, C4 = {:.6}", k[0], k[1], k[2], k[3]);
 
     Ok(results)
 }
@@ -218,4 +244,14 @@ mod tests {
         let results = run();
         assert!(!results.time.is_empty());
     }
+
+    #[test]
+    fn test_c26l3_gains_reach_lqr() {
+        let results = run();
+        let last = [results.c1.last(), results.c2.last(), results.c3.last(), results.c4.last()];
+        for (c, k) in last.iter().zip(&results.steady_state) {
+            let c = *c.unwrap();
+            assert!((c - k).abs() < 1e-3 * k.abs(), "{} vs {}", c, k);
+        }
+    }
 }
@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -14,12 +15,15 @@ pub struct Results {
 
 /// Run the C26L4 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(100.0, 0.65))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     // QGYR=0; then QGYR=1
     let qgyr: i32 = 1;
     let wg: f64 = 200.0;
     let zg: f64 = 0.5;
-    let wact: f64 = 100.0;
-    let zact: f64 = 0.65;
     let xkd: f64 = 9000.0;
     let wr: f64 = 2.0;
     let c1: f64 = 3.0;
@@ -54,43 +58,45 @@ pub fn run() -> Results {
         let _phimddold = phimdd;
 
         // First derivative evaluation
-        let (_delc, deldd, phidd, phimddd) = if qgyr == 1 {
+        let (delrate, deldd, phidd, phimddd) = if qgyr == 1 {
             let delc = -c1 * phim - c2 * phimd - c3 * del - c4 * deld;
-            let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-            let phidd = xkd * del - wr * phid;
+            let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+            let phidd = xkd * actuator.output(del) - wr * phid;
             let phimddd = wg * wg * (phid - phimd - 2.0 * zg * phimdd / wg);
-            (delc, deldd, phidd, phimddd)
+            (delrate, deldd, phidd, phimddd)
         } else {
             let delc = -c1 * phi - c2 * phid - c3 * del - c4 * deld;
-            let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-            let phidd = xkd * del - wr * phid;
+            let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+            let phidd = xkd * actuator.output(del) - wr * phid;
             let phimddd = 0.0;
-            (delc, deldd, phidd, phimddd)
+            (delrate, deldd, phidd, phimddd)
         };
 
         // Euler step
         phi += h * phid;
         phid += h * phidd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
         phim += h * phimd;
         phimd += h * phimdd;
         phimdd += h * phimddd;
         t += h;
 
         // Second derivative for RK2
-        let (_delc, _deldd, phidd, _phimddd) = if qgyr == 1 {
+        let (_delrate, _deldd, phidd, _phimddd) = if qgyr == 1 {
             let delc = -c1 * phim - c2 * phimd - c3 * del - c4 * deld;
-            let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-            let phidd = xkd * del - wr * phid;
+            let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+            let phidd = xkd * actuator.output(del) - wr * phid;
             let phimddd = wg * wg * (phid - phimd - 2.0 * zg * phimdd / wg);
-            (delc, deldd, phidd, phimddd)
+            (delrate, deldd, phidd, phimddd)
         } else {
             let delc = -c1 * phi - c2 * phid - c3 * del - c4 * deld;
-            let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-            let phidd = xkd * del - wr * phid;
+            let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+            let phidd = xkd * actuator.output(del) - wr * phid;
             let phimddd = 0.0;
-            (delc, deldd, phidd, phimddd)
+            (delrate, deldd, phidd, phimddd)
         };
 
         // RK2 averaging
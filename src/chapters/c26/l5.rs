@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::{Actuator, ActuatorDynamics};
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -20,10 +21,14 @@ const ORDER: usize = 6;
 
 /// Run the C26L5 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(100.0, 0.65))
+}
+
+/// Design the gains for an arbitrary fin actuator; only its linear
+/// dynamics enter the Riccati equation
+pub fn run_with_actuator(actuator: Actuator) -> Results {
     let wg: f64 = 200.0;
     let zg: f64 = 0.5;
-    let wact: f64 = 100.0;
-    let zact: f64 = 0.65;
     let kdel: f64 = 9000.0;
     let wr: f64 = 2.0;
     let delcmx: f64 = 30.0;
@@ -36,17 +41,26 @@ pub fn run() -> Results {
     let mut f = [[0.0; ORDER]; ORDER];
     let mut s = [[0.0; ORDER]; ORDER];
 
-    g[3][0] = wact * wact;
     f[0][4] = 1.0;
     f[1][1] = -wr;
     f[1][2] = kdel;
-    f[2][3] = 1.0;
-    f[3][2] = -wact * wact;
-    f[3][3] = -2.0 * zact * wact;
     f[4][5] = 1.0;
     f[5][1] = wg * wg;
     f[5][4] = -wg * wg;
     f[5][5] = -2.0 * zg * wg;
+    // Fin states DEL and DELD; a first-order lag leaves DELD at zero
+    match actuator.dynamics {
+        ActuatorDynamics::SecondOrder { wact, zact } => {
+            g[3][0] = wact * wact;
+            f[2][3] = 1.0;
+            f[3][2] = -wact * wact;
+            f[3][3] = -2.0 * zact * wact;
+        }
+        ActuatorDynamics::FirstOrder { tau } => {
+            g[2][0] = 1.0 / tau;
+            f[2][2] = -1.0 / tau;
+        }
+    }
     a[0][0] = (delcmx / phimx).powi(2);
     a[1][1] = (delcmx / phidmx).powi(2);
 
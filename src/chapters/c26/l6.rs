@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -14,10 +15,13 @@ pub struct Results {
 
 /// Run the C26L6 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(100.0, 0.65))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     let wg: f64 = 200.0;
     let zg: f64 = 0.5;
-    let wact: f64 = 100.0;
-    let zact: f64 = 0.65;
     let xkd: f64 = 9000.0;
     let wr: f64 = 2.0;
     let c1: f64 = 3.0;
@@ -55,16 +59,18 @@ pub fn run() -> Results {
 
         // First derivative evaluation
         let delc = -c1 * phi - c2 * phid - c3 * del - c4 * deld - c5 * phimd - c6 * phimdd;
-        let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let e = del;
+        let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+        let e = actuator.output(del);
         let phidd = xkd * e - wr * phid;
         let phimddd = wg * wg * (phid - phimd - 2.0 * zg * phimdd / wg);
 
         // Euler step
         phi += h * phid;
         phid += h * phidd;
-        del += h * deld;
+        del += h * delrate;
         deld += h * deldd;
+        actuator.limit(&mut del, &mut deld);
+        actuator.latch(del);
         phim += h * phimd;
         phimd += h * phimdd;
         phimdd += h * phimddd;
@@ -72,8 +78,8 @@ pub fn run() -> Results {
 
         // Second derivative for RK2
         let delc = -c1 * phi - c2 * phid - c3 * del - c4 * deld - c5 * phimd - c6 * phimdd;
-        let _deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-        let e = del;
+        let (_delrate, _deldd) = actuator.derivatives(delc, del, deld);
+        let e = actuator.output(del);
         let phidd = xkd * e - wr * phid;
         let _phimddd = wg * wg * (phid - phimd - 2.0 * zg * phimdd / wg);
 
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::filters::fading_memory::Gains;
 
 pub struct Results {
     pub tf: Vec<f64>,
@@ -33,8 +34,9 @@ pub fn run() -> Results {
         let mut s: f64 = 0.0;
         let mut s2: f64 = 0.0;
 
-        let gfilter = 1.0 - beta * beta;
-        let hfilter = (1.0 - beta).powi(2);
+        let gains = Gains::fading_memory(2, beta);
+        let gfilter = gains.g;
+        let hfilter = gains.h;
 
         let mut xlamhold: f64 = 0.0;
         let mut xlamdhold: f64 = 0.0;
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::filters::fading_memory::Gains;
 
 pub struct Results {
     pub tp: Vec<f64>,
@@ -23,8 +24,9 @@ pub fn run() -> Results {
     let ts2: f64 = 0.02;
     let h: f64 = 0.001;
 
-    let gfilter = 1.0 - beta * beta;
-    let hfilter = (1.0 - beta).powi(2);
+    let gains = Gains::fading_memory(2, beta);
+    let gfilter = gains.g;
+    let hfilter = gains.h;
 
     let mut tp = 0.00001;
     let mut s: f64 = 0.0;
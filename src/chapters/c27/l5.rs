@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::noise::{BlindRange, NoiseSource, White};
 
 pub struct Results {
     pub tgos: Vec<f64>,
@@ -13,15 +14,22 @@ pub struct Results {
 
 /// Run the C27L5 simulation
 pub fn run() -> Results {
-    let qzero: i32 = 0;
     let tblind: f64 = 0.5;
+    let vc: f64 = 4000.0;
+    let signoise: f64 = 0.001;
+    run_with_noise(&BlindRange::new(White::new(signoise), vc * tblind))
+}
+
+/// Run with an arbitrary seeker noise model; the filter covariance uses its
+/// variance and no measurement is taken where it is blind
+pub fn run_with_noise(noise: &dyn NoiseSource) -> Results {
+    let qzero: i32 = 0;
     let vm: f64 = 3000.0;
     let vc: f64 = 4000.0;
     let xnt: f64 = 96.6;
     let yic: f64 = 0.0;
     let hedeg: f64 = 20.0;
     let xnp: f64 = 3.0;
-    let signoise: f64 = 0.001;
     let ts: f64 = 0.1;
     let tau: f64 = 0.5;
     let apn: i32 = 2;
@@ -45,7 +53,7 @@ pub fn run() -> Results {
         let mut s: f64 = 0.0;
 
         let rtm = vc * tf;
-        let sigpos = rtm * signoise;
+        let sigpos = rtm * noise.sigma(rtm);
         let sign2 = sigpos.powi(2);
 
         let mut p11 = sign2;
@@ -102,10 +110,11 @@ pub fn run() -> Results {
                 let rtm = vc * tgo;
 
                 // Apply blind range - large measurement noise when TGO < TBLIND
-                let sigpos = if tgo >= tblind {
-                    rtm * signoise
-                } else {
+                let blind = noise.is_blind(rtm);
+                let sigpos = if blind {
                     9999999999.0
+                } else {
+                    rtm * noise.sigma(rtm)
                 };
                 let sign2 = sigpos.powi(2);
 
@@ -129,7 +138,7 @@ pub fn run() -> Results {
                 p23 = -k2 * m13 + m23;
                 p33 = -k3 * m13 + m33;
 
-                let xkblind = if tgo >= tblind { 1.0 } else { 0.0 };
+                let xkblind = if blind { 0.0 } else { 1.0 };
                 // Use XLAM from RK2 loop (not recomputed) to match MATLAB
                 let ystar = rtm * xlam * xkblind;
                 let res = ystar - yh - ts * ydh - 0.5 * ts * ts * (xnth - xnl);
@@ -154,7 +163,7 @@ pub fn run() -> Results {
                     xnc = xnpp * vc * xlamdh + 0.5 * xnpp * xnth - xnew;
                 }
 
-                if qzero == 1 && tgo < tblind {
+                if qzero == 1 && blind {
                     xnc = 0.0;
                 }
             }
@@ -208,4 +217,13 @@ mod tests {
         let results = run();
         assert!(!results.tgos.is_empty());
     }
+
+    #[test]
+    fn test_c27l5_longer_blind_range_hurts() {
+        // Same white angle noise as run(); only the blind range grows
+        let short = run();
+        let long = run_with_noise(&BlindRange::new(White::new(0.001), 4000.0));
+        let worst = |r: &Results| r.y.iter().fold(0.0_f64, |m, y| m.max(y.abs()));
+        assert!(worst(&long) > worst(&short));
+    }
 }
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::targets::{TargetManeuver, Weave};
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -13,10 +14,13 @@ pub struct Results {
 
 /// Run the C29L1 simulation
 pub fn run() -> Results {
+    run_with_target(&Weave::new(193.2, 3.0))
+}
+
+/// Run with an arbitrary target maneuver
+pub fn run_with_target(target: &dyn TargetManeuver) -> Results {
     let xnp: f64 = 3.0;
     let tau: f64 = 1.0;
-    let xnt: f64 = 193.2;
-    let w: f64 = 3.0;
 
     let mut array_t = Vec::new();
     let mut array_rtmp = Vec::new();
@@ -63,7 +67,7 @@ pub fn run() -> Results {
             // First derivative evaluation
             vt1 = -vt * beta.cos();
             vt2 = vt * beta.sin();
-            let betad = xnt * (w * t).sin() / vt;
+            let betad = target.accel(t) / vt;
             rtm1 = rt1 - rm1;
             rtm2 = rt2 - rm2;
             rtm = (rtm1 * rtm1 + rtm2 * rtm2).sqrt();
@@ -90,7 +94,7 @@ pub fn run() -> Results {
             // Second derivative for RK2
             vt1 = -vt * beta.cos();
             vt2 = vt * beta.sin();
-            let betad = xnt * (w * t).sin() / vt;
+            let betad = target.accel(t) / vt;
             rtm1 = rt1 - rm1;
             rtm2 = rt2 - rm2;
             rtm = (rtm1 * rtm1 + rtm2 * rtm2).sqrt();
@@ -163,4 +167,11 @@ mod tests {
         let results = run();
         assert!(!results.time.is_empty());
     }
+
+    #[test]
+    fn test_c29l1_step_target() {
+        use crate::utils::targets::Step;
+        let results = run_with_target(&Step::new(193.2));
+        assert!(results.miss.iter().all(|m| m.is_finite()));
+    }
 }
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::evasion::{nonlinear_miss, worst_bang_bang, worst_weave, Engagement, HomingLoop};
 
 // Use MATLAB's PI value for exact matching
 const PI: f64 = 3.1416;
@@ -143,6 +144,17 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
     println!("C29L5: Simulation finished");
     println!("  Data saved to: {}", data_file);
 
+    // Worst 3 g maneuvers for a 10 s flight, checked in the nonlinear engagement
+    let homing = HomingLoop::single_lag(4.0, 1.0);
+    let eng = Engagement { vm: 3000.0, vt: 1000.0, tf: 10.0 };
+    let resp = homing.adjoint(eng.tf, 0.01);
+    let ww = worst_weave(&resp, 96.6, &results.w);
+    let bb = worst_bang_bang(&resp, 96.6, Some(1));
+    println!("  Worst weave W = {:.2} r/s: adjoint miss {:.1} ft, nonlinear {:.1} ft",
+        ww.weave.w, ww.predicted_miss, nonlinear_miss(&homing, &eng, &ww.weave));
+    println!("  Worst single switch at t = {:.2} s: adjoint miss {:.1} ft, nonlinear {:.1} ft",
+        bb.switch_times.first().copied().unwrap_or(0.0), bb.predicted_miss, nonlinear_miss(&homing, &eng, &bb));
+
     Ok(results)
 }
 
@@ -0,0 +1,350 @@
+//! Chapter 30, Lesson 4: Angle-Only Tracking with a Passive Seeker
+//!
+//! Two-dimensional engagement in which an IR seeker measures only the
+//! line-of-sight angle. A bearings-only EKF in modified polar coordinates
+//! estimates LOS rate, closing velocity and range, and is compared with a
+//! range-available filter that also measures range as in L1-L3. Range only
+//! becomes observable once the missile maneuvers, which the heading error
+//! forces early in flight. Guidance is limited to 20 G.
+
+use crate::plotting::{PlotConfig, Series, line_plot};
+use crate::save_data;
+use crate::utils::filters::angle_only::{ModifiedPolar, closing_velocity_estimate, range_estimate, range_uncertainty};
+use crate::utils::filters::ekf::{Ekf, EkfModel};
+use crate::utils::matrix::{Matrix, diag, zeros};
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+
+/// Filter whose estimates drive the guidance law
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tracker {
+    AngleOnly,
+    RangeAvailable,
+}
+
+pub struct Results {
+    pub time: Vec<f64>,
+    pub xlamd: Vec<f64>,
+    /// LOS rate estimate of the angle-only filter
+    pub xlamdh_angle: Vec<f64>,
+    /// LOS rate estimate of the range-available filter
+    pub xlamdh_range: Vec<f64>,
+    /// Angle-only observability metric (relative one-sigma range uncertainty)
+    pub range_sigma: Vec<f64>,
+    pub rtm: Vec<f64>,
+    pub rtmh: Vec<f64>,
+    /// Angle-only closing velocity estimate
+    pub vch: Vec<f64>,
+    pub miss: f64,
+}
+
+/// Cartesian relative position and velocity filter measuring range and angle
+#[derive(Debug, Clone, Copy)]
+struct RangeAngle {
+    phis: f64,
+    sigr: f64,
+    sigth: f64,
+}
+
+impl EkfModel for RangeAngle {
+    fn n_states(&self) -> usize {
+        4
+    }
+
+    fn propagate(&self, x: &[f64], u: &[f64], ts: f64) -> Vec<f64> {
+        let mut c = x.to_vec();
+        for k in 0..2 {
+            c[k] += ts * c[k + 2] - 0.5 * ts * ts * u[k];
+            c[k + 2] -= ts * u[k];
+        }
+        c
+    }
+
+    fn transition(&self, _x: &[f64], _u: &[f64], ts: f64) -> Matrix {
+        let mut phi = diag(&[1.0; 4]);
+        phi[0][2] = ts;
+        phi[1][3] = ts;
+        phi
+    }
+
+    fn process_noise(&self, _x: &[f64], ts: f64) -> Matrix {
+        let mut q = zeros(4, 4);
+        for k in 0..2 {
+            q[k][k] = self.phis * ts.powi(3) / 3.0;
+            q[k][k + 2] = self.phis * ts * ts / 2.0;
+            q[k + 2][k] = q[k][k + 2];
+            q[k + 2][k + 2] = self.phis * ts;
+        }
+        q
+    }
+
+    fn measure(&self, x: &[f64]) -> Vec<f64> {
+        vec![(x[0] * x[0] + x[1] * x[1]).sqrt(), x[1].atan2(x[0])]
+    }
+
+    fn measurement_jacobian(&self, x: &[f64]) -> Matrix {
+        let r2 = x[0] * x[0] + x[1] * x[1];
+        let r = r2.sqrt();
+        vec![vec![x[0] / r, x[1] / r, 0.0, 0.0], vec![-x[1] / r2, x[0] / r2, 0.0, 0.0]]
+    }
+
+    fn measurement_noise(&self, _x: &[f64]) -> Matrix {
+        diag(&[self.sigr * self.sigr, self.sigth * self.sigth])
+    }
+
+    fn residual(&self, z: &[f64], zh: &[f64]) -> Vec<f64> {
+        let d = z[1] - zh[1];
+        vec![z[0] - zh[0], d.sin().atan2(d.cos())]
+    }
+}
+
+/// Run the C30L4 simulation
+pub fn run() -> Results {
+    run_with_tracker(Tracker::AngleOnly, Some(1))
+}
+
+/// Run with guidance driven by the chosen filter; both filters process the
+/// same measurements
+pub fn run_with_tracker(tracker: Tracker, seed: Option<u64>) -> Results {
+    let vm: f64 = 3000.0;
+    let vt: f64 = 1000.0;
+    let hedeg: f64 = 20.0;
+    let xnp: f64 = 4.0;
+    let ts: f64 = 0.05;
+    let signoise: f64 = 0.0005;
+    let sigr: f64 = 20.0;
+    let phis: f64 = 100.0;
+    let rguess: f64 = 1.3;
+    let vcguess: f64 = 3500.0;
+    let xlim: f64 = 644.0;
+
+    let mut rng: rand::rngs::StdRng = match seed {
+        Some(s) => rand::rngs::StdRng::seed_from_u64(s),
+        None => rand::rngs::StdRng::from_entropy(),
+    };
+    let normal = Normal::new(0.0, 1.0).unwrap();
+
+    let mut rt1: f64 = 40000.0;
+    let mut rt2: f64 = 10000.0;
+    let mut rm1: f64 = 0.0;
+    let mut rm2: f64 = 10000.0;
+    let vt1 = -vt;
+    let vt2: f64 = 0.0;
+
+    let mut rtm1 = rt1 - rm1;
+    let mut rtm2 = rt2 - rm2;
+    let mut rtm = (rtm1 * rtm1 + rtm2 * rtm2).sqrt();
+    let xlam0 = rtm2.atan2(rtm1);
+    let xlead = (vt * (xlam0).sin() / vm).asin();
+    let he = hedeg / 57.3;
+    let mut vm1 = vm * (xlam0 + xlead + he).cos();
+    let mut vm2 = vm * (xlam0 + xlead + he).sin();
+    let mut vc = -(rtm1 * (vt1 - vm1) + rtm2 * (vt2 - vm2)) / rtm;
+
+    // Angle-only filter cued with a poor range and closing velocity guess
+    let polar = ModifiedPolar::new(phis, signoise);
+    let (x0, p0) = polar.initial(xlam0, rguess * rtm, vcguess, 0.5, vm * he / rtm);
+    let mut angle = Ekf::new(polar, x0, p0);
+
+    // Range-available filter initialized from the first range and angle
+    let cart = ModifiedPolar::to_cartesian(&angle.x);
+    let scale = rtm / range_estimate(&angle);
+    let x0: Vec<f64> = cart.iter().map(|c| c * scale).collect();
+    let p0 = diag(&[sigr * sigr, (rtm * signoise).powi(2), (0.5 * vcguess).powi(2), (vm * he).powi(2)]);
+    let mut range = Ekf::new(RangeAngle { phis, sigr, sigth: signoise }, x0, p0);
+
+    let mut am = [0.0, 0.0];
+    let mut t: f64 = 0.0;
+    let mut s: f64 = 0.0;
+
+    let mut array_t = Vec::new();
+    let mut array_xlamd = Vec::new();
+    let mut array_xlamdh_angle = Vec::new();
+    let mut array_xlamdh_range = Vec::new();
+    let mut array_range_sigma = Vec::new();
+    let mut array_rtm = Vec::new();
+    let mut array_rtmh = Vec::new();
+    let mut array_vch = Vec::new();
+
+    while vc >= 0.0 {
+        let h = if rtm < 1000.0 { 0.0002 } else { 0.01 };
+
+        let rt1old = rt1;
+        let rt2old = rt2;
+        let rm1old = rm1;
+        let rm2old = rm2;
+        let vm1old = vm1;
+        let vm2old = vm2;
+
+        // Euler step (target constant velocity, missile acceleration held)
+        rt1 += h * vt1;
+        rt2 += h * vt2;
+        rm1 += h * vm1;
+        rm2 += h * vm2;
+        vm1 += h * am[0];
+        vm2 += h * am[1];
+        t += h;
+
+        // RK2 averaging
+        rt1 = 0.5 * (rt1old + rt1 + h * vt1);
+        rt2 = 0.5 * (rt2old + rt2 + h * vt2);
+        rm1 = 0.5 * (rm1old + rm1 + h * vm1);
+        rm2 = 0.5 * (rm2old + rm2 + h * vm2);
+        vm1 = 0.5 * (vm1old + vm1 + h * am[0]);
+        vm2 = 0.5 * (vm2old + vm2 + h * am[1]);
+
+        rtm1 = rt1 - rm1;
+        rtm2 = rt2 - rm2;
+        rtm = (rtm1 * rtm1 + rtm2 * rtm2).sqrt();
+        let vtm1 = vt1 - vm1;
+        let vtm2 = vt2 - vm2;
+        vc = -(rtm1 * vtm1 + rtm2 * vtm2) / rtm;
+
+        s += h;
+        if s >= ts - 1e-5 {
+            // Both filters propagate with the acceleration held over the interval
+            angle.predict(&am, s);
+            range.predict(&am, s);
+            s = 0.0;
+
+            let xlam = rtm2.atan2(rtm1);
+            let xlamd = (rtm1 * vtm2 - rtm2 * vtm1) / (rtm * rtm);
+            let xlamm = xlam + signoise * normal.sample(&mut rng);
+            let rtmm = rtm + sigr * normal.sample(&mut rng);
+            angle.update(&[xlamm]);
+            range.update(&[rtmm, xlamm]);
+
+            let c = &range.x;
+            let r2 = c[0] * c[0] + c[1] * c[1];
+            let xlamdh_range = (c[0] * c[3] - c[1] * c[2]) / r2;
+            let vch_range = -(c[0] * c[2] + c[1] * c[3]) / r2.sqrt();
+            let xlamdh_angle = angle.x[1];
+
+            // Proportional navigation perpendicular to the estimated LOS. The
+            // angle-only missile uses its nominal closing velocity: the range
+            // estimate degrades in the endgame once PN has nulled the LOS rate
+            let (xlamh, xlamdh, vch) = match tracker {
+                Tracker::AngleOnly => (angle.x[0], xlamdh_angle, vcguess),
+                Tracker::RangeAvailable => (c[1].atan2(c[0]), xlamdh_range, vch_range),
+            };
+            let xnc = (xnp * vch * xlamdh).clamp(-xlim, xlim);
+            am = [-xnc * xlamh.sin(), xnc * xlamh.cos()];
+
+            array_t.push(t);
+            array_xlamd.push(xlamd);
+            array_xlamdh_angle.push(xlamdh_angle);
+            array_xlamdh_range.push(xlamdh_range);
+            array_range_sigma.push(range_uncertainty(&angle));
+            array_rtm.push(rtm);
+            array_rtmh.push(range_estimate(&angle));
+            array_vch.push(closing_velocity_estimate(&angle));
+        }
+    }
+
+    Results {
+        time: array_t,
+        xlamd: array_xlamd,
+        xlamdh_angle: array_xlamdh_angle,
+        xlamdh_range: array_xlamdh_range,
+        range_sigma: array_range_sigma,
+        rtm: array_rtm,
+        rtmh: array_rtmh,
+        vch: array_vch,
+        miss: rtm,
+    }
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+    let range_guided = run_with_tracker(Tracker::RangeAvailable, Some(1));
+
+    let data_file = format!("{}/c30l4_datfil.txt", output_dir);
+    save_data(&data_file, &[
+        results.time.clone(),
+        results.xlamd.clone(),
+        results.xlamdh_angle.clone(),
+        results.xlamdh_range.clone(),
+        results.range_sigma.clone(),
+        results.rtm.clone(),
+        results.rtmh.clone(),
+        results.vch.clone(),
+    ])?;
+
+    let plot_file = format!("{}/c30l4_losrate.png", output_dir);
+    let config = PlotConfig::new("Angle-Only vs Range-Available LOS Rate Estimate")
+        .with_labels("Time (Sec)", "Line of Sight Rate (Rad/Sec)");
+
+    let series = vec![
+        Series::new(results.time.clone(), results.xlamd.clone())
+            .with_color(plotters::prelude::BLACK)
+            .with_label("Actual"),
+        Series::new(results.time.clone(), results.xlamdh_angle.clone())
+            .with_color(plotters::prelude::BLUE)
+            .with_label("Angle Only"),
+        Series::new(results.time.clone(), results.xlamdh_range.clone())
+            .with_color(plotters::prelude::RED)
+            .with_label("Range Available"),
+    ];
+
+    line_plot(&plot_file, &config, &series).ok();
+
+    let plot_file2 = format!("{}/c30l4_observability.png", output_dir);
+    let config2 = PlotConfig::new("Angle-Only Range Observability")
+        .with_labels("Time (Sec)", "Relative Range Uncertainty");
+
+    let series2 = vec![
+        Series::new(results.time.clone(), results.range_sigma.clone())
+            .with_color(plotters::prelude::BLUE),
+    ];
+
+    line_plot(&plot_file2, &config2, &series2).ok();
+
+    println!("C30L4: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    println!("  Miss with angle-only guidance:      {:.2} ft", results.miss);
+    println!("  Miss with range-available guidance: {:.2} ft", range_guided.miss);
+
+    // Median miss over repeated noise seeds
+    let median = |tracker: Tracker| {
+        let mut miss: Vec<f64> = (0..20).map(|k| run_with_tracker(tracker, Some(100 + k)).miss).collect();
+        miss.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        0.5 * (miss[9] + miss[10])
+    };
+    println!(
+        "  Median miss over 20 runs: angle-only {:.2} ft, range-available {:.2} ft",
+        median(Tracker::AngleOnly),
+        median(Tracker::RangeAvailable)
+    );
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c30l4_runs() {
+        let results = run();
+        assert!(!results.time.is_empty());
+        assert!(results.miss < 5.0);
+    }
+
+    #[test]
+    fn test_c30l4_range_becomes_observable() {
+        // Heading error correction early in flight makes range observable
+        let results = run();
+        let first = results.range_sigma[0];
+        let (k, best) = results
+            .range_sigma
+            .iter()
+            .enumerate()
+            .fold((0, f64::MAX), |acc, (i, &v)| if v < acc.1 { (i, v) } else { acc });
+        assert!(best < 0.5 * first);
+        // Range estimate consistent with the filter's own uncertainty
+        assert!((results.rtmh[k] - results.rtm[k]).abs() < 3.0 * best * results.rtm[k]);
+
+        let ranged = run_with_tracker(Tracker::RangeAvailable, Some(1));
+        assert!(ranged.miss < 5.0);
+    }
+}
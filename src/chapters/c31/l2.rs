@@ -0,0 +1,253 @@
+//! Chapter 31, Lesson 2: Interacting Multiple Model Estimator
+//!
+//! Reruns the C31L1 weaving-target engagement with an interacting multiple
+//! model (IMM) estimator in place of the MMAE. The three weave filters
+//! (W = 1, 2 and 4 rad/sec) are full four-state Kalman filters whose
+//! estimates are mixed through a Markov transition matrix before every
+//! prediction, so a model that falls out of favour can be recovered if the
+//! target changes frequency. The combined frequency estimate drives the
+//! same optimal guidance law as L1.
+//!
+//! The L1 port propagates only the position channel of each filter, so its
+//! three residuals coincide and the MMAE probabilities stay at their initial
+//! values. The MMAE baseline here is therefore built from the same weave
+//! filter bank with an identity transition matrix, which turns off the
+//! mixing and leaves the plain Bayesian reweighting of L1. It sees the same
+//! measurements as the IMM but does not drive the guidance.
+
+use crate::plotting::{PlotConfig, Series, line_plot};
+use crate::save_data;
+use crate::utils::filters::imm::{Imm, LinearModel, markov_matrix};
+use crate::utils::matrix::diag;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+
+pub struct Results {
+    pub time: Vec<f64>,
+    pub prob1: Vec<f64>,
+    pub prob2: Vec<f64>,
+    pub prob3: Vec<f64>,
+    /// Probability of the correct (W=2) model in the MMAE baseline
+    pub mmae_prob2: Vec<f64>,
+    pub wreal: Vec<f64>,
+    pub whpz: Vec<f64>,
+    pub miss: f64,
+}
+
+/// Run the C31L2 simulation
+pub fn run() -> Results {
+    run_with_seed(Some(1), 0.999)
+}
+
+/// Run with optional seed and the probability `p_stay` that the target keeps
+/// its weave frequency from one sample to the next
+pub fn run_with_seed(seed: Option<u64>, p_stay: f64) -> Results {
+    let tau: f64 = 0.5;
+    let vc: f64 = 9000.0;
+    let w = [1.0, 2.0, 4.0];
+    let wreal: f64 = 2.0;
+    let xnt: f64 = 96.6;
+    let xntreal: f64 = 96.6;
+    let ts: f64 = 0.01;
+    let yic: f64 = 0.0;
+    let vm: f64 = 3000.0;
+    let hedeg: f64 = 0.0;
+    let hedegfil: f64 = 20.0;
+    let sigrin: f64 = 0.0001;
+    let tf: f64 = 10.0;
+    let xlim: f64 = 322.0;
+
+    let mut rng: rand::rngs::StdRng = match seed {
+        Some(s) => rand::rngs::StdRng::seed_from_u64(s),
+        None => rand::rngs::StdRng::from_entropy(),
+    };
+    let normal = Normal::new(0.0, 1.0).unwrap();
+
+    let mut y = yic;
+    let mut yd = -vm * hedeg / 57.3;
+
+    let rtm_init = vc * tf;
+    let sigpos = rtm_init * sigrin;
+    let sign2_init = sigpos * sigpos;
+
+    let models: Vec<LinearModel> = w
+        .iter()
+        .map(|&wi| LinearModel::weave(wi, wi * wi * xnt * xnt / tf, sign2_init, ts))
+        .collect();
+    // Shared initial covariance; the weave-rate term uses the middle frequency
+    let p0 = diag(&[sign2_init, (vm * hedegfil / 57.3).powi(2), xnt * xnt, w[1] * w[1] * xnt * xnt]);
+    let mut imm = Imm::new(models.clone(), vec![0.0; 4], p0.clone(), markov_matrix(w.len(), p_stay), vec![1.0 / 3.0; 3]);
+    let mut mmae = Imm::new(models, vec![0.0; 4], p0, markov_matrix(w.len(), 1.0), vec![1.0 / 3.0; 3]);
+
+    let mut t: f64 = 0.0;
+    let h: f64 = 0.001;
+    let mut s: f64 = 0.0;
+    let mut xnc: f64 = 0.0;
+    let mut xnl: f64 = 0.0;
+
+    let mut array_t = Vec::new();
+    let mut array_prob1 = Vec::new();
+    let mut array_prob2 = Vec::new();
+    let mut array_prob3 = Vec::new();
+    let mut array_mmae_prob2 = Vec::new();
+    let mut array_wreal = Vec::new();
+    let mut array_whpz = Vec::new();
+
+    while t <= tf - 0.0001 {
+        s += h;
+
+        let yold = y;
+        let ydold = yd;
+        let xnlold = xnl;
+
+        let ytdd = xntreal * (wreal * t).sin();
+        let xnld = (xnc - xnl) / tau;
+        let ydd = ytdd - xnl;
+
+        y += h * yd;
+        yd += h * ydd;
+        xnl += h * xnld;
+        t += h;
+
+        let ytdd = xntreal * (wreal * t).sin();
+        let xnld = (xnc - xnl) / tau;
+        let ydd = ytdd - xnl;
+
+        y = 0.5 * (yold + y + h * yd);
+        yd = 0.5 * (ydold + yd + h * ydd);
+        xnl = 0.5 * (xnlold + xnl + h * xnld);
+
+        if s >= ts - 0.00001 {
+            s = 0.0;
+            let tgo = tf - t + 0.000001;
+            let rtm = vc * tgo;
+            let xlam = y / rtm;
+            let sigpos = rtm * sigrin;
+
+            let ystar = rtm * (xlam + sigrin * normal.sample(&mut rng));
+            for est in [&mut imm, &mut mmae] {
+                est.set_measurement_noise(vec![vec![sigpos * sigpos]]);
+                est.predict(&[xnl]);
+                est.update(&[ystar]);
+            }
+
+            let (xh, _) = imm.estimate();
+            let whpz: f64 = w.iter().zip(&imm.prob).map(|(wi, p)| wi * p).sum();
+
+            // Guidance law of L1 on the combined estimate
+            let xs = tgo / tau;
+            let top = 6.0 * xs * xs * ((-xs).exp() - 1.0 + xs);
+            let bot1 = 2.0 * xs.powi(3) + 3.0 + 6.0 * xs - 6.0 * xs * xs;
+            let bot2 = -12.0 * xs * (-xs).exp() - 3.0 * (-2.0 * xs).exp();
+            let xnpp = top / (0.0001 + bot1 + bot2);
+            let c1 = xnpp / (tgo * tgo);
+            let c2 = xnpp / tgo;
+            let c3 = xnpp * (1.0 - (whpz * tgo).cos()) / (whpz * whpz * tgo * tgo);
+            let c4 = -xnpp * ((-xs).exp() + xs - 1.0) / (xs * xs);
+
+            xnc = (c1 * xh[0] + c2 * xh[1] + c3 * xh[2] + c4 * xnl).clamp(-xlim, xlim);
+
+            array_t.push(t);
+            array_prob1.push(imm.prob[0]);
+            array_prob2.push(imm.prob[1]);
+            array_prob3.push(imm.prob[2]);
+            array_mmae_prob2.push(mmae.prob[1]);
+            array_wreal.push(wreal);
+            array_whpz.push(whpz);
+        }
+    }
+
+    Results {
+        time: array_t,
+        prob1: array_prob1,
+        prob2: array_prob2,
+        prob3: array_prob3,
+        mmae_prob2: array_mmae_prob2,
+        wreal: array_wreal,
+        whpz: array_whpz,
+        miss: y,
+    }
+}
+
+/// First time after which the probability stays above `level`
+pub fn convergence_time(time: &[f64], prob: &[f64], level: f64) -> Option<f64> {
+    match prob.iter().rposition(|&p| p <= level) {
+        None => time.first().copied(),
+        Some(i) if i + 1 < time.len() => Some(time[i + 1]),
+        Some(_) => None,
+    }
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+
+    let data_file = format!("{}/c31l2_datfil.txt", output_dir);
+    save_data(&data_file, &[
+        results.time.clone(),
+        results.prob1.clone(),
+        results.prob2.clone(),
+        results.prob3.clone(),
+        results.mmae_prob2.clone(),
+        results.wreal.clone(),
+        results.whpz.clone(),
+    ])?;
+
+    let plot_file = format!("{}/c31l2_prob.png", output_dir);
+    let config = PlotConfig::new("IMM Model Probabilities")
+        .with_labels("Time (s)", "Probability");
+
+    let series = vec![
+        Series::new(results.time.clone(), results.prob1.clone())
+            .with_color(plotters::prelude::BLUE)
+            .with_label("W=1"),
+        Series::new(results.time.clone(), results.prob2.clone())
+            .with_color(plotters::prelude::RED)
+            .with_label("W=2"),
+        Series::new(results.time.clone(), results.prob3.clone())
+            .with_color(plotters::prelude::GREEN)
+            .with_label("W=4"),
+        Series::new(results.time.clone(), results.mmae_prob2.clone())
+            .with_color(plotters::prelude::BLACK)
+            .with_label("MMAE W=2"),
+    ];
+
+    line_plot(&plot_file, &config, &series).ok();
+
+    let fmt = |t: Option<f64>| t.map_or("never".to_string(), |t| format!("{:.2} s", t));
+    println!("C31L2: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    println!("  W=2 probability above 0.9: IMM {}, MMAE {}",
+        fmt(convergence_time(&results.time, &results.prob2, 0.9)),
+        fmt(convergence_time(&results.time, &results.mmae_prob2, 0.9)));
+    println!("  Final W=2 probability: IMM {:.3}, MMAE {:.3}",
+        results.prob2.last().unwrap_or(&0.0), results.mmae_prob2.last().unwrap_or(&0.0));
+    println!("  Miss with IMM: {:.2} ft", results.miss);
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c31l2_imm_identifies_weave_frequency() {
+        let results = run_with_seed(Some(12345), 0.999);
+        for k in 0..results.time.len() {
+            assert!((results.prob1[k] + results.prob2[k] + results.prob3[k] - 1.0).abs() < 1e-9);
+        }
+        assert!(*results.prob2.last().unwrap() > 0.8);
+        // With the weave held constant the unmixed MMAE bank also settles on W=2
+        assert!(*results.mmae_prob2.last().unwrap() > 0.8);
+        assert!((results.whpz.last().unwrap() - 2.0).abs() < 0.5);
+        assert!(results.miss.abs() < 10.0);
+    }
+
+    #[test]
+    fn test_convergence_time() {
+        let t = [0.0, 1.0, 2.0, 3.0];
+        assert_eq!(convergence_time(&t, &[0.2, 0.95, 0.5, 0.95], 0.9), Some(3.0));
+        assert_eq!(convergence_time(&t, &[0.95; 4], 0.9), Some(0.0));
+        assert_eq!(convergence_time(&t, &[0.2; 4], 0.9), None);
+    }
+}
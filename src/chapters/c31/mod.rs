@@ -3,4 +3,5 @@
 //! MMAE for frequency estimation with multiple Kalman filters
 
 pub mod l1;
+pub mod l2;
 
@@ -5,179 +5,13 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::gains::{generate_gains, GeneratedGains};
 
 pub struct Results {
     pub tf: Vec<f64>,
     pub y: Vec<f64>,
 }
 
-/// Generate optimal guidance gains using Riccati equation
-#[allow(clippy::type_complexity)]
-fn generate_gains(
-    tau: f64,
-    w: f64,
-    z: f64,
-    wz: f64,
-    gam: f64,
-    tf: f64,
-    ts: f64,
-) -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) {
-    // Initialize matrices (6x6)
-    let mut f = [[0.0; 6]; 6];
-    let mut s = [[0.0; 6]; 6];
-    let mut g = [0.0; 6];
-
-    g[5] = w * w / tau;
-
-    f[0][1] = 1.0;
-    f[1][2] = 1.0;
-    f[1][3] = -1.0;
-    f[1][5] = 1.0 / (wz * wz);
-    f[3][4] = 1.0;
-    f[4][5] = 1.0;
-    f[5][3] = -w * w / tau;
-    f[5][4] = -w * w * (2.0 * z / w + tau) / tau;
-    f[5][5] = -w * w * (1.0 / (w * w) + 2.0 * z * tau / w) / tau;
-
-    s[0][0] = 1.0;
-
-    let mut t: f64 = 0.0;
-    let h: f64 = 0.0001;
-    let mut s1: f64 = 0.0;
-
-    let mut c1_vec = Vec::new();
-    let mut c2_vec = Vec::new();
-    let mut c3_vec = Vec::new();
-    let mut c4_vec = Vec::new();
-    let mut c5_vec = Vec::new();
-    let mut c6_vec = Vec::new();
-
-    let gaminv = 1.0 / gam;
-
-    while t < tf - 0.0001 {
-        s1 += h;
-        let sold = s;
-
-        // Compute SF = S * F
-        let mut sf = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                for k in 0..6 {
-                    sf[i][j] += s[i][k] * f[k][j];
-                }
-            }
-        }
-
-        // Compute GTS = G' * S
-        let mut gts = [0.0; 6];
-        for j in 0..6 {
-            for k in 0..6 {
-                gts[j] += g[k] * s[k][j];
-            }
-        }
-
-        // C = (1/gam) * GTS
-        let mut c = [0.0; 6];
-        for i in 0..6 {
-            c[i] = gaminv * gts[i];
-        }
-
-        // SFT = SF'
-        let mut sft = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                sft[i][j] = sf[j][i];
-            }
-        }
-
-        // CTBC = gam * C' * C
-        let mut ctbc = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                ctbc[i][j] = gam * c[i] * c[j];
-            }
-        }
-
-        // SD = SF + SFT - CTBC
-        let mut sd = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                sd[i][j] = sf[i][j] + sft[i][j] - ctbc[i][j];
-            }
-        }
-
-        // Euler step
-        for i in 0..6 {
-            for j in 0..6 {
-                s[i][j] += h * sd[i][j];
-            }
-        }
-        t += h;
-
-        // Recompute for RK2
-        let mut sf = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                for k in 0..6 {
-                    sf[i][j] += s[i][k] * f[k][j];
-                }
-            }
-        }
-
-        let mut gts = [0.0; 6];
-        for j in 0..6 {
-            for k in 0..6 {
-                gts[j] += g[k] * s[k][j];
-            }
-        }
-
-        let mut c = [0.0; 6];
-        for i in 0..6 {
-            c[i] = gaminv * gts[i];
-        }
-
-        let mut sft = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                sft[i][j] = sf[j][i];
-            }
-        }
-
-        let mut ctbc = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                ctbc[i][j] = gam * c[i] * c[j];
-            }
-        }
-
-        let mut sd = [[0.0; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                sd[i][j] = sf[i][j] + sft[i][j] - ctbc[i][j];
-            }
-        }
-
-        // RK2 averaging
-        for i in 0..6 {
-            for j in 0..6 {
-                s[i][j] = 0.5 * (sold[i][j] + s[i][j]) + 0.5 * h * sd[i][j];
-            }
-        }
-
-        if s1 >= ts - 0.0001 {
-            s1 = 0.0;
-            c1_vec.push(-c[0]);
-            c2_vec.push(-c[1]);
-            c3_vec.push(-c[2]);
-            c4_vec.push(-c[3]);
-            c5_vec.push(-c[4]);
-            c6_vec.push(-c[5]);
-        }
-    }
-
-    (c1_vec, c2_vec, c3_vec, c4_vec, c5_vec, c6_vec)
-}
-
 /// Run the C35L6 simulation
 pub fn run() -> Results {
     let tau: f64 = 1.0;
@@ -195,10 +29,10 @@ pub fn run() -> Results {
     let _vm: f64 = 3000.0;
 
     // Generate gains if APN == 3
-    let (c1, c2, c3, c4, c5, c6) = if apn == 3 {
+    let GeneratedGains { c1, c2, c3, c4, c5, c6 } = if apn == 3 {
         generate_gains(tau, w, z, wz, gam, tfmax, ts)
     } else {
-        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new())
+        GeneratedGains { c1: Vec::new(), c2: Vec::new(), c3: Vec::new(), c4: Vec::new(), c5: Vec::new(), c6: Vec::new() }
     };
 
     let mut array_tf = Vec::new();
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::targets::{TargetManeuver, TrapezoidalWeave};
 use rand::SeedableRng;
 use rand_distr::{Distribution, Uniform};
 
@@ -13,29 +14,6 @@ pub struct Results {
     pub rms: Vec<f64>,
 }
 
-/// Compute trapezoidal weave acceleration
-fn trapezoidal_weave(t: f64, tstart: f64, xnt: f64, pz: f64, tr: f64, x: f64) -> f64 {
-    if t < tstart {
-        return 0.0;
-    }
-
-    let t_rel = t - tstart;
-    let period_num = (t_rel / pz).floor() as i32;
-    let tstar = t_rel - (period_num as f64) * pz;
-
-    if tstar < tr / 2.0 {
-        2.0 * xnt * tstar / tr
-    } else if tstar < tr / 2.0 + x {
-        xnt
-    } else if tstar < 3.0 * tr / 2.0 + x {
-        -2.0 * xnt * tstar / tr + 2.0 * xnt + 2.0 * xnt * x / tr
-    } else if tstar < 3.0 * tr / 2.0 + 2.0 * x {
-        -xnt
-    } else {
-        2.0 * xnt * tstar / tr - 4.0 * xnt - 4.0 * xnt * x / tr
-    }
-}
-
 /// Run the C38L2 simulation
 pub fn run() -> Results {
     run_with_seed(None)
@@ -53,7 +31,6 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
     let tau: f64 = 0.5;
     let xnp: f64 = 3.0;
     let _tf: f64 = 10.0;
-    let x = pz / 2.0 - tr;
 
     let mut rng: rand::rngs::StdRng = match seed {
         Some(s) => rand::rngs::StdRng::seed_from_u64(s),
@@ -72,6 +49,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
         for (jj, z_item) in z.iter_mut().enumerate().take(num_runs) {
             let sum: f64 = uniform.sample(&mut rng);
             let tstart = sum * tf_val;
+            let target = TrapezoidalWeave::new(xnt, pz, tr).starting_at(tstart);
 
             let mut y: f64 = 0.0;
             let mut yd: f64 = 0.0;
@@ -86,7 +64,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
                 let xlamhold = xlamh;
 
                 // First derivative evaluation
-                let ytdd = trapezoidal_weave(t, tstart, xnt, pz, tr, x);
+                let ytdd = target.accel(t);
                 let tgo = tf_val - t + 0.00001;
                 let xlam = y / (vc * tgo);
                 let xlamhd = (xlam - xlamh) / tau;
@@ -100,7 +78,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
                 t += h;
 
                 // Second derivative for RK2
-                let ytdd = trapezoidal_weave(t, tstart, xnt, pz, tr, x);
+                let ytdd = target.accel(t);
                 let tgo = tf_val - t + 0.00001;
                 let xlam = y / (vc * tgo);
                 let xlamhd = (xlam - xlamh) / tau;
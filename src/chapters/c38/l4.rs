@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::targets::{TargetManeuver, TrapezoidalWeave};
 use rand::SeedableRng;
 use rand_distr::{Distribution, Normal};
 
@@ -62,29 +63,6 @@ fn project6s(
     (y, yd, x1, x2, x3, x4)
 }
 
-/// Compute trapezoidal weave acceleration
-fn trapezoidal_weave(t: f64, tstart: f64, xnt: f64, pz: f64, tr: f64, x: f64) -> f64 {
-    if t < tstart {
-        return 0.0;
-    }
-
-    let t_rel = t - tstart;
-    let period_num = (t_rel / pz).floor() as i32;
-    let tstar = t_rel - (period_num as f64) * pz;
-
-    if tstar < tr / 2.0 {
-        2.0 * xnt * tstar / tr
-    } else if tstar < tr / 2.0 + x {
-        xnt
-    } else if tstar < 3.0 * tr / 2.0 + x {
-        -2.0 * xnt * tstar / tr + 2.0 * xnt + 2.0 * xnt * x / tr
-    } else if tstar < 3.0 * tr / 2.0 + 2.0 * x {
-        -xnt
-    } else {
-        2.0 * xnt * tstar / tr - 4.0 * xnt - 4.0 * xnt * x / tr
-    }
-}
-
 /// Run the C38L4 simulation
 pub fn run() -> Results {
     run_with_seed(None)
@@ -120,7 +98,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
     let xlim: f64 = 1288.0;
 
     let _xntic = xnt;
-    let x = pz / 2.0 - tr;
+    let target = TrapezoidalWeave::new(xnt, pz, tr).starting_at(tstart);
     let alf = PI * tr / (2.0 * xl);
     let phis = xnt * xnt / 6.0;
 
@@ -234,7 +212,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
         let tgo = tf - t + 0.000001;
         let _rtm = vc * tgo;
         let _xlam = y / (vc * tgo);
-        let ytdd = trapezoidal_weave(t, tstart, xnt, pz, tr, x);
+        let ytdd = target.accel(t);
         let xnld = (xnc - xnl) / tau;
         let ydd = ytdd - xnl;
 
@@ -248,7 +226,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
         let _tgo = tf - t + 0.000001;
         let _rtm = vc * _tgo;
         let _xlam = y / (vc * _tgo);
-        let ytdd = trapezoidal_weave(t, tstart, xnt, pz, tr, x);
+        let ytdd = target.accel(t);
         let xnld = (xnc - xnl) / tau;
         let ydd = ytdd - xnl;
 
@@ -339,7 +317,7 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
                 xnc = -xlim;
             }
 
-            let ytdd = trapezoidal_weave(t, tstart, xnt, pz, tr, x);
+            let ytdd = target.accel(t);
             array_t.push(t);
             array_ytdd.push(ytdd);
             array_xnth.push(xnth);
@@ -3,6 +3,7 @@
 //! Full autopilot model simulation with optimal guidance.
 
 use crate::save_data;
+use crate::utils::actuator::Actuator;
 
 pub struct Results {
     pub tf: Vec<f64>,
@@ -11,6 +12,11 @@ pub struct Results {
 
 /// Run the C39L3 simulation
 pub fn run() -> Results {
+    run_with_actuator(Actuator::second_order(150.0, 0.7))
+}
+
+/// Run with an arbitrary fin actuator model
+pub fn run_with_actuator(mut actuator: Actuator) -> Results {
     // System parameters
     let alt: f64 = 100000.0;
     let tau: f64 = 0.5;
@@ -20,8 +26,6 @@ pub fn run() -> Results {
     let wcr: f64 = 50.0;
     let h: f64 = 0.001;
     let xnp: f64 = 3.0;
-    let wact: f64 = 150.0;
-    let zact: f64 = 0.7;
     let vc: f64 = 4000.0;
     let vm: f64 = 3000.0;
     let fr: f64 = 3.0;
@@ -121,6 +125,7 @@ pub fn run() -> Results {
         let mut xnl: f64 = 0.0;
         let mut deld: f64 = 0.0;
         let mut del: f64 = 0.0;
+        actuator.reset();
         let mut e: f64 = 0.0;
         let mut ed: f64 = 0.0;
 
@@ -174,8 +179,8 @@ pub fn run() -> Results {
 
             let thd = xk3 * (e + ta * ed);
             let delc = xkr * (x + thd);
-            let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-            let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+            let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+            let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
             xnl = xk1 * (e - edd / (wz * wz));
             let xd = wi * (thd + xka * (xnl - xncg * xkdc));
             let xnlpz = xnl * 32.2;
@@ -185,8 +190,9 @@ pub fn run() -> Results {
             x += h * xd;
             y += h * yd;
             yd += h * ydd;
-            del += h * deld;
+            del += h * delrate;
             deld += h * deldd;
+            actuator.limit(&mut del, &mut deld);
             e += h * ed;
             ed += h * edd;
             t += h;
@@ -231,8 +237,8 @@ pub fn run() -> Results {
 
             let thd = xk3 * (e + ta * ed);
             let delc = xkr * (x + thd);
-            let deldd = wact * wact * (delc - del - 2.0 * zact * deld / wact);
-            let edd = waf * waf * (del - e - 2.0 * zaf * ed / waf);
+            let (delrate, deldd) = actuator.derivatives(delc, del, deld);
+            let edd = waf * waf * (actuator.output(del) - e - 2.0 * zaf * ed / waf);
             xnl = xk1 * (e - edd / (wz * wz));
             let xd = wi * (thd + xka * (xnl - xncg * xkdc));
             let xnlpz = xnl * 32.2;
@@ -242,8 +248,10 @@ pub fn run() -> Results {
             x = 0.5 * (xold + x + h * xd);
             y = 0.5 * (yold + y + h * yd);
             yd = 0.5 * (ydold + yd + h * ydd);
-            del = 0.5 * (delold + del + h * deld);
+            del = 0.5 * (delold + del + h * delrate);
             deld = 0.5 * (deldold + deld + h * deldd);
+            actuator.limit(&mut del, &mut deld);
+            actuator.latch(del);
             e = 0.5 * (eold + e + h * ed);
             ed = 0.5 * (edold + ed + h * edd);
         }
@@ -268,8 +276,19 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
         results.miss.clone(),
     ])?;
 
+    // Same sweep with a 300 deg/s fin rate limit
+    let limited = run_with_actuator(Actuator::second_order(150.0, 0.7).with_rate_limit(300.0));
+    let rate_file = format!("{}/c39l3_ratelim_datfil.txt", output_dir);
+    save_data(&rate_file, &[
+        limited.tf.clone(),
+        limited.miss.clone(),
+    ])?;
+
+    let max_abs = |v: &[f64]| v.iter().fold(0.0_f64, |m, x| m.max(x.abs()));
     println!("C39L3: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    println!("  Peak miss: linear actuator {:.2} ft, 300 deg/s rate limit {:.2} ft",
+        max_abs(&results.miss), max_abs(&limited.miss));
 
     Ok(results)
 }
@@ -283,4 +302,12 @@ mod tests {
         let results = run();
         assert!(!results.tf.is_empty());
     }
+
+    #[test]
+    fn test_c39l3_rate_limit_changes_miss() {
+        let linear = run();
+        let limited = run_with_actuator(Actuator::second_order(150.0, 0.7).with_rate_limit(30.0));
+        assert_eq!(linear.miss.len(), limited.miss.len());
+        assert!(linear.miss.iter().zip(&limited.miss).any(|(a, b)| (a - b).abs() > 1e-3));
+    }
 }
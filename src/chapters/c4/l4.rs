@@ -108,7 +108,7 @@ mod tests {
     #[test]
     fn test_c4l4_runs() {
         let results = run();
-        assert!(results.time.len() > 0);
+        assert!(!results.time.is_empty());
         assert_eq!(results.time.len(), results.y.len());
         assert_eq!(results.time.len(), results.sig_plus.len());
     }
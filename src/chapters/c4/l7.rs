@@ -242,7 +242,7 @@ mod tests {
     #[test]
     fn test_c4l7_runs() {
         let results = run();
-        assert!(results.tp.len() > 0);
+        assert!(!results.tp.is_empty());
         assert_eq!(results.tp.len(), results.xmgl.len());
     }
 }
@@ -8,11 +8,15 @@
 
 use crate::save_data;
 use crate::utils::{lambert3d, distance3d, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::elements::OrbitalElements;
+use crate::utils::kepler::StateVector;
 
 pub struct Results {
     pub time: Vec<f64>,
     pub dist_rt_nm: Vec<f64>,
     pub alt_nm: Vec<f64>,
+    /// Elements of the Lambert trajectory at launch
+    pub elements: OrbitalElements,
 }
 
 /// Run the C40L2 simulation
@@ -50,6 +54,7 @@ pub fn run() -> Results {
     let mut xtd = result.vrx;
     let mut ytd = result.vry;
     let mut ztd = result.vrz;
+    let elements = OrbitalElements::from_state(&StateVector::new(xt, yt, zt, xtd, ytd, ztd), gm);
 
     let mut t = 0.0;
     let mut s = 0.0;
@@ -117,6 +122,7 @@ pub fn run() -> Results {
         time: array_t,
         dist_rt_nm: array_distrtnm,
         alt_nm: array_altnm,
+        elements,
     }
 }
 
@@ -130,8 +136,14 @@ pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
         results.alt_nm.clone(),
     ])?;
 
+    let oe = &results.elements;
     println!("C40L2: Simulation finished");
     println!("  Data saved to: {}", data_file);
+    if let Some(ra) = oe.apogee_radius() {
+        println!("  Apogee altitude (Nmi) = {:.1}", (ra - EARTH_RADIUS_FT) / 6076.0);
+    }
+    println!("  Time to apogee (s) = {:.1}", oe.time_between(oe.nu, std::f64::consts::PI));
+    println!("  Eccentricity = {:.4}", oe.e);
 
     Ok(results)
 }
@@ -145,4 +157,12 @@ mod tests {
         let results = run();
         assert!(!results.time.is_empty());
     }
+
+    #[test]
+    fn test_c40l2_apogee_from_elements() {
+        let results = run();
+        let apogee = (results.elements.apogee_radius().unwrap() - EARTH_RADIUS_FT) / 6076.0;
+        let peak = results.alt_nm.iter().cloned().fold(f64::MIN, f64::max);
+        assert!((apogee - peak).abs() < 1.0, "apogee {} peak {}", apogee, peak);
+    }
 }
@@ -0,0 +1,111 @@
+//! Chapter 40, Lesson 6: Earth Model Range Errors
+//!
+//! The L2 missile aims its burnout velocity with the book's spherical,
+//! non-rotating Earth and Lambert solution. The same ground-relative
+//! burnout state is then flown against Earth models with rotation, the
+//! WGS-84 ellipsoid and J2 gravity to show how far each effect moves the
+//! impact point. A last case leads the aim point by the Earth's rotation
+//! during the flight, as L2 does, leaving only the ellipsoid and J2 errors.
+//! Output: 5 columns [CASE, IMPACT LAT (DEG), IMPACT LONG (DEG), TF, MISS (NMI)]
+
+use crate::save_data;
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::impact::{ballistic_impact, Impact};
+use crate::utils::kepler::StateVector;
+use crate::utils::lambert3d;
+use crate::utils::rk2::Vec3;
+
+pub struct Results {
+    pub cases: Vec<Case>,
+}
+
+pub struct Case {
+    pub label: &'static str,
+    pub impact: Impact,
+    /// Distance from the aim point (Nmi)
+    pub miss_nm: f64,
+}
+
+/// Run the C40L6 simulation
+pub fn run() -> Results {
+    let launch = Geodetic::from_degrees(43.75, 7.42, 0.0);
+    let aim = Geodetic::from_degrees(36.175, -115.136, 0.0);
+    let tf: f64 = 2000.0;
+    let h: f64 = 0.01;
+
+    let book = EarthModel::spherical();
+    let wgs84 = EarthModel::wgs84();
+
+    // Burnout velocity from the book's model, aiming at the ground point
+    let v_book = burnout_velocity(&book, &launch, &aim, tf, 0.0);
+    // Same aim, leading the target by the Earth's rotation during flight
+    let v_lead = burnout_velocity(&wgs84, &launch, &aim, tf, wgs84.omega);
+
+    let runs = [
+        ("Spherical, non-rotating (book)", book, v_book),
+        ("Spherical, rotating", book.rotating(), v_book),
+        ("WGS-84 and J2, non-rotating", wgs84.non_rotating(), v_book),
+        ("WGS-84 and J2, rotating", wgs84, v_book),
+        ("WGS-84 and J2, rotating, aim lead", wgs84, v_lead),
+    ];
+
+    let cases = runs.iter().map(|&(label, earth, v)| {
+        let r0 = earth.geodetic_to_ecef(&launch);
+        let impact = ballistic_impact(&earth, &StateVector::from_vectors(r0, v), h, 3.0 * tf)
+            .expect("trajectory reaches the ground");
+        let miss = (earth.geodetic_to_ecef(&impact.position) - earth.geodetic_to_ecef(&aim)).magnitude();
+        Case { label, impact, miss_nm: miss / 6076.0 }
+    }).collect();
+
+    Results { cases }
+}
+
+/// Ground-relative burnout velocity of the Lambert trajectory to `aim`,
+/// with the aim point carried `omega * tf` east for a rotating Earth
+pub fn burnout_velocity(earth: &EarthModel, launch: &Geodetic, aim: &Geodetic, tf: f64, omega: f64) -> Vec3 {
+    let r0 = earth.geodetic_to_ecef(launch);
+    let rf = earth.geodetic_to_ecef(&Geodetic::new(aim.lat, aim.lon + omega * tf, aim.alt));
+    let v = lambert3d(r0.x, r0.y, r0.z, tf, rf.x, rf.y, rf.z, 0);
+    Vec3::new(v.vrx, v.vry, v.vrz) - Vec3::new(0.0, 0.0, omega).cross(&r0)
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+
+    let column = |f: &dyn Fn(usize, &Case) -> f64| -> Vec<f64> {
+        results.cases.iter().enumerate().map(|(i, c)| f(i, c)).collect()
+    };
+    let data_file = format!("{}/c40l6_datfil.txt", output_dir);
+    save_data(&data_file, &[
+        column(&|i, _| (i + 1) as f64),
+        column(&|_, c| c.impact.position.lat.to_degrees()),
+        column(&|_, c| c.impact.position.lon.to_degrees()),
+        column(&|_, c| c.impact.time),
+        column(&|_, c| c.miss_nm),
+    ])?;
+
+    println!("C40L6: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    for case in &results.cases {
+        println!("  {:<36} miss = {:8.2} Nmi  tf = {:7.1} s", case.label, case.miss_nm, case.impact.time);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c40l6_range_errors() {
+        let results = run();
+        let miss: Vec<f64> = results.cases.iter().map(|c| c.miss_nm).collect();
+        // The book's model hits its own aim point
+        assert!(miss[0] < 0.5, "{:?}", miss);
+        // Ignoring rotation is by far the largest error
+        assert!(miss[1] > 100.0 && miss[3] > 100.0, "{:?}", miss);
+        // Leading the aim point removes most of it, leaving the ellipsoid and J2
+        assert!(miss[4] < miss[3] / 5.0 && miss[4] > miss[0], "{:?}", miss);
+    }
+}
@@ -7,4 +7,6 @@ pub mod l2;
 pub mod l3;
 pub mod l4;
 pub mod l5;
+pub mod l6;
+pub mod l7;
 
@@ -4,6 +4,9 @@
 
 use crate::save_data;
 use crate::utils::{lambert3d, distance3dkm, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::earth::EarthModel;
+use crate::utils::geometry::LocalFrame;
+use crate::utils::rk2::Vec3;
 use rand_distr::{Distribution, Normal};
 
 pub struct Results {
@@ -120,6 +123,7 @@ pub fn run() -> Results {
     let z: f64 = 0.0;
     let zf: f64 = 0.0;
     let zr: f64 = 0.0;
+    let radar = LocalFrame::at_position(&EarthModel::spherical(), &Vec3::new(xr, yr, zr));
 
     let mut rng = rand::thread_rng();
     let normal = Normal::new(0.0, 1.0).unwrap();
@@ -225,9 +229,7 @@ pub fn run() -> Results {
             let _rrkm = ((x - xr).powi(2) + (y - yr).powi(2)).sqrt() / 3280.0;
             let _distrkm = distance3dkm(xr, yr, zr, xfirst, yfirst, zfirst);
             let _altrkm = ((xr * xr + yr * yr).sqrt() - a) / 3280.0;
-            let rrmag = (xr * xr + yr * yr).sqrt();
-            let rrtmag = ((x - xr).powi(2) + (y - yr).powi(2)).sqrt();
-            let eldeg = 90.0 - 57.3 * ((xr * (x - xr) + yr * (y - yr)) / (rrmag * rrtmag)).acos();
+            let eldeg = 57.3 * radar.look_angles(&Vec3::new(x, y, z)).elevation;
 
             let isee = eldeg > 2.0 && eldeg < 85.0;
 
@@ -390,9 +392,8 @@ mod tests {
 
     #[test]
     fn test_c43l2_runs() {
-        let results = run();
+        let _results = run();
         // May or may not have data depending on engagement geometry
         // Just verify it runs without error
-        assert!(true);
     }
 }
@@ -4,7 +4,8 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
-use crate::utils::{lambert3d, distance3dkm, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::{lambert3d, distance3dkm};
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -20,8 +21,9 @@ pub fn run() -> Results {
     let mut tftot = 252.0 + 0.223 * rdeskm - 5.44e-6 * rdeskm * rdeskm;
     tftot += tloft;
 
-    let a = EARTH_RADIUS_FT;
-    let gm = GM_FT;
+    let earth = EarthModel::spherical();
+    let a = earth.a;
+    let gm = earth.gm;
 
     let xlongfdeg = 57.3 * rdeskm * 3280.0 / a;
     let xlongtdeg: f64 = 0.0;
@@ -34,15 +36,17 @@ pub fn run() -> Results {
 
     let xlongf = xlongfdeg / 57.3;
     let xlatf = xlatfdeg / 57.3;
-    let xf = a * xlatf.cos() * xlongf.cos();
-    let yf = a * xlatf.cos() * xlongf.sin();
-    let zf: f64 = 0.0;
+    let pos_f = earth.geodetic_to_ecef(&Geodetic::new(xlatf, xlongf, 0.0));
+    let xf = pos_f.x;
+    let yf = pos_f.y;
+    let zf = pos_f.z;
 
     let xlongt = xlongtdeg / 57.3;
     let xlatt = xlattdeg / 57.3;
-    let mut xt = a * xlatt.cos() * xlongt.cos();
-    let mut yt = a * xlatt.cos() * xlongt.sin();
-    let zt: f64 = 0.0;
+    let pos_t = earth.geodetic_to_ecef(&Geodetic::new(xlatt, xlongt, 0.0));
+    let mut xt = pos_t.x;
+    let mut yt = pos_t.y;
+    let zt = pos_t.z;
     let xtinit = xt;
     let ytinit = yt;
     let ztinit: f64 = 0.0;
@@ -3,7 +3,8 @@
 //! Ballistic missile defense: interceptor velocity vs flight time analysis.
 
 use crate::save_data;
-use crate::utils::{lambert3d, predict44, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::{lambert3d, predict44};
 
 pub struct Results {
     pub tf_tl: Vec<f64>,
@@ -21,8 +22,9 @@ pub fn run() -> Results {
     let switch: i32 = 0;
     let switchm: i32 = 0;
 
-    let a = EARTH_RADIUS_FT;
-    let gm = GM_FT;
+    let earth = EarthModel::spherical();
+    let a = earth.a;
+    let gm = earth.gm;
 
     let mut tftot = 252.0 + 0.223 * rdeskm - 5.44e-6 * rdeskm * rdeskm;
     tftot += tloft;
@@ -47,21 +49,24 @@ pub fn run() -> Results {
         let xlatm = xlatmdegic / 57.3;
         let xlatt = xlattdeg / 57.3;
 
-        let xf = a * xlatf.cos() * xlongf.cos();
-        let yf = a * xlatf.cos() * xlongf.sin();
-        let zf: f64 = 0.0;
+        let pos_f = earth.geodetic_to_ecef(&Geodetic::new(xlatf, xlongf, 0.0));
+        let xf = pos_f.x;
+        let yf = pos_f.y;
+        let zf = pos_f.z;
 
-        let mut xt = a * xlatt.cos() * xlongt.cos();
-        let mut yt = a * xlatt.cos() * xlongt.sin();
-        let zt: f64 = 0.0;
+        let pos_t = earth.geodetic_to_ecef(&Geodetic::new(xlatt, xlongt, 0.0));
+        let mut xt = pos_t.x;
+        let mut yt = pos_t.y;
+        let zt = pos_t.z;
 
         let _xtinit = xt;
         let _ytinit = yt;
         let _ztinit = zt;
 
-        let mut xm = (a + altm) * xlatm.cos() * xlongm.cos();
-        let mut ym = (a + altm) * xlatm.cos() * xlongm.sin();
-        let zm: f64 = 0.0;
+        let pos_m = earth.geodetic_to_ecef(&Geodetic::new(xlatm, xlongm, altm));
+        let mut xm = pos_m.x;
+        let mut ym = pos_m.y;
+        let zm = pos_m.z;
 
         let mut xmd: f64 = 0.0;
         let mut ymd: f64 = 0.0;
@@ -199,8 +204,7 @@ mod tests {
 
     #[test]
     fn test_c44l2_runs() {
-        let results = run();
+        let _results = run();
         // May have zero results depending on parameters
-        assert!(true);
     }
 }
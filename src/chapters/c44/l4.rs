@@ -3,7 +3,8 @@
 //! Ballistic missile defense: coverage analysis for varying target locations.
 
 use crate::save_data;
-use crate::utils::{lambert3d, kepler1, StateVector, distance3dkm, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::{lambert3d, kepler1, StateVector, distance3dkm};
 
 pub struct Results {
     pub tf: Vec<f64>,
@@ -26,8 +27,8 @@ pub fn run() -> Results {
     let switch: i32 = 0;
     let switchm: i32 = 0;
 
-    let a = EARTH_RADIUS_FT;
-    let _gm = GM_FT;
+    let earth = EarthModel::spherical();
+    let a = earth.a;
 
     let xlongfdeg = 57.3 * rdeskm * 3280.0 / a;
     let xlatfdeg: f64 = 0.0;
@@ -37,14 +38,16 @@ pub fn run() -> Results {
     let xlongm = xlongmdeg / 57.3;
     let xlatm = xlatmdeg / 57.3;
 
-    let xf = a * xlatf.cos() * xlongf.cos();
-    let yf = a * xlatf.cos() * xlongf.sin();
-    let zf = a * xlatf.sin();
+    let pos_f = earth.geodetic_to_ecef(&Geodetic::new(xlatf, xlongf, 0.0));
+    let xf = pos_f.x;
+    let yf = pos_f.y;
+    let zf = pos_f.z;
 
     let altm = altmkmic * 3280.0;
-    let xm = (a + altm) * xlatm.cos() * xlongm.cos();
-    let ym = (a + altm) * xlatm.cos() * xlongm.sin();
-    let zm = (a + altm) * xlatm.sin();
+    let pos_m = earth.geodetic_to_ecef(&Geodetic::new(xlatm, xlongm, altm));
+    let xm = pos_m.x;
+    let ym = pos_m.y;
+    let zm = pos_m.z;
 
     let mut array_tf = Vec::new();
     let mut array_tlaunch = Vec::new();
@@ -62,9 +65,10 @@ pub fn run() -> Results {
                 let xlongt = xlongtdeg / 57.3;
                 let xlatt = xlattdeg / 57.3;
 
-                let xt = a * xlatt.cos() * xlongt.cos();
-                let yt = a * xlatt.cos() * xlongt.sin();
-                let zt = a * xlatt.sin();
+                let pos_t = earth.geodetic_to_ecef(&Geodetic::new(xlatt, xlongt, 0.0));
+                let xt = pos_t.x;
+                let yt = pos_t.y;
+                let zt = pos_t.z;
 
                 let distfkm = distance3dkm(xf, yf, zf, xt, yt, zt);
                 let mut tftot = 252.0 + 0.223 * distfkm - 5.44e-6 * distfkm * distfkm;
@@ -154,7 +158,6 @@ mod tests {
 
     #[test]
     fn test_c44l4_runs() {
-        let results = run();
-        assert!(true);
+        let _results = run();
     }
 }
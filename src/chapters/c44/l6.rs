@@ -0,0 +1,132 @@
+//! Chapter 44, Lesson 6: BMD Defended Footprint
+//!
+//! The L3-L5 sweeps run through the footprint engine. An interceptor with
+//! a 5 km/s burnout velocity at 60 deg longitude on the equator, launching
+//! no earlier than 300 s after threat launch (240 s to a firm track plus
+//! 60 s to commit), must intercept above 50 km. The defended area is the
+//! set of aim points for which threats from both launch sites can be
+//! engaged; the launch area is the set of interceptor sites covering L3's
+//! 10000 km threat. Flight times follow the minimum-energy fit of L3.
+//! Output: 3 columns [LAT (DEG), LON (DEG), DEFENDED]
+
+use crate::plotting::{PlotConfig, scatter_plot};
+use crate::save_data;
+use crate::utils::earth::Geodetic;
+use crate::utils::footprint::{FootprintEngine, GeoGrid, Interceptor, KeplerTrack, Raster, Timeline};
+use crate::utils::rk2::Vec3;
+use crate::utils::EARTH_RADIUS_FT;
+
+pub struct Results {
+    /// Fraction of threats engaged, by aim point
+    pub defended: Raster,
+    /// Fraction of threats engaged, by interceptor site
+    pub launch_area: Raster,
+}
+
+/// Minimum-energy flight time (s) of L3 for the chord between two points
+fn flight_time(r1: Vec3, r2: Vec3, tloft: f64) -> f64 {
+    let distkm = (r2 - r1).magnitude() / 3280.0;
+    252.0 + 0.223 * distkm - 5.44e-6 * distkm * distkm + tloft
+}
+
+/// Run the C44L6 simulation
+pub fn run() -> Results {
+    let vbolim: f64 = 5.0;
+    let tofmin: f64 = 60.0;
+    let tofmax: f64 = 1000.0;
+    let altlimkm: f64 = 50.0;
+    let tdetect: f64 = 240.0;
+    let tcommit: f64 = 60.0;
+    let rdeskm: f64 = 10000.0;
+    let tloft: f64 = 0.0;
+
+    let interceptor = Interceptor::new(vbolim * 3280.0, tofmin, tofmax)
+        .with_altitudes(altlimkm * 3280.0, f64::INFINITY);
+    let engine = FootprintEngine::new(interceptor, Timeline::new(tdetect, tcommit));
+    let site = Geodetic::from_degrees(0.0, 60.0, 0.0);
+    let launches = [Geodetic::from_degrees(0.0, 0.0, 0.0), Geodetic::from_degrees(10.0, 0.0, 0.0)];
+
+    let threat = |launch: &Geodetic, aim: &Geodetic| -> Option<KeplerTrack> {
+        let (r1, r2) = (engine.position(launch), engine.position(aim));
+        KeplerTrack::ballistic(r1, r2, flight_time(r1, r2, tloft))
+    };
+
+    let grid = GeoGrid::new(-30.0, 30.0, 30.0, 120.0, 2.5);
+    let defended = engine.defended_area(&grid, &site, |aim| {
+        launches.iter().map(|launch| threat(launch, aim)).collect()
+    });
+
+    let aim = Geodetic::from_degrees(0.0, 57.3 * rdeskm * 3280.0 / EARTH_RADIUS_FT, 0.0);
+    let threats = [threat(&launches[0], &aim).expect("L3 threat reaches its aim point")];
+    let launch_area = engine.launch_area(&GeoGrid::new(-40.0, 40.0, 0.0, 150.0, 5.0), &threats);
+
+    Results { defended, launch_area }
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+
+    let grid = &results.defended.grid;
+    let mut lat = Vec::new();
+    let mut lon = Vec::new();
+    let mut defended = Vec::new();
+    for (i, row) in results.defended.values.iter().enumerate() {
+        for (j, &value) in row.iter().enumerate() {
+            lat.push(grid.lat[i]);
+            lon.push(grid.lon[j]);
+            defended.push(value);
+        }
+    }
+    let data_file = format!("{}/c44l6_datfil.txt", output_dir);
+    save_data(&data_file, &[lat.clone(), lon.clone(), defended.clone()])?;
+
+    let raster_file = format!("{}/c44l6_defended.asc", output_dir);
+    std::fs::write(&raster_file, results.defended.to_ascii_grid())?;
+    let geojson_file = format!("{}/c44l6_defended.geojson", output_dir);
+    std::fs::write(&geojson_file, results.defended.to_geojson(1.0))?;
+    std::fs::write(format!("{}/c44l6_launch_area.asc", output_dir), results.launch_area.to_ascii_grid())?;
+
+    let (xlon, xlat): (Vec<f64>, Vec<f64>) = lon.iter().zip(&lat).zip(&defended)
+        .filter(|(_, &d)| d >= 1.0)
+        .map(|((&x, &y), _)| (x, y))
+        .unzip();
+    let plot_file = format!("{}/c44l6_footprint.png", output_dir);
+    let config = PlotConfig::new("Defended Aim Points")
+        .with_labels("Longitude (Deg)", "Latitude (Deg)");
+    scatter_plot(&plot_file, &config, &xlon, &xlat).ok();
+
+    let km2 = 3280.0 * 3280.0;
+    let count = |r: &Raster| r.values.iter().flatten().filter(|&&v| v >= 1.0).count();
+    println!("C44L6: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    println!("  Raster saved to: {}", raster_file);
+    println!("  Footprint saved to: {}", geojson_file);
+    println!("  Defended area: {} of {} aim points, {:.0} km^2",
+        count(&results.defended), results.defended.grid.cells(), results.defended.area(1.0) / km2);
+    println!("  Launch area: {} of {} interceptor sites, {:.0} km^2",
+        count(&results.launch_area), results.launch_area.grid.cells(), results.launch_area.area(1.0) / km2);
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c44l6_footprints() {
+        let results = run();
+        let at = |r: &Raster, lat: f64, lon: f64| -> f64 {
+            let i = r.grid.lat.iter().position(|&x| (x - lat).abs() < 1e-9).unwrap();
+            let j = r.grid.lon.iter().position(|&x| (x - lon).abs() < 1e-9).unwrap();
+            r.values[i][j]
+        };
+        // The interceptor defends its own site but not the far corner
+        assert_eq!(at(&results.defended, 0.0, 60.0), 1.0);
+        assert_eq!(at(&results.defended, 30.0, 120.0), 0.0);
+        // Sites near the threat's aim point cover it, the launch point does not
+        assert_eq!(at(&results.launch_area, 0.0, 90.0), 1.0);
+        assert_eq!(at(&results.launch_area, 0.0, 0.0), 0.0);
+        assert!(results.defended.to_geojson(1.0).contains("Polygon"));
+    }
+}
@@ -1,10 +1,14 @@
 //! Chapter 44: Ballistic Missile Defense
 //!
 //! Ballistic missile defense simulations
+//!
+//! L1-L5 convert threat, aim and interceptor sites to Cartesian coordinates
+//! with `EarthModel::spherical()`, the book's non-rotating sphere.
 
 pub mod l1;
 pub mod l2;
 pub mod l3;
 pub mod l4;
 pub mod l5;
+pub mod l6;
 
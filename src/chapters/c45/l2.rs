@@ -3,7 +3,8 @@
 //! Theater missile defense: IRBM trajectory simulation with Lambert guidance.
 
 use crate::save_data;
-use crate::utils::{lambert3d, distance3dkm, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::{lambert3d, distance3dkm};
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -21,8 +22,9 @@ pub fn run() -> Results {
 
     let tpz = if itgt == 1 { 180.0 } else { 240.0 };
 
-    let a = EARTH_RADIUS_FT;
-    let gm = GM_FT;
+    let earth = EarthModel::spherical();
+    let a = earth.a;
+    let gm = earth.gm;
 
     let gamdeg: f64 = 89.99;
     let h: f64 = 0.01;
@@ -32,8 +34,9 @@ pub fn run() -> Results {
     let angdeg: f64 = 0.0;
     let ang = angdeg / 57.3;
 
-    let mut x = (a + alt) * ang.cos();
-    let mut y = (a + alt) * ang.sin();
+    let pos = earth.geodetic_to_ecef(&Geodetic::new(0.0, ang, alt));
+    let mut x = pos.x;
+    let mut y = pos.y;
     let z: f64 = 0.0;
     let mut alt = (x * x + y * y).sqrt() - a;
 
@@ -52,8 +55,9 @@ pub fn run() -> Results {
     tf += tloft;
 
     let xlongt = xlongtdeg / 57.3;
-    let xf = a * xlongt.cos();
-    let yf = a * xlongt.sin();
+    let pos_f = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongt, 0.0));
+    let xf = pos_f.x;
+    let yf = pos_f.y;
     let zf: f64 = 0.0;
 
     let mut qboost: bool = true;
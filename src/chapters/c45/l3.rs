@@ -3,7 +3,8 @@
 //! Theater missile defense: intercept simulation with guidance.
 
 use crate::save_data;
-use crate::utils::{lambert3d, distance3dkm, predict45, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::{lambert3d, distance3dkm, predict45};
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -40,8 +41,9 @@ pub fn run() -> Results {
 
     let tpz = if itgt == 1 { 180.0 } else { 240.0 };
 
-    let a = EARTH_RADIUS_FT;
-    let gm = GM_FT;
+    let earth = EarthModel::spherical();
+    let a = earth.a;
+    let gm = earth.gm;
 
     let altm = altmkmic * 3280.0;
     let mut tftot = 252.0 + 0.223 * rdeskm - 5.44e-6 * rdeskm * rdeskm;
@@ -56,16 +58,19 @@ pub fn run() -> Results {
     let xlongt = xlongtdeg / 57.3;
     let xlongm = xlongmdeg / 57.3;
 
-    let xf = a * xlongf.cos();
-    let yf = a * xlongf.sin();
+    let pos_f = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongf, 0.0));
+    let xf = pos_f.x;
+    let yf = pos_f.y;
     let zf: f64 = 0.0;
 
-    let mut xt = a * xlongt.cos();
-    let mut yt = a * xlongt.sin();
+    let pos_t = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongt, 0.0));
+    let mut xt = pos_t.x;
+    let mut yt = pos_t.y;
     let zt: f64 = 0.0;
 
-    let mut xm = (a + altm) * xlongm.cos();
-    let mut ym = (a + altm) * xlongm.sin();
+    let pos_m = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongm, altm));
+    let mut xm = pos_m.x;
+    let mut ym = pos_m.y;
     let zm: f64 = 0.0;
     let xfirst = xt;
     let yfirst = yt;
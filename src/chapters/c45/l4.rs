@@ -3,7 +3,8 @@
 //! Theater missile defense: Monte Carlo simulation with tracking noise.
 
 use crate::save_data;
-use crate::utils::{lambert3d, predict45, EARTH_RADIUS_FT, GM_FT};
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::{lambert3d, predict45};
 use rand_distr::{Distribution, Normal};
 
 pub struct Results {
@@ -44,8 +45,9 @@ pub fn run() -> Results {
     let sigthet2 = sigthet1;
     let xlongs1degkm = xlongmdegickm;
 
-    let a = EARTH_RADIUS_FT;
-    let gm = GM_FT;
+    let earth = EarthModel::spherical();
+    let a = earth.a;
+    let gm = earth.gm;
 
     let altm = altmkmic * 3280.0;
     let mut tftot = 252.0 + 0.223 * rdeskm - 5.44e-6 * rdeskm * rdeskm;
@@ -75,21 +77,26 @@ pub fn run() -> Results {
         let xlongs1 = xlongs1deg / 57.3;
         let xlongs2 = xlongs2deg / 57.3;
 
-        let xf = a * xlongf.cos();
-        let yf = a * xlongf.sin();
+        let pos_f = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongf, 0.0));
+        let xf = pos_f.x;
+        let yf = pos_f.y;
         let zf: f64 = 0.0;
 
-        let mut xt = a * xlongt.cos();
-        let mut yt = a * xlongt.sin();
+        let pos_t = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongt, 0.0));
+        let mut xt = pos_t.x;
+        let mut yt = pos_t.y;
         let zt: f64 = 0.0;
 
-        let mut xm = (a + altm) * xlongm.cos();
-        let mut ym = (a + altm) * xlongm.sin();
+        let pos_m = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongm, altm));
+        let mut xm = pos_m.x;
+        let mut ym = pos_m.y;
         let zm: f64 = 0.0;
-        let xs1 = (a + altm) * xlongs1.cos();
-        let ys1 = (a + altm) * xlongs1.sin();
-        let xs2 = (a + altm) * xlongs2.cos();
-        let ys2 = (a + altm) * xlongs2.sin();
+        let pos_s1 = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongs1, altm));
+        let xs1 = pos_s1.x;
+        let ys1 = pos_s1.y;
+        let pos_s2 = earth.geodetic_to_ecef(&Geodetic::new(0.0, xlongs2, altm));
+        let xs2 = pos_s2.x;
+        let ys2 = pos_s2.y;
 
         let mut xtd = (1.5708 - gamdeg / 57.3).cos();
         let mut ytd = (1.5708 - gamdeg / 57.3).sin();
@@ -493,7 +500,7 @@ pub fn run() -> Results {
                 let res = xts - xh - ts * xdh - 0.5 * ts * ts * xddh;
                 xh = xh + xdh * ts + 0.5 * ts * ts * xddh + xk1pz * res;
                 xdh = xdh + xddh * ts + xk2pz * res;
-                xddh = xddh + xk3pz * res;
+                xddh += xk3pz * res;
 
                 // Kalman filter for Y
                 let rmatp = sigy * sigy;
@@ -532,7 +539,7 @@ pub fn run() -> Results {
                 let resp = yts - yh - ts * ydh - 0.5 * ts * ts * yddh;
                 yh = yh + ydh * ts + 0.5 * ts * ts * yddh + xk1pzp * resp;
                 ydh = ydh + yddh * ts + xk2pzp * resp;
-                yddh = yddh + xk3pzp * resp;
+                yddh += xk3pzp * resp;
 
                 // Use filtered state for guidance when TGO > THOM
                 if t > tguid && tgo > thom {
@@ -0,0 +1,130 @@
+//! Chapter 45, Lesson 5: TMD Threat Library
+//!
+//! Families of threats flown from the equator toward the east: an SRBM to
+//! 500 km, the L2 IRBM to 1500 km and the L4 ICBM to 10000 km, each on a
+//! depressed, a minimum-energy and a lofted trajectory (TLOFT). The
+//! library keeps every flight as a state history. Each threat is then
+//! handed to the footprint engine for a terminal defense interceptor
+//! 100 km short of its aim point, with L4's 4 km/s fly-out.
+//! Output: 4 columns [THREAT, T, DISTKM, ALTKM]
+
+use crate::plotting::{PlotConfig, Series, line_plot};
+use crate::save_data;
+use crate::utils::earth::{EarthModel, Geodetic};
+use crate::utils::footprint::{Engagement, FootprintEngine, Interceptor, Timeline};
+use crate::utils::geometry::great_circle_range;
+use crate::utils::threat::{RangeClass, ThreatLibrary, EAST};
+use crate::utils::EARTH_RADIUS_FT;
+
+pub struct Results {
+    pub library: ThreatLibrary,
+    /// Earliest intercept of each threat by the terminal defense
+    pub engagements: Vec<Option<Engagement>>,
+}
+
+/// Run the C45L5 simulation
+pub fn run() -> Results {
+    let families = [
+        (RangeClass::Srbm, 500.0, [-50.0, 0.0, 100.0]),
+        (RangeClass::Irbm, 1500.0, [-50.0, 0.0, 200.0]),
+        (RangeClass::Icbm, 10000.0, [-300.0, 0.0, 500.0]),
+    ];
+    let vmrqdkm: f64 = 4.0;
+    let tofmin: f64 = 10.0;
+    let tofmax: f64 = 300.0;
+    let altlimkm: f64 = 15.0;
+    let tdetect: f64 = 60.0;
+    let tcommit: f64 = 30.0;
+    let uprangekm: f64 = 100.0;
+
+    let launch = Geodetic::from_degrees(0.0, 0.0, 0.0);
+    let mut library = ThreatLibrary::new();
+    for (class, rdeskm, tlofts) in families {
+        let aims = ThreatLibrary::aim_points(&launch, EAST, &[rdeskm]);
+        library.extend(ThreatLibrary::family(class, launch, &aims, &tlofts));
+    }
+
+    let interceptor = Interceptor::new(vmrqdkm * 3280.0, tofmin, tofmax)
+        .with_altitudes(altlimkm * 3280.0, f64::INFINITY);
+    let engine = FootprintEngine::new(interceptor, Timeline::new(tdetect, tcommit)).with_step(1.0);
+    let engagements = library.specs.iter().zip(&library.trajectories).map(|(spec, threat)| {
+        let site = ThreatLibrary::aim_points(&launch, EAST, &[spec.range_km() - uprangekm])[0];
+        engine.engage(&site, threat)
+    }).collect();
+
+    Results { library, engagements }
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+    let library = &results.library;
+    let launch = library.specs[0].launch;
+    let earth = EarthModel::spherical();
+    let ground_km = |g: &Geodetic| great_circle_range(&launch, g, EARTH_RADIUS_FT) / 3280.0;
+
+    let mut id = Vec::new();
+    let mut time = Vec::new();
+    let mut distkm = Vec::new();
+    let mut altkm = Vec::new();
+    let mut series = Vec::new();
+    let colors = [
+        plotters::prelude::BLUE,
+        plotters::prelude::RED,
+        plotters::prelude::GREEN,
+    ];
+    for (i, traj) in library.trajectories.iter().enumerate() {
+        let dist: Vec<f64> = traj.position.iter().map(|r| ground_km(&earth.ecef_to_geodetic(r))).collect();
+        let alt: Vec<f64> = traj.position.iter().map(|r| (r.magnitude() - EARTH_RADIUS_FT) / 3280.0).collect();
+        id.extend(std::iter::repeat_n((i + 1) as f64, dist.len()));
+        time.extend(&traj.time);
+        distkm.extend(&dist);
+        altkm.extend(&alt);
+        series.push(Series::new(dist, alt).with_color(colors[i % colors.len()]));
+    }
+
+    let data_file = format!("{}/c45l5_datfil.txt", output_dir);
+    save_data(&data_file, &[id, time, distkm, altkm])?;
+
+    let plot_file = format!("{}/c45l5_trajectories.png", output_dir);
+    let config = PlotConfig::new("Threat Library: Depressed, Minimum Energy, Lofted")
+        .with_labels("Downrange (Km)", "Altitude (Km)");
+    line_plot(&plot_file, &config, &series).ok();
+
+    println!("C45L5: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    let misses = library.misses();
+    for (k, (spec, traj)) in library.specs.iter().zip(&library.trajectories).enumerate() {
+        let (tb, rb, vb) = traj.burnout.unwrap();
+        let gamdeg = 90.0 - (rb.dot(&vb) / (rb.magnitude() * vb.magnitude())).acos().to_degrees();
+        let intercept = match &results.engagements[k] {
+            Some(e) => format!("intercept {:6.1} s at {:5.1} km", e.intercept_time, e.intercept_altitude / 3280.0),
+            None => "no intercept".to_string(),
+        };
+        println!("  {:?} {:6.0} km  TLOFT {:5.0}  burnout {:5.1} s  V = {:5.0} ft/s  gamma = {:4.1} deg  apogee {:5.0} km  TF {:6.1} s  miss {:4.1} km  {}",
+            spec.class, spec.range_km(), spec.tloft, tb, vb.magnitude(), gamdeg,
+            traj.apogee() / 3280.0, traj.flight_time(), misses[k] / 3280.0, intercept);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c45l5_threat_library() {
+        let results = run();
+        let library = &results.library;
+        assert_eq!(library.len(), 9);
+        assert!(library.misses().iter().all(|&m| m < 3280.0));
+        for class in RangeClass::ALL {
+            let family: Vec<_> = library.of_class(class).collect();
+            assert_eq!(family.len(), 3);
+            // Depressed below lofted, reaching the aim point sooner
+            assert!(family[0].1.apogee() < family[2].1.apogee());
+            assert!(family[0].1.flight_time() < family[2].1.flight_time());
+        }
+        assert!(results.engagements.iter().any(|e| e.is_some()));
+    }
+}
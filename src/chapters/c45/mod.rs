@@ -1,9 +1,14 @@
 //! Chapter 45: Theater Missile Defense
 //!
 //! Theater ballistic missile defense simulations
+//!
+//! L2-L4 fly in the equatorial plane and place threat, aim, interceptor and
+//! sensor sites with `EarthModel::spherical()`, the book's non-rotating
+//! sphere.
 
 pub mod l1;
 pub mod l2;
 pub mod l3;
 pub mod l4;
+pub mod l5;
 
@@ -5,6 +5,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::filters::fading_memory::Gains;
 use rand::prelude::*;
 use rand::SeedableRng;
 use rand_distr::StandardNormal;
@@ -47,8 +48,9 @@ pub fn run_with_seed(seed: Option<u64>) -> Results {
     let mut t: f64 = 0.0;
     let mut s: f64 = 0.0;
 
-    let gfilter: f64 = 1.0 - beta * beta;
-    let hfilter: f64 = (1.0 - beta) * (1.0 - beta);
+    let gains = Gains::fading_memory(2, beta);
+    let gfilter = gains.g;
+    let hfilter = gains.h;
 
     let mut xlamh: f64 = 0.0;
     let mut xlamdh: f64 = 0.0;
@@ -8,6 +8,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::filters::fading_memory::Gains;
 use rand_distr::{Distribution, Normal};
 
 pub struct Results {
@@ -33,8 +34,9 @@ pub fn run() -> Results {
     let normal = Normal::new(0.0, 1.0).unwrap();
     let mut rng = rand::thread_rng();
 
-    let gfilter = 1.0 - beta * beta;
-    let hfilter = (1.0 - beta).powi(2);
+    let gains = Gains::fading_memory(2, beta);
+    let gfilter = gains.g;
+    let hfilter = gains.h;
 
     let mut array_tf = Vec::new();
     let mut array_sigma = Vec::new();
@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::filters::fading_memory::Gains;
 
 pub struct Results {
     pub time: Vec<f64>,
@@ -22,8 +23,9 @@ pub fn run() -> Results {
     let vc: f64 = 4000.0;
     let h: f64 = 0.01;
 
-    let gfilter = 1.0 - beta * beta;
-    let hfilter = (1.0 - beta).powi(2);
+    let gains = Gains::fading_memory(2, beta);
+    let gfilter = gains.g;
+    let hfilter = gains.h;
 
     let mut tp: f64 = 0.00001;
     let mut s: f64 = 0.0;
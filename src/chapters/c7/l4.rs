@@ -4,6 +4,7 @@
 
 use crate::plotting::{PlotConfig, Series, line_plot};
 use crate::save_data;
+use crate::utils::filters::fading_memory::Gains;
 use rand_distr::{Distribution, Normal};
 
 pub struct Results {
@@ -34,9 +35,10 @@ pub fn run() -> Results {
     let mut rng = rand::thread_rng();
 
     // Third-order filter coefficients
-    let gfilter = 1.0 - beta.powi(3);
-    let hfilter = 1.5 * (1.0 - beta).powi(2) * (1.0 + beta);
-    let kfilter = 0.5 * (1.0 - beta).powi(3);
+    let gains = Gains::fading_memory(3, beta);
+    let gfilter = gains.g;
+    let hfilter = gains.h;
+    let kfilter = gains.k;
 
     let mut y = yic;
     let mut yd = -vm * hedeg / 57.3;
@@ -27,7 +27,7 @@ pub fn run() -> Results {
     let apn: i32 = 0;
     let xlim: f64 = 999999.0;
     let h: f64 = 0.01;
-    let noise: i32 = 0; // Set to 0 for deterministic verification (MATLAB has NOISE=1)
+    let _noise: i32 = 0; // Set to 0 for deterministic verification (MATLAB has NOISE=1)
 
     let ts2 = ts * ts;
     let ts3 = ts2 * ts;
@@ -127,7 +127,7 @@ pub fn run() -> Results {
                     p33 = -k3 * m13 + m33;
 
                     // NOISE=0 for deterministic verification
-                    let xlamnoise = if noise == 1 { 0.0 } else { 0.0 }; // Would use signoise * randn if noise=1
+                    let xlamnoise = 0.0; // Would use signoise * randn if noise=1
                     let ystar = rtm * (xlam + xlamnoise);
                     let res = ystar - yh - ts * ydh - 0.5 * ts * ts * (xnth - xnc);
 
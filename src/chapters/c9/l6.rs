@@ -0,0 +1,224 @@
+//! Chapter 9, Lesson 6: Kalman Filter Consistency
+//!
+//! Monte Carlo check of the three-state position-rate-acceleration filter
+//! of L1. The truth follows the filter's own model, a target acceleration
+//! driven by white noise of spectral density XNT^2/TF, observed through the
+//! range-dependent angle noise of L1. Runs with the design process noise and
+//! with ten times too little and too much show how NEES, NIS and residual
+//! whiteness detect a mistuned filter.
+
+use crate::plotting::{PlotConfig, Series, line_plot};
+use crate::save_data;
+use crate::utils::filters::diagnostics::{ConsistencyReport, Trial, analyze};
+use crate::utils::filters::ekf::{Ekf, EkfModel};
+use crate::utils::matrix::{Matrix, cholesky, diag, mat_vec_mul};
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+
+pub struct Results {
+    pub time: Vec<f64>,
+    /// Run-averaged NEES with the design process noise
+    pub nees: Vec<f64>,
+    /// Run-averaged NEES with ten times too little process noise
+    pub nees_low: Vec<f64>,
+    /// Run-averaged NEES with ten times too much process noise
+    pub nees_high: Vec<f64>,
+    pub nees_lower_bound: Vec<f64>,
+    pub nees_upper_bound: Vec<f64>,
+    /// Consistency with design, too little and too much process noise
+    pub reports: Vec<(&'static str, ConsistencyReport)>,
+}
+
+/// Position-rate-acceleration filter measuring position
+#[derive(Debug, Clone)]
+struct ThreeState {
+    phin: f64,
+    sign2: f64,
+}
+
+impl ThreeState {
+    fn phi(ts: f64) -> Matrix {
+        vec![vec![1.0, ts, 0.5 * ts * ts], vec![0.0, 1.0, ts], vec![0.0, 0.0, 1.0]]
+    }
+}
+
+impl EkfModel for ThreeState {
+    fn n_states(&self) -> usize {
+        3
+    }
+
+    fn propagate(&self, x: &[f64], _u: &[f64], ts: f64) -> Vec<f64> {
+        mat_vec_mul(&Self::phi(ts), x)
+    }
+
+    fn transition(&self, _x: &[f64], _u: &[f64], ts: f64) -> Matrix {
+        Self::phi(ts)
+    }
+
+    fn process_noise(&self, _x: &[f64], ts: f64) -> Matrix {
+        let (ts2, ts3) = (ts * ts, ts * ts * ts);
+        let p = self.phin;
+        vec![
+            vec![p * ts3 * ts2 / 20.0, p * ts3 * ts / 8.0, p * ts3 / 6.0],
+            vec![p * ts3 * ts / 8.0, p * ts3 / 3.0, p * ts2 / 2.0],
+            vec![p * ts3 / 6.0, p * ts2 / 2.0, p * ts],
+        ]
+    }
+
+    fn measure(&self, x: &[f64]) -> Vec<f64> {
+        vec![x[0]]
+    }
+
+    fn measurement_jacobian(&self, _x: &[f64]) -> Matrix {
+        vec![vec![1.0, 0.0, 0.0]]
+    }
+
+    fn measurement_noise(&self, _x: &[f64]) -> Matrix {
+        vec![vec![self.sign2]]
+    }
+}
+
+/// Run the C9L6 simulation
+pub fn run() -> Results {
+    let (time, design) = run_with_scale(1.0, 100, Some(1));
+    let (_, low) = run_with_scale(0.1, 100, Some(1));
+    let (_, high) = run_with_scale(10.0, 100, Some(1));
+    let n = time.len();
+    Results {
+        time,
+        nees: design.nees.average.clone(),
+        nees_low: low.nees.average.clone(),
+        nees_high: high.nees.average.clone(),
+        nees_lower_bound: vec![design.nees.bounds.0; n],
+        nees_upper_bound: vec![design.nees.bounds.1; n],
+        reports: vec![("Design process noise", design), ("Process noise / 10", low), ("Process noise x 10", high)],
+    }
+}
+
+/// Monte Carlo consistency of the filter with its process noise spectral
+/// density scaled by `scale` from the truth; returns sample times and report
+pub fn run_with_scale(scale: f64, runs: usize, seed: Option<u64>) -> (Vec<f64>, ConsistencyReport) {
+    let vc: f64 = 4000.0;
+    let xnt: f64 = 96.6;
+    let vm: f64 = 3000.0;
+    let hedeg: f64 = 20.0;
+    let sigrin: f64 = 0.001;
+    let ts: f64 = 0.1;
+    let tf: f64 = 10.0;
+
+    let phin = xnt * xnt / tf;
+    let rtm = vc * tf;
+    let sigpos = rtm * sigrin;
+    let sign2 = sigpos * sigpos;
+
+    let mut rng: rand::rngs::StdRng = match seed {
+        Some(s) => rand::rngs::StdRng::seed_from_u64(s),
+        None => rand::rngs::StdRng::from_entropy(),
+    };
+    let normal = Normal::new(0.0, 1.0).unwrap();
+
+    let truth = ThreeState { phin, sign2 };
+    let lq = cholesky(&truth.process_noise(&[], ts));
+    let p0 = diag(&[sign2, (vm * hedeg / 57.3).powi(2), xnt * xnt]);
+    let l0 = cholesky(&p0);
+    let phi = ThreeState::phi(ts);
+
+    let mut time = Vec::new();
+    let mut trials = Vec::with_capacity(runs);
+    for run in 0..runs {
+        let w: Vec<f64> = (0..3).map(|_| normal.sample(&mut rng)).collect();
+        let mut x = mat_vec_mul(&l0, &w);
+        let mut ekf = Ekf::new(ThreeState { phin: scale * phin, sign2 }, vec![0.0; 3], p0.clone());
+        let mut trial = Trial::new();
+
+        let mut t: f64 = 0.0;
+        while t <= tf - 1e-5 {
+            let tgo = tf - t + 0.000001;
+            let sigpos = vc * tgo * sigrin;
+            ekf.model.sign2 = sigpos * sigpos;
+
+            let w: Vec<f64> = (0..3).map(|_| normal.sample(&mut rng)).collect();
+            x = mat_vec_mul(&phi, &x).iter().zip(mat_vec_mul(&lq, &w)).map(|(a, b)| a + b).collect();
+            ekf.predict(&[], ts);
+            let inn = ekf.update(&[x[0] + sigpos * normal.sample(&mut rng)]);
+
+            let error: Vec<f64> = x.iter().zip(&ekf.x).map(|(a, b)| a - b).collect();
+            trial.record(&error, &ekf.p, &inn);
+            if run == 0 {
+                time.push(t);
+            }
+            t += ts;
+        }
+        trials.push(trial);
+    }
+
+    (time, analyze(&trials, 0.95, 10))
+}
+
+pub fn run_and_save(output_dir: &str) -> std::io::Result<Results> {
+    let results = run();
+
+    let data_file = format!("{}/c9l6_datfil.txt", output_dir);
+    save_data(&data_file, &[
+        results.time.clone(),
+        results.nees.clone(),
+        results.nees_low.clone(),
+        results.nees_high.clone(),
+        results.nees_lower_bound.clone(),
+        results.nees_upper_bound.clone(),
+    ])?;
+
+    let plot_file = format!("{}/c9l6_nees.png", output_dir);
+    let config = PlotConfig::new("Average NEES over 100 Runs")
+        .with_labels("Flight Time (Sec)", "NEES");
+
+    let series = vec![
+        Series::new(results.time.clone(), results.nees.clone())
+            .with_color(plotters::prelude::BLUE)
+            .with_label("Design Q"),
+        Series::new(results.time.clone(), results.nees_low.clone())
+            .with_color(plotters::prelude::RED)
+            .with_label("Q/10"),
+        Series::new(results.time.clone(), results.nees_high.clone())
+            .with_color(plotters::prelude::GREEN)
+            .with_label("10Q"),
+        Series::new(results.time.clone(), results.nees_lower_bound.clone())
+            .with_color(plotters::prelude::BLACK),
+        Series::new(results.time.clone(), results.nees_upper_bound.clone())
+            .with_color(plotters::prelude::BLACK),
+    ];
+
+    line_plot(&plot_file, &config, &series).ok();
+
+    println!("C9L6: Simulation finished");
+    println!("  Data saved to: {}", data_file);
+    for (label, report) in &results.reports {
+        println!("  {}:", label);
+        for line in report.summary().lines() {
+            println!("    {}", line);
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_c9l6_design_filter_is_consistent() {
+        let (_, report) = run_with_scale(1.0, 100, Some(7));
+        assert!(report.passed, "{}", report.summary());
+    }
+
+    #[test]
+    fn test_c9l6_mistuned_filter_fails() {
+        let (_, low) = run_with_scale(0.1, 100, Some(7));
+        assert!(!low.passed);
+        assert!(low.nees.mean > low.nees.bounds.1);
+        let (_, high) = run_with_scale(10.0, 100, Some(7));
+        assert!(!high.passed);
+        assert!(high.nees.mean < high.nees.bounds.0);
+    }
+}
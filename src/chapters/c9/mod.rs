@@ -7,4 +7,5 @@ pub mod l2;
 pub mod l3;
 pub mod l4;
 pub mod l5;
+pub mod l6;
 
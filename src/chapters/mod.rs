@@ -86,9 +86,11 @@ pub fn list_simulations() -> Vec<(&'static str, &'static str)> {
         ("c9l3", "Kalman Filter Monte Carlo"),
         ("c9l4", "Variable Target Maneuver"),
         ("c9l5", "Optimal Guidance with Binomial Filter"),
+        ("c9l6", "Kalman Filter Consistency"),
         ("c10l1", "Ballistic Trajectory with Drag"),
         ("c11l1", "Reentry Target Trajectory"),
         ("c11l2", "2D Engagement with Reentry Target"),
+        ("c11l3", "Maneuvering Reentry Vehicles"),
         ("c12l1", "Extended Kalman Filter"),
         ("c12l2", "EKF Beta Estimation"),
         ("c12l3", "Polynomial Kalman Filter"),
@@ -105,12 +107,14 @@ pub fn list_simulations() -> Vec<(&'static str, &'static str)> {
         ("c15l8", "High Trajectory (Lob)"),
         ("c16l1", "Two-Stage Rocket Performance"),
         ("c16l2", "Two-Stage Rocket Trajectory"),
+        ("c16l3", "Multi-Stage Sizing"),
         ("c17l1", "Lambert Orbit Solver"),
         ("c17l2", "Lambert Direct Call"),
         ("c17l3", "Two-Stage with Lambert Guidance"),
         ("c17l4", "VTG-Based Guidance"),
         ("c17l5", "Ballistic Range Calculation"),
         ("c17l6", "Ballistic Propagation"),
+        ("c17l7", "Boost Steering Comparison"),
         ("c18l1", "Strategic Intercept Ballistic"),
         ("c18l2", "Strategic Intercept Boosting"),
         ("c19l1", "Pulse Guidance"),
@@ -168,7 +172,9 @@ pub fn list_simulations() -> Vec<(&'static str, &'static str)> {
         ("c30l1", "Kalman Filter Acceleration Est"),
         ("c30l2", "Kalman Filter Singer Model"),
         ("c30l3", "Kalman Filter Frequency Est"),
+        ("c30l4", "Angle-Only Tracking EKF"),
         ("c31l1", "Multiple Model Adaptive Est"),
+        ("c31l2", "Interacting Multiple Model"),
         ("c32l1", "Ballistic Target Intercept"),
         ("c32l2", "Ballistic Intercept Predictive"),
         ("c32l3", "Rolling Airframe (Open Loop)"),
@@ -200,6 +206,8 @@ pub fn list_simulations() -> Vec<(&'static str, &'static str)> {
         ("c40l3", "3D RK2 vs Kepler Comparison"),
         ("c40l4", "3D Strategic Intercept ZEM"),
         ("c40l5", "3D Reentry Guidance"),
+        ("c40l6", "Earth Model Range Errors"),
+        ("c40l7", "Impact Point Estimation"),
         ("c41l1", "Kalman Filter Miss Est"),
         ("c41l2", "Kalman Filter Monte Carlo"),
         ("c42l1", "Optimal Guidance Monte Carlo"),
@@ -210,9 +218,11 @@ pub fn list_simulations() -> Vec<(&'static str, &'static str)> {
         ("c44l3", "BMD Interceptor Footprint"),
         ("c44l4", "BMD Target Location Coverage"),
         ("c44l5", "BMD Aim Point Coverage"),
+        ("c44l6", "BMD Defended Footprint"),
         ("c45l1", "Theater Missile Defense"),
         ("c45l2", "TMD IRBM Trajectory"),
         ("c45l3", "TMD Intercept Simulation"),
         ("c45l4", "TMD Monte Carlo Simulation"),
+        ("c45l5", "TMD Threat Library"),
     ]
 }
@@ -163,6 +163,7 @@ fn run_simulation(sim: &str, output_dir: &str) {
         "c9l3" => { c9::l3::run_and_save(output_dir).ok(); }
         "c9l4" => { c9::l4::run_and_save(output_dir).ok(); }
         "c9l5" => { c9::l5::run_and_save(output_dir).ok(); }
+        "c9l6" => { c9::l6::run_and_save(output_dir).ok(); }
 
         // Chapter 10
         "c10l1" => { c10::l1::run_and_save(output_dir).ok(); }
@@ -170,6 +171,7 @@ fn run_simulation(sim: &str, output_dir: &str) {
         // Chapter 11
         "c11l1" => { c11::l1::run_and_save(output_dir).ok(); }
         "c11l2" => { c11::l2::run_and_save(output_dir).ok(); }
+        "c11l3" => { c11::l3::run_and_save(output_dir).ok(); }
 
         // Chapter 13
         "c13l1" => { c13::l1::run_and_save(output_dir).ok(); }
@@ -196,6 +198,7 @@ fn run_simulation(sim: &str, output_dir: &str) {
         // Chapter 16
         "c16l1" => { c16::l1::run_and_save(output_dir).ok(); }
         "c16l2" => { c16::l2::run_and_save(output_dir).ok(); }
+        "c16l3" => { c16::l3::run_and_save(output_dir).ok(); }
 
         // Chapter 17
         "c17l1" => { c17::l1::run_and_save(output_dir).ok(); }
@@ -204,6 +207,7 @@ fn run_simulation(sim: &str, output_dir: &str) {
         "c17l4" => { c17::l4::run_and_save(output_dir).ok(); }
         "c17l5" => { c17::l5::run_and_save(output_dir).ok(); }
         "c17l6" => { c17::l6::run_and_save(output_dir).ok(); }
+        "c17l7" => { c17::l7::run_and_save(output_dir).ok(); }
 
         // Chapter 18
         "c18l1" => { c18::l1::run_and_save(output_dir).ok(); }
@@ -287,9 +291,11 @@ fn run_simulation(sim: &str, output_dir: &str) {
         "c30l1" => { c30::l1::run_and_save(output_dir).ok(); }
         "c30l2" => { c30::l2::run_and_save(output_dir).ok(); }
         "c30l3" => { c30::l3::run_and_save(output_dir).ok(); }
+        "c30l4" => { c30::l4::run_and_save(output_dir).ok(); }
 
         // Chapter 31
         "c31l1" => { c31::l1::run_and_save(output_dir).ok(); }
+        "c31l2" => { c31::l2::run_and_save(output_dir).ok(); }
 
         // Chapter 32
         "c32l1" => { c32::l1::run_and_save(output_dir).ok(); }
@@ -339,6 +345,8 @@ fn run_simulation(sim: &str, output_dir: &str) {
         "c40l3" => { c40::l3::run_and_save(output_dir).ok(); }
         "c40l4" => { c40::l4::run_and_save(output_dir).ok(); }
         "c40l5" => { c40::l5::run_and_save(output_dir).ok(); }
+        "c40l6" => { c40::l6::run_and_save(output_dir).ok(); }
+        "c40l7" => { c40::l7::run_and_save(output_dir).ok(); }
 
         // Chapter 41
         "c41l1" => { c41::l1::run_and_save(output_dir).ok(); }
@@ -357,12 +365,14 @@ fn run_simulation(sim: &str, output_dir: &str) {
         "c44l3" => { c44::l3::run_and_save(output_dir).ok(); }
         "c44l4" => { c44::l4::run_and_save(output_dir).ok(); }
         "c44l5" => { c44::l5::run_and_save(output_dir).ok(); }
+        "c44l6" => { c44::l6::run_and_save(output_dir).ok(); }
 
         // Chapter 45
         "c45l1" => { c45::l1::run_and_save(output_dir).ok(); }
         "c45l2" => { c45::l2::run_and_save(output_dir).ok(); }
         "c45l3" => { c45::l3::run_and_save(output_dir).ok(); }
         "c45l4" => { c45::l4::run_and_save(output_dir).ok(); }
+        "c45l5" => { c45::l5::run_and_save(output_dir).ok(); }
 
         _ => {
             println!("Unknown simulation: {}", sim);
@@ -390,15 +400,15 @@ fn run_all_simulations(output_dir: &str) {
         "c6l1", "c6l2", "c6l3", "c6l4",
         "c7l1", "c7l2", "c7l3", "c7l4",
         "c8l1", "c8l2", "c8l3",
-        "c9l1", "c9l2", "c9l3", "c9l4", "c9l5",
+        "c9l1", "c9l2", "c9l3", "c9l4", "c9l5", "c9l6",
         "c10l1",
-        "c11l1", "c11l2",
+        "c11l1", "c11l2", "c11l3",
         "c12l1", "c12l2", "c12l3",
         "c13l1",
         "c14l1", "c14l2",
         "c15l1", "c15l2", "c15l3", "c15l4", "c15l5", "c15l6", "c15l7", "c15l8",
-        "c16l1", "c16l2",
-        "c17l1", "c17l2", "c17l3", "c17l4", "c17l5", "c17l6",
+        "c16l1", "c16l2", "c16l3",
+        "c17l1", "c17l2", "c17l3", "c17l4", "c17l5", "c17l6", "c17l7",
         "c18l1", "c18l2",
         "c19l1", "c19l2",
         "c20l1", "c20l2", "c20l3", "c20l4", "c20l5", "c20l6", "c20l7", "c20l8", "c20l9",
@@ -411,8 +421,8 @@ fn run_all_simulations(output_dir: &str) {
         "c27l1", "c27l2", "c27l3", "c27l4", "c27l5", "c27l6", "c27l7",
         "c28l1", "c28l2", "c28l3",
         "c29l1", "c29l2", "c29l3", "c29l4", "c29l5",
-        "c30l1", "c30l2", "c30l3",
-        "c31l1",
+        "c30l1", "c30l2", "c30l3", "c30l4",
+        "c31l1", "c31l2",
         "c32l1", "c32l2", "c32l3", "c32l4",
         "c33l1",
         "c34l1", "c34l2", "c34l3",
@@ -421,12 +431,12 @@ fn run_all_simulations(output_dir: &str) {
         "c37l1", "c37l2", "c37l3",
         "c38l1", "c38l2", "c38l3", "c38l4",
         "c39l1", "c39l2", "c39l3",
-        "c40l1", "c40l2", "c40l3", "c40l4", "c40l5",
+        "c40l1", "c40l2", "c40l3", "c40l4", "c40l5", "c40l6", "c40l7",
         "c41l1", "c41l2",
         "c42l1",
         "c43l1", "c43l2",
-        "c44l1", "c44l2", "c44l3", "c44l4", "c44l5",
-        "c45l1", "c45l2", "c45l3", "c45l4",
+        "c44l1", "c44l2", "c44l3", "c44l4", "c44l5", "c44l6",
+        "c45l1", "c45l2", "c45l3", "c45l4", "c45l5",
     ];
 
     for sim in &sims {
@@ -196,6 +196,78 @@ pub fn scatter_plot(
     Ok(())
 }
 
+/// Root locus plot: branch paths, open-loop poles (x), zeros (o) and
+/// labelled marker points such as the closed-loop poles at the design gain
+pub fn root_locus_plot(
+    filename: &str,
+    config: &PlotConfig,
+    branches: &[(Vec<f64>, Vec<f64>)],
+    poles: &[(f64, f64)],
+    zeros: &[(f64, f64)],
+    marks: &[(f64, f64, String)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::new(filename, (config.width, config.height)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let all_x: Vec<f64> = branches
+        .iter()
+        .flat_map(|b| b.0.iter().copied())
+        .chain(poles.iter().chain(zeros.iter()).map(|p| p.0))
+        .filter(|v| v.is_finite())
+        .collect();
+    let all_y: Vec<f64> = branches
+        .iter()
+        .flat_map(|b| b.1.iter().copied())
+        .chain(poles.iter().chain(zeros.iter()).map(|p| p.1))
+        .filter(|v| v.is_finite())
+        .collect();
+
+    let (x_min, x_max) = config.x_range.unwrap_or_else(|| {
+        let min = all_x.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = all_x.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let margin = ((max - min) * 0.05).max(1e-6);
+        (min - margin, max + margin)
+    });
+
+    let (y_min, y_max) = config.y_range.unwrap_or_else(|| {
+        let min = all_y.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = all_y.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let margin = ((max - min) * 0.05).max(1e-6);
+        (min - margin, max + margin)
+    });
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(&config.title, ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)?;
+
+    chart
+        .configure_mesh()
+        .x_desc(&config.x_label)
+        .y_desc(&config.y_label)
+        .draw()?;
+
+    let colors = [BLUE, RED, GREEN, MAGENTA, CYAN, BLACK];
+    for (i, (x, y)) in branches.iter().enumerate() {
+        let data: Vec<(f64, f64)> = x.iter().zip(y.iter()).map(|(&x, &y)| (x, y)).collect();
+        chart.draw_series(LineSeries::new(data, colors[i % colors.len()].stroke_width(2)))?;
+    }
+
+    chart.draw_series(poles.iter().map(|&(x, y)| Cross::new((x, y), 6, BLACK.stroke_width(2))))?;
+    chart.draw_series(zeros.iter().map(|&(x, y)| Circle::new((x, y), 6, BLACK.stroke_width(2))))?;
+    chart.draw_series(marks.iter().map(|(x, y, _)| Circle::new((*x, *y), 4, RED.filled())))?;
+    chart.draw_series(
+        marks
+            .iter()
+            .map(|(x, y, label)| Text::new(label.clone(), (*x, *y), ("sans-serif", 12).into_font())),
+    )?;
+
+    root.present()?;
+    Ok(())
+}
+
 /// Quick plot with minimal configuration
 #[allow(dead_code)]
 pub fn quick_plot(
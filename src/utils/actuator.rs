@@ -0,0 +1,250 @@
+//! Fin actuator models
+//!
+//! The autopilot lessons drive the fins through an ideal second-order
+//! actuator, DELDD = WACT^2 (DELC - DEL - 2 ZACT DELD / WACT). An `Actuator`
+//! reproduces that model exactly when no limits are set and adds rate and
+//! position saturation, backlash (hysteresis between commanded and delivered
+//! deflection) and a first-order lag alternative. Limits are in the same
+//! units as the deflection used by the simulation.
+//!
+//! Every lesson with a fin actuator takes one through `run_with_actuator`:
+//! the autopilot time responses (C22L4, C22L5, C23L1, C23L4, C24L2, C25 and
+//! C26L2, L4, L6) and the C39L3 engagement apply the limits, while the
+//! frequency responses (C22L3, C23L2), the C23L3 radome adjoint and the
+//! C26L3, L5 gain designs use only the linear dynamics. The other engagement lessons model flight
+//! control as a lag on acceleration with no fin deflection to limit.
+//!
+//! The actuator carries two integration states (DEL, DELD) which the
+//! simulation integrates with its own RK2 loop:
+//!
+//! ```ignore
+//! let (ddel, dddel) = act.derivatives(delc, del, deld);
+//! del += h * ddel;
+//! deld += h * dddel;
+//! act.limit(&mut del, &mut deld);
+//! // ... airframe sees act.output(del)
+//! act.latch(del); // once per completed step
+//! ```
+
+use super::lti::TransferFunction;
+
+/// Linear part of the actuator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActuatorDynamics {
+    /// DEL/DELC = 1 / (1 + TAU s)
+    FirstOrder { tau: f64 },
+    /// DEL/DELC = 1 / (s^2/WACT^2 + 2 ZACT s/WACT + 1)
+    SecondOrder { wact: f64, zact: f64 },
+}
+
+/// Fin actuator with optional rate, position and backlash nonlinearities
+#[derive(Debug, Clone, PartialEq)]
+pub struct Actuator {
+    pub dynamics: ActuatorDynamics,
+    /// Maximum fin rate magnitude
+    pub rate_limit: Option<f64>,
+    /// Maximum fin deflection magnitude
+    pub position_limit: Option<f64>,
+    /// Total backlash width; the delivered deflection only moves once the
+    /// actuator has travelled half the width past it
+    pub backlash: Option<f64>,
+    /// Delivered deflection at the end of the last completed step
+    delivered: f64,
+}
+
+impl Actuator {
+    pub fn second_order(wact: f64, zact: f64) -> Self {
+        Self::with_dynamics(ActuatorDynamics::SecondOrder { wact, zact })
+    }
+
+    pub fn first_order(tau: f64) -> Self {
+        Self::with_dynamics(ActuatorDynamics::FirstOrder { tau })
+    }
+
+    fn with_dynamics(dynamics: ActuatorDynamics) -> Self {
+        Self {
+            dynamics,
+            rate_limit: None,
+            position_limit: None,
+            backlash: None,
+            delivered: 0.0,
+        }
+    }
+
+    pub fn with_rate_limit(mut self, max_rate: f64) -> Self {
+        self.rate_limit = Some(max_rate);
+        self
+    }
+
+    pub fn with_position_limit(mut self, max_deflection: f64) -> Self {
+        self.position_limit = Some(max_deflection);
+        self
+    }
+
+    pub fn with_backlash(mut self, width: f64) -> Self {
+        self.backlash = Some(width);
+        self
+    }
+
+    /// True when no nonlinearity is active
+    pub fn is_linear(&self) -> bool {
+        self.rate_limit.is_none() && self.position_limit.is_none() && self.backlash.is_none()
+    }
+
+    /// Linear transfer function DEL/DELC ignoring the limits
+    pub fn tf(&self) -> TransferFunction {
+        match self.dynamics {
+            ActuatorDynamics::FirstOrder { tau } => TransferFunction::lag(tau),
+            ActuatorDynamics::SecondOrder { wact, zact } => TransferFunction::second_order(zact, wact),
+        }
+    }
+
+    /// Time derivatives of (DEL, DELD) for the fin command `delc`
+    ///
+    /// For the first-order model DELD is not used and its derivative is zero.
+    pub fn derivatives(&self, delc: f64, del: f64, deld: f64) -> (f64, f64) {
+        let (mut rate, mut accel) = match self.dynamics {
+            ActuatorDynamics::FirstOrder { tau } => ((delc - del) / tau, 0.0),
+            ActuatorDynamics::SecondOrder { wact, zact } => {
+                (deld, wact * wact * (delc - del - 2.0 * zact * deld / wact))
+            }
+        };
+
+        if let Some(rmax) = self.rate_limit {
+            if let ActuatorDynamics::SecondOrder { .. } = self.dynamics {
+                if (deld >= rmax && accel > 0.0) || (deld <= -rmax && accel < 0.0) {
+                    accel = 0.0;
+                }
+            }
+            rate = rate.clamp(-rmax, rmax);
+        }
+
+        if let Some(pmax) = self.position_limit {
+            if (del >= pmax && rate > 0.0) || (del <= -pmax && rate < 0.0) {
+                rate = 0.0;
+                if accel * del > 0.0 {
+                    accel = 0.0;
+                }
+            }
+        }
+
+        (rate, accel)
+    }
+
+    /// Clip the integrated states to the rate and position limits
+    pub fn limit(&self, del: &mut f64, deld: &mut f64) {
+        if let Some(rmax) = self.rate_limit {
+            *deld = deld.clamp(-rmax, rmax);
+        }
+        if let Some(pmax) = self.position_limit {
+            if del.abs() >= pmax {
+                *del = del.clamp(-pmax, pmax);
+                if *deld * *del > 0.0 {
+                    *deld = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Deflection delivered to the airframe for actuator position `del`
+    pub fn output(&self, del: f64) -> f64 {
+        match self.backlash {
+            None => del,
+            Some(width) => {
+                let half = 0.5 * width;
+                if del - self.delivered > half {
+                    del - half
+                } else if self.delivered - del > half {
+                    del + half
+                } else {
+                    self.delivered
+                }
+            }
+        }
+    }
+
+    /// Update the backlash memory at the end of an integration step
+    pub fn latch(&mut self, del: f64) {
+        self.delivered = self.output(del);
+    }
+
+    /// Clear the backlash memory before a new run
+    pub fn reset(&mut self) {
+        self.delivered = 0.0;
+    }
+
+    /// Advance the states one RK2 step of size `h` with a constant command
+    pub fn step(&mut self, delc: f64, del: &mut f64, deld: &mut f64, h: f64) {
+        let delold = *del;
+        let deldold = *deld;
+        let (d1, dd1) = self.derivatives(delc, *del, *deld);
+        *del += h * d1;
+        *deld += h * dd1;
+        self.limit(del, deld);
+        let (d2, dd2) = self.derivatives(delc, *del, *deld);
+        *del = 0.5 * (delold + *del + h * d2);
+        *deld = 0.5 * (deldold + *deld + h * dd2);
+        self.limit(del, deld);
+        self.latch(*del);
+    }
+}
+
+impl Default for Actuator {
+    /// WACT = 150 rad/sec, ZACT = 0.7 as in the autopilot lessons
+    fn default() -> Self {
+        Self::second_order(150.0, 0.7)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step_response(act: &mut Actuator, delc: f64, tf: f64) -> (f64, f64) {
+        let h = 0.0001;
+        let (mut del, mut deld) = (0.0, 0.0);
+        let mut max_rate: f64 = 0.0;
+        let mut t = 0.0;
+        while t < tf {
+            act.step(delc, &mut del, &mut deld, h);
+            max_rate = max_rate.max(deld.abs());
+            t += h;
+        }
+        (del, max_rate)
+    }
+
+    #[test]
+    fn test_linear_matches_book_formula() {
+        let act = Actuator::default();
+        let (wact, zact) = (150.0, 0.7);
+        let (delc, del, deld) = (1.0, 0.3, -2.0);
+        let (d, dd) = act.derivatives(delc, del, deld);
+        assert_eq!(d, deld);
+        assert_eq!(dd, wact * wact * (delc - del - 2.0 * zact * deld / wact));
+        assert!(act.is_linear());
+    }
+
+    #[test]
+    fn test_rate_and_position_limits() {
+        let mut act = Actuator::default().with_rate_limit(5.0).with_position_limit(0.5);
+        let (del, max_rate) = step_response(&mut act, 1.0, 0.5);
+        assert!(max_rate <= 5.0 + 1e-12);
+        assert!((del - 0.5).abs() < 1e-9);
+
+        let mut lag = Actuator::first_order(0.02);
+        let (del, _) = step_response(&mut lag, 1.0, 0.2);
+        assert!((del - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_backlash_dead_zone() {
+        let mut act = Actuator::default().with_backlash(0.2);
+        act.latch(0.05);
+        assert_eq!(act.output(0.05), 0.0);
+        act.latch(0.3);
+        assert!((act.output(0.3) - 0.2).abs() < 1e-12);
+        // Reversing holds the delivered deflection until the gap closes
+        assert!((act.output(0.15) - 0.2).abs() < 1e-12);
+        assert!((act.output(0.0) - 0.1).abs() < 1e-12);
+    }
+}
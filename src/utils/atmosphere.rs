@@ -0,0 +1,45 @@
+//! Book atmosphere
+//!
+//! The autopilot and reentry listings compute air density inline from a
+//! two-segment exponential fit, switching at 30,000 ft. The shared models
+//! (radome, reentry, impact prediction) use the same fit from here, with
+//! the low-altitude segment up to and including 30,000 ft as in C11 and the
+//! C21-C24 listings. C22L4 and C10L1 switch strictly below 30,000 ft and
+//! keep their own comparison.
+
+/// Sea level density of the book's atmosphere (slug/ft^3)
+pub const RHO_SEA_LEVEL: f64 = 0.002378;
+
+/// Altitude at which the density fit changes segment (ft)
+const SEGMENT_ALTITUDE: f64 = 30000.0;
+
+/// Exponential atmosphere density (slug/ft^3) at altitude `alt` (ft)
+pub fn air_density(alt: f64) -> f64 {
+    if alt <= SEGMENT_ALTITUDE {
+        RHO_SEA_LEVEL * (-alt / 30000.0).exp()
+    } else {
+        0.0034 * (-alt / 22000.0).exp()
+    }
+}
+
+/// Speed of sound in the standard atmosphere (ft/s), isothermal above the
+/// tropopause at 36,089 ft
+pub fn speed_of_sound(alt: f64) -> f64 {
+    let temp = 518.67 - 0.003566 * alt.clamp(0.0, 36089.0);
+    1116.4 * (temp / 518.67).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atmosphere() {
+        assert_eq!(air_density(0.0), RHO_SEA_LEVEL);
+        // 30,000 ft is still on the low-altitude segment, as in the listings
+        assert_eq!(air_density(30000.0), 0.002378 * (-1.0f64).exp());
+        assert_eq!(air_density(30000.1), 0.0034 * (-30000.1f64 / 22000.0).exp());
+        assert!((speed_of_sound(0.0) - 1116.4).abs() < 1e-9);
+        assert!((speed_of_sound(36089.0) - speed_of_sound(200000.0)).abs() < 1e-9);
+    }
+}
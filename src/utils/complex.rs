@@ -0,0 +1,147 @@
+//! Minimal complex number type
+//!
+//! Just enough complex arithmetic for frequency response evaluation and
+//! polynomial root finding without pulling in an extra dependency.
+
+/// Complex number in rectangular form
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /// Purely real value
+    pub fn real(re: f64) -> Self {
+        Self { re, im: 0.0 }
+    }
+
+    /// Point on the imaginary axis (s = jw)
+    pub fn jw(w: f64) -> Self {
+        Self { re: 0.0, im: w }
+    }
+
+    pub fn from_polar(mag: f64, angle: f64) -> Self {
+        Self { re: mag * angle.cos(), im: mag * angle.sin() }
+    }
+
+    /// Magnitude |z|
+    pub fn abs(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Argument in radians, range (-pi, pi]
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(&self) -> Self {
+        Self { re: self.re, im: -self.im }
+    }
+
+    pub fn norm_sqr(&self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// Reciprocal 1/z
+    pub fn inv(&self) -> Self {
+        let d = self.norm_sqr();
+        Self { re: self.re / d, im: -self.im / d }
+    }
+
+    pub fn exp(&self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Principal natural logarithm
+    pub fn ln(&self) -> Self {
+        Self { re: self.abs().ln(), im: self.arg() }
+    }
+
+    /// Principal square root
+    pub fn sqrt(&self) -> Self {
+        Self::from_polar(self.abs().sqrt(), 0.5 * self.arg())
+    }
+
+    pub fn scale(&self, factor: f64) -> Self {
+        Self { re: self.re * factor, im: self.im * factor }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+}
+
+impl std::ops::Add for Complex {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self { re: self.re + other.re, im: self.im + other.im }
+    }
+}
+
+impl std::ops::Sub for Complex {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self { re: self.re - other.re, im: self.im - other.im }
+    }
+}
+
+impl std::ops::Mul for Complex {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+}
+
+impl std::ops::Div for Complex {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        self * other.inv()
+    }
+}
+
+impl std::ops::Neg for Complex {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self { re: -self.re, im: -self.im }
+    }
+}
+
+impl std::ops::Mul<f64> for Complex {
+    type Output = Self;
+    fn mul(self, scalar: f64) -> Self {
+        self.scale(scalar)
+    }
+}
+
+impl std::ops::Add<f64> for Complex {
+    type Output = Self;
+    fn add(self, scalar: f64) -> Self {
+        Self { re: self.re + scalar, im: self.im }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complex_arithmetic() {
+        let a = Complex::new(1.0, 2.0);
+        let b = Complex::new(3.0, -1.0);
+        let p = a * b;
+        assert!((p.re - 5.0).abs() < 1e-12 && (p.im - 5.0).abs() < 1e-12);
+        let q = p / b;
+        assert!((q.re - a.re).abs() < 1e-12 && (q.im - a.im).abs() < 1e-12);
+        let r = Complex::new(-4.0, 0.0).sqrt();
+        assert!(r.re.abs() < 1e-12 && (r.im - 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,349 @@
+//! Continuous-to-discrete conversion and sampled-data loop analysis
+//!
+//! The book's guidance filters run at a sampling interval `ts` (C1L3, C7, C27)
+//! while the frequency analysis in `lti` is continuous. This module converts
+//! `lti` models to the z-domain by zero-order hold, Tustin or matched
+//! pole-zero mapping, evaluates discrete frequency response on the unit
+//! circle, and models pure time delay (C22L4) either exactly or with a Pade
+//! approximation.
+//!
+//! Discrete polynomials use descending powers of z.
+
+use super::complex::Complex;
+use super::lti::{FrequencyResponse, StateSpace, TransferFunction};
+use super::matrix::{char_poly, expm, zeros, Matrix};
+use super::polynomial::{
+    poly_add, poly_eval_complex, poly_from_roots, poly_mul, poly_roots, poly_scale, poly_trim,
+};
+
+/// Continuous-to-discrete conversion method (MATLAB `c2d` options)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Discretization {
+    /// Zero-order hold on the input
+    ZeroOrderHold,
+    /// Bilinear (trapezoidal) transform s = (2/T)(z - 1)/(z + 1)
+    Tustin,
+    /// Matched pole-zero mapping z = e^(sT)
+    MatchedPoleZero,
+}
+
+/// Discrete transfer function num(z)/den(z) with sampling interval `ts`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscreteTransferFunction {
+    pub num: Vec<f64>,
+    pub den: Vec<f64>,
+    pub ts: f64,
+}
+
+impl DiscreteTransferFunction {
+    pub fn new(num: &[f64], den: &[f64], ts: f64) -> Self {
+        Self { num: poly_trim(num), den: poly_trim(den), ts }
+    }
+
+    /// Pure delay of `n` samples, z^-n
+    pub fn delay(n: usize, ts: f64) -> Self {
+        let mut den = vec![0.0; n + 1];
+        den[0] = 1.0;
+        Self::new(&[1.0], &den, ts)
+    }
+
+    /// Evaluate at an arbitrary point z
+    pub fn eval(&self, z: Complex) -> Complex {
+        poly_eval_complex(&self.num, z) / poly_eval_complex(&self.den, z)
+    }
+
+    pub fn series(&self, other: &Self) -> Self {
+        Self::new(&poly_mul(&self.num, &other.num), &poly_mul(&self.den, &other.den), self.ts)
+    }
+
+    /// Closed loop with feedback path `h`; `sign` is -1.0 for negative feedback
+    pub fn feedback(&self, h: &Self, sign: f64) -> Self {
+        let num = poly_mul(&self.num, &h.den);
+        let den = poly_add(
+            &poly_mul(&self.den, &h.den),
+            &poly_scale(&poly_mul(&self.num, &h.num), -sign),
+        );
+        Self::new(&num, &den, self.ts)
+    }
+
+    pub fn poles(&self) -> Vec<Complex> {
+        poly_roots(&self.den)
+    }
+
+    pub fn zeros(&self) -> Vec<Complex> {
+        poly_roots(&self.num)
+    }
+
+    /// Steady-state gain G(z = 1)
+    pub fn dc_gain(&self) -> f64 {
+        self.eval(Complex::real(1.0)).re
+    }
+
+    /// True when every pole lies strictly inside the unit circle
+    pub fn is_stable(&self) -> bool {
+        self.poles().iter().all(|p| p.abs() < 1.0)
+    }
+
+    /// Largest frequency that can be represented, pi/T (rad/sec)
+    pub fn nyquist_frequency(&self) -> f64 {
+        std::f64::consts::PI / self.ts
+    }
+
+    /// Step response y[k] for k = 0..n_samples by direct difference equation
+    pub fn step_response(&self, n_samples: usize) -> Vec<f64> {
+        let n = self.den.len();
+        let mut num = vec![0.0; n.max(self.num.len())];
+        let off = num.len() - self.num.len();
+        num[off..].copy_from_slice(&self.num);
+        let mut den = vec![0.0; num.len()];
+        let off = den.len() - self.den.len();
+        den[off..].copy_from_slice(&self.den);
+        let lead = den[0];
+
+        let mut y: Vec<f64> = Vec::with_capacity(n_samples);
+        for k in 0..n_samples {
+            let mut acc = 0.0;
+            for (i, &b) in num.iter().enumerate() {
+                if k >= i {
+                    acc += b;
+                }
+            }
+            for (i, &a) in den.iter().enumerate().skip(1) {
+                if k >= i {
+                    acc -= a * y[k - i];
+                }
+            }
+            y.push(acc / lead);
+        }
+        y
+    }
+}
+
+impl FrequencyResponse for DiscreteTransferFunction {
+    fn freq_response(&self, w: f64) -> Complex {
+        self.eval(Complex::from_polar(1.0, w * self.ts))
+    }
+}
+
+/// Discrete state-space model x[k+1] = A x[k] + B u[k], y[k] = C x[k] + D u[k]
+#[derive(Debug, Clone)]
+pub struct DiscreteStateSpace {
+    pub a: Matrix,
+    pub b: Matrix,
+    pub c: Matrix,
+    pub d: Matrix,
+    pub ts: f64,
+}
+
+impl DiscreteStateSpace {
+    /// Transfer function from `input` to `output` in z
+    pub fn to_tf(&self, input: usize, output: usize) -> DiscreteTransferFunction {
+        let tf = StateSpace::new(self.a.clone(), self.b.clone(), self.c.clone(), self.d.clone())
+            .to_tf(input, output);
+        DiscreteTransferFunction::new(&tf.num, &tf.den, self.ts)
+    }
+
+    pub fn poles(&self) -> Vec<Complex> {
+        poly_roots(&char_poly(&self.a))
+    }
+}
+
+/// Zero-order hold discretization of a state-space model
+///
+/// Uses the block exponential exp([[A, B], [0, 0]] T) = [[Phi, Gamma], [0, I]].
+#[allow(clippy::needless_range_loop)]
+pub fn c2d_state_space(sys: &StateSpace, ts: f64) -> DiscreteStateSpace {
+    let n = sys.n_states();
+    let m = sys.n_inputs();
+    let mut blk = zeros(n + m, n + m);
+    for i in 0..n {
+        for j in 0..n {
+            blk[i][j] = sys.a[i][j] * ts;
+        }
+        for j in 0..m {
+            blk[i][n + j] = sys.b[i][j] * ts;
+        }
+    }
+    let e = expm(&blk);
+    let a: Matrix = e[..n].iter().map(|r| r[..n].to_vec()).collect();
+    let b: Matrix = e[..n].iter().map(|r| r[n..].to_vec()).collect();
+    DiscreteStateSpace { a, b, c: sys.c.clone(), d: sys.d.clone(), ts }
+}
+
+/// Convert a continuous transfer function to discrete time (MATLAB `c2d`)
+pub fn c2d(sys: &TransferFunction, ts: f64, method: Discretization) -> DiscreteTransferFunction {
+    match method {
+        Discretization::ZeroOrderHold => c2d_state_space(&sys.to_state_space(), ts).to_tf(0, 0),
+        Discretization::Tustin => tustin(sys, ts),
+        Discretization::MatchedPoleZero => matched(sys, ts),
+    }
+}
+
+/// Substitute s = (2/T)(z - 1)/(z + 1) and clear the (z + 1)^N denominators
+fn tustin(sys: &TransferFunction, ts: f64) -> DiscreteTransferFunction {
+    let order = sys.num.len().max(sys.den.len()) - 1;
+    let k = 2.0 / ts;
+    let map = |p: &[f64]| -> Vec<f64> {
+        let deg = p.len() - 1;
+        let mut out = vec![0.0];
+        for (i, &c) in p.iter().enumerate() {
+            let power = deg - i;
+            let mut term = vec![c * k.powi(power as i32)];
+            for _ in 0..power {
+                term = poly_mul(&term, &[1.0, -1.0]);
+            }
+            for _ in 0..(order - power) {
+                term = poly_mul(&term, &[1.0, 1.0]);
+            }
+            out = poly_add(&out, &term);
+        }
+        out
+    };
+    let num = map(&sys.num);
+    let den = map(&sys.den);
+    let lead = den[0];
+    DiscreteTransferFunction::new(&poly_scale(&num, 1.0 / lead), &poly_scale(&den, 1.0 / lead), ts)
+}
+
+/// Map poles and zeros through z = e^(sT), placing all but one of the excess
+/// zeros at z = -1 (the remaining one-sample delay keeps the result causal),
+/// and match the low-frequency gain.
+fn matched(sys: &TransferFunction, ts: f64) -> DiscreteTransferFunction {
+    let poles = sys.poles();
+    let zeros = sys.zeros();
+    let map = |r: &Complex| (*r * ts).exp();
+    let mut zd: Vec<Complex> = zeros.iter().map(map).collect();
+    let pd: Vec<Complex> = poles.iter().map(map).collect();
+    let excess = poles.len().saturating_sub(zeros.len());
+    for _ in 1..excess {
+        zd.push(Complex::real(-1.0));
+    }
+
+    // Match gain with any roots at the origin (z = 1) factored out, using
+    // (z - 1) ~ sT near zero frequency
+    let one = Complex::real(1.0);
+    let at_origin = |r: &&Complex| r.abs() < 1e-12;
+    let at_one = |r: &&Complex| (**r - one).abs() < 1e-12;
+    let product = |roots: Vec<Complex>| roots.iter().fold(one, |g, &r| g * r).re;
+    let cont_gain = sys.num[0] / sys.den[0]
+        * product(zeros.iter().filter(|r| !at_origin(r)).map(|&r| -r).collect())
+        / product(poles.iter().filter(|r| !at_origin(r)).map(|&r| -r).collect());
+    let disc_num = product(zd.iter().filter(|r| !at_one(r)).map(|&r| one - r).collect());
+    let disc_den = product(pd.iter().filter(|r| !at_one(r)).map(|&r| one - r).collect());
+    let n_origin = poles.iter().filter(at_origin).count() as i32
+        - zeros.iter().filter(at_origin).count() as i32;
+    let k = cont_gain * disc_den / disc_num * ts.powi(n_origin);
+
+    DiscreteTransferFunction::new(&poly_scale(&poly_from_roots(&zd), k), &poly_from_roots(&pd), ts)
+}
+
+/// Pade approximation of a pure delay e^(-s*delay) of the given order
+pub fn pade(delay: f64, order: usize) -> TransferFunction {
+    // c_k = (2n - k)! n! / ((2n)! k! (n - k)!)
+    let n = order;
+    let mut coeffs = vec![1.0];
+    let mut c = 1.0;
+    for k in 1..=n {
+        c *= (n - k + 1) as f64 / (k * (2 * n - k + 1)) as f64;
+        coeffs.push(c * delay.powi(k as i32));
+    }
+    // Ascending powers -> descending
+    let den: Vec<f64> = coeffs.iter().rev().copied().collect();
+    let num: Vec<f64> = coeffs
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(k, &c)| if k % 2 == 1 { -c } else { c })
+        .collect();
+    TransferFunction::new(&num, &den)
+}
+
+/// Exact pure time delay e^(-s*delay) for frequency-domain analysis
+#[derive(Debug, Clone, Copy)]
+pub struct TimeDelay {
+    pub delay: f64,
+}
+
+impl FrequencyResponse for TimeDelay {
+    fn freq_response(&self, w: f64) -> Complex {
+        Complex::from_polar(1.0, -w * self.delay)
+    }
+}
+
+/// Open loop followed by an exact transport delay
+pub struct Delayed<'a, S: FrequencyResponse + ?Sized> {
+    pub sys: &'a S,
+    pub delay: f64,
+}
+
+impl<S: FrequencyResponse + ?Sized> FrequencyResponse for Delayed<'_, S> {
+    fn freq_response(&self, w: f64) -> Complex {
+        self.sys.freq_response(w) * TimeDelay { delay: self.delay }.freq_response(w)
+    }
+}
+
+/// Frequency response of a continuous plant driven through a zero-order hold
+/// with sampling interval `ts`, including the ZOH's half-sample phase lag
+pub struct SampledHold<'a, S: FrequencyResponse + ?Sized> {
+    pub sys: &'a S,
+    pub ts: f64,
+}
+
+impl<S: FrequencyResponse + ?Sized> FrequencyResponse for SampledHold<'_, S> {
+    fn freq_response(&self, w: f64) -> Complex {
+        // ZOH = (1 - e^(-sT))/(sT), magnitude sinc(wT/2), phase -wT/2
+        let x = 0.5 * w * self.ts;
+        let mag = if x.abs() < 1e-12 { 1.0 } else { x.sin() / x };
+        self.sys.freq_response(w) * Complex::from_polar(mag, -x)
+    }
+}
+
+/// Frequencies from 10^lo up to (just below) the Nyquist frequency pi/ts
+pub fn discrete_freq_grid(lo: f64, ts: f64, n: usize) -> Vec<f64> {
+    let hi = (0.999 * std::f64::consts::PI / ts).log10();
+    super::lti::logspace(lo, hi, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::lti::margins;
+
+    #[test]
+    fn test_zoh_first_order() {
+        // 1/(s + 1) with ZOH -> (1 - e^-T)/(z - e^-T)
+        let g = TransferFunction::new(&[1.0], &[1.0, 1.0]);
+        let ts = 0.1;
+        let d = c2d(&g, ts, Discretization::ZeroOrderHold);
+        let a = (-ts).exp();
+        assert!((d.den[1] + a).abs() < 1e-12);
+        assert!((d.num[d.num.len() - 1] - (1.0 - a)).abs() < 1e-12);
+        assert!((d.dc_gain() - 1.0).abs() < 1e-12);
+        assert!(d.is_stable());
+    }
+
+    #[test]
+    fn test_tustin_and_matched_dc_gain() {
+        let g = TransferFunction::new(&[2.0, 10.0], &[1.0, 3.0, 10.0]);
+        for method in [Discretization::Tustin, Discretization::MatchedPoleZero] {
+            let d = c2d(&g, 0.01, method);
+            assert!((d.dc_gain() - g.dc_gain()).abs() < 1e-9, "{:?}", method);
+            let a = g.freq_response(1.0);
+            let b = d.freq_response(1.0);
+            assert!((a - b).abs() < 0.01 * a.abs(), "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn test_delay_reduces_phase_margin() {
+        let g = TransferFunction::new(&[10.0], &[1.0, 1.0, 0.0]);
+        let w = super::super::lti::logspace(-2.0, 2.0, 400);
+        let base = margins(&g, &w).phase_margin_deg.unwrap();
+        let exact = margins(&Delayed { sys: &g, delay: 0.05 }, &w);
+        let approx = margins(&g.series(&pade(0.05, 3)), &w);
+        let wc = exact.gain_crossover.unwrap();
+        let expected = base - 0.05 * wc.to_degrees();
+        assert!((exact.phase_margin_deg.unwrap() - expected).abs() < 1e-6);
+        assert!((approx.phase_margin_deg.unwrap() - expected).abs() < 0.01);
+    }
+}
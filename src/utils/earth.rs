@@ -0,0 +1,293 @@
+//! Earth models
+//!
+//! The book's strategic lessons use a spherical, non-rotating Earth of
+//! radius `EARTH_RADIUS_FT`; C44 and C45 place their sites with
+//! `EarthModel::spherical()`, which reproduces the listings' inline
+//! a cos(lat) cos(long) exactly. `EarthModel` adds the WGS-84 ellipsoid, Earth
+//! rotation and J2 gravity so that the same trajectories can be flown
+//! against a more realistic Earth. All quantities are in feet and seconds.
+//!
+//! ECEF axes rotate with the Earth; ECI axes coincide with them at t = 0.
+
+use super::constants::{EARTH_RADIUS_FT, GM_FT};
+use super::kepler::StateVector;
+use super::rk2::Vec3;
+
+/// WGS-84 equatorial radius (ft)
+pub const WGS84_A_FT: f64 = 6378137.0 / 0.3048;
+
+/// WGS-84 flattening
+pub const WGS84_F: f64 = 1.0 / 298.257223563;
+
+/// WGS-84 gravitational parameter (ft^3/s^2)
+pub const WGS84_GM_FT: f64 = 3.986004418e14 / (0.3048 * 0.3048 * 0.3048);
+
+/// WGS-84 Earth rotation rate (rad/s)
+pub const WGS84_OMEGA: f64 = 7.292115e-5;
+
+/// Second zonal harmonic of the WGS-84 gravity field
+pub const WGS84_J2: f64 = 1.08262668e-3;
+
+/// Geodetic coordinates
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Geodetic {
+    /// Geodetic latitude (rad)
+    pub lat: f64,
+    /// Longitude (rad)
+    pub lon: f64,
+    /// Height above the ellipsoid (ft)
+    pub alt: f64,
+}
+
+impl Geodetic {
+    pub fn new(lat: f64, lon: f64, alt: f64) -> Self {
+        Self { lat, lon, alt }
+    }
+
+    /// From latitude and longitude in degrees and altitude in feet
+    pub fn from_degrees(lat_deg: f64, lon_deg: f64, alt: f64) -> Self {
+        Self::new(lat_deg.to_radians(), lon_deg.to_radians(), alt)
+    }
+}
+
+/// Shape, gravity and rotation of the Earth
+#[derive(Debug, Clone, Copy)]
+pub struct EarthModel {
+    /// Equatorial radius (ft)
+    pub a: f64,
+    /// Flattening
+    pub f: f64,
+    /// Gravitational parameter (ft^3/s^2)
+    pub gm: f64,
+    /// Rotation rate (rad/s)
+    pub omega: f64,
+    /// Second zonal harmonic, zero for point-mass gravity
+    pub j2: f64,
+}
+
+impl EarthModel {
+    /// The book's spherical, non-rotating Earth with point-mass gravity
+    pub fn spherical() -> Self {
+        Self { a: EARTH_RADIUS_FT, f: 0.0, gm: GM_FT, omega: 0.0, j2: 0.0 }
+    }
+
+    /// Rotating WGS-84 ellipsoid with J2 gravity
+    pub fn wgs84() -> Self {
+        Self { a: WGS84_A_FT, f: WGS84_F, gm: WGS84_GM_FT, omega: WGS84_OMEGA, j2: WGS84_J2 }
+    }
+
+    pub fn rotating(mut self) -> Self {
+        self.omega = WGS84_OMEGA;
+        self
+    }
+
+    pub fn non_rotating(mut self) -> Self {
+        self.omega = 0.0;
+        self
+    }
+
+    /// First eccentricity squared
+    pub fn e2(&self) -> f64 {
+        self.f * (2.0 - self.f)
+    }
+
+    /// Polar radius (ft)
+    pub fn b(&self) -> f64 {
+        self.a * (1.0 - self.f)
+    }
+
+    pub fn geodetic_to_ecef(&self, g: &Geodetic) -> Vec3 {
+        let e2 = self.e2();
+        let (slat, clat) = g.lat.sin_cos();
+        let n = self.a / (1.0 - e2 * slat * slat).sqrt();
+        Vec3::new(
+            (n + g.alt) * clat * g.lon.cos(),
+            (n + g.alt) * clat * g.lon.sin(),
+            (n * (1.0 - e2) + g.alt) * slat,
+        )
+    }
+
+    /// Inverse of `geodetic_to_ecef`, starting from Bowring's latitude and
+    /// refining with fixed-point iterations that stay well behaved at the poles
+    pub fn ecef_to_geodetic(&self, r: &Vec3) -> Geodetic {
+        let e2 = self.e2();
+        let p = (r.x * r.x + r.y * r.y).sqrt();
+        let lon = r.y.atan2(r.x);
+        let b = self.b();
+        let ep2 = e2 / (1.0 - e2);
+        let theta = (r.z * self.a).atan2(p * b);
+        let mut lat = (r.z + ep2 * b * theta.sin().powi(3)).atan2(p - e2 * self.a * theta.cos().powi(3));
+        let mut n = self.a;
+        for _ in 0..5 {
+            let slat = lat.sin();
+            n = self.a / (1.0 - e2 * slat * slat).sqrt();
+            let next = (r.z + e2 * n * slat).atan2(p);
+            let done = (next - lat).abs() < 1e-15;
+            lat = next;
+            if done {
+                break;
+            }
+        }
+        let (slat, clat) = lat.sin_cos();
+        let alt = p * clat + (r.z + e2 * n * slat) * slat - n;
+        Geodetic::new(lat, lon, alt)
+    }
+
+    /// Rotates an ECEF position at time `t` into ECI
+    pub fn ecef_to_eci(&self, r: &Vec3, t: f64) -> Vec3 {
+        rotate_z(r, self.omega * t)
+    }
+
+    pub fn eci_to_ecef(&self, r: &Vec3, t: f64) -> Vec3 {
+        rotate_z(r, -self.omega * t)
+    }
+
+    /// ECEF state to ECI, adding the velocity of the rotating frame
+    pub fn ecef_to_eci_state(&self, sv: &StateVector, t: f64) -> StateVector {
+        let r = sv.position();
+        let v = sv.velocity() + self.omega_vec().cross(&r);
+        StateVector::from_vectors(self.ecef_to_eci(&r, t), self.ecef_to_eci(&v, t))
+    }
+
+    pub fn eci_to_ecef_state(&self, sv: &StateVector, t: f64) -> StateVector {
+        let r = self.eci_to_ecef(&sv.position(), t);
+        let v = self.eci_to_ecef(&sv.velocity(), t) - self.omega_vec().cross(&r);
+        StateVector::from_vectors(r, v)
+    }
+
+    fn omega_vec(&self) -> Vec3 {
+        Vec3::new(0.0, 0.0, self.omega)
+    }
+
+    /// Gravitational acceleration, point mass plus J2. The field is
+    /// symmetric about the spin axis, so `r` may be in ECEF or ECI.
+    pub fn gravity(&self, r: &Vec3) -> Vec3 {
+        let rmag = r.magnitude();
+        let g = -self.gm / (rmag * rmag * rmag);
+        if self.j2 == 0.0 {
+            return *r * g;
+        }
+        let k = 1.5 * self.j2 * (self.a / rmag).powi(2);
+        let z2 = (r.z / rmag).powi(2);
+        Vec3::new(
+            g * r.x * (1.0 + k * (1.0 - 5.0 * z2)),
+            g * r.y * (1.0 + k * (1.0 - 5.0 * z2)),
+            g * r.z * (1.0 + k * (3.0 - 5.0 * z2)),
+        )
+    }
+
+    /// Acceleration in the rotating ECEF frame: gravity plus the Coriolis
+    /// and centrifugal terms
+    pub fn acceleration_ecef(&self, r: &Vec3, v: &Vec3) -> Vec3 {
+        let w = self.omega_vec();
+        let coriolis = w.cross(v) * -2.0;
+        let centrifugal = w.cross(&w.cross(r)) * -1.0;
+        self.gravity(r) + coriolis + centrifugal
+    }
+
+    /// Second-order Runge-Kutta step of an unpowered ECEF state
+    pub fn step(&self, sv: &StateVector, h: f64) -> StateVector {
+        let (r0, v0) = (sv.position(), sv.velocity());
+        let a0 = self.acceleration_ecef(&r0, &v0);
+        let r1 = r0 + v0 * h;
+        let v1 = v0 + a0 * h;
+        let a1 = self.acceleration_ecef(&r1, &v1);
+        StateVector::from_vectors(
+            (r0 + r1 + v1 * h) * 0.5,
+            (v0 + v1 + a1 * h) * 0.5,
+        )
+    }
+
+    /// Flies an ECEF state for `duration` seconds with step `h`
+    pub fn propagate(&self, sv: &StateVector, duration: f64, h: f64) -> StateVector {
+        let mut x = *sv;
+        let mut t = 0.0;
+        while t < duration - 1e-9 {
+            let dt = h.min(duration - t);
+            x = self.step(&x, dt);
+            t += dt;
+        }
+        x
+    }
+}
+
+impl Default for EarthModel {
+    fn default() -> Self {
+        Self::spherical()
+    }
+}
+
+fn rotate_z(r: &Vec3, angle: f64) -> Vec3 {
+    let (s, c) = angle.sin_cos();
+    Vec3::new(c * r.x - s * r.y, s * r.x + c * r.y, r.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_earth_geodetic_round_trip() {
+        let earth = EarthModel::wgs84();
+        let equator = earth.geodetic_to_ecef(&Geodetic::new(0.0, 0.0, 0.0));
+        assert!((equator.x - WGS84_A_FT).abs() < 1e-6);
+        let pole = earth.geodetic_to_ecef(&Geodetic::from_degrees(90.0, 0.0, 0.0));
+        assert!((pole.z - earth.b()).abs() < 1e-6);
+
+        for (lat, lon, alt) in [(43.75, 7.42, 0.0), (-33.9, 151.2, 1.0e6), (89.999, -60.0, 5.0e5), (-90.0, 0.0, 100.0)] {
+            let g = Geodetic::from_degrees(lat, lon, alt);
+            let back = earth.ecef_to_geodetic(&earth.geodetic_to_ecef(&g));
+            assert!((back.lat - g.lat).abs() < 1e-12, "lat {}", lat);
+            assert!((back.alt - g.alt).abs() < 1e-5, "alt {}", back.alt);
+            if lat.abs() < 90.0 {
+                assert!((back.lon - g.lon).abs() < 1e-12);
+            }
+        }
+
+        // The spherical model reproduces the book's conversion
+        let sphere = EarthModel::spherical();
+        let (lat, lon): (f64, f64) = (0.7, -1.2);
+        let r = sphere.geodetic_to_ecef(&Geodetic::new(lat, lon, 0.0));
+        let a = EARTH_RADIUS_FT;
+        assert!((r.x - a * lat.cos() * lon.cos()).abs() < 1e-6);
+        assert!((r.z - a * lat.sin()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_earth_eci_state_round_trip() {
+        let earth = EarthModel::wgs84();
+        let sv = StateVector::new(2.0e7, 3.0e6, 1.0e6, 100.0, 2.0e4, 500.0);
+        let back = earth.eci_to_ecef_state(&earth.ecef_to_eci_state(&sv, 1234.0), 1234.0);
+        assert!((back.position() - sv.position()).magnitude() < 1e-6);
+        assert!((back.velocity() - sv.velocity()).magnitude() < 1e-9);
+        // A point fixed on the ground moves eastward at omega * r in ECI
+        let ground = StateVector::new(WGS84_A_FT, 0.0, 0.0, 0.0, 0.0, 0.0);
+        let eci = earth.ecef_to_eci_state(&ground, 0.0);
+        assert!((eci.vy - WGS84_OMEGA * WGS84_A_FT).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_earth_j2_gravity() {
+        let earth = EarthModel::wgs84();
+        let r = WGS84_A_FT;
+        let eq = earth.gravity(&Vec3::new(r, 0.0, 0.0)).magnitude();
+        let pole = earth.gravity(&Vec3::new(0.0, 0.0, r)).magnitude();
+        let point = WGS84_GM_FT / (r * r);
+        assert!((eq / point - (1.0 + 1.5 * WGS84_J2)).abs() < 1e-12);
+        assert!((pole / point - (1.0 - 3.0 * WGS84_J2)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_earth_rotating_frame_matches_inertial_flight() {
+        // Flying in ECEF with Coriolis and centrifugal terms must agree with
+        // flying the same state in ECI and rotating the result
+        let earth = EarthModel::wgs84();
+        let sv = StateVector::new(WGS84_A_FT, 0.0, 1.0e6, 5000.0, 8000.0, 12000.0);
+        let tf = 600.0;
+        let rotating = earth.propagate(&sv, tf, 0.01);
+        let inertial = earth.non_rotating().propagate(&earth.ecef_to_eci_state(&sv, 0.0), tf, 0.01);
+        let back = earth.eci_to_ecef_state(&inertial, tf);
+        assert!((rotating.position() - back.position()).magnitude() < 1.0);
+        assert!((rotating.velocity() - back.velocity()).magnitude() < 1e-3);
+    }
+}
@@ -0,0 +1,419 @@
+//! Orbital elements
+//!
+//! Classical and modified equinoctial element sets with conversions to and
+//! from `StateVector`, orbit characteristics (apogee, perigee, period,
+//! energy), time of flight between true anomalies and propagation by mean
+//! anomaly. All functions take the gravitational parameter in units
+//! consistent with the state, so feet and kilometers both work.
+
+use std::f64::consts::PI;
+use super::kepler::StateVector;
+use super::rk2::Vec3;
+
+/// Below this eccentricity or inclination the orbit is treated as circular
+/// or equatorial and the undefined angles are set to zero
+const SINGULAR_TOL: f64 = 1e-11;
+
+/// Eccentricities this close to one are treated as parabolic
+const PARABOLIC_TOL: f64 = 1e-10;
+
+/// Classical orbital elements
+///
+/// The semi-latus rectum replaces the semi-major axis so that parabolic
+/// orbits are represented. For circular orbits the argument of periapsis is
+/// zero and the true anomaly is the argument of latitude; for equatorial
+/// orbits the node is zero and the argument of periapsis is measured from x.
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitalElements {
+    /// Semi-latus rectum
+    pub p: f64,
+    /// Eccentricity
+    pub e: f64,
+    /// Inclination (rad)
+    pub i: f64,
+    /// Right ascension of the ascending node (rad)
+    pub raan: f64,
+    /// Argument of periapsis (rad)
+    pub argp: f64,
+    /// True anomaly (rad)
+    pub nu: f64,
+    /// Gravitational parameter
+    pub mu: f64,
+}
+
+impl OrbitalElements {
+    pub fn from_state(sv: &StateVector, mu: f64) -> Self {
+        let r = sv.position();
+        let v = sv.velocity();
+        let rmag = r.magnitude();
+        let hvec = r.cross(&v);
+        let h = hvec.magnitude();
+        let ih = hvec.normalize();
+        let evec = (r * (v.dot(&v) - mu / rmag) - v * r.dot(&v)) * (1.0 / mu);
+        let e = evec.magnitude();
+        let i = ih.z.clamp(-1.0, 1.0).acos();
+
+        // Node vector, or the x axis for an equatorial orbit
+        let node = Vec3::new(-hvec.y, hvec.x, 0.0);
+        let (raan, reference) = if node.magnitude() > SINGULAR_TOL * h {
+            (wrap(node.y.atan2(node.x)), node.normalize())
+        } else {
+            (0.0, Vec3::new(1.0, 0.0, 0.0))
+        };
+        let (argp, nu) = if e > SINGULAR_TOL {
+            (angle_about(&reference, &evec, &ih), angle_about(&evec, &r, &ih))
+        } else {
+            (0.0, angle_about(&reference, &r, &ih))
+        };
+
+        Self { p: h * h / mu, e, i, raan, argp, nu, mu }
+    }
+
+    pub fn to_state(self) -> StateVector {
+        let (snu, cnu) = self.nu.sin_cos();
+        let r = self.p / (1.0 + self.e * cnu);
+        let vp = (self.mu / self.p).sqrt();
+        let rpf = Vec3::new(r * cnu, r * snu, 0.0);
+        let vpf = Vec3::new(-vp * snu, vp * (self.e + cnu), 0.0);
+        let (pvec, qvec) = self.perifocal_axes();
+        StateVector::from_vectors(
+            pvec * rpf.x + qvec * rpf.y,
+            pvec * vpf.x + qvec * vpf.y,
+        )
+    }
+
+    /// Unit vectors toward periapsis and 90 deg ahead in the orbit plane
+    fn perifocal_axes(&self) -> (Vec3, Vec3) {
+        let (so, co) = self.raan.sin_cos();
+        let (sw, cw) = self.argp.sin_cos();
+        let (si, ci) = self.i.sin_cos();
+        (
+            Vec3::new(co * cw - so * sw * ci, so * cw + co * sw * ci, sw * si),
+            Vec3::new(-co * sw - so * cw * ci, -so * sw + co * cw * ci, cw * si),
+        )
+    }
+
+    /// Semi-major axis, infinite for a parabola and negative for a hyperbola
+    pub fn semi_major_axis(&self) -> f64 {
+        if self.is_parabolic() { f64::INFINITY } else { self.p / (1.0 - self.e * self.e) }
+    }
+
+    pub fn is_parabolic(&self) -> bool {
+        (self.e - 1.0).abs() < PARABOLIC_TOL
+    }
+
+    /// Specific orbital energy
+    pub fn energy(&self) -> f64 {
+        -self.mu * (1.0 - self.e * self.e) / (2.0 * self.p)
+    }
+
+    /// Specific angular momentum
+    pub fn angular_momentum(&self) -> f64 {
+        (self.mu * self.p).sqrt()
+    }
+
+    pub fn perigee_radius(&self) -> f64 {
+        self.p / (1.0 + self.e)
+    }
+
+    /// Apogee radius, `None` for an open orbit
+    pub fn apogee_radius(&self) -> Option<f64> {
+        if self.e < 1.0 { Some(self.p / (1.0 - self.e)) } else { None }
+    }
+
+    /// Orbital period, `None` for an open orbit
+    pub fn period(&self) -> Option<f64> {
+        if self.e < 1.0 {
+            Some(2.0 * PI * (self.semi_major_axis().powi(3) / self.mu).sqrt())
+        } else {
+            None
+        }
+    }
+
+    /// Radius at the current true anomaly
+    pub fn radius(&self) -> f64 {
+        self.p / (1.0 + self.e * self.nu.cos())
+    }
+
+    /// Flight path angle above the local horizontal at the current true anomaly
+    pub fn flight_path_angle(&self) -> f64 {
+        (self.e * self.nu.sin()).atan2(1.0 + self.e * self.nu.cos())
+    }
+
+    /// Time to fly from true anomaly `nu1` forward to `nu2`. On an ellipse the
+    /// result lies within one period; on an open orbit it is negative when
+    /// `nu2` precedes `nu1`.
+    pub fn time_between(&self, nu1: f64, nu2: f64) -> f64 {
+        let n = self.mean_motion();
+        let dm = mean_anomaly(nu2, self.e) - mean_anomaly(nu1, self.e);
+        if self.e < 1.0 && !self.is_parabolic() {
+            wrap(dm) / n
+        } else {
+            dm / n
+        }
+    }
+
+    /// Elements after `dt` of Keplerian motion
+    pub fn propagate(&self, dt: f64) -> Self {
+        let m = mean_anomaly(self.nu, self.e) + self.mean_motion() * dt;
+        Self { nu: true_anomaly(m, self.e), ..*self }
+    }
+
+    /// Mean motion, with the parabolic form 2 sqrt(mu / p^3) of Barker's equation
+    fn mean_motion(&self) -> f64 {
+        if self.is_parabolic() {
+            2.0 * (self.mu / self.p.powi(3)).sqrt()
+        } else {
+            let a = self.semi_major_axis().abs();
+            (self.mu / (a * a * a)).sqrt()
+        }
+    }
+}
+
+/// Propagates a state vector by converting to elements and back
+pub fn propagate_state(sv: &StateVector, dt: f64, mu: f64) -> StateVector {
+    OrbitalElements::from_state(sv, mu).propagate(dt).to_state()
+}
+
+/// Modified equinoctial elements
+///
+/// Nonsingular for circular and equatorial orbits; singular only for
+/// inclinations of exactly 180 deg.
+#[derive(Debug, Clone, Copy)]
+pub struct EquinoctialElements {
+    /// Semi-latus rectum
+    pub p: f64,
+    /// e cos(argp + raan)
+    pub f: f64,
+    /// e sin(argp + raan)
+    pub g: f64,
+    /// tan(i/2) cos(raan)
+    pub h: f64,
+    /// tan(i/2) sin(raan)
+    pub k: f64,
+    /// True longitude raan + argp + nu (rad)
+    pub l: f64,
+    /// Gravitational parameter
+    pub mu: f64,
+}
+
+impl EquinoctialElements {
+    pub fn from_state(sv: &StateVector, mu: f64) -> Self {
+        let r = sv.position();
+        let v = sv.velocity();
+        let hvec = r.cross(&v);
+        let hmag = hvec.magnitude();
+        let ih = hvec.normalize();
+        let h = -ih.y / (1.0 + ih.z);
+        let k = ih.x / (1.0 + ih.z);
+        let eq = Self { p: hmag * hmag / mu, f: 0.0, g: 0.0, h, k, l: 0.0, mu };
+        let (fhat, ghat) = eq.axes();
+        let evec = v.cross(&hvec) * (1.0 / mu) - r.normalize();
+        Self {
+            f: evec.dot(&fhat),
+            g: evec.dot(&ghat),
+            l: wrap(r.dot(&ghat).atan2(r.dot(&fhat))),
+            ..eq
+        }
+    }
+
+    pub fn to_state(self) -> StateVector {
+        let (sl, cl) = self.l.sin_cos();
+        let r = self.p / (1.0 + self.f * cl + self.g * sl);
+        let vp = (self.mu / self.p).sqrt();
+        let (fhat, ghat) = self.axes();
+        StateVector::from_vectors(
+            fhat * (r * cl) + ghat * (r * sl),
+            fhat * (-vp * (self.g + sl)) + ghat * (vp * (self.f + cl)),
+        )
+    }
+
+    /// Equinoctial reference axes in the orbit plane
+    fn axes(&self) -> (Vec3, Vec3) {
+        let (h, k) = (self.h, self.k);
+        let s2 = 1.0 + h * h + k * k;
+        (
+            Vec3::new(1.0 - k * k + h * h, 2.0 * k * h, -2.0 * k) * (1.0 / s2),
+            Vec3::new(2.0 * k * h, 1.0 + k * k - h * h, 2.0 * h) * (1.0 / s2),
+        )
+    }
+}
+
+impl From<OrbitalElements> for EquinoctialElements {
+    fn from(oe: OrbitalElements) -> Self {
+        let lp = oe.raan + oe.argp;
+        let t = (oe.i / 2.0).tan();
+        Self {
+            p: oe.p,
+            f: oe.e * lp.cos(),
+            g: oe.e * lp.sin(),
+            h: t * oe.raan.cos(),
+            k: t * oe.raan.sin(),
+            l: wrap(lp + oe.nu),
+            mu: oe.mu,
+        }
+    }
+}
+
+impl From<EquinoctialElements> for OrbitalElements {
+    fn from(eq: EquinoctialElements) -> Self {
+        let e = (eq.f * eq.f + eq.g * eq.g).sqrt();
+        let t = (eq.h * eq.h + eq.k * eq.k).sqrt();
+        let raan = if t > SINGULAR_TOL { wrap(eq.k.atan2(eq.h)) } else { 0.0 };
+        let lp = if e > SINGULAR_TOL { eq.g.atan2(eq.f) } else { raan };
+        Self {
+            p: eq.p,
+            e,
+            i: 2.0 * t.atan(),
+            raan,
+            argp: wrap(lp - raan),
+            nu: wrap(eq.l - lp),
+            mu: eq.mu,
+        }
+    }
+}
+
+/// Mean anomaly from true anomaly; for a parabola, Barker's D + D^3/3
+pub fn mean_anomaly(nu: f64, e: f64) -> f64 {
+    if (e - 1.0).abs() < PARABOLIC_TOL {
+        let d = (nu / 2.0).tan();
+        d + d * d * d / 3.0
+    } else if e < 1.0 {
+        let ea = 2.0 * (((1.0 - e) / (1.0 + e)).sqrt() * (nu / 2.0).tan()).atan();
+        ea - e * ea.sin()
+    } else {
+        let ha = 2.0 * (((e - 1.0) / (e + 1.0)).sqrt() * (nu / 2.0).tan()).atanh();
+        e * ha.sinh() - ha
+    }
+}
+
+/// True anomaly from mean anomaly, solving Kepler's equation by Newton iteration
+pub fn true_anomaly(m: f64, e: f64) -> f64 {
+    if (e - 1.0).abs() < PARABOLIC_TOL {
+        // Closed-form solution of Barker's equation
+        let w = (1.5 * m + (2.25 * m * m + 1.0).sqrt()).cbrt();
+        return 2.0 * (w - 1.0 / w).atan();
+    }
+    if e < 1.0 {
+        let revs = ((m + PI) / (2.0 * PI)).floor();
+        let m = m - 2.0 * PI * revs;
+        let mut ea = if e < 0.8 { m } else { PI.copysign(m) };
+        for _ in 0..50 {
+            let step = (ea - e * ea.sin() - m) / (1.0 - e * ea.cos());
+            ea -= step;
+            if step.abs() < 1e-14 {
+                break;
+            }
+        }
+        let nu = 2.0 * (((1.0 + e) / (1.0 - e)).sqrt() * (ea / 2.0).tan()).atan();
+        wrap(nu + 2.0 * PI * revs)
+    } else {
+        let mut ha = (m / e).asinh();
+        for _ in 0..50 {
+            let step = (e * ha.sinh() - ha - m) / (e * ha.cosh() - 1.0);
+            ha -= step;
+            if step.abs() < 1e-14 {
+                break;
+            }
+        }
+        2.0 * (((e + 1.0) / (e - 1.0)).sqrt() * (ha / 2.0).tanh()).atan()
+    }
+}
+
+/// Angle from `a` to `b` measured positive about `axis`, in [0, 2pi)
+fn angle_about(a: &Vec3, b: &Vec3, axis: &Vec3) -> f64 {
+    wrap(a.cross(b).dot(axis).atan2(a.dot(b)))
+}
+
+/// Wraps an angle into [0, 2pi)
+fn wrap(angle: f64) -> f64 {
+    angle.rem_euclid(2.0 * PI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::GM_KM;
+    use crate::utils::kepler::kepler1;
+
+    fn assert_state_close(a: &StateVector, b: &StateVector, tol: f64) {
+        let dr = (a.position() - b.position()).magnitude() / a.position().magnitude();
+        let dv = (a.velocity() - b.velocity()).magnitude() / a.velocity().magnitude();
+        assert!(dr < tol && dv < tol, "dr {} dv {}", dr, dv);
+    }
+
+    #[test]
+    fn test_elements_vallado_example() {
+        // Vallado example 2-5
+        let sv = StateVector::new(6524.834, 6862.875, 6448.296, 4.901327, 5.533756, -1.976341);
+        let oe = OrbitalElements::from_state(&sv, 398600.4418);
+        assert!((oe.p - 11067.790).abs() < 0.01);
+        assert!((oe.e - 0.83285).abs() < 1e-5);
+        assert!((oe.i.to_degrees() - 87.87).abs() < 0.01);
+        assert!((oe.raan.to_degrees() - 227.89).abs() < 0.01);
+        assert!((oe.argp.to_degrees() - 53.38).abs() < 0.01);
+        assert!((oe.nu.to_degrees() - 92.335).abs() < 0.01);
+        assert_state_close(&sv, &oe.to_state(), 1e-12);
+    }
+
+    #[test]
+    fn test_elements_round_trip_all_conics() {
+        let mu = GM_KM;
+        let states = [
+            StateVector::new(7000.0, 0.0, 0.0, 0.0, 7.546, 0.0),     // circular equatorial
+            StateVector::new(7000.0, 0.0, 0.0, 0.0, 6.0, 5.0),       // inclined ellipse
+            StateVector::new(-5000.0, 3000.0, 1000.0, 1.0, -4.0, -6.0),
+            StateVector::new(7000.0, 0.0, 0.0, 0.0, -9.0, 0.3),      // near-retrograde ellipse
+            StateVector::new(7000.0, 100.0, 0.0, 0.0, 12.0, 2.0),    // hyperbola
+        ];
+        for sv in states {
+            let oe = OrbitalElements::from_state(&sv, mu);
+            assert_state_close(&sv, &oe.to_state(), 1e-10);
+            let eq = EquinoctialElements::from_state(&sv, mu);
+            assert_state_close(&sv, &eq.to_state(), 1e-10);
+            let via: OrbitalElements = EquinoctialElements::from(oe).into();
+            assert_state_close(&sv, &via.to_state(), 1e-10);
+        }
+        // Retrograde equatorial is singular for equinoctial elements only
+        let sv = StateVector::new(7000.0, 0.0, 0.0, 0.0, -9.0, 0.0);
+        let oe = OrbitalElements::from_state(&sv, mu);
+        assert!((oe.i - PI).abs() < 1e-12);
+        assert_state_close(&sv, &oe.to_state(), 1e-10);
+    }
+
+    #[test]
+    fn test_elements_orbit_characteristics() {
+        let mu = GM_KM;
+        let rp = 7000.0;
+        let ra = 14000.0;
+        let a: f64 = 0.5 * (rp + ra);
+        let vp = (mu * (2.0 / rp - 1.0 / a)).sqrt();
+        let oe = OrbitalElements::from_state(&StateVector::new(rp, 0.0, 0.0, 0.0, vp, 0.0), mu);
+        assert!((oe.perigee_radius() - rp).abs() < 1e-6);
+        assert!((oe.apogee_radius().unwrap() - ra).abs() < 1e-6);
+        assert!((oe.semi_major_axis() - a).abs() < 1e-6);
+        assert!((oe.energy() + mu / (2.0 * a)).abs() < 1e-9);
+        let period = oe.period().unwrap();
+        assert!((period - 2.0 * PI * (a * a * a / mu).sqrt()).abs() < 1e-6);
+        // Perigee to apogee is half a period and the return is the other half
+        assert!((oe.time_between(0.0, PI) - period / 2.0).abs() < 1e-6);
+        assert!((oe.time_between(PI, 0.0) - period / 2.0).abs() < 1e-6);
+        assert!(oe.flight_path_angle().abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_elements_propagation_matches_kepler1() {
+        let sv = StateVector::new(7000.0, 500.0, 1000.0, 1.0, 7.0, 2.5);
+        for dt in [100.0, 1500.0, 9000.0] {
+            let by_elements = propagate_state(&sv, dt, GM_KM);
+            let by_kepler = kepler1(&sv, 0.0, dt);
+            assert_state_close(&by_kepler, &by_elements, 1e-7);
+        }
+        // Parabolic time of flight agrees with propagation
+        let vesc = (2.0 * GM_KM / 7000.0).sqrt();
+        let oe = OrbitalElements::from_state(&StateVector::new(7000.0, 0.0, 0.0, 0.0, vesc, 0.0), GM_KM);
+        assert!(oe.is_parabolic() || (oe.e - 1.0).abs() < 1e-9);
+        let t = oe.time_between(0.0, 1.0);
+        assert!((oe.propagate(t).nu - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,428 @@
+//! Worst-case evasive maneuvers from the adjoint
+//!
+//! C20L2 and C29L5 run the adjoint of a single-lag proportional navigation
+//! loop to see how a target displacement or weave maps into miss. Here the
+//! adjoint of any linear homing loop (PN through a flight control transfer
+//! function) gives the miss per unit target acceleration impulse at each
+//! time to go. With an acceleration budget XNT the miss is largest when the
+//! target flies bang-bang with the sign of that impulse response, or weaves
+//! at the frequency where its Fourier transform peaks. Both answers can be
+//! checked in a nonlinear two-dimensional engagement.
+
+use super::complex::Complex;
+use super::lti::{StateSpace, TransferFunction};
+use super::matrix::{mat_vec_mul, transpose, zeros, Matrix};
+use super::targets::{TargetManeuver, Weave};
+
+/// Proportional navigation homing loop with a flight control system
+#[derive(Debug, Clone)]
+pub struct HomingLoop {
+    /// Effective navigation ratio
+    pub xnp: f64,
+    /// Achieved per commanded acceleration
+    pub flight_control: TransferFunction,
+}
+
+impl HomingLoop {
+    pub fn new(xnp: f64, flight_control: TransferFunction) -> Self {
+        Self { xnp, flight_control }
+    }
+
+    /// Single time constant guidance system of C20L2 and C29L5
+    pub fn single_lag(xnp: f64, tau: f64) -> Self {
+        Self::new(xnp, TransferFunction::lag(tau))
+    }
+
+    /// Linear engagement matrix for states [y, yd, flight control states]
+    fn system_matrix(&self, fcs: &StateSpace, tgo: f64) -> Matrix {
+        let nf = fcs.n_states();
+        let mut a = zeros(nf + 2, nf + 2);
+        let (cy, cyd) = (self.xnp / (tgo * tgo), self.xnp / tgo);
+        let d = fcs.d[0][0];
+        a[0][1] = 1.0;
+        a[1][0] = -d * cy;
+        a[1][1] = -d * cyd;
+        for j in 0..nf {
+            a[1][j + 2] = -fcs.c[0][j];
+            a[j + 2][0] = fcs.b[j][0] * cy;
+            a[j + 2][1] = fcs.b[j][0] * cyd;
+            for k in 0..nf {
+                a[j + 2][k + 2] = fcs.a[j][k];
+            }
+        }
+        a
+    }
+
+    /// Integrate the adjoint from tgo = 0 to `tf`, sampled every `h`
+    ///
+    /// The PN gains grow as 1/tgo^2 near intercept, so each sample interval
+    /// is covered with RK2 steps no larger than 5% of the time to go.
+    pub fn adjoint(&self, tf: f64, h: f64) -> AdjointResponse {
+        let fcs = self.flight_control.to_state_space();
+        let n = fcs.n_states() + 2;
+        let mut x = vec![0.0; n];
+        x[0] = 1.0;
+        let mut tp: f64 = 0.00001;
+
+        let mut array_tgo = vec![0.0];
+        let mut array_impulse = vec![x[1]];
+        let mut array_step = vec![0.0];
+
+        let deriv = |tp: f64, x: &[f64]| mat_vec_mul(&transpose(&self.system_matrix(&fcs, tp)), x);
+
+        let mut k = 1;
+        while (k as f64) * h <= tf + 0.00001 {
+            let tnext = k as f64 * h;
+            while tp < tnext - 1e-12 {
+                let dt = (0.05 * tp).min(tnext - tp);
+                let xold = x.clone();
+                let xd = deriv(tp, &x);
+                for (xi, d) in x.iter_mut().zip(&xd) {
+                    *xi += dt * d;
+                }
+                tp += dt;
+                let xd = deriv(tp, &x);
+                for ((xi, xo), d) in x.iter_mut().zip(&xold).zip(&xd) {
+                    *xi = 0.5 * (xo + *xi + dt * d);
+                }
+            }
+
+            let last = array_impulse[array_impulse.len() - 1];
+            let step = array_step[array_step.len() - 1] + 0.5 * h * (last + x[1]);
+            array_tgo.push(tnext);
+            array_impulse.push(x[1]);
+            array_step.push(step);
+            k += 1;
+        }
+
+        // A target displacement also steps the measured line of sight, so its
+        // miss is the slope of the impulse response (C20L2 X3), not the y adjoint
+        let m = array_impulse.len();
+        let array_disp: Vec<f64> = (0..m)
+            .map(|i| {
+                let (lo, hi) = (i.saturating_sub(1), (i + 1).min(m - 1));
+                (array_impulse[hi] - array_impulse[lo]) / (array_tgo[hi] - array_tgo[lo])
+            })
+            .collect();
+
+        AdjointResponse {
+            h,
+            tgo: array_tgo,
+            displacement: array_disp,
+            impulse: array_impulse,
+            step: array_step,
+        }
+    }
+}
+
+/// Adjoint miss sensitivities versus time to go
+#[derive(Debug, Clone)]
+pub struct AdjointResponse {
+    pub h: f64,
+    pub tgo: Vec<f64>,
+    /// Miss per unit target displacement (C20L2 X3)
+    pub displacement: Vec<f64>,
+    /// Miss per unit target acceleration impulse
+    pub impulse: Vec<f64>,
+    /// Miss per unit target step maneuver (C29L5 X1)
+    pub step: Vec<f64>,
+}
+
+impl AdjointResponse {
+    /// Flight time covered by the response
+    pub fn tf(&self) -> f64 {
+        self.tgo[self.tgo.len() - 1]
+    }
+
+    /// Impulse response at flight time t for a flight of length tf
+    fn impulse_at_flight_time(&self, i: usize) -> f64 {
+        self.impulse[self.impulse.len() - 1 - i]
+    }
+
+    /// Miss predicted for an arbitrary maneuver over the full flight time
+    pub fn miss(&self, target: &dyn TargetManeuver) -> f64 {
+        let n = self.impulse.len();
+        (0..n)
+            .map(|i| {
+                let w = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+                w * self.h * self.impulse_at_flight_time(i) * target.accel(i as f64 * self.h)
+            })
+            .sum()
+    }
+}
+
+/// Piecewise constant +/-XNT maneuver with the given reversal times
+#[derive(Debug, Clone)]
+pub struct BangBang {
+    /// Initial acceleration, sign included
+    pub xnt: f64,
+    pub switch_times: Vec<f64>,
+    /// Adjoint miss prediction
+    pub predicted_miss: f64,
+}
+
+impl TargetManeuver for BangBang {
+    fn accel(&self, t: f64) -> f64 {
+        let n = self.switch_times.partition_point(|&ts| ts <= t);
+        if n % 2 == 0 {
+            self.xnt
+        } else {
+            -self.xnt
+        }
+    }
+
+    fn jerk(&self, _t: f64) -> f64 {
+        0.0
+    }
+}
+
+/// Bang-bang maneuver with at most `max_switches` reversals maximizing |miss|
+///
+/// Without a limit the target reverses at every zero crossing of the
+/// impulse response; with one the best sign sequence is found by dynamic
+/// programming over the adjoint grid.
+pub fn worst_bang_bang(resp: &AdjointResponse, xnt: f64, max_switches: Option<usize>) -> BangBang {
+    let n = resp.impulse.len();
+    let w: Vec<f64> = (0..n)
+        .map(|i| {
+            let c = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+            c * resp.h * resp.impulse_at_flight_time(i)
+        })
+        .collect();
+    let k = match max_switches {
+        Some(k) => k,
+        None => {
+            let sign: Vec<f64> = w.iter().map(|&wi| if wi < 0.0 { -1.0 } else { 1.0 }).collect();
+            let switch_times = (1..n).filter(|&i| sign[i] != sign[i - 1]).map(|i| i as f64 * resp.h).collect();
+            let predicted_miss = xnt * w.iter().map(|wi| wi.abs()).sum::<f64>();
+            return BangBang { xnt: sign[0] * xnt, switch_times, predicted_miss };
+        }
+    };
+
+    // best[c][s]: largest sum with c reversals so far, current sign s (0 = +, 1 = -)
+    let sgn = [1.0, -1.0];
+    let mut best = vec![[f64::NEG_INFINITY; 2]; k + 1];
+    let mut from: Vec<Vec<[bool; 2]>> = Vec::with_capacity(n);
+    best[0][0] = w[0];
+    best[0][1] = -w[0];
+    from.push(vec![[false; 2]; k + 1]);
+    for wi in w.iter().skip(1) {
+        let mut next = vec![[f64::NEG_INFINITY; 2]; k + 1];
+        let mut switched = vec![[false; 2]; k + 1];
+        for c in 0..=k {
+            for s in 0..2 {
+                let stay = best[c][s];
+                let flip = if c > 0 { best[c - 1][1 - s] } else { f64::NEG_INFINITY };
+                let (v, sw) = if flip > stay { (flip, true) } else { (stay, false) };
+                next[c][s] = v + sgn[s] * wi;
+                switched[c][s] = sw;
+            }
+        }
+        best = next;
+        from.push(switched);
+    }
+
+    let (mut c, mut s, mut val) = (0, 0, f64::NEG_INFINITY);
+    for (ci, b) in best.iter().enumerate() {
+        for (si, &v) in b.iter().enumerate() {
+            if v > val {
+                (c, s, val) = (ci, si, v);
+            }
+        }
+    }
+
+    let mut switch_times = Vec::new();
+    for i in (1..n).rev() {
+        if from[i][c][s] {
+            switch_times.push(i as f64 * resp.h);
+            c -= 1;
+            s = 1 - s;
+        }
+    }
+    switch_times.reverse();
+
+    BangBang { xnt: sgn[s] * xnt, switch_times, predicted_miss: xnt * val }
+}
+
+/// Worst sinusoidal weave and the miss it produces
+#[derive(Debug, Clone, Copy)]
+pub struct WorstWeave {
+    pub weave: Weave,
+    pub predicted_miss: f64,
+}
+
+/// Weave frequency and phase among `freqs` maximizing |miss|
+pub fn worst_weave(resp: &AdjointResponse, xnt: f64, freqs: &[f64]) -> WorstWeave {
+    let n = resp.impulse.len();
+    let mut best = WorstWeave { weave: Weave::new(xnt, 0.0), predicted_miss: f64::NEG_INFINITY };
+    for &w in freqs {
+        let mut c = Complex::real(0.0);
+        for i in 0..n {
+            let t = i as f64 * resp.h;
+            let wt = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+            c = c + Complex::from_polar(wt * resp.h * resp.impulse_at_flight_time(i), w * t);
+        }
+        let miss = xnt * c.abs();
+        if miss > best.predicted_miss {
+            let phase = std::f64::consts::FRAC_PI_2 - c.arg();
+            best = WorstWeave { weave: Weave::new(xnt, w).with_phase(phase), predicted_miss: miss };
+        }
+    }
+    best
+}
+
+/// Head-on engagement used to check adjoint predictions
+#[derive(Debug, Clone, Copy)]
+pub struct Engagement {
+    pub vm: f64,
+    pub vt: f64,
+    pub tf: f64,
+}
+
+/// Miss distance of the nonlinear two-dimensional engagement
+///
+/// The missile flies PN with the exact line-of-sight rate through the
+/// flight control system; the target turns with the given maneuver.
+pub fn nonlinear_miss(homing: &HomingLoop, eng: &Engagement, target: &dyn TargetManeuver) -> f64 {
+    let fcs = homing.flight_control.to_state_space();
+    let nf = fcs.n_states();
+    let (vm, vt) = (eng.vm, eng.vt);
+
+    let mut rm1: f64 = 0.0;
+    let mut rm2: f64 = 0.0;
+    let mut rt1: f64 = (vm + vt) * eng.tf;
+    let mut rt2: f64 = 0.0;
+    let mut vm1: f64 = vm;
+    let mut vm2: f64 = 0.0;
+    let mut beta: f64 = 0.0;
+    let mut xf = vec![0.0; nf];
+    let mut t: f64 = 0.0;
+    let mut h: f64 = 0.01;
+
+    // Derivatives: (betad, vt1, vt2, am1, am2, xfd, vc, rtm)
+    let deriv = |t: f64, beta: f64, rt: (f64, f64), rm: (f64, f64), vmv: (f64, f64), xf: &[f64]| {
+        let vt1 = -vt * beta.cos();
+        let vt2 = vt * beta.sin();
+        let betad = target.accel(t) / vt;
+        let rtm1 = rt.0 - rm.0;
+        let rtm2 = rt.1 - rm.1;
+        let rtm = (rtm1 * rtm1 + rtm2 * rtm2).sqrt();
+        let vtm1 = vt1 - vmv.0;
+        let vtm2 = vt2 - vmv.1;
+        let vc = -(rtm1 * vtm1 + rtm2 * vtm2) / rtm;
+        let xlam = rtm2.atan2(rtm1);
+        let xlamd = (rtm1 * vtm2 - rtm2 * vtm1) / (rtm * rtm);
+        let xnc = homing.xnp * vc * xlamd;
+        let mut xnl = fcs.d[0][0] * xnc;
+        let mut xfd = vec![0.0; xf.len()];
+        for j in 0..xf.len() {
+            xnl += fcs.c[0][j] * xf[j];
+            xfd[j] = fcs.b[j][0] * xnc + (0..xf.len()).map(|k| fcs.a[j][k] * xf[k]).sum::<f64>();
+        }
+        let am1 = -xnl * xlam.sin();
+        let am2 = xnl * xlam.cos();
+        (betad, vt1, vt2, am1, am2, xfd, vc, rtm)
+    };
+
+    let mut vc = vm + vt;
+    let mut rtm = rt1;
+    let mut rtm2 = 0.0;
+    while vc > 0.0 {
+        if rtm < 1000.0 {
+            h = 0.0002;
+        }
+
+        let betaold = beta;
+        let (rt1old, rt2old, rm1old, rm2old) = (rt1, rt2, rm1, rm2);
+        let (vm1old, vm2old) = (vm1, vm2);
+        let xfold = xf.clone();
+
+        // First derivative evaluation
+        let (betad, vt1, vt2, am1, am2, xfd, _, _) = deriv(t, beta, (rt1, rt2), (rm1, rm2), (vm1, vm2), &xf);
+
+        // Euler step
+        beta += h * betad;
+        rt1 += h * vt1;
+        rt2 += h * vt2;
+        rm1 += h * vm1;
+        rm2 += h * vm2;
+        vm1 += h * am1;
+        vm2 += h * am2;
+        for j in 0..nf {
+            xf[j] += h * xfd[j];
+        }
+        t += h;
+
+        // Second derivative for RK2
+        let (betad, vt1, vt2, am1, am2, xfd, _, _) = deriv(t, beta, (rt1, rt2), (rm1, rm2), (vm1, vm2), &xf);
+
+        // RK2 averaging
+        beta = 0.5 * (betaold + beta + h * betad);
+        rt1 = 0.5 * (rt1old + rt1 + h * vt1);
+        rt2 = 0.5 * (rt2old + rt2 + h * vt2);
+        rm1 = 0.5 * (rm1old + rm1 + h * vm1);
+        rm2 = 0.5 * (rm2old + rm2 + h * vm2);
+        vm1 = 0.5 * (vm1old + vm1 + h * am1);
+        vm2 = 0.5 * (vm2old + vm2 + h * am2);
+        for j in 0..nf {
+            xf[j] = 0.5 * (xfold[j] + xf[j] + h * xfd[j]);
+        }
+
+        let (_, _, _, _, _, _, vcn, rtmn) = deriv(t, beta, (rt1, rt2), (rm1, rm2), (vm1, vm2), &xf);
+        vc = vcn;
+        rtm = rtmn;
+        rtm2 = rt2 - rm2;
+    }
+
+    if rtm2 > 0.0 {
+        rtm
+    } else {
+        -rtm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::targets::Step;
+
+    #[test]
+    fn test_adjoint_matches_c20l2_theory() {
+        let resp = HomingLoop::single_lag(3.0, 1.0).adjoint(5.0, 0.01);
+        for (i, &tgo) in resp.tgo.iter().enumerate().step_by(50) {
+            let x = tgo;
+            let theory = (-x).exp() * (1.0 - 2.0 * x + 0.5 * x * x);
+            assert!((resp.displacement[i] - theory).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_bang_bang_beats_step() {
+        let homing = HomingLoop::single_lag(3.0, 1.0);
+        let resp = homing.adjoint(10.0, 0.01);
+        let step = resp.miss(&Step::new(96.6)).abs();
+        let one = worst_bang_bang(&resp, 96.6, Some(1));
+        let free = worst_bang_bang(&resp, 96.6, None);
+        assert_eq!(one.switch_times.len(), 1);
+        assert!(one.predicted_miss > step);
+        assert!(free.predicted_miss >= one.predicted_miss - 1e-9);
+        assert!((resp.miss(&one) - one.predicted_miss).abs() < 1e-6 * one.predicted_miss);
+    }
+
+    #[test]
+    fn test_worst_maneuvers_verified_nonlinear() {
+        let homing = HomingLoop::single_lag(3.0, 1.0);
+        let eng = Engagement { vm: 3000.0, vt: 1000.0, tf: 5.0 };
+        let resp = homing.adjoint(eng.tf, 0.01);
+
+        let bb = worst_bang_bang(&resp, 32.2, Some(1));
+        let sim = nonlinear_miss(&homing, &eng, &bb);
+        assert!((sim - bb.predicted_miss).abs() < 0.1 * bb.predicted_miss);
+
+        let freqs: Vec<f64> = (1..=60).map(|i| 0.1 * i as f64).collect();
+        let ww = worst_weave(&resp, 32.2, &freqs);
+        assert!(ww.predicted_miss >= resp.miss(&Weave::new(32.2, 2.0)).abs());
+        let sim = nonlinear_miss(&homing, &eng, &ww.weave);
+        assert!((sim - ww.predicted_miss).abs() < 0.1 * ww.predicted_miss);
+    }
+}
@@ -0,0 +1,191 @@
+//! Angle-only (bearings-only) tracking in modified polar coordinates
+//!
+//! A passive seeker measures only the line-of-sight angle. In Cartesian
+//! coordinates the range states of such a filter are unobservable until the
+//! missile maneuvers and the filter diverges. Modified polar coordinates
+//! (Aidala and Hammel)
+//!
+//!   y = [LAM, LAMD, RTMD/RTM, 1/RTM]
+//!
+//! separate the observable LOS angle, LOS rate and normalized closing rate
+//! from the inverse range, which only becomes observable through missile
+//! acceleration. The target is assumed non-maneuvering apart from white
+//! acceleration noise; missile acceleration is the known input.
+
+use super::ekf::{Ekf, EkfModel, numerical_jacobian};
+use crate::utils::matrix::{Matrix, diag, mat_mul, transpose, zeros};
+
+/// Bearings-only model in modified polar coordinates
+#[derive(Debug, Clone, Copy)]
+pub struct ModifiedPolar {
+    /// Target acceleration spectral density per axis (ft^2/sec^3)
+    pub phis: f64,
+    /// One-sigma angle measurement noise (rad)
+    pub sigma: f64,
+}
+
+impl ModifiedPolar {
+    pub fn new(phis: f64, sigma: f64) -> Self {
+        Self { phis, sigma }
+    }
+
+    /// Relative position and velocity [RTM1, RTM2, VTM1, VTM2]
+    pub fn to_cartesian(y: &[f64]) -> Vec<f64> {
+        let r = 1.0 / y[3];
+        let (s, c) = y[0].sin_cos();
+        vec![r * c, r * s, r * (y[2] * c - y[1] * s), r * (y[2] * s + y[1] * c)]
+    }
+
+    pub fn from_cartesian(c: &[f64]) -> Vec<f64> {
+        let r2 = c[0] * c[0] + c[1] * c[1];
+        vec![
+            c[1].atan2(c[0]),
+            (c[0] * c[3] - c[1] * c[2]) / r2,
+            (c[0] * c[2] + c[1] * c[3]) / r2,
+            1.0 / r2.sqrt(),
+        ]
+    }
+
+    /// Initial estimate and covariance from a cued LOS angle and a guess of
+    /// range and closing velocity with relative one-sigma `range_ratio`
+    pub fn initial(&self, los: f64, range: f64, vc: f64, range_ratio: f64, los_rate_sigma: f64) -> (Vec<f64>, Matrix) {
+        let x = vec![los, 0.0, -vc / range, 1.0 / range];
+        let p = diag(&[
+            self.sigma * self.sigma,
+            los_rate_sigma * los_rate_sigma,
+            (range_ratio * vc / range).powi(2),
+            (range_ratio / range).powi(2),
+        ]);
+        (x, p)
+    }
+}
+
+impl EkfModel for ModifiedPolar {
+    fn n_states(&self) -> usize {
+        4
+    }
+
+    /// Exact propagation through Cartesian coordinates with the missile
+    /// acceleration `u` = [AM1, AM2] held over the step
+    fn propagate(&self, x: &[f64], u: &[f64], ts: f64) -> Vec<f64> {
+        let mut c = Self::to_cartesian(x);
+        let am = if u.is_empty() { [0.0, 0.0] } else { [u[0], u[1]] };
+        for k in 0..2 {
+            c[k] += ts * c[k + 2] - 0.5 * ts * ts * am[k];
+            c[k + 2] -= ts * am[k];
+        }
+        Self::from_cartesian(&c)
+    }
+
+    fn transition(&self, x: &[f64], u: &[f64], ts: f64) -> Matrix {
+        let dx = [
+            1e-7,
+            1e-9 + 1e-6 * x[1].abs(),
+            1e-9 + 1e-6 * x[2].abs(),
+            1e-6 * x[3].abs(),
+        ];
+        numerical_jacobian(|y| self.propagate(y, u, ts), x, &dx)
+    }
+
+    /// Cartesian white-acceleration process noise mapped into modified
+    /// polar coordinates
+    fn process_noise(&self, x: &[f64], ts: f64) -> Matrix {
+        let c = Self::to_cartesian(x);
+        let dc: Vec<f64> = c.iter().map(|v| 1e-6 * v.abs().max(1.0)).collect();
+        let g = numerical_jacobian(Self::from_cartesian, &c, &dc);
+        let mut q = zeros(4, 4);
+        for k in 0..2 {
+            q[k][k] = self.phis * ts.powi(3) / 3.0;
+            q[k][k + 2] = self.phis * ts * ts / 2.0;
+            q[k + 2][k] = q[k][k + 2];
+            q[k + 2][k + 2] = self.phis * ts;
+        }
+        mat_mul(&mat_mul(&g, &q), &transpose(&g))
+    }
+
+    fn measure(&self, x: &[f64]) -> Vec<f64> {
+        vec![x[0]]
+    }
+
+    fn measurement_jacobian(&self, _x: &[f64]) -> Matrix {
+        vec![vec![1.0, 0.0, 0.0, 0.0]]
+    }
+
+    fn measurement_noise(&self, _x: &[f64]) -> Matrix {
+        vec![vec![self.sigma * self.sigma]]
+    }
+
+    fn residual(&self, z: &[f64], zh: &[f64]) -> Vec<f64> {
+        let d = z[0] - zh[0];
+        vec![d.sin().atan2(d.cos())]
+    }
+}
+
+/// Observability metric: relative one-sigma range uncertainty, the standard
+/// deviation of 1/RTM over its estimate. Stays near its initial value while
+/// the geometry is unobservable and falls as missile maneuvers reveal range.
+pub fn range_uncertainty(ekf: &Ekf<ModifiedPolar>) -> f64 {
+    ekf.sigma(3) / ekf.x[3].abs()
+}
+
+/// Estimated range (ft)
+pub fn range_estimate(ekf: &Ekf<ModifiedPolar>) -> f64 {
+    1.0 / ekf.x[3]
+}
+
+/// Estimated closing velocity (ft/sec)
+pub fn closing_velocity_estimate(ekf: &Ekf<ModifiedPolar>) -> f64 {
+    -ekf.x[2] / ekf.x[3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand_distr::{Distribution, Normal};
+
+    #[test]
+    fn test_coordinate_round_trip() {
+        let c = [30000.0, 5000.0, -3500.0, 400.0];
+        let y = ModifiedPolar::from_cartesian(&c);
+        let back = ModifiedPolar::to_cartesian(&y);
+        for (a, b) in c.iter().zip(back.iter()) {
+            assert!((a - b).abs() < 1e-8 * a.abs().max(1.0));
+        }
+    }
+
+    /// Track a constant velocity target; `weave` is the missile acceleration
+    /// amplitude (ft/sec^2) used to make range observable
+    fn track(weave: f64) -> (f64, f64) {
+        let model = ModifiedPolar::new(1.0, 0.001);
+        let mut rel: [f64; 4] = [30000.0, 3000.0, -3000.0, 100.0];
+        let ts = 0.1;
+        let los0 = rel[1].atan2(rel[0]);
+        let (x0, p0) = model.initial(los0, 45000.0, 3000.0, 0.5, 0.01);
+        let mut ekf = Ekf::new(model, x0, p0);
+        let mut rng = StdRng::seed_from_u64(5);
+        let noise = Normal::new(0.0, 0.001).unwrap();
+        let initial = range_uncertainty(&ekf);
+        for k in 0..60 {
+            let am = [0.0, weave * (0.5 * k as f64 * ts).sin()];
+            for i in 0..2 {
+                rel[i] += ts * rel[i + 2] - 0.5 * ts * ts * am[i];
+                rel[i + 2] -= ts * am[i];
+            }
+            ekf.predict(&am, ts);
+            ekf.update(&[rel[1].atan2(rel[0]) + noise.sample(&mut rng)]);
+        }
+        let r = (rel[0] * rel[0] + rel[1] * rel[1]).sqrt();
+        (range_uncertainty(&ekf) / initial, (range_estimate(&ekf) - r).abs() / r)
+    }
+
+    #[test]
+    fn test_maneuver_makes_range_observable() {
+        let (still, _) = track(0.0);
+        let (weaving, err) = track(322.0);
+        assert!(still > 0.8);
+        assert!(weaving < 0.1);
+        assert!(err < 0.05);
+    }
+}
@@ -0,0 +1,319 @@
+//! Filter consistency diagnostics
+//!
+//! The Kalman lessons compare the actual estimation error with the filter's
+//! own sigma, which shows a mistuned filter only by eye. A consistent filter
+//! satisfies three statistical tests (Bar-Shalom, Li and Kirubarajan):
+//!
+//! - NEES, e' P^-1 e, is chi-square with n (states) degrees of freedom
+//! - NIS, r' S^-1 r, is chi-square with m (measurements) degrees of freedom
+//! - the normalized residuals are white, tested with the Box-Pierce
+//!   portmanteau statistic over the first few autocorrelation lags
+//!
+//! Averaged over N Monte Carlo runs, N times the mean NEES at each sample is
+//! chi-square with N n degrees of freedom, giving a two-sided acceptance
+//! interval. Too small a process noise drives NEES above the interval; too
+//! large a process noise drives it below.
+
+use super::ekf::Innovation;
+use crate::utils::matrix::{Matrix, mat_inv, mat_vec_mul};
+
+/// Fraction of samples, below the confidence level, allowed to fall
+/// outside the acceptance interval before a check fails. Run averages at
+/// neighbouring samples are strongly correlated, so the fraction inside
+/// scatters far more than independent samples would.
+const SLACK: f64 = 0.10;
+
+/// Normalized estimation error squared e' P^-1 e
+pub fn nees(error: &[f64], p: &Matrix) -> f64 {
+    quadratic(error, p)
+}
+
+/// Normalized innovation squared r' S^-1 r
+pub fn nis(inn: &Innovation) -> f64 {
+    quadratic(&inn.residual, &inn.s)
+}
+
+fn quadratic(v: &[f64], m: &Matrix) -> f64 {
+    let inv = mat_inv(m).expect("singular covariance");
+    v.iter().zip(mat_vec_mul(&inv, v)).map(|(a, b)| a * b).sum()
+}
+
+/// Standard normal quantile (Acklam's rational approximation, relative
+/// error below 1.2e-9)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    let tail = |q: f64| {
+        let q = (-2.0 * q.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < 0.02425 {
+        tail(p)
+    } else if p > 1.0 - 0.02425 {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Chi-square quantile with `dof` degrees of freedom by the Wilson-Hilferty
+/// cube-root approximation
+pub fn chi_square_quantile(p: f64, dof: f64) -> f64 {
+    let a = 2.0 / (9.0 * dof);
+    dof * (1.0 - a + normal_quantile(p) * a.sqrt()).powi(3).max(0.0)
+}
+
+/// Consistency statistics of one Monte Carlo run
+#[derive(Debug, Clone, Default)]
+pub struct Trial {
+    pub nees: Vec<f64>,
+    pub nis: Vec<f64>,
+    /// Residuals divided by their predicted sigma, one vector per sample
+    pub residuals: Vec<Vec<f64>>,
+    pub n_states: usize,
+    pub n_meas: usize,
+}
+
+impl Trial {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one sample: true estimation error `error` with filter
+    /// covariance `p`, and the innovation of the update
+    pub fn record(&mut self, error: &[f64], p: &Matrix, inn: &Innovation) {
+        self.n_states = error.len();
+        self.n_meas = inn.residual.len();
+        self.nees.push(nees(error, p));
+        self.nis.push(nis(inn));
+        self.residuals.push(inn.residual.iter().enumerate().map(|(i, r)| r / inn.s[i][i].sqrt()).collect());
+    }
+}
+
+/// One chi-square check of a statistic averaged over the runs
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// Average over the runs at each sample
+    pub average: Vec<f64>,
+    /// Mean over all samples and runs
+    pub mean: f64,
+    /// Acceptance interval for the run average
+    pub bounds: (f64, f64),
+    pub fraction_inside: f64,
+    pub passed: bool,
+}
+
+impl Check {
+    fn new(series: Vec<&[f64]>, dof: usize, confidence: f64) -> Self {
+        let runs = series.len() as f64;
+        let samples = series.iter().map(|s| s.len()).min().unwrap_or(0);
+        let average: Vec<f64> = (0..samples).map(|k| series.iter().map(|s| s[k]).sum::<f64>() / runs).collect();
+        let mean = average.iter().sum::<f64>() / samples.max(1) as f64;
+
+        let total = runs * dof as f64;
+        let bounds = (
+            chi_square_quantile(0.5 * (1.0 - confidence), total) / runs,
+            chi_square_quantile(0.5 * (1.0 + confidence), total) / runs,
+        );
+        let inside = average.iter().filter(|&&a| a >= bounds.0 && a <= bounds.1).count();
+        let fraction_inside = inside as f64 / samples.max(1) as f64;
+        Self { average, mean, bounds, fraction_inside, passed: fraction_inside >= confidence - SLACK }
+    }
+}
+
+/// Consistency of a filter over a set of Monte Carlo runs
+#[derive(Debug, Clone)]
+pub struct ConsistencyReport {
+    pub runs: usize,
+    pub confidence: f64,
+    pub nees: Check,
+    pub nis: Check,
+    /// Normalized residual autocorrelation at lags 1, 2, ..., pooled over
+    /// runs and measurement components
+    pub autocorrelation: Vec<f64>,
+    /// Per-lag acceptance bound on |autocorrelation| for white residuals
+    pub lag_bound: f64,
+    /// Box-Pierce statistic n sum(rho^2), chi-square with one degree of
+    /// freedom per lag for white residuals
+    pub portmanteau: f64,
+    pub portmanteau_bound: f64,
+    pub whiteness_passed: bool,
+    pub passed: bool,
+}
+
+/// Run the NEES, NIS and whiteness tests at two-sided `confidence` with
+/// residual autocorrelation out to `max_lag` samples
+pub fn analyze(trials: &[Trial], confidence: f64, max_lag: usize) -> ConsistencyReport {
+    assert!(!trials.is_empty(), "consistency analysis needs at least one run");
+    let nees = Check::new(trials.iter().map(|t| t.nees.as_slice()).collect(), trials[0].n_states, confidence);
+    let nis = Check::new(trials.iter().map(|t| t.nis.as_slice()).collect(), trials[0].n_meas, confidence);
+
+    let mut pairs = 0usize;
+    let autocorrelation: Vec<f64> = (1..=max_lag)
+        .map(|lag| {
+            let (mut cross, mut e0, mut e1) = (0.0, 0.0, 0.0);
+            let mut n = 0;
+            for t in trials {
+                for k in lag..t.residuals.len() {
+                    for (a, b) in t.residuals[k - lag].iter().zip(&t.residuals[k]) {
+                        cross += a * b;
+                        e0 += a * a;
+                        e1 += b * b;
+                        n += 1;
+                    }
+                }
+            }
+            pairs = if pairs == 0 { n } else { pairs.min(n) };
+            cross / (e0 * e1).sqrt().max(f64::MIN_POSITIVE)
+        })
+        .collect();
+
+    let lag_bound = normal_quantile(0.5 * (1.0 + confidence)) / (pairs.max(1) as f64).sqrt();
+    let portmanteau = pairs as f64 * autocorrelation.iter().map(|r| r * r).sum::<f64>();
+    let portmanteau_bound = chi_square_quantile(confidence, max_lag as f64);
+    let whiteness_passed = portmanteau <= portmanteau_bound;
+
+    ConsistencyReport {
+        runs: trials.len(),
+        confidence,
+        passed: nees.passed && nis.passed && whiteness_passed,
+        nees,
+        nis,
+        autocorrelation,
+        lag_bound,
+        portmanteau,
+        portmanteau_bound,
+        whiteness_passed,
+    }
+}
+
+impl ConsistencyReport {
+    /// Multi-line pass/fail summary
+    pub fn summary(&self) -> String {
+        let verdict = |ok: bool| if ok { "PASS" } else { "FAIL" };
+        let check = |name: &str, c: &Check| {
+            format!(
+                "{}: mean {:.3}, {:.1}% of samples inside [{:.3}, {:.3}] {}",
+                name,
+                c.mean,
+                100.0 * c.fraction_inside,
+                c.bounds.0,
+                c.bounds.1,
+                verdict(c.passed)
+            )
+        };
+        [
+            format!("{} runs at {:.0}% confidence", self.runs, 100.0 * self.confidence),
+            check("NEES", &self.nees),
+            check("NIS", &self.nis),
+            format!(
+                "Whiteness: portmanteau {:.1} over {} lags, bound {:.1} {}",
+                self.portmanteau,
+                self.autocorrelation.len(),
+                self.portmanteau_bound,
+                verdict(self.whiteness_passed)
+            ),
+            format!("Overall: {}", verdict(self.passed)),
+        ]
+        .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::filters::ekf::{Ekf, EkfModel};
+    use crate::utils::matrix::{diag, zeros};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand_distr::{Distribution, Normal};
+
+    #[test]
+    fn test_quantiles() {
+        assert!((normal_quantile(0.975) - 1.959964).abs() < 1e-6);
+        assert!((normal_quantile(0.001) + 3.090232).abs() < 1e-6);
+        // Table values chi2(0.975, 100) = 129.561, chi2(0.025, 100) = 74.222
+        assert!((chi_square_quantile(0.975, 100.0) - 129.561).abs() < 0.1);
+        assert!((chi_square_quantile(0.025, 100.0) - 74.222).abs() < 0.1);
+    }
+
+    /// Scalar random walk observed directly, truth noise `q_true`
+    struct RandomWalk {
+        q: f64,
+    }
+
+    impl EkfModel for RandomWalk {
+        fn n_states(&self) -> usize {
+            1
+        }
+        fn propagate(&self, x: &[f64], _u: &[f64], _ts: f64) -> Vec<f64> {
+            x.to_vec()
+        }
+        fn transition(&self, _x: &[f64], _u: &[f64], _ts: f64) -> Matrix {
+            diag(&[1.0])
+        }
+        fn process_noise(&self, _x: &[f64], _ts: f64) -> Matrix {
+            diag(&[self.q])
+        }
+        fn measure(&self, x: &[f64]) -> Vec<f64> {
+            x.to_vec()
+        }
+        fn measurement_jacobian(&self, _x: &[f64]) -> Matrix {
+            diag(&[1.0])
+        }
+        fn measurement_noise(&self, _x: &[f64]) -> Matrix {
+            diag(&[1.0])
+        }
+    }
+
+    fn monte_carlo(q_filter: f64) -> ConsistencyReport {
+        let q_true: f64 = 1.0;
+        let mut rng = StdRng::seed_from_u64(3);
+        let unit = Normal::new(0.0, 1.0).unwrap();
+        let trials: Vec<Trial> = (0..50)
+            .map(|_| {
+                let mut x = 0.0;
+                let mut ekf = Ekf::new(RandomWalk { q: q_filter }, vec![0.0], zeros(1, 1));
+                let mut trial = Trial::new();
+                for _ in 0..100 {
+                    x += q_true.sqrt() * unit.sample(&mut rng);
+                    ekf.predict(&[], 1.0);
+                    let inn = ekf.update(&[x + unit.sample(&mut rng)]);
+                    trial.record(&[x - ekf.x[0]], &ekf.p, &inn);
+                }
+                trial
+            })
+            .collect();
+        analyze(&trials, 0.95, 10)
+    }
+
+    #[test]
+    fn test_tuned_filter_is_consistent() {
+        let report = monte_carlo(1.0);
+        assert!(report.passed, "{}", report.summary());
+        assert!((report.nees.mean - 1.0).abs() < 0.1);
+        assert!(report.autocorrelation.iter().all(|r| r.abs() < 2.0 * report.lag_bound));
+    }
+
+    #[test]
+    fn test_mistuned_filter_fails() {
+        // Too little process noise: errors exceed the filter's covariance
+        // and the lagging estimate leaves correlated residuals
+        let low = monte_carlo(0.01);
+        assert!(!low.nees.passed && !low.whiteness_passed);
+        assert!(low.nees.mean > low.nees.bounds.1);
+
+        // Too much process noise: the filter follows the measurements, so with
+        // the state measured directly NEES still passes but the innovations
+        // are smaller than predicted and correlated from sample to sample
+        let high = monte_carlo(100.0);
+        assert!(high.nees.passed);
+        assert!(!high.nis.passed && !high.whiteness_passed);
+        assert!(high.nis.mean < high.nis.bounds.0);
+    }
+}
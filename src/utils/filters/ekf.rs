@@ -0,0 +1,171 @@
+//! Extended Kalman filter
+//!
+//! An `EkfModel` supplies the nonlinear propagation and measurement with
+//! their Jacobians; `Ekf` runs the predict/update cycle of C12L1 on general
+//! matrices. A linear filter is the special case where the Jacobians are
+//! constant.
+
+use crate::utils::matrix::{Matrix, identity, mat_add, mat_inv, mat_mul, mat_sub, mat_vec_mul, transpose, zeros};
+
+/// Dynamics and measurement of a tracked system
+pub trait EkfModel {
+    fn n_states(&self) -> usize;
+
+    /// State after `ts` seconds, given the known input `u` held over the step
+    fn propagate(&self, x: &[f64], u: &[f64], ts: f64) -> Vec<f64>;
+
+    /// Jacobian of `propagate` with respect to the state (PHI)
+    fn transition(&self, x: &[f64], u: &[f64], ts: f64) -> Matrix;
+
+    /// Discrete process noise matrix over `ts` (Q)
+    fn process_noise(&self, x: &[f64], ts: f64) -> Matrix;
+
+    /// Predicted measurement
+    fn measure(&self, x: &[f64]) -> Vec<f64>;
+
+    /// Jacobian of `measure` with respect to the state (H)
+    fn measurement_jacobian(&self, x: &[f64]) -> Matrix;
+
+    /// Measurement noise matrix (R)
+    fn measurement_noise(&self, x: &[f64]) -> Matrix;
+
+    /// Measurement residual; override to wrap angles
+    fn residual(&self, z: &[f64], zh: &[f64]) -> Vec<f64> {
+        z.iter().zip(zh).map(|(a, b)| a - b).collect()
+    }
+}
+
+/// Central difference Jacobian of `f` at `x` with per-state steps `dx`
+pub fn numerical_jacobian(f: impl Fn(&[f64]) -> Vec<f64>, x: &[f64], dx: &[f64]) -> Matrix {
+    let m = f(x).len();
+    let mut jac = zeros(m, x.len());
+    let mut xp = x.to_vec();
+    for j in 0..x.len() {
+        xp[j] = x[j] + dx[j];
+        let fp = f(&xp);
+        xp[j] = x[j] - dx[j];
+        let fm = f(&xp);
+        xp[j] = x[j];
+        for i in 0..m {
+            jac[i][j] = (fp[i] - fm[i]) / (2.0 * dx[j]);
+        }
+    }
+    jac
+}
+
+/// Measurement residual and its predicted covariance from one update
+#[derive(Debug, Clone)]
+pub struct Innovation {
+    pub residual: Vec<f64>,
+    /// H M H' + R
+    pub s: Matrix,
+}
+
+/// Extended Kalman filter state and covariance
+#[derive(Debug, Clone)]
+pub struct Ekf<M: EkfModel> {
+    pub model: M,
+    pub x: Vec<f64>,
+    pub p: Matrix,
+}
+
+impl<M: EkfModel> Ekf<M> {
+    pub fn new(model: M, x: Vec<f64>, p: Matrix) -> Self {
+        assert_eq!(x.len(), model.n_states(), "initial state has the wrong number of states");
+        Self { model, x, p }
+    }
+
+    /// Propagate estimate and covariance over `ts` (M = PHI P PHI' + Q)
+    pub fn predict(&mut self, u: &[f64], ts: f64) {
+        let phi = self.model.transition(&self.x, u, ts);
+        let q = self.model.process_noise(&self.x, ts);
+        self.x = self.model.propagate(&self.x, u, ts);
+        self.p = mat_add(&mat_mul(&mat_mul(&phi, &self.p), &transpose(&phi)), &q);
+    }
+
+    /// Incorporate measurement `z`; the covariance update uses the Joseph
+    /// form so it stays symmetric and positive definite
+    pub fn update(&mut self, z: &[f64]) -> Innovation {
+        let h = self.model.measurement_jacobian(&self.x);
+        let r = self.model.measurement_noise(&self.x);
+        let ht = transpose(&h);
+        let pht = mat_mul(&self.p, &ht);
+        let s = mat_add(&mat_mul(&h, &pht), &r);
+        let k = mat_mul(&pht, &mat_inv(&s).expect("singular innovation covariance"));
+
+        let residual = self.model.residual(z, &self.model.measure(&self.x));
+        let dx = mat_vec_mul(&k, &residual);
+        self.x.iter_mut().zip(dx).for_each(|(x, d)| *x += d);
+
+        let ikh = mat_sub(&identity(self.x.len()), &mat_mul(&k, &h));
+        let krk = mat_mul(&mat_mul(&k, &r), &transpose(&k));
+        self.p = mat_add(&mat_mul(&mat_mul(&ikh, &self.p), &transpose(&ikh)), &krk);
+
+        Innovation { residual, s }
+    }
+
+    /// One-sigma error of state `i`
+    pub fn sigma(&self, i: usize) -> f64 {
+        self.p[i][i].sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two-state polynomial filter measuring position (C9 with ORDER = 2)
+    struct ConstantVelocity {
+        sign: f64,
+    }
+
+    impl EkfModel for ConstantVelocity {
+        fn n_states(&self) -> usize {
+            2
+        }
+        fn propagate(&self, x: &[f64], _u: &[f64], ts: f64) -> Vec<f64> {
+            vec![x[0] + ts * x[1], x[1]]
+        }
+        fn transition(&self, _x: &[f64], _u: &[f64], ts: f64) -> Matrix {
+            vec![vec![1.0, ts], vec![0.0, 1.0]]
+        }
+        fn process_noise(&self, _x: &[f64], _ts: f64) -> Matrix {
+            zeros(2, 2)
+        }
+        fn measure(&self, x: &[f64]) -> Vec<f64> {
+            vec![x[0]]
+        }
+        fn measurement_jacobian(&self, _x: &[f64]) -> Matrix {
+            vec![vec![1.0, 0.0]]
+        }
+        fn measurement_noise(&self, _x: &[f64]) -> Matrix {
+            vec![vec![self.sign * self.sign]]
+        }
+    }
+
+    #[test]
+    fn test_matches_recursive_least_squares_gains() {
+        // With no process noise and infinite initial covariance the gains are
+        // the recursive least squares gains 2(2k-1)/(k(k+1)) and 6/(k(k+1) Ts)
+        let mut ekf = Ekf::new(ConstantVelocity { sign: 1.0 }, vec![0.0, 0.0], vec![vec![1e12, 0.0], vec![0.0, 1e12]]);
+        let ts = 0.1;
+        for k in 1..=10 {
+            if k > 1 {
+                ekf.predict(&[], ts);
+            }
+            let before = ekf.x.clone();
+            let inn = ekf.update(&[(k * k) as f64]);
+            let gain = (ekf.x[0] - before[0]) / inn.residual[0];
+            let kf = k as f64;
+            assert!((gain - 2.0 * (2.0 * kf - 1.0) / (kf * (kf + 1.0))).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_numerical_jacobian() {
+        let f = |x: &[f64]| vec![x[0] * x[1], x[0].sin()];
+        let j = numerical_jacobian(f, &[0.5, 2.0], &[1e-6, 1e-6]);
+        assert!((j[0][0] - 2.0).abs() < 1e-8 && (j[0][1] - 0.5).abs() < 1e-8);
+        assert!((j[1][0] - 0.5_f64.cos()).abs() < 1e-8 && j[1][1].abs() < 1e-12);
+    }
+}
@@ -0,0 +1,310 @@
+//! Fading-memory and alpha-beta-gamma polynomial filters
+//!
+//! C7, C14 and C27 expand the recursive polynomial filter by hand with gains
+//! from the memory parameter BETA. The filter of order n (1 to 3) tracks a
+//! polynomial of degree n-1 with no steady-state error:
+//!
+//!   RES = X* - (XH + TS XDH + 0.5 TS^2 XDDH)
+//!   XH  = XH + TS XDH + 0.5 TS^2 XDDH + G RES
+//!   XDH = XDH + TS XDDH + H RES / TS
+//!   XDDH = XDDH + 2 K RES / TS^2
+//!
+//! A fading-memory filter weights past measurements by BETA^n, which fixes
+//! G, H and K; an alpha-beta(-gamma) filter sets them independently. The
+//! steady-state variance reduction ratios (estimate variance over
+//! measurement noise variance) and the lag for the first unmodeled
+//! derivative follow from the gains alone, so a filter can be sized without
+//! running a simulation.
+
+use crate::utils::matrix::{Matrix, identity, mat_mul, solve, zeros};
+
+/// Polynomial filter gains in the notation of C7: position G, rate H/TS and
+/// acceleration 2K/TS^2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gains {
+    pub g: f64,
+    pub h: f64,
+    pub k: f64,
+}
+
+impl Gains {
+    /// Fading-memory gains of order 1 to 3 for memory parameter `beta`
+    pub fn fading_memory(order: usize, beta: f64) -> Self {
+        match order {
+            1 => Self { g: 1.0 - beta, h: 0.0, k: 0.0 },
+            2 => Self { g: 1.0 - beta * beta, h: (1.0 - beta).powi(2), k: 0.0 },
+            3 => Self {
+                g: 1.0 - beta.powi(3),
+                h: 1.5 * (1.0 - beta).powi(2) * (1.0 + beta),
+                k: 0.5 * (1.0 - beta).powi(3),
+            },
+            _ => panic!("fading-memory filters are of order 1 to 3"),
+        }
+    }
+
+    pub fn alpha_beta(alpha: f64, beta: f64) -> Self {
+        Self { g: alpha, h: beta, k: 0.0 }
+    }
+
+    /// Alpha-beta-gamma gains with the acceleration updated by
+    /// GAMMA RES / (2 TS^2)
+    pub fn alpha_beta_gamma(alpha: f64, beta: f64, gamma: f64) -> Self {
+        Self { g: alpha, h: beta, k: 0.25 * gamma }
+    }
+
+    /// Steady-state Kalman alpha-beta gains (Kalata) for tracking index
+    /// `lambda` = SIGMA_ACC TS^2 / SIGMA_NOISE, with piecewise constant
+    /// white acceleration of one-sigma SIGMA_ACC
+    pub fn tracking_index(lambda: f64) -> Self {
+        let r = (4.0 + lambda - (8.0 * lambda + lambda * lambda).sqrt()) / 4.0;
+        let alpha = 1.0 - r * r;
+        Self::alpha_beta(alpha, 2.0 * (2.0 - alpha) - 4.0 * (1.0 - alpha).sqrt())
+    }
+}
+
+/// Recursive polynomial filter of order 1 to 3
+#[derive(Debug, Clone)]
+pub struct PolynomialFilter {
+    pub order: usize,
+    pub gains: Gains,
+    pub ts: f64,
+    /// Estimates [XH, XDH, XDDH]; unused derivatives stay zero
+    pub x: [f64; 3],
+}
+
+impl PolynomialFilter {
+    pub fn new(order: usize, gains: Gains, ts: f64) -> Self {
+        assert!((1..=3).contains(&order), "polynomial filters are of order 1 to 3");
+        Self { order, gains, ts, x: [0.0; 3] }
+    }
+
+    pub fn fading_memory(order: usize, beta: f64, ts: f64) -> Self {
+        Self::new(order, Gains::fading_memory(order, beta), ts)
+    }
+
+    /// Incorporate measurement `z`, returning the residual
+    pub fn update(&mut self, z: f64) -> f64 {
+        self.update_with_input(z, 0.0)
+    }
+
+    /// Incorporate measurement `z` with a known acceleration `u` added to the
+    /// estimated one over the step (second and third order), as when the
+    /// missile acceleration enters the relative motion in C7L4
+    pub fn update_with_input(&mut self, z: f64, u: f64) -> f64 {
+        let ts = self.ts;
+        let acc = if self.order >= 2 { self.x[2] + u } else { 0.0 };
+        let (xp, xdp) = match self.order {
+            1 => (self.x[0], 0.0),
+            _ => (self.x[0] + ts * self.x[1] + 0.5 * ts * ts * acc, self.x[1] + ts * acc),
+        };
+        let res = z - xp;
+        self.x[0] = xp + self.gains.g * res;
+        if self.order >= 2 {
+            self.x[1] = xdp + self.gains.h * res / ts;
+        }
+        if self.order == 3 {
+            self.x[2] += 2.0 * self.gains.k * res / (ts * ts);
+        }
+        res
+    }
+}
+
+/// Transition and gain vector of the filter with unit sample time
+fn normalized(order: usize, gains: &Gains) -> (Matrix, Vec<f64>) {
+    let phi = [[1.0, 1.0, 0.5], [0.0, 1.0, 1.0], [0.0, 0.0, 1.0]];
+    let phi: Matrix = (0..order).map(|i| phi[i][..order].to_vec()).collect();
+    let kv = [gains.g, gains.h, 2.0 * gains.k];
+    (phi, kv[..order].to_vec())
+}
+
+/// Steady-state variance reduction ratios [position, rate TS^2,
+/// acceleration TS^4] over measurement noise variance, from the discrete
+/// Lyapunov equation P = F P F' + K K' with F = (I - K H) PHI
+pub fn variance_reduction(order: usize, gains: &Gains) -> Vec<f64> {
+    let (phi, kv) = normalized(order, gains);
+    let n = order;
+    let mut ikh = identity(n);
+    for i in 0..n {
+        ikh[i][0] -= kv[i];
+    }
+    let f = mat_mul(&ikh, &phi);
+
+    // (I - F kron F) vec(P) = vec(K K')
+    let mut a = zeros(n * n, n * n);
+    let mut b = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..n {
+            let row = i * n + j;
+            b[row] = kv[i] * kv[j];
+            for k in 0..n {
+                for l in 0..n {
+                    a[row][k * n + l] = if row == k * n + l { 1.0 } else { 0.0 } - f[i][k] * f[j][l];
+                }
+            }
+        }
+    }
+    let p = solve(&a, &b).expect("filter gains are unstable");
+    (0..n).map(|i| p[i * n + i]).collect()
+}
+
+/// Closed-form fading-memory variance reduction ratios [position, rate
+/// TS^2, acceleration TS^4] (Brookner, Tracking and Kalman Filtering Made Easy)
+pub fn fading_memory_vrr(order: usize, beta: f64) -> Vec<f64> {
+    let (b, c) = (beta, 1.0 - beta);
+    match order {
+        1 => vec![c / (1.0 + b)],
+        2 => vec![
+            c * (1.0 + 4.0 * b + 5.0 * b * b) / (1.0 + b).powi(3),
+            2.0 * c.powi(3) / (1.0 + b).powi(3),
+        ],
+        3 => vec![
+            c * (1.0 + 6.0 * b + 16.0 * b * b + 24.0 * b.powi(3) + 19.0 * b.powi(4)) / (1.0 + b).powi(5),
+            c.powi(3) * (13.0 + 50.0 * b + 49.0 * b * b) / (2.0 * (1.0 + b).powi(5)),
+            6.0 * c.powi(5) / (1.0 + b).powi(5),
+        ],
+        _ => panic!("fading-memory filters are of order 1 to 3"),
+    }
+}
+
+/// Steady-state position lag per unit of the first derivative the filter
+/// does not model, times TS^order: a ramp for order 1, a constant
+/// acceleration for order 2 and a constant jerk for order 3
+pub fn lag_error(order: usize, gains: &Gains) -> f64 {
+    let (phi, kv) = normalized(order, gains);
+    let n = order;
+    // Taylor remainder over one unit step of the unmodeled derivative
+    let delta: Vec<f64> = (0..n).map(|i| 1.0 / (1..=n - i).product::<usize>() as f64).collect();
+
+    // e = (I - K H)(PHI e + delta)
+    let mut ikh = identity(n);
+    for i in 0..n {
+        ikh[i][0] -= kv[i];
+    }
+    let f = mat_mul(&ikh, &phi);
+    let a: Matrix = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 } - f[i][j]).collect()).collect();
+    let rhs: Vec<f64> = (0..n).map(|i| (0..n).map(|j| ikh[i][j] * delta[j]).sum()).collect();
+    solve(&a, &rhs).expect("filter gains are unstable")[0]
+}
+
+/// Closed-form fading-memory lag per unit unmodeled derivative times TS^order
+pub fn fading_memory_lag(order: usize, beta: f64) -> f64 {
+    (beta / (1.0 - beta)).powi(order as i32)
+}
+
+/// Memory parameter giving position variance reduction ratio `vrr`
+pub fn beta_for_noise_reduction(order: usize, vrr: f64) -> f64 {
+    // The ratio falls monotonically from 1 at BETA = 0 to 0 at BETA = 1
+    bisect(|b| fading_memory_vrr(order, b)[0] - vrr)
+}
+
+/// Memory parameter giving position lag `lag` per unit unmodeled derivative
+/// (ft per ft/sec^order) at sample time `ts`
+pub fn beta_for_lag(order: usize, lag: f64, ts: f64) -> f64 {
+    let normalized = lag / ts.powi(order as i32);
+    bisect(|b| normalized - fading_memory_lag(order, b))
+}
+
+/// Root in (0, 1) of a function positive at 0 and negative at 1
+fn bisect(f: impl Fn(f64) -> f64) -> f64 {
+    let (mut lo, mut hi) = (0.0, 1.0 - 1e-12);
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if f(mid) > 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closed_forms_match_lyapunov() {
+        for order in 1..=3 {
+            for beta in [0.3, 0.6, 0.8, 0.95] {
+                let gains = Gains::fading_memory(order, beta);
+                let exact = variance_reduction(order, &gains);
+                let closed = fading_memory_vrr(order, beta);
+                for (a, b) in exact.iter().zip(&closed) {
+                    assert!((a - b).abs() < 1e-9 * b.max(1e-6), "order {} beta {}: {} vs {}", order, beta, a, b);
+                }
+                assert!((lag_error(order, &gains) - fading_memory_lag(order, beta)).abs() < 1e-9 * fading_memory_lag(order, beta));
+            }
+        }
+    }
+
+    #[test]
+    fn test_alpha_beta_formulas() {
+        // Benedict-Bordner steady state: VRR = (2 a^2 + b (2 - 3a)) / (a (4 - 2a - b)),
+        // lag per unit acceleration (1 - a) / b
+        let (a, b) = (0.5, 0.2);
+        let gains = Gains::alpha_beta(a, b);
+        let vrr = variance_reduction(2, &gains);
+        assert!((vrr[0] - (2.0 * a * a + b * (2.0 - 3.0 * a)) / (a * (4.0 - 2.0 * a - b))).abs() < 1e-12);
+        assert!((vrr[1] - 2.0 * b * b / (a * (4.0 - 2.0 * a - b))).abs() < 1e-12);
+        assert!((lag_error(2, &gains) - (1.0 - a) / b).abs() < 1e-12);
+
+        // Kalata: lambda^2 = b^2 / (1 - a)
+        let lambda = 0.7;
+        let g = Gains::tracking_index(lambda);
+        assert!((g.h * g.h / (1.0 - g.g) - lambda * lambda).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_filter_lag() {
+        // Constant acceleration through a second-order filter lags by
+        // a TS^2 BETA^2 / (1 - BETA)^2
+        let (beta, ts, acc) = (0.8, 0.1, 32.2);
+        let mut f = PolynomialFilter::fading_memory(2, beta, ts);
+        let mut x = 0.0;
+        for k in 1..=400 {
+            let t = k as f64 * ts;
+            x = 0.5 * acc * t * t;
+            f.update(x);
+        }
+        assert!((x - f.x[0] - acc * ts * ts * fading_memory_lag(2, beta)).abs() < 1e-6);
+
+        // The third-order filter follows the same trajectory exactly
+        let mut f3 = PolynomialFilter::fading_memory(3, beta, ts);
+        for k in 1..=400 {
+            let t = k as f64 * ts;
+            f3.update(0.5 * acc * t * t);
+        }
+        assert!((f3.x[2] - acc).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_noise_matches_variance_reduction() {
+        use rand::SeedableRng;
+        use rand_distr::{Distribution, Normal};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let noise = Normal::new(0.0, 1.0).unwrap();
+        let gains = Gains::alpha_beta_gamma(0.5, 0.3, 0.1);
+        let mut f = PolynomialFilter::new(3, gains, 1.0);
+        let n = 200_000;
+        let mut sum = [0.0; 3];
+        for k in 0..n {
+            f.update(noise.sample(&mut rng));
+            if k >= 1000 {
+                (0..3).for_each(|i| sum[i] += f.x[i] * f.x[i]);
+            }
+        }
+        let vrr = variance_reduction(3, &gains);
+        for i in 0..3 {
+            let measured = sum[i] / (n - 1000) as f64;
+            assert!((measured / vrr[i] - 1.0).abs() < 0.05, "state {}: {} vs {}", i, measured, vrr[i]);
+        }
+    }
+
+    #[test]
+    fn test_design_inverts_ratios() {
+        let beta = beta_for_noise_reduction(2, 0.1);
+        assert!((fading_memory_vrr(2, beta)[0] - 0.1).abs() < 1e-9);
+        let beta = beta_for_lag(3, 2.0, 0.1);
+        assert!((fading_memory_lag(3, beta) * 0.001 - 2.0).abs() < 1e-6);
+    }
+}
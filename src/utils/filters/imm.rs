@@ -0,0 +1,378 @@
+//! Interacting multiple model (IMM) estimator
+//!
+//! C31L1 runs a bank of weave filters side by side and reweights them with
+//! Bayes' rule, so once a model's probability collapses it never recovers.
+//! The IMM estimator (Blom and Bar-Shalom) lets the target switch between
+//! models according to a Markov transition matrix and, before every
+//! prediction, mixes the filter estimates so each one restarts from the
+//! combined hypothesis that it is now the active mode.
+//!
+//! All models share the cross-range state of C31
+//!
+//!   x = [Y, YD, YTDD, YTDDD]
+//!
+//! with known missile acceleration input u = [XNL] and the measurement
+//! YSTAR = Y. Models that do not use the higher states hold them at zero.
+
+use super::ekf::{Ekf, EkfModel, Innovation};
+use crate::utils::matrix::{Matrix, expm, mat_add, mat_inv, mat_mul, mat_scale, mat_vec_mul, transpose, zeros};
+
+/// Number of states shared by every model
+pub const N_STATES: usize = 4;
+
+/// Linear time-invariant target model discretized for a fixed sample time
+#[derive(Debug, Clone)]
+pub struct LinearModel {
+    pub name: String,
+    pub ts: f64,
+    pub phi: Matrix,
+    /// Discrete input gain for the missile acceleration
+    pub gamma: Vec<f64>,
+    pub q: Matrix,
+    pub h: Matrix,
+    /// Measurement noise, updated as range closes (see `Imm::set_measurement_noise`)
+    pub r: Matrix,
+}
+
+impl LinearModel {
+    /// Discretize dx/dt = A x + b u + w, E[w w'] = Qc delta(t), by Van Loan's
+    /// method; the measurement is Y with variance `r`
+    pub fn from_continuous(name: &str, a: &Matrix, b: &[f64], qc: &Matrix, r: f64, ts: f64) -> Self {
+        let n = a.len();
+
+        let mut m = zeros(2 * n, 2 * n);
+        for i in 0..n {
+            for j in 0..n {
+                m[i][j] = -a[i][j] * ts;
+                m[i][n + j] = qc[i][j] * ts;
+                m[n + i][n + j] = a[j][i] * ts;
+            }
+        }
+        let e = expm(&m);
+        let phi = transpose(&e[n..].iter().map(|row| row[n..].to_vec()).collect::<Matrix>());
+        let e12: Matrix = e[..n].iter().map(|row| row[n..].to_vec()).collect();
+        let q = mat_mul(&phi, &e12);
+
+        let mut mb = zeros(n + 1, n + 1);
+        for i in 0..n {
+            for j in 0..n {
+                mb[i][j] = a[i][j] * ts;
+            }
+            mb[i][n] = b[i] * ts;
+        }
+        let eb = expm(&mb);
+        let gamma = (0..n).map(|i| eb[i][n]).collect();
+
+        let mut h = zeros(1, n);
+        h[0][0] = 1.0;
+        Self { name: name.to_string(), ts, phi, gamma, q, h, r: vec![vec![r]] }
+    }
+
+    /// Hold states `from..` at zero for models that do not use them
+    fn truncate(mut self, from: usize) -> Self {
+        for i in from..N_STATES {
+            for j in 0..N_STATES {
+                self.phi[i][j] = 0.0;
+                self.phi[j][i] = 0.0;
+                self.q[i][j] = 0.0;
+                self.q[j][i] = 0.0;
+            }
+            self.gamma[i] = 0.0;
+        }
+        self
+    }
+
+    /// Non-maneuvering target with white acceleration of spectral density `phis`
+    pub fn constant_velocity(phis: f64, r: f64, ts: f64) -> Self {
+        let mut a = zeros(N_STATES, N_STATES);
+        a[0][1] = 1.0;
+        let mut qc = zeros(N_STATES, N_STATES);
+        qc[1][1] = phis;
+        Self::from_continuous("CV", &a, &miss_input(), &qc, r, ts).truncate(2)
+    }
+
+    /// Constant target acceleration driven by white jerk of spectral density `phis`
+    pub fn constant_acceleration(phis: f64, r: f64, ts: f64) -> Self {
+        let mut a = zeros(N_STATES, N_STATES);
+        a[0][1] = 1.0;
+        a[1][2] = 1.0;
+        let mut qc = zeros(N_STATES, N_STATES);
+        qc[2][2] = phis;
+        Self::from_continuous("CA", &a, &miss_input(), &qc, r, ts).truncate(3)
+    }
+
+    /// Singer model: target acceleration is first-order Markov with time
+    /// constant `tau` and one-sigma `sig`
+    pub fn singer(tau: f64, sig: f64, r: f64, ts: f64) -> Self {
+        let mut a = zeros(N_STATES, N_STATES);
+        a[0][1] = 1.0;
+        a[1][2] = 1.0;
+        a[2][2] = -1.0 / tau;
+        let mut qc = zeros(N_STATES, N_STATES);
+        qc[2][2] = 2.0 * sig * sig / tau;
+        Self::from_continuous("Singer", &a, &miss_input(), &qc, r, ts).truncate(3)
+    }
+
+    /// Weaving target of frequency `w` (rad/sec), the C31 filter model, with
+    /// white noise of spectral density `phis` driving the jerk derivative
+    pub fn weave(w: f64, phis: f64, r: f64, ts: f64) -> Self {
+        let mut a = zeros(N_STATES, N_STATES);
+        a[0][1] = 1.0;
+        a[1][2] = 1.0;
+        a[2][3] = 1.0;
+        a[3][2] = -w * w;
+        let mut qc = zeros(N_STATES, N_STATES);
+        qc[3][3] = phis;
+        Self::from_continuous(&format!("Weave W={}", w), &a, &miss_input(), &qc, r, ts)
+    }
+
+    fn check_step(&self, ts: f64) {
+        debug_assert!((ts - self.ts).abs() < 1e-9, "model discretized for a different sample time");
+    }
+}
+
+/// Missile acceleration enters as YDD = YTDD - XNL
+fn miss_input() -> Vec<f64> {
+    vec![0.0, -1.0, 0.0, 0.0]
+}
+
+impl EkfModel for LinearModel {
+    fn n_states(&self) -> usize {
+        self.phi.len()
+    }
+
+    fn propagate(&self, x: &[f64], u: &[f64], ts: f64) -> Vec<f64> {
+        self.check_step(ts);
+        let xnl = u.first().copied().unwrap_or(0.0);
+        mat_vec_mul(&self.phi, x).iter().zip(&self.gamma).map(|(p, g)| p + g * xnl).collect()
+    }
+
+    fn transition(&self, _x: &[f64], _u: &[f64], ts: f64) -> Matrix {
+        self.check_step(ts);
+        self.phi.clone()
+    }
+
+    fn process_noise(&self, _x: &[f64], _ts: f64) -> Matrix {
+        self.q.clone()
+    }
+
+    fn measure(&self, x: &[f64]) -> Vec<f64> {
+        mat_vec_mul(&self.h, x)
+    }
+
+    fn measurement_jacobian(&self, _x: &[f64]) -> Matrix {
+        self.h.clone()
+    }
+
+    fn measurement_noise(&self, _x: &[f64]) -> Matrix {
+        self.r.clone()
+    }
+}
+
+/// Markov transition matrix that stays in each of `n` modes with
+/// probability `p_stay` and switches uniformly otherwise
+pub fn markov_matrix(n: usize, p_stay: f64) -> Matrix {
+    let off = if n > 1 { (1.0 - p_stay) / (n - 1) as f64 } else { 0.0 };
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { if n > 1 { p_stay } else { 1.0 } } else { off }).collect())
+        .collect()
+}
+
+/// Log of the Gaussian likelihood N(res; 0, S)
+fn log_likelihood(inn: &Innovation) -> f64 {
+    let s_inv = mat_inv(&inn.s).expect("singular innovation covariance");
+    let d2: f64 = inn.residual.iter().zip(mat_vec_mul(&s_inv, &inn.residual)).map(|(a, b)| a * b).sum();
+    -0.5 * (d2 + log_det_spd(&inn.s) + inn.residual.len() as f64 * (2.0 * std::f64::consts::PI).ln())
+}
+
+/// log det of a symmetric positive definite matrix by Cholesky factorization
+fn log_det_spd(a: &Matrix) -> f64 {
+    let n = a.len();
+    let mut l = zeros(n, n);
+    let mut log_det = 0.0;
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                l[i][i] = (a[i][i] - sum).max(f64::MIN_POSITIVE).sqrt();
+                log_det += 2.0 * l[i][i].ln();
+            } else {
+                l[i][j] = (a[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    log_det
+}
+
+/// Interacting multiple model estimator over a bank of linear filters
+#[derive(Debug, Clone)]
+pub struct Imm {
+    pub filters: Vec<Ekf<LinearModel>>,
+    /// Markov mode transition probabilities, row `i` from mode `i`
+    pub transition: Matrix,
+    /// Mode probabilities; after `predict` these are the predicted probabilities
+    pub prob: Vec<f64>,
+}
+
+impl Imm {
+    /// Every filter starts from the same estimate and covariance
+    pub fn new(models: Vec<LinearModel>, x0: Vec<f64>, p0: Matrix, transition: Matrix, prob: Vec<f64>) -> Self {
+        let n = models.len();
+        assert!(n > 0, "IMM needs at least one model");
+        assert_eq!(transition.len(), n, "transition matrix must be square in the number of models");
+        assert_eq!(prob.len(), n, "one initial probability per model");
+        for row in &transition {
+            assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-9, "transition matrix rows must sum to one");
+        }
+        let filters = models.into_iter().map(|m| Ekf::new(m, x0.clone(), p0.clone())).collect();
+        Self { filters, transition, prob }
+    }
+
+    /// Set the measurement noise of every model
+    pub fn set_measurement_noise(&mut self, r: Matrix) {
+        for f in &mut self.filters {
+            f.model.r = r.clone();
+        }
+    }
+
+    /// Mix the filter estimates and propagate each filter one sample
+    pub fn predict(&mut self, u: &[f64]) {
+        let n = self.filters.len();
+
+        // Predicted mode probabilities c_j and mixing weights mu_i|j
+        let c: Vec<f64> = (0..n).map(|j| (0..n).map(|i| self.transition[i][j] * self.prob[i]).sum()).collect();
+        let mixed: Vec<(Vec<f64>, Matrix)> = (0..n)
+            .map(|j| {
+                let w: Vec<f64> = (0..n).map(|i| self.transition[i][j] * self.prob[i] / c[j].max(1e-300)).collect();
+                combine(&self.filters, &w)
+            })
+            .collect();
+
+        for (f, (x, p)) in self.filters.iter_mut().zip(mixed) {
+            f.x = x;
+            f.p = p;
+            let ts = f.model.ts;
+            f.predict(u, ts);
+        }
+        self.prob = c;
+    }
+
+    /// Update every filter with measurement `z` and reweight the modes by
+    /// their innovation likelihoods
+    pub fn update(&mut self, z: &[f64]) -> Vec<Innovation> {
+        let innovations: Vec<Innovation> = self.filters.iter_mut().map(|f| f.update(z)).collect();
+        let log_l: Vec<f64> = innovations.iter().map(log_likelihood).collect();
+
+        // Normalize in log space so small likelihoods do not underflow
+        let max = log_l.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let weighted: Vec<f64> = self.prob.iter().zip(&log_l).map(|(p, l)| p * (l - max).exp()).collect();
+        let total: f64 = weighted.iter().sum();
+        self.prob = weighted.iter().map(|w| w / total).collect();
+        innovations
+    }
+
+    /// Probability weighted estimate and covariance including the spread of means
+    pub fn estimate(&self) -> (Vec<f64>, Matrix) {
+        combine(&self.filters, &self.prob)
+    }
+
+    /// Index of the most probable mode
+    pub fn most_likely(&self) -> usize {
+        (0..self.prob.len()).fold(0, |best, i| if self.prob[i] > self.prob[best] { i } else { best })
+    }
+}
+
+/// Gaussian mixture moments: sum w_i x_i and sum w_i (P_i + dx dx')
+fn combine(filters: &[Ekf<LinearModel>], w: &[f64]) -> (Vec<f64>, Matrix) {
+    let n = filters[0].x.len();
+    let mut x = vec![0.0; n];
+    for (f, wi) in filters.iter().zip(w) {
+        x.iter_mut().zip(&f.x).for_each(|(a, b)| *a += wi * b);
+    }
+    let mut p = zeros(n, n);
+    for (f, wi) in filters.iter().zip(w) {
+        let dx: Vec<f64> = f.x.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let spread: Matrix = dx.iter().map(|a| dx.iter().map(|b| a * b).collect()).collect();
+        p = mat_add(&p, &mat_scale(&mat_add(&f.p, &spread), *wi));
+    }
+    (x, p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::matrix::diag;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand_distr::{Distribution, Normal};
+
+    #[test]
+    fn test_discretization() {
+        let ts = 0.1;
+        let cv = LinearModel::constant_velocity(2.0, 1.0, ts);
+        assert!((cv.phi[0][1] - ts).abs() < 1e-12);
+        assert!((cv.q[0][0] - 2.0 * ts.powi(3) / 3.0).abs() < 1e-12);
+        assert!((cv.q[0][1] - ts * ts).abs() < 1e-12);
+        assert!((cv.gamma[0] + 0.5 * ts * ts).abs() < 1e-12);
+
+        // The weave transition is the C31 harmonic oscillator
+        let w = 2.0;
+        let weave = LinearModel::weave(w, 0.0, 1.0, ts);
+        assert!((weave.phi[2][2] - (w * ts).cos()).abs() < 1e-12);
+        assert!((weave.phi[2][3] - (w * ts).sin() / w).abs() < 1e-12);
+        assert!((weave.phi[3][2] + w * (w * ts).sin()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_single_model_matches_kalman_filter() {
+        let ts = 0.1;
+        let model = LinearModel::constant_acceleration(1.0, 4.0, ts);
+        let p0 = diag(&[100.0, 100.0, 100.0, 0.0]);
+        let mut imm = Imm::new(vec![model.clone()], vec![0.0; 4], p0.clone(), markov_matrix(1, 0.9), vec![1.0]);
+        let mut ekf = Ekf::new(model, vec![0.0; 4], p0);
+        for k in 0..20 {
+            let z = [0.5 * (k as f64 * ts).powi(2)];
+            imm.predict(&[1.0]);
+            imm.update(&z);
+            ekf.predict(&[1.0], ts);
+            ekf.update(&z);
+        }
+        let (x, _) = imm.estimate();
+        for (a, b) in x.iter().zip(&ekf.x) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_switches_to_maneuver_model() {
+        // Target flies straight, then pulls a 3 G step maneuver at 5 sec
+        let ts = 0.1;
+        let sig = 1.0;
+        let models = vec![
+            LinearModel::constant_velocity(1.0, sig * sig, ts),
+            LinearModel::constant_acceleration(1000.0, sig * sig, ts),
+        ];
+        let p0 = diag(&[sig * sig, 1e4, 1e4, 0.0]);
+        let mut imm = Imm::new(models, vec![0.0; 4], p0, markov_matrix(2, 0.99), vec![0.5, 0.5]);
+        let mut rng = StdRng::seed_from_u64(7);
+        let noise = Normal::new(0.0, sig).unwrap();
+
+        let (mut y, mut yd) = (0.0, 100.0);
+        let mut before = 0.0;
+        for k in 1..=100 {
+            let ytdd = if k > 50 { 96.6 } else { 0.0 };
+            y += ts * yd + 0.5 * ts * ts * ytdd;
+            yd += ts * ytdd;
+            imm.predict(&[0.0]);
+            imm.update(&[y + noise.sample(&mut rng)]);
+            assert!((imm.prob.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            if k == 50 {
+                before = imm.prob[0];
+            }
+        }
+        assert!(before > 0.5);
+        assert!(imm.prob[1] > 0.9);
+        assert_eq!(imm.most_likely(), 1);
+        assert!((imm.estimate().0[2] - 96.6).abs() < 20.0);
+    }
+}
@@ -0,0 +1,11 @@
+//! General-order estimation filters
+//!
+//! The Kalman lessons expand every filter by hand for a fixed set of states
+//! (C12, C30, C31). These modules run the same recursions on `Matrix` for
+//! any model, so new trackers only describe their dynamics and measurement.
+
+pub mod ekf;
+pub mod angle_only;
+pub mod imm;
+pub mod diagnostics;
+pub mod fading_memory;
@@ -0,0 +1,243 @@
+//! Flexible body dynamics and notch filter design
+//!
+//! C25L1-L3 hardcode one or two bending modes and the notch filters that
+//! suppress them. Here an airframe carries any number of modes, each seen by
+//! the rate gyro as K s (1 + s^2/wz^2) / (s^2/w^2 + 2 z s/w + 1), and the notch
+//! designer places a filter on each mode and reports the rigid-body phase
+//! cost and flex-mode gain stabilization of the resulting rate loop.
+
+use super::lti::{bode, logspace, margins, FrequencyResponse, TransferFunction};
+
+/// A single structural bending mode as seen by the rate gyro
+#[derive(Debug, Clone, Copy)]
+pub struct BendingMode {
+    /// Modal frequency (rad/sec)
+    pub w: f64,
+    /// Modal damping ratio
+    pub zeta: f64,
+    /// Gyro rate per unit fin deflection rate (mode-shape slope at the gyro
+    /// times the modal fin force over generalized mass), XKFB in C25
+    pub slope: f64,
+    /// Numerator zero frequency from fin inertia (rad/sec), WZFB in C25
+    pub wz: f64,
+}
+
+impl BendingMode {
+    pub fn new(w: f64, zeta: f64, slope: f64, wz: f64) -> Self {
+        Self { w, zeta, slope, wz }
+    }
+
+    /// Gyro rate per unit fin deflection contributed by this mode
+    pub fn rate_tf(&self) -> TransferFunction {
+        TransferFunction::new(
+            &[self.slope / (self.wz * self.wz), 0.0, self.slope, 0.0],
+            &[1.0 / (self.w * self.w), 2.0 * self.zeta / self.w, 1.0],
+        )
+    }
+}
+
+/// Rigid airframe rate response plus any number of bending modes
+#[derive(Debug, Clone)]
+pub struct FlexibleAirframe {
+    /// Rigid body rate per unit fin deflection
+    pub rigid: TransferFunction,
+    pub modes: Vec<BendingMode>,
+}
+
+impl FlexibleAirframe {
+    /// Rigid body K3 (1 + Ta s) / (B12 s^2 + B11 s + 1) as in C25
+    pub fn new(xk3: f64, ta: f64, b11: f64, b12: f64) -> Self {
+        Self {
+            rigid: TransferFunction::new(&[xk3 * ta, xk3], &[b12, b11, 1.0]),
+            modes: Vec::new(),
+        }
+    }
+
+    pub fn with_mode(mut self, mode: BendingMode) -> Self {
+        self.modes.push(mode);
+        self
+    }
+
+    /// Total gyro rate per unit fin deflection
+    pub fn rate_tf(&self) -> TransferFunction {
+        self.modes
+            .iter()
+            .fold(self.rigid.clone(), |acc, m| acc.parallel(&m.rate_tf()))
+    }
+}
+
+/// Notch filter (s^2/wt^2 + 2 zt s/wt + 1) / (s^2/wb^2 + 2 zb s/wb + 1)
+#[derive(Debug, Clone, Copy)]
+pub struct NotchFilter {
+    pub wt: f64,
+    pub zt: f64,
+    pub wb: f64,
+    pub zb: f64,
+}
+
+impl NotchFilter {
+    pub fn tf(&self) -> TransferFunction {
+        TransferFunction::new(
+            &[1.0 / (self.wt * self.wt), 2.0 * self.zt / self.wt, 1.0],
+            &[1.0 / (self.wb * self.wb), 2.0 * self.zb / self.wb, 1.0],
+        )
+    }
+
+    /// Attenuation at the notch center, 20 log10(zt/zb) when wt = wb
+    pub fn depth_db(&self) -> f64 {
+        20.0 * self.tf().freq_response(self.wt).abs().log10()
+    }
+}
+
+/// Rate gyro loop: DELC = KR * NOTCH * THD, closing 1 - KR*ACT*NOTCH*G = 0
+#[derive(Debug, Clone)]
+pub struct RateLoop {
+    pub airframe: FlexibleAirframe,
+    pub actuator: TransferFunction,
+    pub kr: f64,
+}
+
+impl RateLoop {
+    /// Open loop L(s) for 1 + L = 0 with the given notches in the gyro path
+    pub fn open_loop(&self, notches: &[NotchFilter]) -> TransferFunction {
+        notches
+            .iter()
+            .fold(self.actuator.series(&self.airframe.rate_tf()), |acc, n| acc.series(&n.tf()))
+            .scale(-self.kr)
+    }
+
+    /// Open loop with the bending modes removed
+    pub fn rigid_open_loop(&self) -> TransferFunction {
+        self.actuator.series(&self.airframe.rigid).scale(-self.kr)
+    }
+}
+
+/// Notch placement options
+#[derive(Debug, Clone, Copy)]
+pub struct NotchDesign {
+    /// Denominator damping setting the notch width (C25 uses 0.5)
+    pub zb: f64,
+    /// Numerator damping setting the depth; `None` uses the mode damping
+    pub zt: Option<f64>,
+    /// Required flex-mode loop gain below 0 dB (gain stabilization, dB)
+    pub required_attenuation_db: f64,
+}
+
+impl Default for NotchDesign {
+    fn default() -> Self {
+        Self { zb: 0.5, zt: None, required_attenuation_db: 6.0 }
+    }
+}
+
+/// Flex-mode loop gain before and after notching
+#[derive(Debug, Clone, Copy)]
+pub struct ModeGain {
+    pub w: f64,
+    pub peak_gain_db_unfiltered: f64,
+    pub peak_gain_db_filtered: f64,
+    pub gain_stabilized: bool,
+}
+
+/// Notch design summary
+#[derive(Debug, Clone)]
+pub struct NotchReport {
+    pub notches: Vec<NotchFilter>,
+    /// Rigid-body gain crossover (rad/sec)
+    pub rigid_crossover: Option<f64>,
+    /// Phase lag added by the notches at the rigid-body crossover (deg)
+    pub rigid_phase_loss_deg: f64,
+    pub modes: Vec<ModeGain>,
+}
+
+/// Place one notch on each bending mode
+pub fn place_notches(airframe: &FlexibleAirframe, design: &NotchDesign) -> Vec<NotchFilter> {
+    airframe
+        .modes
+        .iter()
+        .map(|m| NotchFilter { wt: m.w, zt: design.zt.unwrap_or(m.zeta), wb: m.w, zb: design.zb })
+        .collect()
+}
+
+/// Peak open-loop gain within +/-30% of a modal frequency
+fn peak_gain_db(l: &TransferFunction, w: f64) -> f64 {
+    let freq = logspace((0.7 * w).log10(), (1.3 * w).log10(), 400);
+    bode(l, &freq).gain_db.into_iter().fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// Evaluate the effect of a set of notches on the rate loop
+pub fn evaluate_notches(rate_loop: &RateLoop, notches: &[NotchFilter], design: &NotchDesign) -> NotchReport {
+    let rigid = rate_loop.rigid_open_loop();
+    let max_w = rate_loop.airframe.modes.iter().fold(100.0_f64, |m, mode| m.max(mode.w));
+    let freq = logspace(-1.0, (10.0 * max_w).log10(), 2000);
+    let rigid_crossover = margins(&rigid, &freq).gain_crossover;
+
+    let rigid_phase_loss_deg = rigid_crossover.map_or(0.0, |wc| {
+        -notches.iter().map(|n| n.tf().freq_response(wc).arg().to_degrees()).sum::<f64>()
+    });
+
+    let unfiltered = rate_loop.open_loop(&[]);
+    let filtered = rate_loop.open_loop(notches);
+    let modes = rate_loop
+        .airframe
+        .modes
+        .iter()
+        .map(|m| {
+            let after = peak_gain_db(&filtered, m.w);
+            ModeGain {
+                w: m.w,
+                peak_gain_db_unfiltered: peak_gain_db(&unfiltered, m.w),
+                peak_gain_db_filtered: after,
+                gain_stabilized: after <= -design.required_attenuation_db,
+            }
+        })
+        .collect();
+
+    NotchReport {
+        notches: notches.to_vec(),
+        rigid_crossover,
+        rigid_phase_loss_deg,
+        modes,
+    }
+}
+
+/// Place notches on every mode and report the result
+pub fn design_notches(rate_loop: &RateLoop, design: &NotchDesign) -> NotchReport {
+    let notches = place_notches(&rate_loop.airframe, design);
+    evaluate_notches(rate_loop, &notches, design)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two-mode airframe and rate loop from C25L3
+    fn c25l3_loop() -> RateLoop {
+        let airframe = FlexibleAirframe::new(-0.362, 0.831, 0.00461, 0.00136)
+            .with_mode(BendingMode::new(259.0, 0.015, 0.00134, 395.0))
+            .with_mode(BendingMode::new(649.0, 0.022, 0.000664, 255.0));
+        RateLoop { airframe, actuator: TransferFunction::second_order(0.7, 400.0), kr: 0.3 }
+    }
+
+    #[test]
+    fn test_notches_gain_stabilize_c25l3() {
+        let rate_loop = c25l3_loop();
+        let report = design_notches(&rate_loop, &NotchDesign::default());
+        assert_eq!(report.notches.len(), 2);
+        assert!(report.modes[0].peak_gain_db_unfiltered > 0.0);
+        assert!(report.modes.iter().all(|m| m.peak_gain_db_filtered < m.peak_gain_db_unfiltered));
+        assert!(report.rigid_phase_loss_deg > 0.0 && report.rigid_phase_loss_deg < 45.0);
+        assert!(report.modes.iter().all(|m| m.gain_stabilized));
+
+        // NOTCH=1 in C25L3: only the first mode is filtered
+        let design = NotchDesign::default();
+        let first_only = evaluate_notches(&rate_loop, &report.notches[..1], &design);
+        assert!(first_only.modes[0].gain_stabilized);
+        assert!(!first_only.modes[1].gain_stabilized);
+    }
+
+    #[test]
+    fn test_notch_depth() {
+        let n = NotchFilter { wt: 259.0, zt: 0.015, wb: 259.0, zb: 0.5 };
+        assert!((n.depth_db() - 20.0 * (0.015_f64 / 0.5).log10()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,599 @@
+//! Defended footprints
+//!
+//! Generalizes the fixed sweeps of C44 L3-L5 into a footprint engine. An
+//! interceptor is described by its burnout velocity, time of flight limits
+//! and intercept altitude window, and the battle-management timeline sets
+//! its earliest launch. For each threat trajectory the fly-out fan is
+//! searched: intercept times along the threat's path, each paired with the
+//! interceptor time of flight needing the least burnout speed (Lambert).
+//! Threats are anything implementing `ThreatTrack`; `KeplerTrack` is the
+//! coasting conic C44 samples with `kepler1`. Grid cells are either
+//! interceptor sites (launch area) or aim points (defended area); rows are
+//! spread over threads. The resulting raster can be written as an ESRI
+//! ASCII grid or outlined as a GeoJSON polygon. Non-rotating spherical
+//! Earth, as in C44.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use super::constants::{EARTH_RADIUS_FT, GM_FT};
+use super::earth::{EarthModel, Geodetic};
+use super::elements::propagate_state;
+use super::kepler::StateVector;
+use super::lambert::{lambert, Branch, Direction};
+use super::rk2::Vec3;
+
+/// Golden-section iterations when minimizing burnout speed over time of flight
+const TOF_ITERATIONS: usize = 20;
+
+/// Polygon ring of (lon, lat) vertices in degrees, first vertex not repeated
+type Ring = Vec<(f64, f64)>;
+
+/// Threat position and velocity over its flight (ECI ft, ft/s; time from
+/// threat launch)
+pub trait ThreatTrack {
+    /// Time of impact
+    fn flight_time(&self) -> f64;
+
+    /// State at `t`, or `None` outside the flight
+    fn state_at(&self, t: f64) -> Option<(Vec3, Vec3)>;
+}
+
+/// Coasting conic from launch to impact, evaluated analytically
+#[derive(Debug, Clone, Copy)]
+pub struct KeplerTrack {
+    /// State at launch
+    pub initial: StateVector,
+    pub tf: f64,
+}
+
+impl KeplerTrack {
+    /// Conic from `launch` reaching `aim` after `tf`, or `None` when no
+    /// Lambert solution connects the two points
+    pub fn ballistic(launch: Vec3, aim: Vec3, tf: f64) -> Option<Self> {
+        let solution = lambert(launch, aim, tf, GM_FT, Direction::ShortWay, 0, Branch::Left)?;
+        Some(Self { initial: StateVector::from_vectors(launch, solution.v1), tf })
+    }
+}
+
+impl ThreatTrack for KeplerTrack {
+    fn flight_time(&self) -> f64 {
+        self.tf
+    }
+
+    fn state_at(&self, t: f64) -> Option<(Vec3, Vec3)> {
+        if !(0.0..=self.tf).contains(&t) {
+            return None;
+        }
+        let x = propagate_state(&self.initial, t, GM_FT);
+        Some((x.position(), x.velocity()))
+    }
+}
+
+/// Interceptor performance
+#[derive(Debug, Clone, Copy)]
+pub struct Interceptor {
+    /// Burnout velocity available (ft/s)
+    pub burnout_velocity: f64,
+    /// Shortest time of flight, launch to intercept (s)
+    pub min_tof: f64,
+    /// Longest time of flight, launch to intercept (s)
+    pub max_tof: f64,
+    /// Intercept altitude window (ft)
+    pub min_altitude: f64,
+    pub max_altitude: f64,
+}
+
+impl Interceptor {
+    pub fn new(burnout_velocity: f64, min_tof: f64, max_tof: f64) -> Self {
+        Self { burnout_velocity, min_tof, max_tof, min_altitude: 0.0, max_altitude: f64::INFINITY }
+    }
+
+    pub fn with_altitudes(mut self, min_altitude: f64, max_altitude: f64) -> Self {
+        self.min_altitude = min_altitude;
+        self.max_altitude = max_altitude;
+        self
+    }
+
+    /// Burnout velocity that carries the interceptor from `site` to
+    /// `target` in `tof`, neglecting the boost phase as C44 does
+    pub fn required_velocity(&self, site: Vec3, target: Vec3, tof: f64) -> Option<Vec3> {
+        lambert(site, target, tof, GM_FT, Direction::ShortWay, 0, Branch::Left).map(|s| s.v1)
+    }
+}
+
+/// Battle-management timeline, in threat flight time
+#[derive(Debug, Clone, Copy)]
+pub struct Timeline {
+    /// Time the threat is first tracked well enough to engage (s)
+    pub detection: f64,
+    /// Track to interceptor launch: assessment, weapon assignment and
+    /// launch preparation (s)
+    pub commit_delay: f64,
+}
+
+impl Timeline {
+    pub fn new(detection: f64, commit_delay: f64) -> Self {
+        Self { detection, commit_delay }
+    }
+
+    /// Earliest interceptor launch
+    pub fn earliest_launch(&self) -> f64 {
+        self.detection + self.commit_delay
+    }
+}
+
+/// Feasible intercept of one threat
+#[derive(Debug, Clone, Copy)]
+pub struct Engagement {
+    pub launch_time: f64,
+    pub intercept_time: f64,
+    /// Burnout speed the fly-out needs (ft/s)
+    pub burnout_speed: f64,
+    pub intercept_point: Vec3,
+    pub intercept_altitude: f64,
+}
+
+/// Latitude/longitude grid of cell centers with a common spacing (deg)
+#[derive(Debug, Clone)]
+pub struct GeoGrid {
+    pub lat: Vec<f64>,
+    pub lon: Vec<f64>,
+    pub step: f64,
+}
+
+impl GeoGrid {
+    /// Cells centered from the minimum to the maximum of each axis, inclusive
+    pub fn new(lat_min: f64, lat_max: f64, lon_min: f64, lon_max: f64, step: f64) -> Self {
+        let axis = |lo: f64, hi: f64| -> Vec<f64> {
+            let n = ((hi - lo) / step + 1e-9).floor() as usize + 1;
+            (0..n).map(|i| lo + i as f64 * step).collect()
+        };
+        Self { lat: axis(lat_min, lat_max), lon: axis(lon_min, lon_max), step }
+    }
+
+    pub fn cells(&self) -> usize {
+        self.lat.len() * self.lon.len()
+    }
+}
+
+/// Values on a `GeoGrid`, `values[i][j]` at `lat[i]`, `lon[j]`
+#[derive(Debug, Clone)]
+pub struct Raster {
+    pub grid: GeoGrid,
+    pub values: Vec<Vec<f64>>,
+}
+
+impl Raster {
+    /// Cells at or above `threshold`
+    fn covered(&self, threshold: f64) -> Vec<Vec<bool>> {
+        self.values.iter().map(|row| row.iter().map(|&v| v >= threshold).collect()).collect()
+    }
+
+    /// Surface area of the cells at or above `threshold` (ft^2)
+    pub fn area(&self, threshold: f64) -> f64 {
+        let a = EARTH_RADIUS_FT;
+        let half = (0.5 * self.grid.step).to_radians();
+        let dlon = self.grid.step.to_radians();
+        self.values.iter().zip(&self.grid.lat).map(|(row, &lat)| {
+            let lat = lat.to_radians();
+            let cell = a * a * dlon * ((lat + half).min(0.5 * std::f64::consts::PI).sin()
+                - (lat - half).max(-0.5 * std::f64::consts::PI).sin());
+            cell * row.iter().filter(|&&v| v >= threshold).count() as f64
+        }).sum()
+    }
+
+    /// ESRI ASCII grid, rows from north to south
+    pub fn to_ascii_grid(&self) -> String {
+        let step = self.grid.step;
+        let mut out = String::new();
+        writeln!(out, "ncols {}", self.grid.lon.len()).unwrap();
+        writeln!(out, "nrows {}", self.grid.lat.len()).unwrap();
+        writeln!(out, "xllcorner {}", self.grid.lon.first().copied().unwrap_or(0.0) - 0.5 * step).unwrap();
+        writeln!(out, "yllcorner {}", self.grid.lat.first().copied().unwrap_or(0.0) - 0.5 * step).unwrap();
+        writeln!(out, "cellsize {}", step).unwrap();
+        writeln!(out, "NODATA_value -9999").unwrap();
+        for row in self.values.iter().rev() {
+            let line: Vec<String> = row.iter().map(|v| format!("{}", v)).collect();
+            writeln!(out, "{}", line.join(" ")).unwrap();
+        }
+        out
+    }
+
+    /// GeoJSON Feature outlining the cells at or above `threshold` as a
+    /// MultiPolygon: exterior rings counterclockwise, holes clockwise
+    pub fn to_geojson(&self, threshold: f64) -> String {
+        let rings = self.outline(threshold);
+        let (outer, holes): (Vec<_>, Vec<_>) = rings.into_iter().partition(|(r, _)| ring_area(r) > 0.0);
+        let mut polygons: Vec<Vec<_>> = outer.into_iter().map(|r| vec![r]).collect();
+        for (hole, inside) in holes {
+            let owner = polygons.iter().enumerate()
+                .filter(|(_, p)| contains(&p[0].0, inside))
+                .min_by(|a, b| ring_area(&a.1[0].0).total_cmp(&ring_area(&b.1[0].0)))
+                .map(|(k, _)| k);
+            if let Some(k) = owner {
+                polygons[k].push((hole, inside));
+            }
+        }
+
+        let ring_json = |ring: &[(f64, f64)]| -> String {
+            let mut points: Vec<String> = ring.iter().map(|(lon, lat)| format!("[{:.4},{:.4}]", lon, lat)).collect();
+            points.push(points[0].clone());
+            format!("[{}]", points.join(","))
+        };
+        let polygon_json: Vec<String> = polygons.iter()
+            .map(|p| format!("[{}]", p.iter().map(|(r, _)| ring_json(r)).collect::<Vec<_>>().join(",")))
+            .collect();
+        format!(
+            "{{\"type\":\"Feature\",\"properties\":{{\"threshold\":{}}},\"geometry\":{{\"type\":\"MultiPolygon\",\"coordinates\":[{}]}}}}",
+            threshold, polygon_json.join(","),
+        )
+    }
+
+    /// Boundary rings of the covered cells in (lon, lat) degrees, traced
+    /// with the covered side on the left. Each ring carries a point just
+    /// to its right, used to place holes inside their exteriors.
+    fn outline(&self, threshold: f64) -> Vec<(Ring, (f64, f64))> {
+        let covered = self.covered(threshold);
+        let (nlat, nlon) = (self.grid.lat.len(), self.grid.lon.len());
+        let is_covered = |i: isize, j: isize| -> bool {
+            i >= 0 && j >= 0 && (i as usize) < nlat && (j as usize) < nlon && covered[i as usize][j as usize]
+        };
+
+        // Directed cell edges between vertices (i, j), interior on the left
+        let mut edges: HashMap<(isize, isize), Vec<(isize, isize)>> = HashMap::new();
+        for i in 0..nlat as isize {
+            for j in 0..nlon as isize {
+                if !is_covered(i, j) {
+                    continue;
+                }
+                let sides = [
+                    (!is_covered(i - 1, j), (i, j), (i, j + 1)),
+                    (!is_covered(i, j + 1), (i, j + 1), (i + 1, j + 1)),
+                    (!is_covered(i + 1, j), (i + 1, j + 1), (i + 1, j)),
+                    (!is_covered(i, j - 1), (i + 1, j), (i, j)),
+                ];
+                for (open, from, to) in sides {
+                    if open {
+                        edges.entry(from).or_default().push(to);
+                    }
+                }
+            }
+        }
+
+        let step = self.grid.step;
+        let lat0 = self.grid.lat.first().copied().unwrap_or(0.0) - 0.5 * step;
+        let lon0 = self.grid.lon.first().copied().unwrap_or(0.0) - 0.5 * step;
+        let to_degrees = |i: f64, j: f64| (lon0 + j * step, lat0 + i * step);
+
+        let mut rings = Vec::new();
+        while let Some(&start) = edges.keys().next() {
+            let first = take_edge(&mut edges, start, None).unwrap();
+            let dir0 = (first.0 - start.0, first.1 - start.1);
+            // Cell center to the right of the first edge
+            let right = ((start.0 + first.0) as f64 * 0.5 - dir0.1 as f64 * 0.5,
+                (start.1 + first.1) as f64 * 0.5 + dir0.0 as f64 * 0.5);
+            let mut ring = Vec::new();
+            let (mut at, mut dir) = (first, dir0);
+            while at != start {
+                let next = take_edge(&mut edges, at, Some(dir)).expect("cell boundary rings close");
+                let turn = (next.0 - at.0, next.1 - at.1);
+                if turn != dir {
+                    ring.push(to_degrees(at.0 as f64, at.1 as f64));
+                }
+                at = next;
+                dir = turn;
+            }
+            if dir != dir0 {
+                ring.push(to_degrees(start.0 as f64, start.1 as f64));
+            }
+            rings.push((ring, to_degrees(right.0, right.1)));
+        }
+        rings
+    }
+}
+
+/// Removes and returns the edge leaving `at`, preferring a left turn from
+/// `dir` so cells touching only at a corner stay in separate rings
+fn take_edge(
+    edges: &mut HashMap<(isize, isize), Vec<(isize, isize)>>,
+    at: (isize, isize),
+    dir: Option<(isize, isize)>,
+) -> Option<(isize, isize)> {
+    let out = edges.get_mut(&at)?;
+    let k = match dir {
+        Some((di, dj)) => [(dj, -di), (di, dj), (-dj, di)].iter()
+            .find_map(|d| out.iter().position(|to| (to.0 - at.0, to.1 - at.1) == *d))?,
+        None => 0,
+    };
+    let to = out.swap_remove(k);
+    if out.is_empty() {
+        edges.remove(&at);
+    }
+    Some(to)
+}
+
+/// Signed shoelace area, positive counterclockwise
+fn ring_area(ring: &[(f64, f64)]) -> f64 {
+    let n = ring.len();
+    (0..n).map(|k| {
+        let (x0, y0) = ring[k];
+        let (x1, y1) = ring[(k + 1) % n];
+        x0 * y1 - x1 * y0
+    }).sum::<f64>() * 0.5
+}
+
+/// Even-odd point in polygon test
+fn contains(ring: &[(f64, f64)], (x, y): (f64, f64)) -> bool {
+    let n = ring.len();
+    let mut inside = false;
+    for k in 0..n {
+        let (x0, y0) = ring[k];
+        let (x1, y1) = ring[(k + 1) % n];
+        if (y0 > y) != (y1 > y) && x < x0 + (y - y0) * (x1 - x0) / (y1 - y0) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Evaluates `cell` at every grid point, one row at a time across the
+/// available threads
+pub fn evaluate_grid<F>(grid: &GeoGrid, cell: F) -> Raster
+where
+    F: Fn(&Geodetic) -> f64 + Sync,
+{
+    let nlat = grid.lat.len();
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get()).min(nlat.max(1));
+    let next = AtomicUsize::new(0);
+    let mut values = vec![Vec::new(); nlat];
+    std::thread::scope(|s| {
+        let workers: Vec<_> = (0..threads).map(|_| s.spawn(|| {
+            let mut rows = Vec::new();
+            loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= nlat {
+                    break;
+                }
+                let row: Vec<f64> = grid.lon.iter()
+                    .map(|&lon| cell(&Geodetic::from_degrees(grid.lat[i], lon, 0.0)))
+                    .collect();
+                rows.push((i, row));
+            }
+            rows
+        })).collect();
+        for worker in workers {
+            for (i, row) in worker.join().expect("footprint worker panicked") {
+                values[i] = row;
+            }
+        }
+    });
+    Raster { grid: grid.clone(), values }
+}
+
+/// Engagement and footprint calculations for one interceptor type
+#[derive(Debug, Clone, Copy)]
+pub struct FootprintEngine {
+    pub interceptor: Interceptor,
+    pub timeline: Timeline,
+    /// Spacing of candidate intercept times along the threat (s)
+    pub dt: f64,
+    earth: EarthModel,
+}
+
+impl FootprintEngine {
+    pub fn new(interceptor: Interceptor, timeline: Timeline) -> Self {
+        Self { interceptor, timeline, dt: 10.0, earth: EarthModel::spherical() }
+    }
+
+    pub fn with_step(mut self, dt: f64) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Earliest feasible intercept of `threat` from an interceptor at `site`
+    ///
+    /// Intercept times are stepped along the threat from the earliest launch
+    /// plus the shortest fly-out. At each, the time of flight needing the
+    /// least burnout speed is searched between the interceptor's limits and
+    /// the time left since the earliest launch.
+    pub fn engage<T: ThreatTrack + ?Sized>(&self, site: &Geodetic, threat: &T) -> Option<Engagement> {
+        let ic = &self.interceptor;
+        let rm = self.earth.geodetic_to_ecef(site);
+        let earliest = self.timeline.earliest_launch();
+        let mut ti = earliest + ic.min_tof;
+        while ti <= threat.flight_time() {
+            let (rt, _) = threat.state_at(ti)?;
+            let alt = rt.magnitude() - self.earth.a;
+            let tof_hi = (ti - earliest).min(ic.max_tof);
+            // Coasting upward, the interceptor is never faster than at burnout
+            let reachable = (rt - rm).magnitude() <= ic.burnout_velocity * tof_hi;
+            if alt >= ic.min_altitude && alt <= ic.max_altitude && reachable {
+                if let Some((tof, speed)) = self.fly_out(rm, rt, ic.min_tof, tof_hi) {
+                    return Some(Engagement {
+                        launch_time: ti - tof,
+                        intercept_time: ti,
+                        burnout_speed: speed,
+                        intercept_point: rt,
+                        intercept_altitude: alt,
+                    });
+                }
+            }
+            ti += self.dt;
+        }
+        None
+    }
+
+    /// Time of flight in [lo, hi] reaching `target` within the burnout
+    /// velocity, searching the fan from the longest fly-out by golden section
+    fn fly_out(&self, site: Vec3, target: Vec3, lo: f64, hi: f64) -> Option<(f64, f64)> {
+        let vbo = self.interceptor.burnout_velocity;
+        let speed = |tof: f64| self.interceptor.required_velocity(site, target, tof)
+            .map_or(f64::INFINITY, |v| v.magnitude());
+        let feasible = |tof: f64, v: f64| (v <= vbo).then_some((tof, v));
+
+        let vhi = speed(hi);
+        if let Some(found) = feasible(hi, vhi) {
+            return Some(found);
+        }
+        let ratio = 0.5 * (5.0_f64.sqrt() - 1.0);
+        let (mut a, mut b) = (lo, hi);
+        let mut c = b - ratio * (b - a);
+        let mut d = a + ratio * (b - a);
+        let (mut vc, mut vd) = (speed(c), speed(d));
+        for _ in 0..TOF_ITERATIONS {
+            if let Some(found) = feasible(c, vc).or(feasible(d, vd)) {
+                return Some(found);
+            }
+            if vc < vd {
+                b = d;
+                d = c;
+                vd = vc;
+                c = b - ratio * (b - a);
+                vc = speed(c);
+            } else {
+                a = c;
+                c = d;
+                vc = vd;
+                d = a + ratio * (b - a);
+                vd = speed(d);
+            }
+        }
+        feasible(c, vc).or(feasible(d, vd))
+    }
+
+    /// Fraction of `threats` engageable from `site`; zero without threats
+    pub fn coverage<T: ThreatTrack>(&self, site: &Geodetic, threats: &[T]) -> f64 {
+        if threats.is_empty() {
+            return 0.0;
+        }
+        let engaged = threats.iter().filter(|&t| self.engage(site, t).is_some()).count();
+        engaged as f64 / threats.len() as f64
+    }
+
+    /// Launch area: with interceptor sites at the grid cells, the fraction
+    /// of `threats` each can engage (C44 L3)
+    pub fn launch_area<T: ThreatTrack + Sync>(&self, grid: &GeoGrid, threats: &[T]) -> Raster {
+        evaluate_grid(grid, |cell| self.coverage(cell, threats))
+    }
+
+    /// Defended area: with aim points at the grid cells, the fraction of
+    /// the threats `threats_at` generates against each that an interceptor
+    /// at `site` can engage (C44 L4/L5). A threat the generator cannot fly
+    /// is returned as `None` and counts as unengaged.
+    pub fn defended_area<T, F>(&self, grid: &GeoGrid, site: &Geodetic, threats_at: F) -> Raster
+    where
+        T: ThreatTrack,
+        F: Fn(&Geodetic) -> Vec<Option<T>> + Sync,
+    {
+        evaluate_grid(grid, |aim| {
+            let threats = threats_at(aim);
+            if threats.is_empty() {
+                return 0.0;
+            }
+            let engaged = threats.iter()
+                .filter(|t| t.as_ref().is_some_and(|t| self.engage(site, t).is_some()))
+                .count();
+            engaged as f64 / threats.len() as f64
+        })
+    }
+
+    /// ECEF position of a grid cell or site on this engine's Earth
+    pub fn position(&self, g: &Geodetic) -> Vec3 {
+        self.earth.geodetic_to_ecef(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threat() -> KeplerTrack {
+        let earth = EarthModel::spherical();
+        let launch = earth.geodetic_to_ecef(&Geodetic::from_degrees(0.0, 0.0, 0.0));
+        let aim = earth.geodetic_to_ecef(&Geodetic::from_degrees(0.0, 60.0, 0.0));
+        KeplerTrack::ballistic(launch, aim, 1800.0).unwrap()
+    }
+
+    fn engine(vbo: f64) -> FootprintEngine {
+        FootprintEngine::new(Interceptor::new(vbo, 60.0, 600.0), Timeline::new(240.0, 60.0))
+            .with_step(20.0)
+    }
+
+    #[test]
+    fn test_engagement_respects_limits() {
+        let threat = threat();
+        let site = Geodetic::from_degrees(0.0, 55.0, 0.0);
+        let e = engine(16400.0).engage(&site, &threat).expect("site under the threat engages it");
+        assert!(e.launch_time >= 300.0 - 1e-9);
+        let tof = e.intercept_time - e.launch_time;
+        assert!((60.0..=600.0 + 1e-9).contains(&tof));
+        assert!(e.burnout_speed <= 16400.0);
+
+        // A slow interceptor must wait for the threat to come closer
+        let slow = engine(8000.0).engage(&site, &threat).expect("slow interceptor engages late");
+        assert!(slow.intercept_time > e.intercept_time);
+        // Far from the threat's path nothing is reachable
+        assert!(engine(16400.0).engage(&Geodetic::from_degrees(60.0, 150.0, 0.0), &threat).is_none());
+    }
+
+    #[test]
+    fn test_launch_area_parallel_matches_serial() {
+        let threats = vec![threat()];
+        let grid = GeoGrid::new(-20.0, 20.0, 30.0, 70.0, 10.0);
+        let eng = engine(8000.0);
+        let raster = eng.launch_area(&grid, &threats);
+        for (i, &lat) in grid.lat.iter().enumerate() {
+            for (j, &lon) in grid.lon.iter().enumerate() {
+                assert_eq!(raster.values[i][j], eng.coverage(&Geodetic::from_degrees(lat, lon, 0.0), &threats));
+            }
+        }
+        let covered = raster.values.iter().flatten().filter(|&&v| v == 1.0).count();
+        assert!(covered > 0 && covered < grid.cells());
+    }
+
+    #[test]
+    fn test_defended_area_counts_unflown_threats() {
+        let grid = GeoGrid::new(0.0, 0.0, 55.0, 55.0, 1.0);
+        let site = Geodetic::from_degrees(0.0, 55.0, 0.0);
+        let eng = engine(16400.0);
+        let both = eng.defended_area(&grid, &site, |_| vec![Some(threat()), Some(threat())]);
+        assert_eq!(both.values[0][0], 1.0);
+        // One threat could not be flown: the aim point is only half defended
+        let one = eng.defended_area(&grid, &site, |_| vec![Some(threat()), None]);
+        assert_eq!(one.values[0][0], 0.5);
+    }
+
+    #[test]
+    fn test_geojson_outline_with_hole() {
+        // A 3x3 block missing its center, plus a separate corner cell
+        let grid = GeoGrid::new(0.0, 4.0, 0.0, 4.0, 1.0);
+        let mut values = vec![vec![0.0; 5]; 5];
+        for (i, row) in values.iter_mut().enumerate().take(3) {
+            for (j, v) in row.iter_mut().enumerate().take(3) {
+                *v = if i == 1 && j == 1 { 0.0 } else { 1.0 };
+            }
+        }
+        values[4][4] = 1.0;
+        let raster = Raster { grid, values };
+
+        let rings = raster.outline(1.0);
+        assert_eq!(rings.len(), 3);
+        let mut areas: Vec<f64> = rings.iter().map(|(r, _)| ring_area(r)).collect();
+        areas.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(areas, vec![-1.0, 1.0, 9.0]);
+
+        let json = raster.to_geojson(1.0);
+        assert!(json.contains("\"MultiPolygon\""));
+        // Two polygons, the first with its hole
+        assert_eq!(json.matches("]]]").count(), 2);
+        assert!(json.contains("[-0.5000,-0.5000]"));
+
+        let asc = raster.to_ascii_grid();
+        assert!(asc.starts_with("ncols 5\nnrows 5\nxllcorner -0.5\nyllcorner -0.5\ncellsize 1\n"));
+        assert!(asc.contains("\n0 0 0 0 1\n"));
+        // Nine one-degree cells near the equator
+        let cell = (EARTH_RADIUS_FT * 1.0_f64.to_radians()).powi(2);
+        assert!((raster.area(1.0) / cell - 9.0).abs() < 0.01);
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! Implements GENERATEGAINS for computing time-varying Kalman filter gains
 
+use crate::utils::matrix::{diag, zeros};
+use crate::utils::riccati::integrate_riccati;
+
 /// Container for generated gains over time
 #[derive(Debug, Clone)]
 pub struct GeneratedGains {
@@ -38,9 +41,8 @@ pub fn generate_gains(
     tf: f64,
     ts: f64,
 ) -> GeneratedGains {
-    // Initialize matrices
     // F matrix (6x6) - system dynamics
-    let mut f = [[0.0f64; 6]; 6];
+    let mut f = zeros(6, 6);
     f[0][1] = 1.0;
     f[1][2] = 1.0;
     f[1][3] = -1.0;
@@ -52,153 +54,29 @@ pub fn generate_gains(
     f[5][5] = -w * w * (1.0 / (w * w) + 2.0 * z * tau / w) / tau;
 
     // G vector (6x1) - input
-    let mut g = [0.0f64; 6];
-    g[5] = w * w / tau;
-
-    // S matrix (6x6) - Riccati solution, initialized with measurement
-    let mut s = [[0.0f64; 6]; 6];
-    s[0][0] = 1.0;
-
-    let h = 0.0001;
-    let mut t = 0.0;
-    let mut s1 = 0.0;
-
-    let mut c1 = Vec::new();
-    let mut c2 = Vec::new();
-    let mut c3 = Vec::new();
-    let mut c4 = Vec::new();
-    let mut c5 = Vec::new();
-    let mut c6 = Vec::new();
-
-    while t < tf - 0.0001 {
-        s1 += h;
-        let sold = s;
-
-        // Compute S*F
-        let mut sf = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                for k in 0..6 {
-                    sf[i][j] += s[i][k] * f[k][j];
-                }
-            }
-        }
-
-        // Compute G' * S
-        let mut gts = [0.0f64; 6];
-        for j in 0..6 {
-            for i in 0..6 {
-                gts[j] += g[i] * s[i][j];
-            }
-        }
-
-        // Compute C = (1/gamma) * G' * S
-        let gam_inv = 1.0 / gam;
-        let c: Vec<f64> = gts.iter().map(|&x| gam_inv * x).collect();
-
-        // Compute C' * C
-        let mut ctc = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                ctc[i][j] = c[i] * c[j];
-            }
-        }
-
-        // Compute gamma * C' * C
-        let mut ctbc = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                ctbc[i][j] = gam * ctc[i][j];
-            }
-        }
-
-        // Compute SD = S*F + F'*S - gamma*C'*C
-        let mut sd = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                sd[i][j] = sf[i][j] + sf[j][i] - ctbc[i][j];
-            }
-        }
-
-        // Euler step
-        for i in 0..6 {
-            for j in 0..6 {
-                s[i][j] += h * sd[i][j];
-            }
-        }
-        t += h;
-
-        // RK2 correction
-        // Recompute sf, gts, c, ctc, ctbc, sd with new s
-        let mut sf2 = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                for k in 0..6 {
-                    sf2[i][j] += s[i][k] * f[k][j];
-                }
-            }
-        }
-
-        let mut gts2 = [0.0f64; 6];
-        for j in 0..6 {
-            for i in 0..6 {
-                gts2[j] += g[i] * s[i][j];
-            }
-        }
-
-        let c2_temp: Vec<f64> = gts2.iter().map(|&x| gam_inv * x).collect();
-
-        let mut ctc2 = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                ctc2[i][j] = c2_temp[i] * c2_temp[j];
-            }
-        }
-
-        let mut ctbc2 = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                ctbc2[i][j] = gam * ctc2[i][j];
-            }
-        }
-
-        let mut sd2 = [[0.0f64; 6]; 6];
-        for i in 0..6 {
-            for j in 0..6 {
-                sd2[i][j] = sf2[i][j] + sf2[j][i] - ctbc2[i][j];
-            }
-        }
-
-        // RK2 averaging
-        for i in 0..6 {
-            for j in 0..6 {
-                s[i][j] = 0.5 * (sold[i][j] + s[i][j]) + 0.5 * h * sd2[i][j];
-            }
-        }
-
-        // Store gains at sampling intervals
-        if s1 >= ts - 0.0001 {
-            s1 = 0.0;
-
-            // Recompute c for storage
-            let mut gts_final = [0.0f64; 6];
-            for j in 0..6 {
-                for i in 0..6 {
-                    gts_final[j] += g[i] * s[i][j];
-                }
-            }
-            let c_final: Vec<f64> = gts_final.iter().map(|&x| -gam_inv * x).collect();
-
-            c1.push(c_final[0]);
-            c2.push(c_final[1]);
-            c3.push(c_final[2]);
-            c4.push(c_final[3]);
-            c5.push(c_final[4]);
-            c6.push(c_final[5]);
-        }
+    let mut g = zeros(6, 1);
+    g[5][0] = w * w / tau;
+
+    // Terminal weight on miss distance only, no state weighting
+    let mut s0 = zeros(6, 6);
+    s0[0][0] = 1.0;
+
+    let history = integrate_riccati(&f, &g, &zeros(6, 6), &diag(&[gam]), &s0, tf, 0.0001, ts);
+
+    // GENERATEGAINS.m forms C inside the derivative evaluation, so the stored
+    // gain comes from the Euler-predicted S rather than the averaged one
+    let mut gains = GeneratedGains { c1: Vec::new(), c2: Vec::new(), c3: Vec::new(), c4: Vec::new(), c5: Vec::new(), c6: Vec::new() };
+    for s in &history.s_predicted {
+        // C = -(1/gamma) G' S
+        let c: Vec<f64> = (0..6).map(|j| -(0..6).map(|i| g[i][0] * s[i][j]).sum::<f64>() / gam).collect();
+        gains.c1.push(c[0]);
+        gains.c2.push(c[1]);
+        gains.c3.push(c[2]);
+        gains.c4.push(c[3]);
+        gains.c5.push(c[4]);
+        gains.c6.push(c[5]);
     }
-
-    GeneratedGains { c1, c2, c3, c4, c5, c6 }
+    gains
 }
 
 #[cfg(test)]
@@ -211,4 +89,24 @@ mod tests {
         assert!(!gains.c1.is_empty());
         assert_eq!(gains.c1.len(), gains.c2.len());
     }
+
+    #[test]
+    fn test_generate_gains_regression() {
+        // C35L6 airframe; predictor-stage gains as stored by GENERATEGAINS.m
+        // at TGO = 0.1, 1, 5 and 10 s
+        let gains = generate_gains(1.0, 20.0, 0.7, 5.0, 0.00001, 10.0, 0.01);
+        assert_eq!(gains.c1.len(), 1000);
+        let expected = [
+            (9, [-8.232718012e2, -8.232740015e1, -4.116382447e0, 5.126809678e0, 1.248289396e0, -5.528934743e-2]),
+            (99, [1.740720334e1, 1.740710801e1, 8.703518161e0, -7.247052269e0, -1.112938896e0, -1.343511095e-2]),
+            (499, [1.879287884e-1, 9.394166683e-1, 2.347999247e0, -8.124635060e-1, -9.166699604e-2, -1.849095542e-3]),
+            (999, [3.753608110e-2, 3.751144320e-1, 1.874367808e0, -3.626219593e-1, -3.938247602e-2, -8.373814811e-4]),
+        ];
+        for (k, c) in expected {
+            let actual = [gains.c1[k], gains.c2[k], gains.c3[k], gains.c4[k], gains.c5[k], gains.c6[k]];
+            for (a, e) in actual.iter().zip(c) {
+                assert!((a - e).abs() < 1e-6 * e.abs(), "TGO {}: {} vs {}", k + 1, a, e);
+            }
+        }
+    }
 }
@@ -0,0 +1,189 @@
+//! Site geometry
+//!
+//! Great-circle range and azimuth between ground points, local tangent
+//! frames (ENU and NED) at a site, and azimuth/elevation/range look angles,
+//! so radars, launchers and aim points can be placed anywhere on an
+//! `EarthModel`. Azimuths are measured clockwise from north.
+
+use std::f64::consts::PI;
+use super::earth::{EarthModel, Geodetic};
+use super::rk2::Vec3;
+
+/// Great-circle distance between two points on a sphere of `radius`
+/// (haversine form, accurate at short range)
+pub fn great_circle_range(from: &Geodetic, to: &Geodetic, radius: f64) -> f64 {
+    let dlat = to.lat - from.lat;
+    let dlon = to.lon - from.lon;
+    let h = (dlat / 2.0).sin().powi(2) + from.lat.cos() * to.lat.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * radius * h.sqrt().min(1.0).asin()
+}
+
+/// Initial azimuth of the great circle from `from` toward `to`, in [0, 2pi)
+pub fn great_circle_azimuth(from: &Geodetic, to: &Geodetic) -> f64 {
+    let dlon = to.lon - from.lon;
+    let y = dlon.sin() * to.lat.cos();
+    let x = from.lat.cos() * to.lat.sin() - from.lat.sin() * to.lat.cos() * dlon.cos();
+    y.atan2(x).rem_euclid(2.0 * PI)
+}
+
+/// Point reached by travelling `range` along the great circle leaving
+/// `from` at `azimuth`; the altitude of `from` is kept
+pub fn great_circle_destination(from: &Geodetic, range: f64, azimuth: f64, radius: f64) -> Geodetic {
+    let d = range / radius;
+    let lat = (from.lat.sin() * d.cos() + from.lat.cos() * d.sin() * azimuth.cos()).asin();
+    let lon = from.lon
+        + (azimuth.sin() * d.sin() * from.lat.cos()).atan2(d.cos() - from.lat.sin() * lat.sin());
+    Geodetic::new(lat, (lon + PI).rem_euclid(2.0 * PI) - PI, from.alt)
+}
+
+/// Azimuth, elevation and range of a point seen from a site
+#[derive(Debug, Clone, Copy)]
+pub struct LookAngles {
+    /// Azimuth clockwise from north (rad)
+    pub azimuth: f64,
+    /// Elevation above the local horizontal (rad)
+    pub elevation: f64,
+    pub range: f64,
+}
+
+/// East-north-up tangent frame at a site
+#[derive(Debug, Clone, Copy)]
+pub struct LocalFrame {
+    pub site: Geodetic,
+    /// ECEF position of the site
+    pub origin: Vec3,
+    pub east: Vec3,
+    pub north: Vec3,
+    pub up: Vec3,
+}
+
+impl LocalFrame {
+    pub fn new(earth: &EarthModel, site: &Geodetic) -> Self {
+        let (slat, clat) = site.lat.sin_cos();
+        let (slon, clon) = site.lon.sin_cos();
+        Self {
+            site: *site,
+            origin: earth.geodetic_to_ecef(site),
+            east: Vec3::new(-slon, clon, 0.0),
+            north: Vec3::new(-slat * clon, -slat * slon, clat),
+            up: Vec3::new(clat * clon, clat * slon, slat),
+        }
+    }
+
+    /// Frame at the geodetic point below an ECEF position
+    pub fn at_position(earth: &EarthModel, r: &Vec3) -> Self {
+        Self::new(earth, &earth.ecef_to_geodetic(r))
+    }
+
+    /// ECEF direction to east, north and up components
+    pub fn rotate_to_enu(&self, v: &Vec3) -> Vec3 {
+        Vec3::new(v.dot(&self.east), v.dot(&self.north), v.dot(&self.up))
+    }
+
+    /// East, north and up components to an ECEF direction
+    pub fn rotate_from_enu(&self, enu: &Vec3) -> Vec3 {
+        self.east * enu.x + self.north * enu.y + self.up * enu.z
+    }
+
+    /// ECEF position to ENU coordinates relative to the site
+    pub fn ecef_to_enu(&self, r: &Vec3) -> Vec3 {
+        self.rotate_to_enu(&(*r - self.origin))
+    }
+
+    pub fn enu_to_ecef(&self, enu: &Vec3) -> Vec3 {
+        self.origin + self.rotate_from_enu(enu)
+    }
+
+    /// ECEF position to north-east-down coordinates relative to the site
+    pub fn ecef_to_ned(&self, r: &Vec3) -> Vec3 {
+        let enu = self.ecef_to_enu(r);
+        Vec3::new(enu.y, enu.x, -enu.z)
+    }
+
+    pub fn ned_to_ecef(&self, ned: &Vec3) -> Vec3 {
+        self.enu_to_ecef(&Vec3::new(ned.y, ned.x, -ned.z))
+    }
+
+    /// Look angles from the site to an ECEF position
+    pub fn look_angles(&self, r: &Vec3) -> LookAngles {
+        let enu = self.ecef_to_enu(r);
+        let range = enu.magnitude();
+        LookAngles {
+            azimuth: enu.x.atan2(enu.y).rem_euclid(2.0 * PI),
+            elevation: (enu.z / range).clamp(-1.0, 1.0).asin(),
+            range,
+        }
+    }
+
+    /// ECEF position at the given look angles from the site
+    pub fn look_to_ecef(&self, look: &LookAngles) -> Vec3 {
+        let (sel, cel) = look.elevation.sin_cos();
+        let (saz, caz) = look.azimuth.sin_cos();
+        self.enu_to_ecef(&(Vec3::new(cel * saz, cel * caz, sel) * look.range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::EARTH_RADIUS_FT;
+
+    #[test]
+    fn test_geometry_great_circle() {
+        let a = EARTH_RADIUS_FT;
+        let from = Geodetic::from_degrees(0.0, 0.0, 0.0);
+        let east = Geodetic::from_degrees(0.0, 90.0, 0.0);
+        assert!((great_circle_range(&from, &east, a) - a * PI / 2.0).abs() < 1e-6);
+        assert!((great_circle_azimuth(&from, &east) - PI / 2.0).abs() < 1e-12);
+        let pole = Geodetic::from_degrees(90.0, 0.0, 0.0);
+        assert!(great_circle_azimuth(&from, &pole).abs() < 1e-12);
+
+        // Destination inverts range and azimuth
+        let launch = Geodetic::from_degrees(43.75, 7.42, 0.0);
+        let aim = Geodetic::from_degrees(36.175, -115.136, 0.0);
+        let range = great_circle_range(&launch, &aim, a);
+        let az = great_circle_azimuth(&launch, &aim);
+        let dest = great_circle_destination(&launch, range, az, a);
+        assert!((dest.lat - aim.lat).abs() < 1e-9 && (dest.lon - aim.lon).abs() < 1e-9);
+
+        // Matches the book's distance3d on the sphere
+        let earth = EarthModel::spherical();
+        let (r1, r2) = (earth.geodetic_to_ecef(&launch), earth.geodetic_to_ecef(&aim));
+        let book = crate::utils::predict::distance3d(r1.x, r1.y, r1.z, r2.x, r2.y, r2.z);
+        assert!((range / 6076.0 - book).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_geometry_local_frames() {
+        let earth = EarthModel::wgs84();
+        let site = Geodetic::from_degrees(36.0, -115.0, 2000.0);
+        let frame = LocalFrame::new(&earth, &site);
+        // A point straight up the local normal
+        let above = earth.geodetic_to_ecef(&Geodetic::new(site.lat, site.lon, site.alt + 1.0e5));
+        let enu = frame.ecef_to_enu(&above);
+        assert!(enu.x.abs() < 1e-6 && enu.y.abs() < 1e-6 && (enu.z - 1.0e5).abs() < 1e-6);
+        let ned = frame.ecef_to_ned(&above);
+        assert!((ned.z + 1.0e5).abs() < 1e-6);
+
+        let r = Vec3::new(-8.0e6, -1.6e7, 1.3e7);
+        assert!((frame.enu_to_ecef(&frame.ecef_to_enu(&r)) - r).magnitude() < 1e-6);
+        assert!((frame.ned_to_ecef(&frame.ecef_to_ned(&r)) - r).magnitude() < 1e-6);
+        let look = frame.look_angles(&r);
+        assert!((frame.look_to_ecef(&look) - r).magnitude() < 1e-6);
+        assert!((look.range - (r - frame.origin).magnitude()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_geometry_look_angles() {
+        let earth = EarthModel::spherical();
+        let site = Geodetic::from_degrees(0.0, 0.0, 0.0);
+        let frame = LocalFrame::new(&earth, &site);
+        // A target due north at 100 kft altitude, 100 kft downrange
+        let north = frame.enu_to_ecef(&Vec3::new(0.0, 1.0e5, 1.0e5));
+        let look = frame.look_angles(&north);
+        assert!(look.azimuth.abs() < 1e-12 || (look.azimuth - 2.0 * PI).abs() < 1e-12);
+        assert!((look.elevation - PI / 4.0).abs() < 1e-12);
+        let west = frame.look_angles(&frame.enu_to_ecef(&Vec3::new(-1.0e5, 0.0, 0.0)));
+        assert!((west.azimuth - 1.5 * PI).abs() < 1e-12 && west.elevation.abs() < 1e-12);
+    }
+}
@@ -0,0 +1,376 @@
+//! Impact point prediction
+//!
+//! The book predicts impact points inside each lesson (PREDICTPZ, PREDICT44,
+//! PREDICT45, PREDICTB, PREDICTG, C32's PREDICTP), each with its own Earth,
+//! drag and stopping rule. `ImpactPredictor` flies any unpowered ECEF state
+//! over an `EarthModel`, with drag from a ballistic coefficient when one is
+//! given, to the surface or to an intercept altitude, or for a fixed time.
+//! A state covariance, such as a radar track's, is mapped through the
+//! numerically linearized flight into the covariance of the impact point
+//! and time.
+//!
+//! PREDICTPZ and PREDICT44 step through the predictor. The powered
+//! predictors and C32's flat-Earth PREDICTP with guidance commands keep
+//! their own loops; the tests check PREDICTB and PREDICT45 against it
+//! once the thrust is off.
+
+use super::atmosphere::{air_density, speed_of_sound};
+use super::constants::G_ACCEL;
+use super::earth::{EarthModel, Geodetic};
+use super::geometry::LocalFrame;
+use super::kepler::StateVector;
+use super::matrix::{mat_mul, transpose, Matrix};
+use super::reentry::BallisticCoefficient;
+use super::rk2::Vec3;
+
+/// Finite-difference perturbations of position (ft) and velocity (ft/s)
+const DELTA_POSITION: f64 = 10.0;
+const DELTA_VELOCITY: f64 = 0.1;
+
+/// Height above which drag is neglected (ft); the book's density there is
+/// below 1e-10 slug/ft^3
+const ATMOSPHERE_TOP: f64 = 400000.0;
+
+/// Geocentric heights further than this above the end altitude skip the
+/// geodetic conversion (ft)
+const ALTITUDE_MARGIN: f64 = 100000.0;
+
+/// Ballistic impact on the model's surface, or at the end altitude
+#[derive(Debug, Clone, Copy)]
+pub struct Impact {
+    pub position: Geodetic,
+    /// Flight time to impact
+    pub time: f64,
+    /// ECEF state at impact
+    pub state: StateVector,
+}
+
+/// Predicted impact with its uncertainty
+#[derive(Debug, Clone)]
+pub struct ImpactEstimate {
+    pub impact: Impact,
+    /// Covariance of the impact point east and north of the nominal (ft)
+    /// and of the impact time (s), in that order
+    pub covariance: Matrix,
+}
+
+impl ImpactEstimate {
+    /// One-sigma error ellipse: semi-major and semi-minor axes (ft) and the
+    /// azimuth of the major axis clockwise from north (rad)
+    pub fn error_ellipse(&self) -> (f64, f64, f64) {
+        let (pee, pen, pnn) = (self.covariance[0][0], self.covariance[0][1], self.covariance[1][1]);
+        let mean = 0.5 * (pee + pnn);
+        let dev = (0.25 * (pee - pnn).powi(2) + pen * pen).sqrt();
+        let major = (mean + dev).sqrt();
+        let minor = (mean - dev).max(0.0).sqrt();
+        // Major axis angle counterclockwise from east
+        let theta = 0.5 * (2.0 * pen).atan2(pee - pnn);
+        let azimuth = (std::f64::consts::FRAC_PI_2 - theta).rem_euclid(std::f64::consts::PI);
+        (major, minor, azimuth)
+    }
+
+    /// Circular error probable (ft) from the error ellipse
+    pub fn cep(&self) -> f64 {
+        let (major, minor, _) = self.error_ellipse();
+        0.589 * (major + minor)
+    }
+}
+
+/// Ballistic flight predictor over an `EarthModel`
+#[derive(Debug, Clone)]
+pub struct ImpactPredictor {
+    pub earth: EarthModel,
+    /// Drag on the Earth-relative velocity; `None` flies in vacuum
+    pub drag: Option<BallisticCoefficient>,
+    /// Integration step (s)
+    pub h: f64,
+    /// Longest flight considered (s)
+    pub max_time: f64,
+    /// Height above the surface at which the flight ends (ft)
+    pub altitude: f64,
+}
+
+impl ImpactPredictor {
+    pub fn new(earth: EarthModel) -> Self {
+        Self { earth, drag: None, h: 0.1, max_time: 10000.0, altitude: 0.0 }
+    }
+
+    pub fn with_drag(mut self, beta: BallisticCoefficient) -> Self {
+        self.drag = Some(beta);
+        self
+    }
+
+    pub fn with_step(mut self, h: f64) -> Self {
+        self.h = h;
+        self
+    }
+
+    pub fn with_max_time(mut self, max_time: f64) -> Self {
+        self.max_time = max_time;
+        self
+    }
+
+    /// End the flight descending through `altitude` instead of the surface,
+    /// for intercept points
+    pub fn with_altitude(mut self, altitude: f64) -> Self {
+        self.altitude = altitude;
+        self
+    }
+
+    /// Height of `r` above the ellipsoid, or the lower bound |r| - a when
+    /// that is more than `margin` above `floor`
+    fn height(&self, r: &Vec3, floor: f64, margin: f64) -> f64 {
+        let bound = r.magnitude() - self.earth.a;
+        if bound - floor > margin {
+            bound
+        } else {
+            self.earth.ecef_to_geodetic(r).alt
+        }
+    }
+
+    /// ECEF acceleration: gravity, the rotating-frame terms and drag
+    pub fn acceleration(&self, r: &Vec3, v: &Vec3) -> Vec3 {
+        let a = self.earth.acceleration_ecef(r, v);
+        match &self.drag {
+            Some(_) if r.magnitude() - self.earth.a > ATMOSPHERE_TOP => a,
+            Some(beta) => {
+                let alt = self.earth.ecef_to_geodetic(r).alt;
+                let vel = v.magnitude();
+                let q = 0.5 * air_density(alt) * vel * vel;
+                let drag = G_ACCEL * q / beta.at_mach(vel / speed_of_sound(alt));
+                a + v.normalize() * -drag
+            }
+            None => a,
+        }
+    }
+
+    /// Second-order Runge-Kutta step of an ECEF state
+    pub fn step(&self, sv: &StateVector, h: f64) -> StateVector {
+        let (r0, v0) = (sv.position(), sv.velocity());
+        let a0 = self.acceleration(&r0, &v0);
+        let r1 = r0 + v0 * h;
+        let v1 = v0 + a0 * h;
+        let a1 = self.acceleration(&r1, &v1);
+        StateVector::from_vectors(
+            (r0 + r1 + v1 * h) * 0.5,
+            (v0 + v1 + a1 * h) * 0.5,
+        )
+    }
+
+    /// ECEF state after `duration` seconds of flight
+    pub fn state_at(&self, sv: &StateVector, duration: f64) -> StateVector {
+        let mut x = *sv;
+        let mut t = 0.0;
+        while t < duration - 1e-9 {
+            let dt = self.h.min(duration - t);
+            x = self.step(&x, dt);
+            t += dt;
+        }
+        x
+    }
+
+    /// Flies the state until it descends through the end altitude, or
+    /// `None` if it does not within `max_time`
+    pub fn impact(&self, sv: &StateVector) -> Option<Impact> {
+        let h = self.h;
+        let mut x = *sv;
+        let mut alt = self.height(&x.position(), self.altitude, ALTITUDE_MARGIN) - self.altitude;
+        let mut t = 0.0;
+        while t < self.max_time {
+            let next = self.step(&x, h);
+            let next_alt = self.height(&next.position(), self.altitude, ALTITUDE_MARGIN) - self.altitude;
+            if next_alt < 0.0 && next_alt < alt {
+                // Interpolate to the end altitude within the last step
+                let frac = alt / (alt - next_alt);
+                let state = self.step(&x, frac * h);
+                let mut position = self.earth.ecef_to_geodetic(&state.position());
+                position.alt = self.altitude;
+                return Some(Impact { position, time: t + frac * h, state });
+            }
+            x = next;
+            alt = next_alt;
+            t += h;
+        }
+        None
+    }
+
+    /// Impact with the covariance of its location and time, given the 6x6
+    /// covariance of the ECEF position and velocity
+    pub fn impact_with_covariance(&self, sv: &StateVector, p: &Matrix) -> Option<ImpactEstimate> {
+        let impact = self.impact(sv)?;
+        let frame = LocalFrame::new(&self.earth, &impact.position);
+        let outputs = |sv: &StateVector| -> Option<Vec<f64>> {
+            let other = self.impact(sv)?;
+            let ground = Geodetic::new(other.position.lat, other.position.lon, impact.position.alt);
+            let enu = frame.ecef_to_enu(&self.earth.geodetic_to_ecef(&ground));
+            Some(vec![enu.x, enu.y, other.time])
+        };
+        let jacobian = jacobian(sv, outputs)?;
+        let covariance = mat_mul(&mat_mul(&jacobian, p), &transpose(&jacobian));
+        Some(ImpactEstimate { impact, covariance })
+    }
+
+    /// State after `duration` seconds with its 6x6 covariance, for
+    /// predicted intercept points
+    pub fn state_at_with_covariance(&self, sv: &StateVector, duration: f64, p: &Matrix) -> (StateVector, Matrix) {
+        let state = self.state_at(sv, duration);
+        let outputs = |sv: &StateVector| Some(state_components(&self.state_at(sv, duration)).to_vec());
+        let jacobian = jacobian(sv, outputs).expect("fixed-time flight always completes");
+        (state, mat_mul(&mat_mul(&jacobian, p), &transpose(&jacobian)))
+    }
+}
+
+/// Flies an unpowered ECEF state in vacuum until it descends through zero
+/// altitude, or `None` if it does not within `max_time`
+pub fn ballistic_impact(earth: &EarthModel, sv: &StateVector, h: f64, max_time: f64) -> Option<Impact> {
+    ImpactPredictor::new(*earth).with_step(h).with_max_time(max_time).impact(sv)
+}
+
+fn state_components(sv: &StateVector) -> [f64; 6] {
+    let (r, v) = (sv.position(), sv.velocity());
+    [r.x, r.y, r.z, v.x, v.y, v.z]
+}
+
+/// Central-difference Jacobian of `f` with respect to the six state
+/// components, or `None` if a perturbed flight fails
+fn jacobian(sv: &StateVector, f: impl Fn(&StateVector) -> Option<Vec<f64>>) -> Option<Matrix> {
+    let x0 = state_components(sv);
+    let mut columns = Vec::with_capacity(6);
+    for k in 0..6 {
+        let delta = if k < 3 { DELTA_POSITION } else { DELTA_VELOCITY };
+        let perturbed = |sign: f64| {
+            let mut x = x0;
+            x[k] += sign * delta;
+            StateVector::from_vectors(Vec3::new(x[0], x[1], x[2]), Vec3::new(x[3], x[4], x[5]))
+        };
+        let plus = f(&perturbed(1.0))?;
+        let minus = f(&perturbed(-1.0))?;
+        columns.push(plus.iter().zip(&minus).map(|(p, m)| (p - m) / (2.0 * delta)).collect::<Vec<f64>>());
+    }
+    Some(transpose(&columns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::{EARTH_RADIUS_FT, GM_FT};
+    use crate::utils::elements::propagate_state;
+    use crate::utils::matrix::diag;
+
+    fn lofted_state() -> StateVector {
+        let r = Vec3::new(EARTH_RADIUS_FT + 300000.0, 0.0, 0.0);
+        StateVector::from_vectors(r, Vec3::new(3000.0, 15000.0, 2000.0))
+    }
+
+    #[test]
+    fn test_impact_vacuum_matches_kepler() {
+        let predictor = ImpactPredictor::new(EarthModel::spherical()).with_step(0.01);
+        let sv = lofted_state();
+        let impact = predictor.impact(&sv).unwrap();
+        let kepler = propagate_state(&sv, impact.time, GM_FT);
+        assert!((kepler.position() - impact.state.position()).magnitude() < 50.0);
+        assert!(impact.position.alt == 0.0);
+
+        let later = predictor.state_at(&sv, 300.0);
+        let kepler = propagate_state(&sv, 300.0, GM_FT);
+        assert!((kepler.position() - later.position()).magnitude() < 10.0);
+
+        // Intercept altitude is reached earlier on the way down
+        let intercept = predictor.clone().with_altitude(100000.0).impact(&sv).unwrap();
+        assert!(intercept.time < impact.time);
+        assert!((intercept.state.position().magnitude() - EARTH_RADIUS_FT - 100000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_impact_drag_shortens_range() {
+        let sv = lofted_state();
+        let vacuum = ImpactPredictor::new(EarthModel::spherical()).impact(&sv).unwrap();
+        let drag = ImpactPredictor::new(EarthModel::spherical())
+            .with_drag(BallisticCoefficient::Constant(500.0))
+            .impact(&sv)
+            .unwrap();
+        assert!(drag.position.lon < vacuum.position.lon);
+        assert!(drag.time > vacuum.time);
+        assert!(drag.state.velocity().magnitude() < vacuum.state.velocity().magnitude());
+    }
+
+    #[test]
+    fn test_impact_covariance_linearization() {
+        let predictor = ImpactPredictor::new(EarthModel::wgs84());
+        let sv = lofted_state();
+        let p = diag(&[100.0, 100.0, 100.0, 1.0, 1.0, 1.0]);
+        let est = predictor.impact_with_covariance(&sv, &p).unwrap();
+        for i in 0..3 {
+            assert!(est.covariance[i][i] > 0.0);
+            for j in 0..3 {
+                assert!((est.covariance[i][j] - est.covariance[j][i]).abs() < 1e-6 * est.covariance[i][i].max(1.0));
+            }
+        }
+        // A 1 ft/s error in velocity moves the impact by far more than 10 ft
+        let (major, minor, _) = est.error_ellipse();
+        assert!(major > minor && minor > 10.0);
+        assert!(est.cep() > minor && est.cep() < 1.2 * (major + minor));
+
+        // Zero state covariance gives a zero impact covariance
+        let zero = predictor.impact_with_covariance(&sv, &diag(&[0.0; 6])).unwrap();
+        assert!(zero.covariance.iter().flatten().all(|c| *c == 0.0));
+
+        let (state, cov) = predictor.state_at_with_covariance(&sv, 100.0, &p);
+        assert!((state.position() - predictor.state_at(&sv, 100.0).position()).magnitude() < 1e-9);
+        // Velocity error grows position error roughly as 100 s times 1 ft/s
+        assert!(cov[1][1].sqrt() > 50.0 && cov[1][1].sqrt() < 200.0);
+    }
+
+    #[test]
+    fn test_ballistic_impact() {
+        let earth = EarthModel::spherical();
+        let r = earth.geodetic_to_ecef(&Geodetic::new(0.0, 0.0, 0.0));
+        // Vertical shot returns to the launch point
+        let impact = ballistic_impact(&earth, &StateVector::from_vectors(r, Vec3::new(3000.0, 0.0, 0.0)), 0.01, 1000.0).unwrap();
+        assert!(impact.position.lat.abs() < 1e-9 && impact.position.lon.abs() < 1e-9);
+        let g = GM_FT / (EARTH_RADIUS_FT * EARTH_RADIUS_FT);
+        // Slightly longer than the flat-Earth time as gravity weakens with height
+        let flat = 2.0 * 3000.0 / g;
+        assert!(impact.time > flat && impact.time < 1.02 * flat, "{}", impact.time);
+    }
+
+    #[test]
+    fn test_impact_reproduces_book_predictors() {
+        use crate::utils::lambert3d::lambert3d;
+        use crate::utils::predict::{predict44, predict45, predict_b};
+
+        // The book's Earth in vacuum, stepped at the predictors' 0.01 s
+        let predictor = ImpactPredictor::new(EarthModel::spherical()).with_step(0.01);
+        let at = |x: f64, y: f64, vx: f64, vy: f64, tf: f64| {
+            let sv = StateVector::from_vectors(Vec3::new(x, y, 0.0), Vec3::new(vx, vy, 0.0));
+            predictor.state_at(&sv, tf).position()
+        };
+        let close = |p: crate::utils::predict::PredictResult, r: Vec3| {
+            assert!((p.xtf - r.x).abs() < 1e-3 && (p.ytf - r.y).abs() < 1e-3, "{:?} {:?}", p, r);
+        };
+        let a = EARTH_RADIUS_FT;
+
+        // PREDICTB with both stages spent, so no thrust
+        let (x, y, vx, vy) = (a + 200000.0, 100000.0, 8000.0, 12000.0);
+        close(predict_b(300.0, x, y, vx, vy, 1000.0, 3000.0, 60.0, 0.0, 60.0, 500.0, 1500.0, 0.0, 1000.0), at(x, y, vx, vy, 300.0));
+
+        // PREDICT44 coasts from the Lambert velocity to the aim point
+        let (xf, yf) = (a * 0.1f64.cos(), a * 0.1f64.sin());
+        let v = lambert3d(a, 0.0, 0.0, 900.0, xf, yf, 0.0, 0);
+        close(predict44(0.0, a, 0.0, 0.0, 0.0, 500.0, 900.0, xf, yf), at(a, 0.0, v.vrx, v.vry, 500.0));
+
+        // PREDICT45 past the end of boost, with a velocity far from the
+        // Lambert solution so the guidance shuts the thrust off
+        close(predict45(250.0, x, y, vx, vy, 550.0, 900.0, 0.0, xf, yf, 2), at(x, y, vx, vy, 300.0));
+    }
+
+    #[test]
+    fn test_impact_error_ellipse_orientation() {
+        let impact = ImpactPredictor::new(EarthModel::spherical()).impact(&lofted_state()).unwrap();
+        // Error only along north
+        let est = ImpactEstimate { impact, covariance: diag(&[1.0, 4.0, 0.0]) };
+        let (major, minor, az) = est.error_ellipse();
+        assert!((major - 2.0).abs() < 1e-12 && (minor - 1.0).abs() < 1e-12);
+        assert!(az.abs() < 1e-12 || (az - std::f64::consts::PI).abs() < 1e-12);
+    }
+}
@@ -4,6 +4,7 @@
 //! universal variable formulation solver (KEPLER1.m)
 
 use super::constants::{GM_FT, GM_KM, EARTH_RADIUS_KM};
+use super::rk2::Vec3;
 
 /// Result from simple Kepler solver
 #[derive(Debug, Clone, Copy)]
@@ -141,6 +142,18 @@ impl StateVector {
     pub fn to_array(self) -> [f64; 6] {
         [self.x, self.y, self.z, self.vx, self.vy, self.vz]
     }
+
+    pub fn from_vectors(r: Vec3, v: Vec3) -> Self {
+        Self::new(r.x, r.y, r.z, v.x, v.y, v.z)
+    }
+
+    pub fn position(&self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    pub fn velocity(&self) -> Vec3 {
+        Vec3::new(self.vx, self.vy, self.vz)
+    }
 }
 
 /// Advanced Kepler solver using universal variable formulation (KEPLER1.m)
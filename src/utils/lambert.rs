@@ -0,0 +1,366 @@
+//! Vector Lambert solver
+//!
+//! Finds the departure and arrival velocities of the conic connecting two
+//! position vectors in a given time of flight, for any transfer direction
+//! and number of complete revolutions. Uses Izzo's formulation ("Revisiting
+//! Lambert's problem", 2015): the time of flight is written in terms of a
+//! single variable x and solved with third-order Householder iterations.
+
+use std::f64::consts::PI;
+use super::rk2::Vec3;
+
+/// Convergence tolerance on the iteration variable x
+const X_TOL: f64 = 1e-13;
+
+/// Iteration limit for the Householder and Halley searches
+const MAX_ITERATIONS: usize = 35;
+
+/// Sense of the transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Counterclockwise seen from +z
+    Prograde,
+    /// Clockwise seen from +z
+    Retrograde,
+    /// Transfer angle below 180 deg
+    ShortWay,
+    /// Transfer angle above 180 deg
+    LongWay,
+}
+
+/// Multi-revolution solution branch, either side of the minimum time of flight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Branch {
+    Left,
+    Right,
+}
+
+/// Lambert solution with convergence diagnostics
+#[derive(Debug, Clone, Copy)]
+pub struct LambertSolution {
+    /// Departure velocity
+    pub v1: Vec3,
+    /// Arrival velocity
+    pub v2: Vec3,
+    /// Complete revolutions before arrival
+    pub revs: u32,
+    /// Householder iterations used
+    pub iterations: usize,
+    /// Relative time of flight error of the converged solution
+    pub tof_error: f64,
+    pub converged: bool,
+}
+
+/// Transfer geometry in Izzo's non-dimensional form
+struct Geometry {
+    lambda: f64,
+    t: f64,
+    ir1: Vec3,
+    ir2: Vec3,
+    it1: Vec3,
+    it2: Vec3,
+    r1: f64,
+    r2: f64,
+    c: f64,
+    s: f64,
+}
+
+impl Geometry {
+    fn new(r1: Vec3, r2: Vec3, tof: f64, mu: f64, direction: Direction) -> Option<Self> {
+        let c = (r2 - r1).magnitude();
+        let r1n = r1.magnitude();
+        let r2n = r2.magnitude();
+        if tof <= 0.0 || c == 0.0 || r1n == 0.0 || r2n == 0.0 {
+            return None;
+        }
+        let s = 0.5 * (r1n + r2n + c);
+        let ir1 = r1 * (1.0 / r1n);
+        let ir2 = r2 * (1.0 / r2n);
+        let h = ir1.cross(&ir2);
+        if h.magnitude() < 1e-12 {
+            // Collinear positions leave the transfer plane undefined
+            return None;
+        }
+        let ih = h.normalize();
+
+        let long_way = match direction {
+            Direction::ShortWay => false,
+            Direction::LongWay => true,
+            Direction::Prograde => ih.z < 0.0,
+            Direction::Retrograde => ih.z >= 0.0,
+        };
+        let mut lambda = (1.0 - c / s).max(0.0).sqrt();
+        let (it1, it2) = if long_way {
+            lambda = -lambda;
+            (ir1.cross(&ih), ir2.cross(&ih))
+        } else {
+            (ih.cross(&ir1), ih.cross(&ir2))
+        };
+        let t = (2.0 * mu / (s * s * s)).sqrt() * tof;
+
+        Some(Self { lambda, t, ir1, ir2, it1, it2, r1: r1n, r2: r2n, c, s })
+    }
+
+    /// Velocities from the converged x, y pair
+    fn velocities(&self, x: f64, y: f64, mu: f64) -> (Vec3, Vec3) {
+        let gamma = (mu * self.s / 2.0).sqrt();
+        let rho = (self.r1 - self.r2) / self.c;
+        let sigma = (1.0 - rho * rho).sqrt();
+        let ly = self.lambda * y;
+
+        let vr1 = gamma * ((ly - x) - rho * (ly + x)) / self.r1;
+        let vr2 = -gamma * ((ly - x) + rho * (ly + x)) / self.r2;
+        let vt = gamma * sigma * (y + self.lambda * x);
+
+        (
+            self.ir1 * vr1 + self.it1 * (vt / self.r1),
+            self.ir2 * vr2 + self.it2 * (vt / self.r2),
+        )
+    }
+}
+
+/// Solves Lambert's problem
+///
+/// # Arguments
+/// * `r1` - Departure position
+/// * `r2` - Arrival position
+/// * `tof` - Time of flight
+/// * `mu` - Gravitational parameter, in units consistent with the positions
+/// * `direction` - Sense of the transfer
+/// * `revs` - Complete revolutions before arrival
+/// * `branch` - Solution branch when `revs` > 0, ignored otherwise
+///
+/// # Returns
+/// The solution, or `None` for collinear positions or a time of flight too
+/// short for the requested revolutions
+pub fn lambert(
+    r1: Vec3, r2: Vec3,
+    tof: f64,
+    mu: f64,
+    direction: Direction,
+    revs: u32,
+    branch: Branch,
+) -> Option<LambertSolution> {
+    let geom = Geometry::new(r1, r2, tof, mu, direction)?;
+    if revs > max_revs(geom.lambda, geom.t) {
+        return None;
+    }
+    let m = revs as f64;
+
+    let x0 = initial_guess(geom.lambda, geom.t, revs, branch);
+    let (x, iterations, converged) = householder(x0, geom.lambda, geom.t, m);
+    let y = compute_y(x, geom.lambda);
+    let tof_error = (tof_equation(x, y, geom.lambda, m) - geom.t).abs() / geom.t;
+    let (v1, v2) = geom.velocities(x, y, mu);
+
+    Some(LambertSolution { v1, v2, revs, iterations, tof_error, converged })
+}
+
+/// Largest number of complete revolutions feasible in the time of flight
+pub fn max_revolutions(r1: Vec3, r2: Vec3, tof: f64, mu: f64, direction: Direction) -> Option<u32> {
+    let geom = Geometry::new(r1, r2, tof, mu, direction)?;
+    Some(max_revs(geom.lambda, geom.t))
+}
+
+fn max_revs(lambda: f64, t: f64) -> u32 {
+    let mut m_max = (t / PI).floor() as u32;
+    let t00 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+    if m_max > 0 && t < t00 + m_max as f64 * PI {
+        // The minimum time for m_max revolutions lies above t00 + m_max pi
+        let (_, t_min) = minimum_time(lambda, m_max as f64);
+        if t < t_min {
+            m_max -= 1;
+        }
+    }
+    m_max
+}
+
+fn compute_y(x: f64, lambda: f64) -> f64 {
+    (1.0 - lambda * lambda * (1.0 - x * x)).sqrt()
+}
+
+/// Non-dimensional time of flight (Izzo eq. 18, with the series form near
+/// the parabola for a single revolution)
+fn tof_equation(x: f64, y: f64, lambda: f64, m: f64) -> f64 {
+    if m == 0.0 && x > 0.6_f64.sqrt() && x < 1.4_f64.sqrt() {
+        let eta = y - lambda * x;
+        let s1 = 0.5 * (1.0 - lambda - x * eta);
+        let q = 4.0 / 3.0 * hypergeometric(s1);
+        return 0.5 * (eta * eta * eta * q + 4.0 * lambda * eta);
+    }
+    let psi = if x < 1.0 {
+        (x * y + lambda * (1.0 - x * x)).clamp(-1.0, 1.0).acos()
+    } else if x > 1.0 {
+        ((y - x * lambda) * (x * x - 1.0).sqrt()).asinh()
+    } else {
+        0.0
+    };
+    ((psi + m * PI) / (1.0 - x * x).abs().sqrt() - x + lambda * y) / (1.0 - x * x)
+}
+
+/// Gauss hypergeometric series 2F1(3, 1, 5/2, z)
+fn hypergeometric(z: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut i = 0.0;
+    loop {
+        term *= (3.0 + i) * (1.0 + i) / (2.5 + i) * z / (i + 1.0);
+        let old = sum;
+        sum += term;
+        if sum == old || i > 1000.0 {
+            return sum;
+        }
+        i += 1.0;
+    }
+}
+
+/// First three derivatives of the time of flight with respect to x
+fn tof_derivatives(x: f64, y: f64, t: f64, lambda: f64) -> (f64, f64, f64) {
+    let l2 = lambda * lambda;
+    let l3 = l2 * lambda;
+    let omx2 = 1.0 - x * x;
+    let dt = (3.0 * t * x - 2.0 + 2.0 * l3 * x / y) / omx2;
+    let ddt = (3.0 * t + 5.0 * x * dt + 2.0 * (1.0 - l2) * l3 / (y * y * y)) / omx2;
+    let dddt = (7.0 * x * ddt + 8.0 * dt - 6.0 * (1.0 - l2) * l3 * l2 * x / y.powi(5)) / omx2;
+    (dt, ddt, dddt)
+}
+
+/// Initial guess for x (Izzo eqs. 30 and 31)
+fn initial_guess(lambda: f64, t: f64, revs: u32, branch: Branch) -> f64 {
+    if revs == 0 {
+        let t0 = lambda.acos() + lambda * (1.0 - lambda * lambda).sqrt();
+        let t1 = 2.0 * (1.0 - lambda * lambda * lambda) / 3.0;
+        if t >= t0 {
+            (t0 / t).powf(2.0 / 3.0) - 1.0
+        } else if t < t1 {
+            2.5 * t1 / t * (t1 - t) / (1.0 - lambda.powi(5)) + 1.0
+        } else {
+            (t0 / t).powf((t1 / t0).log2()) - 1.0
+        }
+    } else {
+        let m = revs as f64;
+        match branch {
+            Branch::Left => {
+                let k = ((m * PI + PI) / (8.0 * t)).powf(2.0 / 3.0);
+                (k - 1.0) / (k + 1.0)
+            }
+            Branch::Right => {
+                let k = (8.0 * t / (m * PI)).powf(2.0 / 3.0);
+                (k - 1.0) / (k + 1.0)
+            }
+        }
+    }
+}
+
+/// Householder iterations on T(x) = t; returns x, iterations and convergence
+fn householder(mut x: f64, lambda: f64, t: f64, m: f64) -> (f64, usize, bool) {
+    for i in 1..=MAX_ITERATIONS {
+        let y = compute_y(x, lambda);
+        let tx = tof_equation(x, y, lambda, m);
+        let f = tx - t;
+        let (dt, ddt, dddt) = tof_derivatives(x, y, tx, lambda);
+        let step = f * (dt * dt - f * ddt / 2.0)
+            / (dt * (dt * dt - f * ddt) + dddt * f * f / 6.0);
+        let xnew = x - step;
+        if !xnew.is_finite() {
+            return (x, i, false);
+        }
+        x = xnew;
+        if step.abs() < X_TOL {
+            return (x, i, true);
+        }
+    }
+    (x, MAX_ITERATIONS, false)
+}
+
+/// Location and value of the minimum time of flight for m revolutions,
+/// found with Halley iterations on dT/dx = 0
+fn minimum_time(lambda: f64, m: f64) -> (f64, f64) {
+    if lambda == 1.0 {
+        let x = 0.0;
+        return (x, tof_equation(x, compute_y(x, lambda), lambda, m));
+    }
+    let mut x: f64 = 0.1;
+    for _ in 0..MAX_ITERATIONS {
+        let y = compute_y(x, lambda);
+        let tx = tof_equation(x, y, lambda, m);
+        let (dt, ddt, dddt) = tof_derivatives(x, y, tx, lambda);
+        let step = 2.0 * dt * ddt / (2.0 * ddt * ddt - dt * dddt);
+        x -= step;
+        if step.abs() < X_TOL {
+            break;
+        }
+    }
+    (x, tof_equation(x, compute_y(x, lambda), lambda, m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::GM_KM;
+    use crate::utils::kepler::{StateVector, kepler1};
+
+    fn arrival(r1: Vec3, v1: Vec3, tof: f64) -> Vec3 {
+        let x0 = StateVector::new(r1.x, r1.y, r1.z, v1.x, v1.y, v1.z);
+        let x1 = kepler1(&x0, 0.0, tof);
+        Vec3::new(x1.x, x1.y, x1.z)
+    }
+
+    #[test]
+    fn test_lambert_vallado_example() {
+        // Vallado example 7-5
+        let r1 = Vec3::new(15945.34, 0.0, 0.0);
+        let r2 = Vec3::new(12214.83899, 10249.46731, 0.0);
+        let sol = lambert(r1, r2, 76.0 * 60.0, 398600.4418, Direction::Prograde, 0, Branch::Left).unwrap();
+        assert!(sol.converged);
+        assert!(sol.tof_error < 1e-12);
+        assert!((sol.v1.x - 2.058913).abs() < 1e-5);
+        assert!((sol.v1.y - 2.915965).abs() < 1e-5);
+        assert!((sol.v2.x + 3.451565).abs() < 1e-5);
+        assert!((sol.v2.y - 0.910315).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_lambert_directions_reach_target() {
+        let r1 = Vec3::new(7000.0, 1000.0, 500.0);
+        let r2 = Vec3::new(-2000.0, 7500.0, 1500.0);
+        let tof = 2500.0;
+        for direction in [Direction::ShortWay, Direction::LongWay, Direction::Prograde, Direction::Retrograde] {
+            let sol = lambert(r1, r2, tof, GM_KM, direction, 0, Branch::Left).unwrap();
+            assert!(sol.converged);
+            let miss = (arrival(r1, sol.v1, tof) - r2).magnitude();
+            assert!(miss < 1e-3, "{:?} miss {}", direction, miss);
+        }
+        // Prograde and short way coincide for this geometry
+        let short = lambert(r1, r2, tof, GM_KM, Direction::ShortWay, 0, Branch::Left).unwrap();
+        let pro = lambert(r1, r2, tof, GM_KM, Direction::Prograde, 0, Branch::Left).unwrap();
+        assert!((short.v1 - pro.v1).magnitude() < 1e-12);
+    }
+
+    #[test]
+    fn test_lambert_multi_revolution() {
+        let r1 = Vec3::new(7000.0, 0.0, 0.0);
+        let r2 = Vec3::new(0.0, 8000.0, 0.0);
+        let tof = 20000.0;
+        let m_max = max_revolutions(r1, r2, tof, GM_KM, Direction::Prograde).unwrap();
+        assert!(m_max >= 1);
+        assert!(lambert(r1, r2, tof, GM_KM, Direction::Prograde, m_max + 1, Branch::Left).is_none());
+
+        let left = lambert(r1, r2, tof, GM_KM, Direction::Prograde, 1, Branch::Left).unwrap();
+        let right = lambert(r1, r2, tof, GM_KM, Direction::Prograde, 1, Branch::Right).unwrap();
+        assert!((left.v1 - right.v1).magnitude() > 0.01);
+        for sol in [left, right] {
+            assert!(sol.converged);
+            assert!(sol.tof_error < 1e-10);
+            let miss = (arrival(r1, sol.v1, tof) - r2).magnitude();
+            assert!(miss < 1e-2, "miss {}", miss);
+        }
+    }
+
+    #[test]
+    fn test_lambert_degenerate_geometry() {
+        let r1 = Vec3::new(7000.0, 0.0, 0.0);
+        assert!(lambert(r1, r1 * 1.2, 1000.0, GM_KM, Direction::ShortWay, 0, Branch::Left).is_none());
+        assert!(lambert(r1, Vec3::new(0.0, 7000.0, 0.0), -1.0, GM_KM, Direction::ShortWay, 0, Branch::Left).is_none());
+    }
+}
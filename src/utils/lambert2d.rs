@@ -15,6 +15,10 @@ pub struct Lambert2DResult {
 
 /// Solves the 2D Lambert problem
 ///
+/// Port of the lamberpz flight path angle iteration, returning the last
+/// iterate if it does not converge in 100 steps. New code should use
+/// [`super::lambert::lambert`].
+///
 /// # Arguments
 /// * `xic`, `yic` - Initial position
 /// * `tfdes` - Desired time of flight
@@ -146,4 +150,20 @@ mod tests {
         let v_mag = (result.vrx.powi(2) + result.vry.powi(2)).sqrt();
         assert!(v_mag > 0.0);
     }
+
+    #[test]
+    fn test_lambert2d_matches_gamma_iteration() {
+        // Required velocities from the lamberpz.m iteration
+        let a = EARTH_RADIUS_FT;
+        let cases = [
+            ((a, 0.0, 1000.0, a * 0.8, a * 0.6, 0.0, 0.5), [9.352392e3, 1.545878e4]),
+            ((a, 0.0, 1500.0, a * 0.6, -a * 0.8, 0.0, -0.7), [1.196352e4, -1.659921e4]),
+        ];
+        for (c, expected) in cases {
+            let result = lambert2d(c.0, c.1, c.2, c.3, c.4, c.5, c.6);
+            for (v, e) in [result.vrx, result.vry].iter().zip(expected) {
+                assert!((v - e).abs() < 1e-6 * e.abs(), "{} vs {}", v, e);
+            }
+        }
+    }
 }
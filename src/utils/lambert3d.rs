@@ -19,6 +19,12 @@ pub struct Lambert3DResult {
 
 /// Solves the 3D Lambert problem
 ///
+/// Port of the LAMBERT3D flight path angle iteration. When the iteration
+/// does not reach the time of flight in 100 steps it returns its last
+/// iterate, as the MATLAB listings do; the C44 footprints depend on this.
+/// New code should use [`super::lambert::lambert`], which reports
+/// convergence.
+///
 /// # Arguments
 /// * `xt`, `yt`, `zt` - Initial position (from position)
 /// * `tf` - Time of flight
@@ -251,4 +257,49 @@ mod tests {
         assert!(v_mag > 0.0);
         assert!(v_mag < 100000.0); // Reasonable orbital velocity
     }
+
+    #[test]
+    fn test_lambert3d_matches_gamma_iteration() {
+        // Required velocities from the LAMBERT3D flight path angle iteration
+        let a = EARTH_RADIUS_FT;
+        let cases = [
+            ((a, 0.0, 0.0, 1000.0, 0.0, a * 1.5, 0.0, 0), [-8.843531e3, 3.908335e4, 0.0]),
+            ((a, 0.0, 0.0, 1800.0, a * 0.6, a * 0.8, a * 0.1, 0), [1.451502e4, 1.517166e4, 1.896458e3]),
+            ((a, 0.0, 0.0, 2500.0, a * 0.6, -a * 0.8, 0.0, 1), [-5.091280e3, 2.134023e4, 0.0]),
+        ];
+        for (c, expected) in cases {
+            let result = lambert3d(c.0, c.1, c.2, c.3, c.4, c.5, c.6, c.7);
+            for (v, e) in [result.vrx, result.vry, result.vrz].iter().zip(expected) {
+                assert!((v - e).abs() < 1e-6 * e.abs() + 1e-3, "{} vs {}", v, e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lambert3d_c44l3_ground_track_sites() {
+        // C44L3 interceptor sites on the threat ground track (60 deg lon on
+        // the equator, 65 deg lon at 2.5 deg lat), where the iteration stops
+        // short of the time of flight. The listing keeps the last iterate and
+        // rejects the site on burnout velocity, so it must come back unchanged.
+        let cases = [
+            (
+                (1.0464397795816991e7, 1.81216405099235e7, 0.0, 1.06e3,
+                 1.1862529536281785e7, 2.154055564677245e7, 0.0, 0),
+                [1.914863372998306e4, 3.352673192090768e4, 0.0],
+            ),
+            (
+                (8.836876943267759e6, 1.8946610211114757e7, 9.127121110321069e5, 1.01e3,
+                 9.047998588169076e6, 2.2169189500694264e7, 0.0, 0),
+                [2.1317434252981555e4, 4.647916880412681e4, 1.9407103440167396e3],
+            ),
+        ];
+        for (c, expected) in cases {
+            let result = lambert3d(c.0, c.1, c.2, c.3, c.4, c.5, c.6, c.7);
+            for (v, e) in [result.vrx, result.vry, result.vrz].iter().zip(expected) {
+                assert!((v - e).abs() <= 1e-9 * e.abs(), "{} vs {}", v, e);
+            }
+            let vbo = (result.vrx.powi(2) + result.vry.powi(2) + result.vrz.powi(2)).sqrt() / 3280.0;
+            assert!(vbo > 5.0, "burnout velocity {} km/s", vbo);
+        }
+    }
 }
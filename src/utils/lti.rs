@@ -0,0 +1,575 @@
+//! Linear time-invariant system models and frequency response
+//!
+//! The frequency response listings (C22L3, C23L2, C26L7-L10) expand the
+//! magnitude and phase of each factor by hand. This module represents a loop
+//! as a transfer function, zero-pole-gain or state-space model, composes
+//! models in series, parallel and feedback, and produces Bode, Nyquist and
+//! Nichols data along with gain and phase margins.
+//!
+//! Polynomials use the MATLAB convention of descending powers of s.
+
+use super::complex::Complex;
+use super::matrix::{char_poly, dims, mat_mul, mat_sub, solve_complex, zeros, Matrix};
+use super::polynomial::{
+    poly_add, poly_eval_complex, poly_from_roots, poly_mul, poly_roots, poly_scale, poly_trim,
+};
+
+/// Anything that can be evaluated along the imaginary axis
+pub trait FrequencyResponse {
+    /// Complex response at frequency `w` (rad/sec)
+    fn freq_response(&self, w: f64) -> Complex;
+}
+
+/// Transfer function num(s)/den(s)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferFunction {
+    pub num: Vec<f64>,
+    pub den: Vec<f64>,
+}
+
+impl TransferFunction {
+    pub fn new(num: &[f64], den: &[f64]) -> Self {
+        Self { num: poly_trim(num), den: poly_trim(den) }
+    }
+
+    /// Static gain K
+    pub fn gain(k: f64) -> Self {
+        Self::new(&[k], &[1.0])
+    }
+
+    /// Pure integrator 1/s
+    pub fn integrator() -> Self {
+        Self::new(&[1.0], &[1.0, 0.0])
+    }
+
+    /// First-order lag 1/(tau*s + 1)
+    pub fn lag(tau: f64) -> Self {
+        Self::new(&[1.0], &[tau, 1.0])
+    }
+
+    /// First-order lead (tau*s + 1)
+    pub fn lead(tau: f64) -> Self {
+        Self::new(&[tau, 1.0], &[1.0])
+    }
+
+    /// Unity DC gain quadratic 1/(s^2/w^2 + 2*z*s/w + 1)
+    pub fn second_order(z: f64, w: f64) -> Self {
+        Self::new(&[1.0], &[1.0 / (w * w), 2.0 * z / w, 1.0])
+    }
+
+    /// Evaluate at an arbitrary complex frequency s
+    pub fn eval(&self, s: Complex) -> Complex {
+        poly_eval_complex(&self.num, s) / poly_eval_complex(&self.den, s)
+    }
+
+    /// Multiply by a scalar gain
+    pub fn scale(&self, k: f64) -> Self {
+        Self::new(&poly_scale(&self.num, k), &self.den)
+    }
+
+    /// Cascade connection self * other
+    pub fn series(&self, other: &Self) -> Self {
+        Self::new(&poly_mul(&self.num, &other.num), &poly_mul(&self.den, &other.den))
+    }
+
+    /// Parallel connection self + other
+    pub fn parallel(&self, other: &Self) -> Self {
+        let num = poly_add(&poly_mul(&self.num, &other.den), &poly_mul(&other.num, &self.den));
+        Self::new(&num, &poly_mul(&self.den, &other.den))
+    }
+
+    /// Closed loop with feedback path `h` (MATLAB `feedback(G, H, sign)`)
+    ///
+    /// `sign` is -1.0 for negative feedback G/(1 + GH) and +1.0 for positive
+    /// feedback G/(1 - GH).
+    pub fn feedback(&self, h: &Self, sign: f64) -> Self {
+        let num = poly_mul(&self.num, &h.den);
+        let den = poly_add(
+            &poly_mul(&self.den, &h.den),
+            &poly_scale(&poly_mul(&self.num, &h.num), -sign),
+        );
+        Self::new(&num, &den)
+    }
+
+    /// Negative unity feedback G/(1 + G)
+    pub fn unity_feedback(&self) -> Self {
+        self.feedback(&Self::gain(1.0), -1.0)
+    }
+
+    /// Order of the denominator
+    pub fn order(&self) -> usize {
+        self.den.len() - 1
+    }
+
+    /// True when the numerator degree does not exceed the denominator degree
+    pub fn is_proper(&self) -> bool {
+        self.num.len() <= self.den.len()
+    }
+
+    pub fn poles(&self) -> Vec<Complex> {
+        poly_roots(&self.den)
+    }
+
+    pub fn zeros(&self) -> Vec<Complex> {
+        poly_roots(&self.num)
+    }
+
+    /// Steady-state gain G(0)
+    pub fn dc_gain(&self) -> f64 {
+        self.num[self.num.len() - 1] / self.den[self.den.len() - 1]
+    }
+
+    /// Convert to zero-pole-gain form
+    pub fn to_zpk(&self) -> ZeroPoleGain {
+        ZeroPoleGain {
+            zeros: self.zeros(),
+            poles: self.poles(),
+            gain: self.num[0] / self.den[0],
+        }
+    }
+
+    /// Controllable canonical state-space realization
+    ///
+    /// # Panics
+    /// Panics if the transfer function is improper.
+    pub fn to_state_space(&self) -> StateSpace {
+        assert!(self.is_proper(), "improper transfer function has no state-space realization");
+        let n = self.order();
+        let lead = self.den[0];
+        let den: Vec<f64> = self.den.iter().map(|c| c / lead).collect();
+        let mut num = vec![0.0; n + 1];
+        let off = n + 1 - self.num.len();
+        for (i, &c) in self.num.iter().enumerate() {
+            num[off + i] = c / lead;
+        }
+
+        let d = num[0];
+        let mut a = zeros(n, n);
+        let mut b = zeros(n, 1);
+        let mut c = zeros(1, n);
+        if n > 0 {
+            for j in 0..n {
+                a[0][j] = -den[j + 1];
+                c[0][j] = num[j + 1] - d * den[j + 1];
+            }
+            for i in 1..n {
+                a[i][i - 1] = 1.0;
+            }
+            b[0][0] = 1.0;
+        }
+        StateSpace { a, b, c, d: vec![vec![d]] }
+    }
+}
+
+impl FrequencyResponse for TransferFunction {
+    fn freq_response(&self, w: f64) -> Complex {
+        self.eval(Complex::jw(w))
+    }
+}
+
+/// Zero-pole-gain form K * prod(s - z_i) / prod(s - p_i)
+#[derive(Debug, Clone)]
+pub struct ZeroPoleGain {
+    pub zeros: Vec<Complex>,
+    pub poles: Vec<Complex>,
+    pub gain: f64,
+}
+
+impl ZeroPoleGain {
+    pub fn new(zeros: &[Complex], poles: &[Complex], gain: f64) -> Self {
+        Self { zeros: zeros.to_vec(), poles: poles.to_vec(), gain }
+    }
+
+    pub fn eval(&self, s: Complex) -> Complex {
+        let num = self.zeros.iter().fold(Complex::real(self.gain), |acc, &z| acc * (s - z));
+        self.poles.iter().fold(num, |acc, &p| acc / (s - p))
+    }
+
+    pub fn series(&self, other: &Self) -> Self {
+        let mut zeros = self.zeros.clone();
+        zeros.extend_from_slice(&other.zeros);
+        let mut poles = self.poles.clone();
+        poles.extend_from_slice(&other.poles);
+        Self { zeros, poles, gain: self.gain * other.gain }
+    }
+
+    pub fn to_tf(&self) -> TransferFunction {
+        TransferFunction::new(
+            &poly_scale(&poly_from_roots(&self.zeros), self.gain),
+            &poly_from_roots(&self.poles),
+        )
+    }
+}
+
+impl FrequencyResponse for ZeroPoleGain {
+    fn freq_response(&self, w: f64) -> Complex {
+        self.eval(Complex::jw(w))
+    }
+}
+
+/// State-space model xdot = A x + B u, y = C x + D u
+#[derive(Debug, Clone)]
+pub struct StateSpace {
+    pub a: Matrix,
+    pub b: Matrix,
+    pub c: Matrix,
+    pub d: Matrix,
+}
+
+impl StateSpace {
+    pub fn new(a: Matrix, b: Matrix, c: Matrix, d: Matrix) -> Self {
+        Self { a, b, c, d }
+    }
+
+    pub fn n_states(&self) -> usize {
+        self.a.len()
+    }
+
+    pub fn n_inputs(&self) -> usize {
+        dims(&self.d).1
+    }
+
+    pub fn n_outputs(&self) -> usize {
+        self.d.len()
+    }
+
+    /// Response from `input` to `output` at complex frequency s
+    #[allow(clippy::needless_range_loop)]
+    pub fn eval_channel(&self, s: Complex, input: usize, output: usize) -> Complex {
+        let n = self.n_states();
+        let mut m = vec![vec![Complex::default(); n]; n];
+        for i in 0..n {
+            for j in 0..n {
+                m[i][j] = Complex::real(-self.a[i][j]);
+            }
+            m[i][i] = m[i][i] + s;
+        }
+        let rhs: Vec<Complex> = (0..n).map(|i| Complex::real(self.b[i][input])).collect();
+        let x = solve_complex(&m, &rhs)
+            .unwrap_or_else(|| vec![Complex::new(f64::INFINITY, 0.0); n]);
+        let mut y = Complex::real(self.d[output][input]);
+        for (j, xj) in x.iter().enumerate() {
+            y = y + *xj * self.c[output][j];
+        }
+        y
+    }
+
+    /// Cascade connection: the output of self drives other
+    #[allow(clippy::needless_range_loop)]
+    pub fn series(&self, other: &Self) -> Self {
+        let (n1, n2) = (self.n_states(), other.n_states());
+        let mut a = zeros(n1 + n2, n1 + n2);
+        let b2c1 = mat_mul(&other.b, &self.c);
+        for i in 0..n1 {
+            a[i][..n1].copy_from_slice(&self.a[i]);
+        }
+        for i in 0..n2 {
+            a[n1 + i][..n1].copy_from_slice(&b2c1[i]);
+            a[n1 + i][n1..].copy_from_slice(&other.a[i]);
+        }
+        let b2d1 = mat_mul(&other.b, &self.d);
+        let mut b = self.b.clone();
+        b.extend(b2d1);
+        let d2c1 = mat_mul(&other.d, &self.c);
+        let c: Matrix = d2c1
+            .iter()
+            .zip(other.c.iter())
+            .map(|(l, r)| l.iter().chain(r.iter()).copied().collect())
+            .collect();
+        Self { a, b, c, d: mat_mul(&other.d, &self.d) }
+    }
+
+    /// Parallel connection with shared input and summed outputs
+    #[allow(clippy::needless_range_loop)]
+    pub fn parallel(&self, other: &Self) -> Self {
+        let (n1, n2) = (self.n_states(), other.n_states());
+        let mut a = zeros(n1 + n2, n1 + n2);
+        for i in 0..n1 {
+            a[i][..n1].copy_from_slice(&self.a[i]);
+        }
+        for i in 0..n2 {
+            a[n1 + i][n1..].copy_from_slice(&other.a[i]);
+        }
+        let mut b = self.b.clone();
+        b.extend(other.b.iter().cloned());
+        let c: Matrix = self
+            .c
+            .iter()
+            .zip(other.c.iter())
+            .map(|(l, r)| l.iter().chain(r.iter()).copied().collect())
+            .collect();
+        let d = self
+            .d
+            .iter()
+            .zip(other.d.iter())
+            .map(|(l, r)| l.iter().zip(r.iter()).map(|(x, y)| x + y).collect())
+            .collect();
+        Self { a, b, c, d }
+    }
+
+    /// Closed loop with feedback path `h`; `sign` is -1.0 for negative feedback
+    ///
+    /// Both systems must be SISO and at least one of them strictly proper.
+    #[allow(clippy::needless_range_loop)]
+    pub fn feedback(&self, h: &Self, sign: f64) -> Self {
+        let (n1, n2) = (self.n_states(), h.n_states());
+        let d1 = self.d[0][0];
+        let d2 = h.d[0][0];
+        let e = 1.0 / (1.0 - sign * d1 * d2);
+
+        // u = r + sign * y2, y1 = C1 x1 + D1 u, y2 = C2 x2 + D2 y1
+        let mut a = zeros(n1 + n2, n1 + n2);
+        let mut b = zeros(n1 + n2, 1);
+        let mut c = zeros(1, n1 + n2);
+        for i in 0..n1 {
+            for j in 0..n1 {
+                a[i][j] = self.a[i][j] + sign * e * self.b[i][0] * d2 * self.c[0][j];
+            }
+            for j in 0..n2 {
+                a[i][n1 + j] = sign * e * self.b[i][0] * h.c[0][j];
+            }
+            b[i][0] = e * self.b[i][0];
+        }
+        for i in 0..n2 {
+            for j in 0..n1 {
+                a[n1 + i][j] = e * h.b[i][0] * self.c[0][j];
+            }
+            for j in 0..n2 {
+                a[n1 + i][n1 + j] = h.a[i][j] + sign * e * h.b[i][0] * d1 * h.c[0][j];
+            }
+            b[n1 + i][0] = e * h.b[i][0] * d1;
+        }
+        for j in 0..n1 {
+            c[0][j] = e * self.c[0][j];
+        }
+        for j in 0..n2 {
+            c[0][n1 + j] = sign * e * d1 * h.c[0][j];
+        }
+        Self { a, b, c, d: vec![vec![e * d1]] }
+    }
+
+    pub fn poles(&self) -> Vec<Complex> {
+        poly_roots(&char_poly(&self.a))
+    }
+
+    /// Transfer function from `input` to `output`
+    ///
+    /// Uses the determinant identity c (sI - A)^-1 b = [det(sI - A + b c) - det(sI - A)] / det(sI - A).
+    #[allow(clippy::needless_range_loop)]
+    pub fn to_tf(&self, input: usize, output: usize) -> TransferFunction {
+        let n = self.n_states();
+        let den = char_poly(&self.a);
+        let mut bc = zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                bc[i][j] = self.b[i][input] * self.c[output][j];
+            }
+        }
+        let with_bc = char_poly(&mat_sub(&self.a, &bc));
+        let strict = poly_add(&with_bc, &poly_scale(&den, -1.0));
+        let num = poly_add(&strict, &poly_scale(&den, self.d[output][input]));
+        TransferFunction::new(&num, &den)
+    }
+}
+
+impl FrequencyResponse for StateSpace {
+    fn freq_response(&self, w: f64) -> Complex {
+        self.eval_channel(Complex::jw(w), 0, 0)
+    }
+}
+
+/// Logarithmically spaced frequencies from 10^lo to 10^hi (MATLAB `logspace`)
+pub fn logspace(lo: f64, hi: f64, n: usize) -> Vec<f64> {
+    if n < 2 {
+        return vec![10.0_f64.powf(hi)];
+    }
+    (0..n)
+        .map(|i| 10.0_f64.powf(lo + (hi - lo) * (i as f64) / ((n - 1) as f64)))
+        .collect()
+}
+
+/// Wrap an angle in degrees to (-180, 180]
+pub fn wrap_deg(angle: f64) -> f64 {
+    let mut a = angle % 360.0;
+    if a > 180.0 {
+        a -= 360.0;
+    } else if a <= -180.0 {
+        a += 360.0;
+    }
+    a
+}
+
+/// Bode data: gain in dB and continuous (unwrapped) phase in degrees
+#[derive(Debug, Clone)]
+pub struct BodeData {
+    pub freq: Vec<f64>,
+    pub gain_db: Vec<f64>,
+    pub phase_deg: Vec<f64>,
+}
+
+/// Nyquist data: real and imaginary parts of G(jw)
+#[derive(Debug, Clone)]
+pub struct NyquistData {
+    pub freq: Vec<f64>,
+    pub re: Vec<f64>,
+    pub im: Vec<f64>,
+}
+
+/// Nichols data: open-loop gain in dB against phase in degrees
+#[derive(Debug, Clone)]
+pub struct NicholsData {
+    pub freq: Vec<f64>,
+    pub phase_deg: Vec<f64>,
+    pub gain_db: Vec<f64>,
+}
+
+/// Gain and phase margins of an open-loop response
+///
+/// The gain margin is reported in dB at the phase crossover closest to 0 dB
+/// margin; the phase margin is the smallest margin over all gain crossovers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StabilityMargins {
+    pub gain_margin_db: Option<f64>,
+    pub phase_crossover: Option<f64>,
+    pub phase_margin_deg: Option<f64>,
+    pub gain_crossover: Option<f64>,
+}
+
+/// Bode gain and phase over the given frequencies
+pub fn bode<S: FrequencyResponse + ?Sized>(sys: &S, freq: &[f64]) -> BodeData {
+    let mut gain_db = Vec::with_capacity(freq.len());
+    let mut phase_deg: Vec<f64> = Vec::with_capacity(freq.len());
+    for &w in freq {
+        let g = sys.freq_response(w);
+        gain_db.push(20.0 * g.abs().log10());
+        let raw = g.arg().to_degrees();
+        let phase = match phase_deg.last() {
+            Some(&prev) => prev + wrap_deg(raw - prev),
+            None => raw,
+        };
+        phase_deg.push(phase);
+    }
+    BodeData { freq: freq.to_vec(), gain_db, phase_deg }
+}
+
+/// Nyquist locus over the given frequencies
+pub fn nyquist<S: FrequencyResponse + ?Sized>(sys: &S, freq: &[f64]) -> NyquistData {
+    let resp: Vec<Complex> = freq.iter().map(|&w| sys.freq_response(w)).collect();
+    NyquistData {
+        freq: freq.to_vec(),
+        re: resp.iter().map(|g| g.re).collect(),
+        im: resp.iter().map(|g| g.im).collect(),
+    }
+}
+
+/// Nichols chart data over the given frequencies
+pub fn nichols<S: FrequencyResponse + ?Sized>(sys: &S, freq: &[f64]) -> NicholsData {
+    let b = bode(sys, freq);
+    NicholsData { freq: b.freq, phase_deg: b.phase_deg, gain_db: b.gain_db }
+}
+
+/// Bisection in log-frequency for a sign change of `f` between `lo` and `hi`
+pub(crate) fn refine_crossing<F: Fn(f64) -> f64>(f: F, lo: f64, hi: f64) -> f64 {
+    let (mut lo, mut hi) = (lo.ln(), hi.ln());
+    let f_lo = f(lo.exp());
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if (f(mid.exp()) > 0.0) == (f_lo > 0.0) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    (0.5 * (lo + hi)).exp()
+}
+
+/// Gain and phase margins found by scanning `freq` and refining each crossing
+pub fn margins<S: FrequencyResponse + ?Sized>(sys: &S, freq: &[f64]) -> StabilityMargins {
+    let data = bode(sys, freq);
+    let mut result = StabilityMargins::default();
+
+    // Phase along the grid is unwrapped; between grid points it is tracked
+    // relative to the nearest grid value so the refinement stays continuous.
+    let phase_near = |w: f64, reference: f64| {
+        reference + wrap_deg(sys.freq_response(w).arg().to_degrees() - reference)
+    };
+
+    for i in 0..freq.len().saturating_sub(1) {
+        let (w0, w1) = (freq[i], freq[i + 1]);
+
+        let (g0, g1) = (data.gain_db[i], data.gain_db[i + 1]);
+        if g0 == 0.0 || (g0 > 0.0) != (g1 > 0.0) {
+            let wc = refine_crossing(|w| 20.0 * sys.freq_response(w).abs().log10(), w0, w1);
+            let pm = wrap_deg(phase_near(wc, data.phase_deg[i]) + 180.0);
+            if result.phase_margin_deg.is_none_or(|old| pm < old) {
+                result.phase_margin_deg = Some(pm);
+                result.gain_crossover = Some(wc);
+            }
+        }
+
+        let (q0, q1) = ((data.phase_deg[i] + 180.0) / 360.0, (data.phase_deg[i + 1] + 180.0) / 360.0);
+        if q0.floor() != q1.floor() {
+            let m = q0.floor().max(q1.floor());
+            let target = 360.0 * m - 180.0;
+            let wp = refine_crossing(|w| phase_near(w, data.phase_deg[i]) - target, w0, w1);
+            let gm = -20.0 * sys.freq_response(wp).abs().log10();
+            if result.gain_margin_db.is_none_or(|old| gm.abs() < old.abs()) {
+                result.gain_margin_db = Some(gm);
+                result.phase_crossover = Some(wp);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bode_matches_c22l3() {
+        // Rate gyro flight control system open loop from C22L3
+        let (zact, wact, k3, ta, zaf, waf, kr) = (0.7, 150.0, -1.89, 0.457, 0.058, 25.3, 0.1);
+        let g = TransferFunction::gain(-k3 * kr)
+            .series(&TransferFunction::lead(ta))
+            .series(&TransferFunction::second_order(zaf, waf))
+            .series(&TransferFunction::second_order(zact, wact));
+
+        let reference = crate::chapters::c22::l3::run();
+        let data = bode(&g, &reference.freq);
+        for i in 0..reference.freq.len() {
+            assert!((data.gain_db[i] - reference.gain[i]).abs() < 1e-6);
+            // C22L3 converts with 57.3 deg/rad rather than 180/pi
+            assert!((data.phase_deg[i] - reference.phase[i]).abs() < 1e-4 * reference.phase[i].abs() + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_margins_third_order() {
+        // G = 1/(s(s+1)(s+2)): phase crossover at sqrt(2), gain margin 6 (15.56 dB)
+        let g = TransferFunction::new(&[1.0], &[1.0, 3.0, 2.0, 0.0]);
+        let m = margins(&g, &logspace(-2.0, 2.0, 400));
+        assert!((m.phase_crossover.unwrap() - 2.0_f64.sqrt()).abs() < 1e-6);
+        assert!((m.gain_margin_db.unwrap() - 20.0 * 6.0_f64.log10()).abs() < 1e-6);
+        assert!(m.phase_margin_deg.unwrap() > 50.0);
+    }
+
+    #[test]
+    fn test_state_space_round_trip() {
+        let g = TransferFunction::new(&[2.0, 3.0], &[1.0, 4.0, 5.0, 6.0]);
+        let ss = g.to_state_space();
+        let back = ss.to_tf(0, 0);
+        for w in [0.1, 1.0, 10.0] {
+            let a = g.freq_response(w);
+            let b = back.freq_response(w);
+            assert!((a - b).abs() < 1e-9 * (1.0 + a.abs()));
+        }
+
+        let h = TransferFunction::lag(0.1);
+        let closed_tf = g.feedback(&h, -1.0);
+        let closed_ss = ss.feedback(&h.to_state_space(), -1.0);
+        let r1 = closed_tf.freq_response(2.0);
+        let r2 = closed_ss.freq_response(2.0);
+        assert!((r1 - r2).abs() < 1e-9);
+    }
+}
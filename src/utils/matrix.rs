@@ -0,0 +1,312 @@
+//! Dense matrix helpers for general-order models
+//!
+//! The listings hardcode fixed-size arrays (`[[f64; 6]; 6]`) for each filter.
+//! These helpers operate on `Vec<Vec<f64>>` so that state-space, Riccati and
+//! estimation code can work with any number of states.
+
+use super::complex::Complex;
+use super::polynomial::poly_roots;
+
+/// Row-major dense matrix
+pub type Matrix = Vec<Vec<f64>>;
+
+/// n x m matrix of zeros
+pub fn zeros(n: usize, m: usize) -> Matrix {
+    vec![vec![0.0; m]; n]
+}
+
+/// n x n identity matrix
+pub fn identity(n: usize) -> Matrix {
+    let mut i = zeros(n, n);
+    for (k, row) in i.iter_mut().enumerate() {
+        row[k] = 1.0;
+    }
+    i
+}
+
+/// Diagonal matrix from a vector
+pub fn diag(d: &[f64]) -> Matrix {
+    let mut m = zeros(d.len(), d.len());
+    for (k, &v) in d.iter().enumerate() {
+        m[k][k] = v;
+    }
+    m
+}
+
+/// Number of rows and columns
+pub fn dims(a: &[Vec<f64>]) -> (usize, usize) {
+    (a.len(), a.first().map_or(0, |r| r.len()))
+}
+
+pub fn mat_mul(a: &[Vec<f64>], b: &[Vec<f64>]) -> Matrix {
+    let (n, k) = dims(a);
+    let m = dims(b).1;
+    let mut out = zeros(n, m);
+    for i in 0..n {
+        for p in 0..k {
+            let aip = a[i][p];
+            if aip == 0.0 {
+                continue;
+            }
+            for j in 0..m {
+                out[i][j] += aip * b[p][j];
+            }
+        }
+    }
+    out
+}
+
+pub fn mat_add(a: &[Vec<f64>], b: &[Vec<f64>]) -> Matrix {
+    a.iter()
+        .zip(b.iter())
+        .map(|(ra, rb)| ra.iter().zip(rb.iter()).map(|(x, y)| x + y).collect())
+        .collect()
+}
+
+pub fn mat_sub(a: &[Vec<f64>], b: &[Vec<f64>]) -> Matrix {
+    a.iter()
+        .zip(b.iter())
+        .map(|(ra, rb)| ra.iter().zip(rb.iter()).map(|(x, y)| x - y).collect())
+        .collect()
+}
+
+pub fn mat_scale(a: &[Vec<f64>], s: f64) -> Matrix {
+    a.iter().map(|r| r.iter().map(|x| x * s).collect()).collect()
+}
+
+pub fn transpose(a: &[Vec<f64>]) -> Matrix {
+    let (n, m) = dims(a);
+    let mut t = zeros(m, n);
+    for i in 0..n {
+        for j in 0..m {
+            t[j][i] = a[i][j];
+        }
+    }
+    t
+}
+
+pub fn mat_vec_mul(a: &[Vec<f64>], x: &[f64]) -> Vec<f64> {
+    a.iter()
+        .map(|row| row.iter().zip(x.iter()).map(|(aij, xj)| aij * xj).sum())
+        .collect()
+}
+
+/// Sum of the diagonal elements
+pub fn trace(a: &[Vec<f64>]) -> f64 {
+    a.iter().enumerate().map(|(i, r)| r[i]).sum()
+}
+
+/// Largest absolute row sum (infinity norm)
+pub fn norm_inf(a: &[Vec<f64>]) -> f64 {
+    a.iter()
+        .map(|r| r.iter().map(|x| x.abs()).sum::<f64>())
+        .fold(0.0, f64::max)
+}
+
+/// Matrix inverse by Gauss-Jordan elimination with partial pivoting
+///
+/// Returns `None` if the matrix is singular to working precision.
+#[allow(clippy::needless_range_loop)]
+pub fn mat_inv(a: &[Vec<f64>]) -> Option<Matrix> {
+    let n = a.len();
+    let mut aug = zeros(n, 2 * n);
+    for i in 0..n {
+        aug[i][..n].copy_from_slice(&a[i][..n]);
+        aug[i][n + i] = 1.0;
+    }
+
+    let scale = norm_inf(a).max(1e-300);
+    for i in 0..n {
+        let mut max_row = i;
+        for k in i + 1..n {
+            if aug[k][i].abs() > aug[max_row][i].abs() {
+                max_row = k;
+            }
+        }
+        aug.swap(i, max_row);
+
+        let pivot = aug[i][i];
+        if pivot.abs() < 1e-14 * scale {
+            return None;
+        }
+        for j in 0..2 * n {
+            aug[i][j] /= pivot;
+        }
+        for k in 0..n {
+            if k != i {
+                let factor = aug[k][i];
+                if factor != 0.0 {
+                    for j in 0..2 * n {
+                        aug[k][j] -= factor * aug[i][j];
+                    }
+                }
+            }
+        }
+    }
+
+    Some(aug.into_iter().map(|r| r[n..].to_vec()).collect())
+}
+
+/// Lower triangular Cholesky factor L with A = L L'
+///
+/// A positive semi-definite matrix gives a zero column where the pivot
+/// vanishes, so the factor can still color white noise.
+#[allow(clippy::needless_range_loop)]
+pub fn cholesky(a: &[Vec<f64>]) -> Matrix {
+    let n = a.len();
+    let mut l = zeros(n, n);
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                l[i][i] = (a[i][i] - sum).max(0.0).sqrt();
+            } else if l[j][j] > 0.0 {
+                l[i][j] = (a[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    l
+}
+
+/// Solve the linear system A x = b
+pub fn solve(a: &[Vec<f64>], b: &[f64]) -> Option<Vec<f64>> {
+    mat_inv(a).map(|ai| mat_vec_mul(&ai, b))
+}
+
+/// Solve the complex linear system A x = b by Gaussian elimination
+#[allow(clippy::needless_range_loop)]
+pub fn solve_complex(a: &[Vec<Complex>], b: &[Complex]) -> Option<Vec<Complex>> {
+    let n = a.len();
+    let mut m: Vec<Vec<Complex>> = a.to_vec();
+    let mut x = b.to_vec();
+
+    for i in 0..n {
+        let mut max_row = i;
+        for k in i + 1..n {
+            if m[k][i].abs() > m[max_row][i].abs() {
+                max_row = k;
+            }
+        }
+        m.swap(i, max_row);
+        x.swap(i, max_row);
+
+        let pivot = m[i][i];
+        if pivot.abs() == 0.0 {
+            return None;
+        }
+        for k in i + 1..n {
+            let factor = m[k][i] / pivot;
+            for j in i..n {
+                m[k][j] = m[k][j] - factor * m[i][j];
+            }
+            x[k] = x[k] - factor * x[i];
+        }
+    }
+
+    for i in (0..n).rev() {
+        let mut sum = x[i];
+        for j in i + 1..n {
+            sum = sum - m[i][j] * x[j];
+        }
+        x[i] = sum / m[i][i];
+    }
+    Some(x)
+}
+
+/// Matrix exponential e^A by scaling and squaring with a Pade(6,6) approximant
+pub fn expm(a: &[Vec<f64>]) -> Matrix {
+    let n = a.len();
+    let norm = norm_inf(a);
+    let squarings = if norm > 0.5 { (norm / 0.5).log2().ceil() as i32 } else { 0 };
+    let a_scaled = mat_scale(a, 0.5_f64.powi(squarings));
+
+    // Pade coefficients c_k = (2q - k)! q! / ((2q)! k! (q - k)!) for q = 6
+    let q = 6;
+    let mut c = 1.0;
+    let mut x = identity(n);
+    let mut num = identity(n);
+    let mut den = identity(n);
+    for k in 1..=q {
+        c *= (q - k + 1) as f64 / (k * (2 * q - k + 1)) as f64;
+        x = mat_mul(&a_scaled, &x);
+        let cx = mat_scale(&x, c);
+        num = mat_add(&num, &cx);
+        den = if k % 2 == 0 { mat_add(&den, &cx) } else { mat_sub(&den, &cx) };
+    }
+
+    let mut e = mat_mul(&mat_inv(&den).unwrap_or_else(|| identity(n)), &num);
+    for _ in 0..squarings {
+        e = mat_mul(&e, &e);
+    }
+    e
+}
+
+/// Characteristic polynomial det(sI - A) by the Faddeev-LeVerrier recursion
+///
+/// Returns coefficients in descending powers, leading coefficient 1.
+pub fn char_poly(a: &[Vec<f64>]) -> Vec<f64> {
+    let n = a.len();
+    let mut coeffs = vec![1.0];
+    let mut m = zeros(n, n);
+    let eye = identity(n);
+    for k in 1..=n {
+        let c_prev = coeffs[k - 1];
+        m = mat_add(&mat_mul(a, &m), &mat_scale(&eye, c_prev));
+        let c = -trace(&mat_mul(a, &m)) / (k as f64);
+        coeffs.push(c);
+    }
+    coeffs
+}
+
+/// Eigenvalues of a square matrix (roots of the characteristic polynomial)
+pub fn eigenvalues(a: &[Vec<f64>]) -> Vec<Complex> {
+    if a.is_empty() {
+        return Vec::new();
+    }
+    poly_roots(&char_poly(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mat_inv() {
+        let a = vec![vec![4.0, 7.0], vec![2.0, 6.0]];
+        let ai = mat_inv(&a).unwrap();
+        let p = mat_mul(&a, &ai);
+        assert!((p[0][0] - 1.0).abs() < 1e-12 && p[0][1].abs() < 1e-12);
+        assert!(mat_inv(&[vec![1.0, 2.0], vec![2.0, 4.0]]).is_none());
+    }
+
+    #[test]
+    fn test_cholesky() {
+        let a = vec![vec![4.0, 2.0], vec![2.0, 5.0]];
+        let l = cholesky(&a);
+        assert_eq!(l[0][1], 0.0);
+        let llt = mat_mul(&l, &transpose(&l));
+        assert!((llt[0][0] - 4.0).abs() < 1e-12 && (llt[1][0] - 2.0).abs() < 1e-12 && (llt[1][1] - 5.0).abs() < 1e-12);
+        // Semi-definite: zero column where the pivot vanishes
+        let l = cholesky(&[vec![0.0, 0.0], vec![0.0, 9.0]]);
+        assert_eq!(l, vec![vec![0.0, 0.0], vec![0.0, 3.0]]);
+    }
+
+    #[test]
+    fn test_char_poly() {
+        // Companion form of s^2 + 3s + 2
+        let a = vec![vec![0.0, 1.0], vec![-2.0, -3.0]];
+        let p = char_poly(&a);
+        assert!((p[1] - 3.0).abs() < 1e-12 && (p[2] - 2.0).abs() < 1e-12);
+        let e = eigenvalues(&a);
+        assert!((e[0].re + 2.0).abs() < 1e-9 && (e[1].re + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_expm_rotation() {
+        // exp([[0, w], [-w, 0]] t) is a rotation by w*t
+        let wt = 2.5;
+        let e = expm(&[vec![0.0, wt], vec![-wt, 0.0]]);
+        assert!((e[0][0] - wt.cos()).abs() < 1e-12);
+        assert!((e[0][1] - wt.sin()).abs() < 1e-12);
+    }
+}
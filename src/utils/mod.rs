@@ -8,15 +8,41 @@
 #![allow(dead_code)]
 
 pub mod constants;
+pub mod atmosphere;
+pub mod lambert;
 pub mod lambert3d;
 pub mod lambert2d;
 pub mod olambert;
 pub mod kepler;
+pub mod elements;
+pub mod earth;
+pub mod geometry;
+pub mod rocket;
+pub mod steering;
+pub mod reentry;
+pub mod impact;
+pub mod threat;
+pub mod footprint;
 pub mod predict;
 pub mod project;
 pub mod initial;
 pub mod gains;
 pub mod rk2;
+pub mod complex;
+pub mod polynomial;
+pub mod matrix;
+pub mod lti;
+pub mod discrete;
+pub mod root_locus;
+pub mod flex;
+pub mod actuator;
+pub mod targets;
+pub mod evasion;
+pub mod seeker;
+pub mod radome;
+pub mod noise;
+pub mod filters;
+pub mod riccati;
 
 pub use constants::*;
 pub use lambert3d::*;
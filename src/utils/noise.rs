@@ -0,0 +1,351 @@
+//! Seeker measurement noise sources
+//!
+//! The noise lessons use one white angle noise SIGNOISE (C14L1), a position
+//! noise growing linearly with range (C27) or a single shaping filter (C28,
+//! C34). A `NoiseSource` gives the one-sigma angle noise at any range for
+//! covariance analysis and draws correlated samples for Monte Carlo runs,
+//! so the same model feeds both. Sources add (`NoiseSum`) or wrap one
+//! another (`Fading`, `BlindRange`).
+//!
+//! All sources are angle noise in radians, sampled once per measurement
+//! interval TS. Multiply by range for the position noise of the C27 filters.
+
+use super::lti::TransferFunction;
+use super::targets::ShapingFilter;
+use rand::{Rng, RngCore};
+use rand_distr::StandardNormal;
+
+/// Measurement angle noise
+pub trait NoiseSource: std::fmt::Debug {
+    /// One-sigma angle noise at range `r` (rad)
+    fn sigma(&self, r: f64) -> f64;
+
+    /// Measurement noise variance at range `r` for covariance analysis
+    fn variance(&self, r: f64) -> f64 {
+        self.sigma(r).powi(2)
+    }
+
+    /// Next noise sample at range `r`, `ts` after the previous one
+    fn sample(&mut self, ts: f64, r: f64, rng: &mut dyn RngCore) -> f64;
+
+    /// True when no measurement is available at range `r`
+    fn is_blind(&self, _r: f64) -> bool {
+        false
+    }
+
+    /// Forget correlated state before a new Monte Carlo run
+    fn reset(&mut self) {}
+}
+
+fn gauss(rng: &mut dyn RngCore) -> f64 {
+    rng.sample(StandardNormal)
+}
+
+/// Range-independent white angle noise (SIGNOISE of C14L1)
+#[derive(Debug, Clone, Copy)]
+pub struct White {
+    pub sigma: f64,
+}
+
+impl White {
+    pub fn new(sigma: f64) -> Self {
+        Self { sigma }
+    }
+}
+
+impl NoiseSource for White {
+    fn sigma(&self, _r: f64) -> f64 {
+        self.sigma
+    }
+
+    fn sample(&mut self, _ts: f64, _r: f64, rng: &mut dyn RngCore) -> f64 {
+        self.sigma * gauss(rng)
+    }
+}
+
+/// Receiver thermal noise
+///
+/// Angle noise is inversely proportional to the square root of SNR. Received
+/// power falls as range^2 for a beacon or semi-active illuminator close to the
+/// target and as range^4 for an active radar, so the one-sigma angle noise
+/// grows as range or range^2.
+#[derive(Debug, Clone, Copy)]
+pub struct Thermal {
+    /// One-sigma angle noise at `range_ref` (rad)
+    pub sigma_ref: f64,
+    pub range_ref: f64,
+    /// Power of range in the received signal loss (2 or 4)
+    pub exponent: f64,
+}
+
+impl Thermal {
+    pub fn range_squared(sigma_ref: f64, range_ref: f64) -> Self {
+        Self { sigma_ref, range_ref, exponent: 2.0 }
+    }
+
+    pub fn range_fourth(sigma_ref: f64, range_ref: f64) -> Self {
+        Self { sigma_ref, range_ref, exponent: 4.0 }
+    }
+}
+
+impl NoiseSource for Thermal {
+    fn sigma(&self, r: f64) -> f64 {
+        self.sigma_ref * (r / self.range_ref).powf(0.5 * self.exponent)
+    }
+
+    fn sample(&mut self, _ts: f64, r: f64, rng: &mut dyn RngCore) -> f64 {
+        self.sigma(r) * gauss(rng)
+    }
+}
+
+/// Target glint
+///
+/// The apparent scattering center wanders over the target. The displacement
+/// is a first-order Markov process with correlation time `tau` and one-sigma
+/// of about a third of the target span, so the angle noise grows as 1/range
+/// and dominates near intercept.
+#[derive(Debug, Clone, Copy)]
+pub struct Glint {
+    /// One-sigma glint displacement (ft)
+    pub sigma: f64,
+    /// Correlation time (sec)
+    pub tau: f64,
+    state: Option<f64>,
+}
+
+impl Glint {
+    pub fn new(span: f64, tau: f64) -> Self {
+        Self { sigma: span / 3.0, tau, state: None }
+    }
+
+    /// Spectral density of the glint displacement (ft^2 sec)
+    pub fn spectral_density(&self) -> f64 {
+        2.0 * self.sigma * self.sigma * self.tau
+    }
+
+    /// Displacement as a unity gain lag driven by white noise
+    pub fn shaping_filter(&self) -> ShapingFilter {
+        ShapingFilter { tf: TransferFunction::lag(self.tau), phis: self.spectral_density() }
+    }
+}
+
+impl NoiseSource for Glint {
+    fn sigma(&self, r: f64) -> f64 {
+        self.sigma / r
+    }
+
+    fn sample(&mut self, ts: f64, r: f64, rng: &mut dyn RngCore) -> f64 {
+        let phi = (-ts / self.tau).exp();
+        let x = match self.state {
+            Some(x) => phi * x + self.sigma * (1.0 - phi * phi).sqrt() * gauss(rng),
+            None => self.sigma * gauss(rng),
+        };
+        self.state = Some(x);
+        x / r
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Exponential integral E1(x) for 0 < x <= 5 by its power series
+fn exp_integral(x: f64) -> f64 {
+    let mut sum = -0.5772156649015329 - x.ln();
+    let mut term = 1.0;
+    for k in 1..60 {
+        term *= -x / k as f64;
+        sum -= term / k as f64;
+    }
+    sum
+}
+
+/// Rayleigh amplitude fading
+///
+/// The received amplitude is the magnitude of a complex Gaussian process with
+/// correlation time `tau` and unit mean square. Noise of the wrapped source
+/// scales as 1/amplitude, limited by `floor` (the AGC range) so deep fades
+/// do not produce unbounded samples.
+#[derive(Debug)]
+pub struct Fading {
+    pub inner: Box<dyn NoiseSource>,
+    pub tau: f64,
+    /// Smallest amplitude, relative to RMS, the receiver tracks through
+    pub floor: f64,
+    state: Option<(f64, f64)>,
+}
+
+impl Fading {
+    pub fn new(inner: impl NoiseSource + 'static, tau: f64, floor: f64) -> Self {
+        assert!(floor > 0.0 && floor <= 1.0, "fading floor must be in (0, 1]");
+        Self { inner: Box::new(inner), tau, floor, state: None }
+    }
+
+    /// RMS noise amplification, sqrt(E[1/max(a^2, floor^2)]) with a^2 ~ Exp(1)
+    pub fn rms_gain(&self) -> f64 {
+        let f2 = self.floor * self.floor;
+        ((1.0 - (-f2).exp()) / f2 + exp_integral(f2)).sqrt()
+    }
+}
+
+impl NoiseSource for Fading {
+    fn sigma(&self, r: f64) -> f64 {
+        self.inner.sigma(r) * self.rms_gain()
+    }
+
+    fn sample(&mut self, ts: f64, r: f64, rng: &mut dyn RngCore) -> f64 {
+        let s = std::f64::consts::FRAC_1_SQRT_2;
+        let (i, q) = match self.state {
+            Some((i, q)) => {
+                let phi = (-ts / self.tau).exp();
+                let w = s * (1.0 - phi * phi).sqrt();
+                (phi * i + w * gauss(rng), phi * q + w * gauss(rng))
+            }
+            None => (s * gauss(rng), s * gauss(rng)),
+        };
+        self.state = Some((i, q));
+        let amp = (i * i + q * q).sqrt().max(self.floor);
+        self.inner.sample(ts, r, rng) / amp
+    }
+
+    fn is_blind(&self, r: f64) -> bool {
+        self.inner.is_blind(r)
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+        self.inner.reset();
+    }
+}
+
+/// Blind range (TBLIND of C27L5)
+///
+/// Inside `range` the seeker gives no usable measurement; the noise is
+/// infinite so a Kalman gain computed from it is zero.
+#[derive(Debug)]
+pub struct BlindRange {
+    pub inner: Box<dyn NoiseSource>,
+    pub range: f64,
+}
+
+impl BlindRange {
+    pub fn new(inner: impl NoiseSource + 'static, range: f64) -> Self {
+        Self { inner: Box::new(inner), range }
+    }
+}
+
+impl NoiseSource for BlindRange {
+    fn sigma(&self, r: f64) -> f64 {
+        if self.is_blind(r) { f64::INFINITY } else { self.inner.sigma(r) }
+    }
+
+    fn sample(&mut self, ts: f64, r: f64, rng: &mut dyn RngCore) -> f64 {
+        if self.is_blind(r) { 0.0 } else { self.inner.sample(ts, r, rng) }
+    }
+
+    fn is_blind(&self, r: f64) -> bool {
+        r < self.range || self.inner.is_blind(r)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// Independent sources added together
+#[derive(Debug, Default)]
+pub struct NoiseSum {
+    pub sources: Vec<Box<dyn NoiseSource>>,
+}
+
+impl NoiseSum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, source: impl NoiseSource + 'static) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+}
+
+impl NoiseSource for NoiseSum {
+    fn sigma(&self, r: f64) -> f64 {
+        self.variance(r).sqrt()
+    }
+
+    fn variance(&self, r: f64) -> f64 {
+        self.sources.iter().map(|s| s.variance(r)).sum()
+    }
+
+    fn sample(&mut self, ts: f64, r: f64, rng: &mut dyn RngCore) -> f64 {
+        self.sources.iter_mut().map(|s| s.sample(ts, r, rng)).sum()
+    }
+
+    fn is_blind(&self, r: f64) -> bool {
+        self.sources.iter().any(|s| s.is_blind(r))
+    }
+
+    fn reset(&mut self) {
+        self.sources.iter_mut().for_each(|s| s.reset());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    /// Sample standard deviation over `n` draws at fixed range
+    fn sample_sigma(noise: &mut dyn NoiseSource, ts: f64, r: f64, n: usize) -> f64 {
+        let mut rng = StdRng::seed_from_u64(7);
+        let sum2: f64 = (0..n).map(|_| noise.sample(ts, r, &mut rng).powi(2)).sum();
+        (sum2 / n as f64).sqrt()
+    }
+
+    #[test]
+    fn test_thermal_range_laws() {
+        let semi = Thermal::range_squared(0.001, 10000.0);
+        let active = Thermal::range_fourth(0.001, 10000.0);
+        assert!((semi.sigma(20000.0) - 0.002).abs() < 1e-12);
+        assert!((active.sigma(20000.0) - 0.004).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_monte_carlo_matches_covariance() {
+        let mut glint = Glint::new(30.0, 0.1);
+        let s = sample_sigma(&mut glint, 0.01, 1000.0, 200000);
+        assert!((s / glint.sigma(1000.0) - 1.0).abs() < 0.05);
+
+        let mut fading = Fading::new(White::new(0.001), 0.05, 0.3);
+        let s = sample_sigma(&mut fading, 0.01, 1000.0, 200000);
+        assert!((s / fading.sigma(1000.0) - 1.0).abs() < 0.05);
+
+        let mut sum = NoiseSum::new().with(White::new(0.001)).with(Thermal::range_squared(0.001, 1000.0));
+        let s = sample_sigma(&mut sum, 0.1, 2000.0, 100000);
+        assert!((sum.sigma(2000.0) - 0.001 * 5.0_f64.sqrt()).abs() < 1e-12);
+        assert!((s / sum.sigma(2000.0) - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_glint_correlation_time() {
+        let mut glint = Glint::new(30.0, 0.1);
+        let mut rng = StdRng::seed_from_u64(3);
+        let x: Vec<f64> = (0..1000000).map(|_| glint.sample(0.01, 1.0, &mut rng)).collect();
+        let c0: f64 = x.iter().map(|v| v * v).sum();
+        let c10: f64 = x.windows(11).map(|w| w[0] * w[10]).sum();
+        assert!((c10 / c0 - (-1.0_f64).exp()).abs() < 0.05);
+        assert!((glint.spectral_density() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_blind_range() {
+        let mut noise = NoiseSum::new().with(BlindRange::new(White::new(0.001), 2000.0));
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(noise.is_blind(1500.0));
+        assert!(!noise.is_blind(2500.0));
+        assert_eq!(noise.sample(0.1, 1500.0, &mut rng), 0.0);
+        assert!(noise.sigma(1500.0).is_infinite());
+    }
+}
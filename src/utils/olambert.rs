@@ -1,7 +1,9 @@
 //! olambert.m port - Orbital Lambert solver with iteration history
 //!
 //! Solves Lambert's problem using a for-loop search approach and records
-//! all iteration values, matching the MATLAB implementation exactly.
+//! all iteration values, matching the MATLAB implementation exactly. The
+//! sweep over flight path angle is kept for its iteration history; use
+//! `lambert::lambert` when only the solution is needed.
 
 use std::f64::consts::PI;
 
@@ -29,6 +31,7 @@ pub struct OLambertResult {
 ///
 /// # Returns
 /// `OLambertResult` containing final velocity and iteration history arrays
+#[allow(clippy::too_many_arguments)]
 pub fn olambert(
     xic: f64, yic: f64,
     tfdes: f64,
@@ -98,7 +101,7 @@ pub fn olambert(
                 let top2 = 2.0 * gam.cos();
 
                 // Condition #2: not ((2/xlam - 1) < 0)
-                if !((2.0 / xlam - 1.0) < 0.0) {
+                if (2.0 / xlam - 1.0) >= 0.0 || (2.0 / xlam - 1.0).is_nan() {
                     // Section D
                     let bot2 = xlam * (2.0 / xlam - 1.0).powf(1.5);
                     let top3 = (2.0 / xlam - 1.0).sqrt();
@@ -0,0 +1,210 @@
+//! Real-coefficient polynomial helpers
+//!
+//! Coefficients are stored in descending powers of s, matching the MATLAB
+//! `poly`/`roots`/`conv` convention: `[1, 2, 3]` is s^2 + 2s + 3.
+
+use super::complex::Complex;
+
+/// Remove leading (highest power) zero coefficients, keeping at least one term
+pub fn poly_trim(p: &[f64]) -> Vec<f64> {
+    let first = p.iter().position(|&c| c != 0.0).unwrap_or(p.len().saturating_sub(1));
+    if p.is_empty() {
+        vec![0.0]
+    } else {
+        p[first..].to_vec()
+    }
+}
+
+/// Polynomial degree after trimming leading zeros
+pub fn poly_degree(p: &[f64]) -> usize {
+    poly_trim(p).len() - 1
+}
+
+/// Polynomial multiplication (MATLAB `conv`)
+pub fn poly_mul(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![0.0];
+    }
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, &ai) in a.iter().enumerate() {
+        for (j, &bj) in b.iter().enumerate() {
+            out[i + j] += ai * bj;
+        }
+    }
+    out
+}
+
+/// Polynomial addition, aligning the constant terms
+pub fn poly_add(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let n = a.len().max(b.len());
+    let mut out = vec![0.0; n];
+    for (i, &c) in a.iter().rev().enumerate() {
+        out[n - 1 - i] += c;
+    }
+    for (i, &c) in b.iter().rev().enumerate() {
+        out[n - 1 - i] += c;
+    }
+    poly_trim(&out)
+}
+
+/// Multiply every coefficient by a scalar
+pub fn poly_scale(p: &[f64], k: f64) -> Vec<f64> {
+    p.iter().map(|&c| c * k).collect()
+}
+
+/// Evaluate a real polynomial at a real point (Horner's rule)
+pub fn poly_eval(p: &[f64], x: f64) -> f64 {
+    p.iter().fold(0.0, |acc, &c| acc * x + c)
+}
+
+/// Evaluate a real polynomial at a complex point (Horner's rule)
+pub fn poly_eval_complex(p: &[f64], s: Complex) -> Complex {
+    p.iter().fold(Complex::default(), |acc, &c| acc * s + c)
+}
+
+/// Derivative of a polynomial (MATLAB `polyder`)
+pub fn poly_derivative(p: &[f64]) -> Vec<f64> {
+    let p = poly_trim(p);
+    let n = p.len() - 1;
+    if n == 0 {
+        return vec![0.0];
+    }
+    p[..n].iter().enumerate().map(|(i, &c)| c * (n - i) as f64).collect()
+}
+
+/// Antiderivative with zero constant term (MATLAB `polyint`)
+pub fn poly_integral(p: &[f64]) -> Vec<f64> {
+    let p = poly_trim(p);
+    let n = p.len();
+    let mut out: Vec<f64> = p.iter().enumerate().map(|(i, &c)| c / (n - i) as f64).collect();
+    out.push(0.0);
+    out
+}
+
+/// Monic polynomial with the given roots (MATLAB `poly`)
+///
+/// Complex roots should appear in conjugate pairs so that the result is real;
+/// any residual imaginary part is discarded.
+pub fn poly_from_roots(roots: &[Complex]) -> Vec<f64> {
+    let mut c = vec![Complex::real(1.0)];
+    for &r in roots {
+        let mut next = vec![Complex::default(); c.len() + 1];
+        for (i, &ci) in c.iter().enumerate() {
+            next[i] = next[i] + ci;
+            next[i + 1] = next[i + 1] - ci * r;
+        }
+        c = next;
+    }
+    c.iter().map(|z| z.re).collect()
+}
+
+/// Roots of a real polynomial (MATLAB `roots`)
+///
+/// Uses the Aberth-Ehrlich simultaneous iteration, which converges for
+/// the low-order loop polynomials found in guidance and autopilot work.
+/// Zero roots from trailing zero coefficients are extracted exactly.
+pub fn poly_roots(p: &[f64]) -> Vec<Complex> {
+    let mut p = poly_trim(p);
+    let mut roots = Vec::new();
+
+    while p.len() > 1 && p[p.len() - 1] == 0.0 {
+        p.pop();
+        roots.push(Complex::default());
+    }
+
+    let n = p.len() - 1;
+    if n == 0 {
+        return roots;
+    }
+
+    let lead = p[0];
+    let monic: Vec<f64> = p.iter().map(|&c| c / lead).collect();
+    let dmonic = poly_derivative(&monic);
+
+    // Initial guesses on a circle bounding the roots (Cauchy bound)
+    let radius = 1.0 + monic[1..].iter().fold(0.0_f64, |m, &c| m.max(c.abs()));
+    let mut z: Vec<Complex> = (0..n)
+        .map(|k| {
+            let ang = 2.0 * std::f64::consts::PI * (k as f64) / (n as f64) + 0.4;
+            Complex::from_polar(0.5 * radius, ang)
+        })
+        .collect();
+
+    for _iter in 0..500 {
+        let mut max_step = 0.0_f64;
+        for i in 0..n {
+            let pv = poly_eval_complex(&monic, z[i]);
+            let dv = poly_eval_complex(&dmonic, z[i]);
+            if pv.abs() == 0.0 {
+                continue;
+            }
+            let ratio = pv / dv;
+            let mut sum = Complex::default();
+            for (j, &zj) in z.iter().enumerate() {
+                if j != i {
+                    sum = sum + (z[i] - zj).inv();
+                }
+            }
+            let denom = Complex::real(1.0) - ratio * sum;
+            let step = ratio / denom;
+            if step.is_finite() {
+                z[i] = z[i] - step;
+                max_step = max_step.max(step.abs() / (1.0 + z[i].abs()));
+            }
+        }
+        if max_step < 1e-15 {
+            break;
+        }
+    }
+
+    // Clean up numerically real roots so conjugate pairing stays exact
+    for r in z.iter_mut() {
+        if r.im.abs() < 1e-10 * (1.0 + r.re.abs()) {
+            r.im = 0.0;
+        }
+    }
+
+    roots.extend(z);
+    roots.sort_by(|a, b| {
+        a.re.partial_cmp(&b.re)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.im.partial_cmp(&b.im).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poly_mul_and_add() {
+        // (s + 1)(s + 2) = s^2 + 3s + 2
+        let p = poly_mul(&[1.0, 1.0], &[1.0, 2.0]);
+        assert_eq!(p, vec![1.0, 3.0, 2.0]);
+        assert_eq!(poly_add(&p, &[1.0, 1.0]), vec![1.0, 4.0, 3.0]);
+        assert!((poly_eval(&p, 2.0) - 12.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_poly_integral() {
+        // Integral of 3x^2 + 2x + 1 is x^3 + x^2 + x
+        assert_eq!(poly_integral(&[3.0, 2.0, 1.0]), vec![1.0, 1.0, 1.0, 0.0]);
+        assert_eq!(poly_derivative(&poly_integral(&[3.0, 2.0, 1.0])), vec![3.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_poly_roots() {
+        // s^3 + 2s^2 + 2s = s(s^2 + 2s + 2), roots 0, -1 +/- j
+        let r = poly_roots(&[1.0, 2.0, 2.0, 0.0]);
+        assert_eq!(r.len(), 3);
+        assert!((r[0].re + 1.0).abs() < 1e-9 && (r[0].im + 1.0).abs() < 1e-9);
+        assert!((r[1].re + 1.0).abs() < 1e-9 && (r[1].im - 1.0).abs() < 1e-9);
+        assert!(r[2].abs() < 1e-12);
+
+        let back = poly_from_roots(&r);
+        for (a, b) in back.iter().zip([1.0, 2.0, 2.0, 0.0].iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+}
@@ -1,9 +1,17 @@
 //! Trajectory prediction functions
 //!
 //! Implements predictpz, predict45, and predictg for ballistic and guided trajectory prediction
+//!
+//! PREDICTPZ and PREDICT44 coast the spherical Earth and step through
+//! `ImpactPredictor`. PREDICTB, PREDICT45 and PREDICTG add thrust, so they
+//! keep their own loops; their coasting flight matches the predictor.
 
 use super::constants::{EARTH_RADIUS_FT, GM_FT, G_ACCEL};
+use super::earth::EarthModel;
+use super::impact::ImpactPredictor;
+use super::kepler::StateVector;
 use super::lambert3d::lambert3d;
+use super::rk2::Vec3;
 
 /// Result from pure ballistic prediction
 #[derive(Debug, Clone, Copy)]
@@ -24,49 +32,22 @@ pub struct PredictResult {
 /// # Returns
 /// Final position (xtf, ytf)
 pub fn predict_pz(tf: f64, x: f64, y: f64, x1: f64, y1: f64) -> PredictResult {
-    let h = 0.01;
-    let gm = GM_FT;
-
-    let mut t = 0.0;
-    let mut x = x;
-    let mut y = y;
-    let mut x1 = x1;
-    let mut y1 = y1;
+    let r = coast(tf, 0.0, Vec3::new(x, y, 0.0), Vec3::new(x1, y1, 0.0));
+    PredictResult { xtf: r.x, ytf: r.y }
+}
 
+/// Coasts over the book's spherical Earth from time `t` to `tf` in the
+/// 0.01 s steps of the MATLAB predictors, returning the final position
+fn coast(tf: f64, t: f64, r: Vec3, v: Vec3) -> Vec3 {
+    let h = 0.01;
+    let predictor = ImpactPredictor::new(EarthModel::spherical());
+    let mut t = t;
+    let mut sv = StateVector::from_vectors(r, v);
     while t <= tf - 0.00001 {
-        let xold = x;
-        let yold = y;
-        let x1old = x1;
-        let y1old = y1;
-
-        // First Euler step
-        let tembot = (x * x + y * y).powf(1.5);
-        let x1d = -gm * x / tembot;
-        let y1d = -gm * y / tembot;
-        let xd = x1;
-        let yd = y1;
-
-        x += h * xd;
-        y += h * yd;
-        x1 += h * x1d;
-        y1 += h * y1d;
+        sv = predictor.step(&sv, h);
         t += h;
-
-        // Second evaluation for RK2 correction
-        let tembot = (x * x + y * y).powf(1.5);
-        let x1d = -gm * x / tembot;
-        let y1d = -gm * y / tembot;
-        let xd = x1;
-        let yd = y1;
-
-        // RK2 averaging
-        x = (xold + x) / 2.0 + 0.5 * h * xd;
-        y = (yold + y) / 2.0 + 0.5 * h * yd;
-        x1 = (x1old + x1) / 2.0 + 0.5 * h * x1d;
-        y1 = (y1old + y1) / 2.0 + 0.5 * h * y1d;
     }
-
-    PredictResult { xtf: x, ytf: y }
+    sv.position()
 }
 
 /// Lambert-guided missile trajectory prediction (predict45.m)
@@ -550,53 +531,10 @@ pub fn predict44(
     tftot: f64,
     xf: f64, yf: f64,
 ) -> PredictResult {
-    let switch1 = 0;
-    let gm = GM_FT;
-    let h = 0.01;
-
-    let mut t = tp;
-    let mut xt = xtp;
-    let mut yt = ytp;
-    let zt = 0.0;
-    let zf = 0.0;
-
     // Get initial velocity from Lambert
-    let tgolam = tftot - t;
-    let result = lambert3d(xt, yt, zt, tgolam, xf, yf, zf, switch1);
-    let mut xtd = result.vrx;
-    let mut ytd = result.vry;
-
-    while t <= tf - 0.00001 {
-        let xtold = xt;
-        let ytold = yt;
-        let xtdold = xtd;
-        let ytdold = ytd;
-
-        // First derivative evaluation
-        let tembot = (xt * xt + yt * yt).powf(1.5);
-        let xtdd = -gm * xt / tembot;
-        let ytdd = -gm * yt / tembot;
-
-        // Euler step
-        xt += h * xtd;
-        yt += h * ytd;
-        xtd += h * xtdd;
-        ytd += h * ytdd;
-        t += h;
-
-        // Second evaluation
-        let tembot = (xt * xt + yt * yt).powf(1.5);
-        let xtdd = -gm * xt / tembot;
-        let ytdd = -gm * yt / tembot;
-
-        // RK2 averaging
-        xt = (xtold + xt) / 2.0 + 0.5 * h * xtd;
-        yt = (ytold + yt) / 2.0 + 0.5 * h * ytd;
-        xtd = (xtdold + xtd) / 2.0 + 0.5 * h * xtdd;
-        ytd = (ytdold + ytd) / 2.0 + 0.5 * h * ytdd;
-    }
-
-    PredictResult { xtf: xt, ytf: yt }
+    let result = lambert3d(xtp, ytp, 0.0, tftot - tp, xf, yf, 0.0, 0);
+    let r = coast(tf, tp, Vec3::new(xtp, ytp, 0.0), Vec3::new(result.vrx, result.vry, 0.0));
+    PredictResult { xtf: r.x, ytf: r.y }
 }
 
 #[cfg(test)]
@@ -0,0 +1,241 @@
+//! Radome refraction and the parasitic loop
+//!
+//! C23L3 treats the radome as one slope R, the change in boresight error per
+//! unit look angle. Real radomes have a slope that varies with look angle,
+//! given here as a table or polynomial. The refraction error feeds the
+//! seeker (see `Seeker::with_radome`), so missile body rate appears in the
+//! measured LOS rate and closes the parasitic loop
+//!
+//!   1 + R (N' Vc / Vm) G(s) (1 + Ta s) = 0
+//!
+//! where G is the guidance filter and flight control dynamics and Ta the
+//! turning rate time constant. The stability analysis scans R for the
+//! boundary at each altitude and navigation ratio, giving the largest
+//! slope a radome may have for a set of operating conditions.
+
+use super::atmosphere::air_density;
+use super::complex::Complex;
+use super::lti::TransferFunction;
+use super::polynomial::{poly_eval, poly_integral};
+use super::root_locus::closed_loop_poles;
+
+/// Radome slope versus look angle
+#[derive(Debug, Clone, PartialEq)]
+pub enum RadomeSlope {
+    Constant(f64),
+    /// Slope at tabulated look angles (rad, increasing), linearly interpolated
+    /// and held constant beyond the ends
+    Table { look: Vec<f64>, slope: Vec<f64> },
+    /// Slope polynomial in look angle (rad), descending powers
+    Polynomial(Vec<f64>),
+}
+
+/// Radome refraction model
+#[derive(Debug, Clone, PartialEq)]
+pub struct Radome {
+    pub slope: RadomeSlope,
+}
+
+impl Radome {
+    pub fn constant(r: f64) -> Self {
+        Self { slope: RadomeSlope::Constant(r) }
+    }
+
+    /// Slope tabulated at increasing look angles (rad)
+    ///
+    /// # Panics
+    /// If the table is empty or `look` and `slope` differ in length.
+    pub fn table(look: &[f64], slope: &[f64]) -> Self {
+        assert!(!look.is_empty(), "radome table needs at least one look angle");
+        assert_eq!(look.len(), slope.len(), "radome table needs one slope per look angle");
+        Self { slope: RadomeSlope::Table { look: look.to_vec(), slope: slope.to_vec() } }
+    }
+
+    pub fn polynomial(coeffs: &[f64]) -> Self {
+        Self { slope: RadomeSlope::Polynomial(coeffs.to_vec()) }
+    }
+
+    /// Boresight error slope at look angle `look` (rad)
+    pub fn slope_at(&self, look: f64) -> f64 {
+        match &self.slope {
+            RadomeSlope::Constant(r) => *r,
+            RadomeSlope::Polynomial(p) => poly_eval(p, look),
+            RadomeSlope::Table { look: xs, slope: ys } => {
+                if look <= xs[0] {
+                    return ys[0];
+                }
+                let n = xs.len();
+                if look >= xs[n - 1] {
+                    return ys[n - 1];
+                }
+                let i = xs.partition_point(|&x| x <= look) - 1;
+                ys[i] + (look - xs[i]) * (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i])
+            }
+        }
+    }
+
+    /// Boresight error (rad) at look angle `look`, the integral of the slope
+    /// from zero look angle
+    pub fn error(&self, look: f64) -> f64 {
+        match &self.slope {
+            RadomeSlope::Constant(r) => r * look,
+            RadomeSlope::Polynomial(p) => poly_eval(&poly_integral(p), look),
+            RadomeSlope::Table { .. } => {
+                // Trapezoidal integration through the table breakpoints
+                let n = 50;
+                let dx = look / n as f64;
+                (0..n)
+                    .map(|i| 0.5 * dx * (self.slope_at(i as f64 * dx) + self.slope_at((i + 1) as f64 * dx)))
+                    .sum()
+            }
+        }
+    }
+}
+
+/// Homing loop parameters for the parasitic loop
+#[derive(Debug, Clone)]
+pub struct ParasiticLoop {
+    pub xnp: f64,
+    pub vc: f64,
+    pub vm: f64,
+    /// Turning rate time constant (sec)
+    pub ta: f64,
+    /// Guidance filter and flight control, achieved per LOS-rate command
+    pub guidance: TransferFunction,
+}
+
+impl ParasiticLoop {
+    /// Open loop per unit radome slope, (N' Vc / Vm) G(s) (1 + Ta s)
+    pub fn open_loop(&self) -> TransferFunction {
+        self.guidance
+            .series(&TransferFunction::lead(self.ta))
+            .scale(self.xnp * self.vc / self.vm)
+    }
+
+    /// Closed-loop poles of the parasitic loop for slope `r`
+    pub fn poles(&self, r: f64) -> Vec<Complex> {
+        closed_loop_poles(&self.open_loop(), r)
+    }
+
+    pub fn is_stable(&self, r: f64) -> bool {
+        self.poles(r).iter().all(|p| p.re < 0.0)
+    }
+
+    /// Same loop with the turning rate time constant scaled from sea level
+    /// to `alt`, Ta growing as air density falls
+    pub fn at_altitude(&self, alt: f64) -> Self {
+        Self { ta: self.ta * air_density(0.0) / air_density(alt), ..self.clone() }
+    }
+
+    /// Stable slope interval around R = 0, searched out to |R| = `r_search`
+    pub fn stability_boundary(&self, r_search: f64) -> RadomeBoundary {
+        let edge = |dir: f64| {
+            let n = 200;
+            let mut lo = 0.0;
+            for i in 1..=n {
+                let r = dir * r_search * i as f64 / n as f64;
+                if !self.is_stable(r) {
+                    // Bisect between the last stable and first unstable slope
+                    let mut hi = r;
+                    for _ in 0..50 {
+                        let mid = 0.5 * (lo + hi);
+                        if self.is_stable(mid) {
+                            lo = mid;
+                        } else {
+                            hi = mid;
+                        }
+                    }
+                    return Some(0.5 * (lo + hi));
+                }
+                lo = r;
+            }
+            None
+        };
+        RadomeBoundary { r_min: edge(-1.0), r_max: edge(1.0) }
+    }
+}
+
+/// Parasitic loop stability interval; `None` means stable out to the search limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadomeBoundary {
+    pub r_min: Option<f64>,
+    pub r_max: Option<f64>,
+}
+
+impl RadomeBoundary {
+    /// Largest |R| that is stable for both signs of slope
+    pub fn allowable(&self, r_search: f64) -> f64 {
+        let neg = self.r_min.map_or(r_search, f64::abs);
+        let pos = self.r_max.unwrap_or(r_search);
+        neg.min(pos)
+    }
+}
+
+/// Stability boundary at one altitude and navigation ratio
+#[derive(Debug, Clone, Copy)]
+pub struct BoundaryPoint {
+    pub alt: f64,
+    pub xnp: f64,
+    pub boundary: RadomeBoundary,
+}
+
+/// Stability boundary over a grid of altitudes and navigation ratios
+pub fn boundary_map(base: &ParasiticLoop, altitudes: &[f64], xnps: &[f64], r_search: f64) -> Vec<BoundaryPoint> {
+    altitudes
+        .iter()
+        .flat_map(|&alt| {
+            xnps.iter().map(move |&xnp| {
+                let lp = ParasiticLoop { xnp, ..base.at_altitude(alt) };
+                BoundaryPoint { alt, xnp, boundary: lp.stability_boundary(r_search) }
+            })
+        })
+        .collect()
+}
+
+/// Radome slope specification: the largest |R| stable at every operating
+/// point, divided by a safety factor
+pub fn slope_specification(points: &[BoundaryPoint], r_search: f64, safety_factor: f64) -> f64 {
+    points
+        .iter()
+        .map(|p| p.boundary.allowable(r_search))
+        .fold(r_search, f64::min)
+        / safety_factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slope_models() {
+        let table = Radome::table(&[0.0, 0.2, 0.4], &[0.01, 0.03, 0.03]);
+        assert!((table.slope_at(0.1) - 0.02).abs() < 1e-12);
+        assert_eq!(table.slope_at(1.0), 0.03);
+        assert!((table.error(0.2) - 0.004).abs() < 1e-9);
+
+        let poly = Radome::polynomial(&[0.5, 0.01]);
+        assert!((poly.error(0.2) - (0.25 * 0.04 + 0.002)).abs() < 1e-12);
+        assert_eq!(Radome::constant(0.02).error(0.5), 0.01);
+    }
+
+    #[test]
+    fn test_single_lag_boundary_matches_theory() {
+        // (1 + tau s) + R k (1 + Ta s): unstable for R < max(-1/k, -tau/(k Ta))
+        let lp = ParasiticLoop { xnp: 4.0, vc: 4000.0, vm: 3000.0, ta: 2.0, guidance: TransferFunction::lag(0.5) };
+        let k = 4.0 * 4000.0 / 3000.0;
+        let b = lp.stability_boundary(1.0);
+        assert!((b.r_min.unwrap() + 0.5 / (k * 2.0)).abs() < 1e-6);
+        assert!(b.r_max.is_none());
+    }
+
+    #[test]
+    fn test_boundary_shrinks_with_altitude() {
+        let guidance = TransferFunction::lag(0.2).series(&TransferFunction::second_order(0.7, 20.0));
+        let base = ParasiticLoop { xnp: 3.0, vc: 4000.0, vm: 3000.0, ta: 0.5, guidance };
+        let map = boundary_map(&base, &[0.0, 50000.0], &[3.0, 5.0], 0.5);
+        let allow: Vec<f64> = map.iter().map(|p| p.boundary.allowable(0.5)).collect();
+        assert!(allow[2] < allow[0]);
+        assert!(allow[1] < allow[0]);
+        assert!((slope_specification(&map, 0.5, 2.0) - allow.iter().cloned().fold(0.5, f64::min) / 2.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,324 @@
+//! Reentry vehicle dynamics
+//!
+//! C11 flies its reentry target with a constant ballistic coefficient and
+//! no lift over a flat Earth. `ReentryVehicle` keeps the flat Earth, the
+//! book's exponential atmosphere and its second-order Runge-Kutta
+//! integration, and adds a ballistic coefficient that varies with Mach
+//! number, lift at a fixed lift-to-drag ratio steered by a bank-angle
+//! program, and the stagnation-point heating and aerodynamic load the
+//! vehicle sees along the way.
+//!
+//! Axes follow C40: 1 downrange, 2 altitude, 3 crossrange. Bank angle is
+//! measured from lift-up, positive turning toward +3.
+
+use super::atmosphere::{air_density, speed_of_sound, RHO_SEA_LEVEL};
+use super::constants::G_ACCEL;
+use super::rk2::Vec3;
+
+/// Stagnation-point heating rate (BTU/ft^2/s) for nose radius `rn` (ft),
+/// Detra-Kemp-Riddell correlation
+pub fn stagnation_heating(rho: f64, v: f64, rn: f64) -> f64 {
+    17600.0 / rn.sqrt() * (rho / RHO_SEA_LEVEL).sqrt() * (v / 26000.0).powf(3.15)
+}
+
+/// Ballistic coefficient W/(CD S) in psf
+#[derive(Debug, Clone, PartialEq)]
+pub enum BallisticCoefficient {
+    Constant(f64),
+    /// Interpolated in Mach number, held at the end values outside the table
+    MachTable { mach: Vec<f64>, beta: Vec<f64> },
+}
+
+impl BallisticCoefficient {
+    /// Ballistic coefficient tabulated at increasing Mach numbers
+    ///
+    /// # Panics
+    /// If the table is empty, `mach` and `beta` differ in length, or the
+    /// Mach numbers do not increase.
+    pub fn mach_table(mach: &[f64], beta: &[f64]) -> Self {
+        assert!(!mach.is_empty(), "ballistic coefficient table needs at least one Mach number");
+        assert_eq!(mach.len(), beta.len(), "ballistic coefficient table needs one beta per Mach number");
+        assert!(mach.windows(2).all(|w| w[0] < w[1]), "ballistic coefficient table Mach numbers must increase");
+        Self::MachTable { mach: mach.to_vec(), beta: beta.to_vec() }
+    }
+
+    pub fn at_mach(&self, m: f64) -> f64 {
+        match self {
+            Self::Constant(beta) => *beta,
+            Self::MachTable { mach: xs, beta: ys } => {
+                if m <= xs[0] {
+                    return ys[0];
+                }
+                let n = xs.len();
+                if m >= xs[n - 1] {
+                    return ys[n - 1];
+                }
+                let i = xs.partition_point(|&x| x <= m) - 1;
+                ys[i] + (m - xs[i]) * (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i])
+            }
+        }
+    }
+}
+
+/// How the lift vector is banked
+#[derive(Debug, Clone, PartialEq)]
+pub enum Maneuver {
+    /// No lift
+    Ballistic,
+    /// Lift held at a constant bank angle (rad)
+    Bank(f64),
+    /// Lift vector rolling at `rate` (rad/s) below `start_alt`, so the
+    /// vehicle corkscrews about its mean path
+    Spiral { start_alt: f64, rate: f64 },
+    /// Lift up below `start_alt` whenever the flight path angle is steeper
+    /// than `gamma` (rad), pulling out of the dive into a shallower glide
+    PullUp { start_alt: f64, gamma: f64 },
+    /// Bank angle interpolated in time, held at the end values outside
+    BankSchedule { time: Vec<f64>, bank: Vec<f64> },
+}
+
+impl Maneuver {
+    /// Bank angle at time `t`, altitude `alt` and flight path angle `gamma`,
+    /// or `None` when the lift is off
+    pub fn bank(&self, t: f64, alt: f64, gamma: f64) -> Option<f64> {
+        match self {
+            Self::Ballistic => None,
+            Self::Bank(phi) => Some(*phi),
+            Self::Spiral { start_alt, rate } => (alt < *start_alt).then_some(rate * t),
+            Self::PullUp { start_alt, gamma: gamf } => (alt < *start_alt && gamma < *gamf).then_some(0.0),
+            Self::BankSchedule { time: xs, bank: ys } => {
+                if t <= xs[0] {
+                    return Some(ys[0]);
+                }
+                let n = xs.len();
+                if t >= xs[n - 1] {
+                    return Some(ys[n - 1]);
+                }
+                let i = xs.partition_point(|&x| x <= t) - 1;
+                Some(ys[i] + (t - xs[i]) * (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i]))
+            }
+        }
+    }
+}
+
+/// Aerodynamic conditions at one state
+#[derive(Debug, Clone, Copy)]
+pub struct AeroState {
+    /// Total acceleration including gravity (ft/s^2)
+    pub accel: Vec3,
+    pub mach: f64,
+    /// Dynamic pressure (psf)
+    pub q: f64,
+    /// Drag and lift acceleration (G)
+    pub load_g: f64,
+    /// Stagnation-point heating rate (BTU/ft^2/s)
+    pub heat_rate: f64,
+}
+
+/// Sampled reentry trajectory
+#[derive(Debug, Clone, Default)]
+pub struct ReentryTrajectory {
+    pub time: Vec<f64>,
+    pub position: Vec<Vec3>,
+    pub velocity: Vec<Vec3>,
+    pub mach: Vec<f64>,
+    pub q: Vec<f64>,
+    pub load_g: Vec<f64>,
+    pub heat_rate: Vec<f64>,
+    /// Integrated stagnation-point heat load (BTU/ft^2)
+    pub heat_load: Vec<f64>,
+}
+
+impl ReentryTrajectory {
+    pub fn peak_load_g(&self) -> f64 {
+        self.load_g.iter().cloned().fold(0.0, f64::max)
+    }
+
+    pub fn peak_heat_rate(&self) -> f64 {
+        self.heat_rate.iter().cloned().fold(0.0, f64::max)
+    }
+
+    pub fn total_heat_load(&self) -> f64 {
+        self.heat_load.last().copied().unwrap_or(0.0)
+    }
+}
+
+/// Reentry vehicle with drag, optional lift and a bank-angle program
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReentryVehicle {
+    pub beta: BallisticCoefficient,
+    pub lift_to_drag: f64,
+    /// Nose radius for stagnation-point heating (ft)
+    pub nose_radius: f64,
+    pub maneuver: Maneuver,
+}
+
+impl ReentryVehicle {
+    /// C11 vehicle: constant ballistic coefficient, no lift
+    pub fn ballistic(beta: f64) -> Self {
+        Self {
+            beta: BallisticCoefficient::Constant(beta),
+            lift_to_drag: 0.0,
+            nose_radius: 1.0,
+            maneuver: Maneuver::Ballistic,
+        }
+    }
+
+    pub fn with_beta(mut self, beta: BallisticCoefficient) -> Self {
+        self.beta = beta;
+        self
+    }
+
+    pub fn with_lift(mut self, lift_to_drag: f64, maneuver: Maneuver) -> Self {
+        self.lift_to_drag = lift_to_drag;
+        self.maneuver = maneuver;
+        self
+    }
+
+    pub fn with_nose_radius(mut self, nose_radius: f64) -> Self {
+        self.nose_radius = nose_radius;
+        self
+    }
+
+    /// Acceleration and aerodynamic conditions at time `t`
+    pub fn aero(&self, t: f64, r: &Vec3, v: &Vec3) -> AeroState {
+        let alt = r.y;
+        let vel = v.magnitude();
+        let rho = air_density(alt);
+        let q = 0.5 * rho * vel * vel;
+        let mach = vel / speed_of_sound(alt);
+        let drag = G_ACCEL * q / self.beta.at_mach(mach);
+        let vhat = v.normalize();
+
+        let mut aero = vhat * -drag;
+        let gamma = (v.y / vel).clamp(-1.0, 1.0).asin();
+        if let Some(phi) = self.maneuver.bank(t, alt, gamma) {
+            // Lift-up is the vertical with its velocity component removed;
+            // in a vertical dive it is taken about the fixed crossrange axis
+            let up = Vec3::new(0.0, 1.0, 0.0);
+            let horizontal = up - vhat * up.dot(&vhat);
+            let lift_up = if horizontal.magnitude() > 1e-9 {
+                horizontal.normalize()
+            } else {
+                Vec3::new(0.0, 0.0, 1.0).cross(&vhat)
+            };
+            let right = vhat.cross(&lift_up);
+            aero = aero + (lift_up * phi.cos() + right * phi.sin()) * (self.lift_to_drag * drag);
+        }
+
+        AeroState {
+            accel: aero + Vec3::new(0.0, -G_ACCEL, 0.0),
+            mach,
+            q,
+            load_g: aero.magnitude() / G_ACCEL,
+            heat_rate: stagnation_heating(rho, vel, self.nose_radius),
+        }
+    }
+
+    /// Flies from `r0`, `v0` until the vehicle reaches the ground, sampling
+    /// every `sample` seconds
+    pub fn fly(&self, r0: Vec3, v0: Vec3, h: f64, sample: f64) -> ReentryTrajectory {
+        let mut traj = ReentryTrajectory::default();
+        let (mut r, mut v) = (r0, v0);
+        let mut t = 0.0;
+        let mut s = 0.0;
+        let mut heat_load = 0.0;
+        let mut heat_rate = self.aero(t, &r, &v).heat_rate;
+
+        while r.y >= 0.0 {
+            let (rold, vold) = (r, v);
+            let a0 = self.aero(t, &r, &v).accel;
+            r = r + v * h;
+            v = v + a0 * h;
+            t += h;
+            let a1 = self.aero(t, &r, &v).accel;
+            r = (rold + r + v * h) * 0.5;
+            v = (vold + v + a1 * h) * 0.5;
+
+            let state = self.aero(t, &r, &v);
+            heat_load += 0.5 * h * (heat_rate + state.heat_rate);
+            heat_rate = state.heat_rate;
+
+            s += h;
+            if s >= sample - 1e-5 {
+                s = 0.0;
+                traj.time.push(t);
+                traj.position.push(r);
+                traj.velocity.push(v);
+                traj.mach.push(state.mach);
+                traj.q.push(state.q);
+                traj.load_g.push(state.load_g);
+                traj.heat_rate.push(state.heat_rate);
+                traj.heat_load.push(heat_load);
+            }
+        }
+        traj
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(vel: f64, gamdeg: f64, alt: f64) -> (Vec3, Vec3) {
+        let gam = gamdeg.to_radians();
+        (Vec3::new(0.0, alt, 0.0), Vec3::new(vel * gam.cos(), -vel * gam.sin(), 0.0))
+    }
+
+    #[test]
+    fn test_reentry_models() {
+        assert!((stagnation_heating(RHO_SEA_LEVEL, 26000.0, 1.0) - 17600.0).abs() < 1e-6);
+
+        let beta = BallisticCoefficient::mach_table(&[0.8, 1.2, 3.0], &[1000.0, 800.0, 1200.0]);
+        assert_eq!(beta.at_mach(0.5), 1000.0);
+        assert!((beta.at_mach(1.0) - 900.0).abs() < 1e-9);
+        assert_eq!(beta.at_mach(10.0), 1200.0);
+
+        let pull_up = Maneuver::PullUp { start_alt: 50000.0, gamma: -0.1 };
+        assert_eq!(pull_up.bank(0.0, 60000.0, -0.5), None);
+        assert_eq!(pull_up.bank(0.0, 40000.0, -0.5), Some(0.0));
+        assert_eq!(pull_up.bank(0.0, 40000.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_reentry_ballistic_matches_c11() {
+        // C11L1: 100 kft, 6000 ft/s at 45 deg, BETA = 500
+        let book = crate::chapters::c11::l1::run();
+        let (r0, v0) = entry(6000.0, 45.0, 100000.0);
+        let traj = ReentryVehicle::ballistic(500.0).fly(r0, v0, 0.01, 0.1);
+        let range = traj.position.last().unwrap().x / 1000.0;
+        assert!((range - book.rt1k.last().unwrap()).abs() < 0.1, "{} {:?}", range, book.rt1k.last());
+        assert!(traj.position.iter().all(|r| r.z == 0.0));
+    }
+
+    #[test]
+    fn test_reentry_lift_and_heating() {
+        let (r0, v0) = entry(20000.0, 25.0, 300000.0);
+        let ballistic = ReentryVehicle::ballistic(1500.0);
+        let base = ballistic.fly(r0, v0, 0.01, 0.1);
+        let pull_up = ballistic.clone()
+            .with_lift(1.0, Maneuver::PullUp { start_alt: 150000.0, gamma: -5f64.to_radians() })
+            .fly(r0, v0, 0.01, 0.1);
+        let bank = ballistic.clone().with_lift(0.5, Maneuver::Bank(90f64.to_radians())).fly(r0, v0, 0.01, 0.1);
+
+        let end = |t: &ReentryTrajectory| *t.position.last().unwrap();
+        assert!(end(&pull_up).x > end(&base).x);
+        assert!(end(&bank).z > 1000.0);
+        // Heat load is the integral of the sampled heating rate
+        let trapezoid: f64 = base.heat_rate.windows(2).map(|w| 0.05 * (w[0] + w[1])).sum();
+        assert!((base.total_heat_load() - trapezoid).abs() < 0.02 * base.total_heat_load());
+        assert!(base.peak_load_g() > 10.0 && base.peak_heat_rate() > 100.0);
+    }
+
+    #[test]
+    fn test_reentry_lift_in_vertical_dive() {
+        // Lift-up in a vertical dive falls back to the fixed crossrange axis,
+        // pointing downrange as it does in the limit of a steep dive
+        let vehicle = ReentryVehicle::ballistic(1500.0).with_lift(0.5, Maneuver::Bank(0.0));
+        let (r, v) = entry(10000.0, 90.0, 50000.0);
+        let vertical = vehicle.aero(0.0, &r, &Vec3::new(0.0, v.y, 0.0));
+        let steep = vehicle.aero(0.0, &r, &v);
+        assert!(vertical.accel.x.is_finite() && vertical.accel.x > 0.0);
+        assert!((vertical.accel - steep.accel).magnitude() < 1e-6 * steep.accel.magnitude());
+    }
+}
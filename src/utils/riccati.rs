@@ -0,0 +1,260 @@
+//! Riccati equation solvers
+//!
+//! Optimal control (C26, C35, C39) and steady-state Kalman filtering both
+//! reduce to a Riccati equation in the system matrices:
+//!
+//!   continuous  A'X + XA - XBR^-1B'X + Q = 0
+//!   discrete    X = A'XA - A'XB(R + B'XB)^-1 B'XA + Q
+//!
+//! The continuous equation is solved through the matrix sign function of
+//! the Hamiltonian, the discrete one by the structured doubling algorithm.
+//! The filtering forms follow by duality (A', C' in place of A, B).
+//! `integrate_riccati` gives the finite-horizon solution by integrating the
+//! differential equation in time-to-go, as GENERATEGAINS does for one model.
+
+use crate::utils::matrix::{Matrix, identity, mat_add, mat_inv, mat_mul, mat_scale, mat_sub, transpose, zeros};
+
+fn frobenius(a: &Matrix) -> f64 {
+    a.iter().flatten().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+fn symmetrize(a: &Matrix) -> Matrix {
+    mat_scale(&mat_add(a, &transpose(a)), 0.5)
+}
+
+/// Matrix sign function by the scaled Newton iteration Z = (cZ + Z^-1/c)/2
+fn sign(h: &Matrix) -> Option<Matrix> {
+    let mut z = h.clone();
+    for _ in 0..100 {
+        let zi = mat_inv(&z)?;
+        let c = (frobenius(&zi) / frobenius(&z)).sqrt();
+        let next = mat_scale(&mat_add(&mat_scale(&z, c), &mat_scale(&zi, 1.0 / c)), 0.5);
+        let change = frobenius(&mat_sub(&next, &z));
+        z = next;
+        if change <= 1e-13 * frobenius(&z) {
+            return Some(z);
+        }
+    }
+    None
+}
+
+/// Stabilizing solution of the continuous algebraic Riccati equation
+/// A'X + XA - XBR^-1B'X + Q = 0; `None` if the Hamiltonian has
+/// eigenvalues on the imaginary axis (not stabilizable or detectable)
+pub fn care(a: &Matrix, b: &Matrix, q: &Matrix, r: &Matrix) -> Option<Matrix> {
+    let n = a.len();
+    let g = mat_mul(&mat_mul(b, &mat_inv(r)?), &transpose(b));
+    let at = transpose(a);
+    let mut ham = zeros(2 * n, 2 * n);
+    for i in 0..n {
+        for j in 0..n {
+            ham[i][j] = a[i][j];
+            ham[i][n + j] = -g[i][j];
+            ham[n + i][j] = -q[i][j];
+            ham[n + i][n + j] = -at[i][j];
+        }
+    }
+    let w = sign(&ham)?;
+
+    // The stable subspace [I; X] is the null space of W + I:
+    // [W12; W22 + I] X = -[W11 + I; W21], solved by least squares
+    let mut m = zeros(2 * n, n);
+    let mut rhs = zeros(2 * n, n);
+    for i in 0..n {
+        for j in 0..n {
+            m[i][j] = w[i][n + j];
+            m[n + i][j] = w[n + i][n + j] + if i == j { 1.0 } else { 0.0 };
+            rhs[i][j] = -(w[i][j] + if i == j { 1.0 } else { 0.0 });
+            rhs[n + i][j] = -w[n + i][j];
+        }
+    }
+    let mt = transpose(&m);
+    let x = mat_mul(&mat_inv(&mat_mul(&mt, &m))?, &mat_mul(&mt, &rhs));
+    Some(symmetrize(&x))
+}
+
+/// Stabilizing solution of the discrete algebraic Riccati equation
+/// X = A'XA - A'XB(R + B'XB)^-1 B'XA + Q by structured doubling
+pub fn dare(a: &Matrix, b: &Matrix, q: &Matrix, r: &Matrix) -> Option<Matrix> {
+    let n = a.len();
+    let eye = identity(n);
+    let mut ak = a.clone();
+    let mut gk = mat_mul(&mat_mul(b, &mat_inv(r)?), &transpose(b));
+    let mut hk = q.clone();
+    for _ in 0..100 {
+        let w = mat_inv(&mat_add(&eye, &mat_mul(&gk, &hk)))?;
+        let akw = mat_mul(&ak, &w);
+        let akt = transpose(&ak);
+        let g_next = mat_add(&gk, &mat_mul(&mat_mul(&akw, &gk), &akt));
+        let h_next = mat_add(&hk, &mat_mul(&mat_mul(&akt, &hk), &mat_mul(&w, &ak)));
+        let a_next = mat_mul(&akw, &ak);
+        let change = frobenius(&mat_sub(&h_next, &hk));
+        hk = symmetrize(&h_next);
+        gk = symmetrize(&g_next);
+        ak = a_next;
+        if change <= 1e-13 * frobenius(&hk).max(1e-300) {
+            return Some(hk);
+        }
+    }
+    None
+}
+
+/// Continuous LQR gain K = R^-1 B'X for u = -K x, with the Riccati solution
+pub fn lqr(a: &Matrix, b: &Matrix, q: &Matrix, r: &Matrix) -> Option<(Matrix, Matrix)> {
+    let x = care(a, b, q, r)?;
+    let k = mat_mul(&mat_mul(&mat_inv(r)?, &transpose(b)), &x);
+    Some((k, x))
+}
+
+/// Discrete LQR gain K = (R + B'XB)^-1 B'XA for u = -K x
+pub fn dlqr(a: &Matrix, b: &Matrix, q: &Matrix, r: &Matrix) -> Option<(Matrix, Matrix)> {
+    let x = dare(a, b, q, r)?;
+    let bt = transpose(b);
+    let k = mat_mul(&mat_inv(&mat_add(r, &mat_mul(&mat_mul(&bt, &x), b)))?, &mat_mul(&mat_mul(&bt, &x), a));
+    Some((k, x))
+}
+
+/// Steady-state continuous Kalman filter gain K = P C' R^-1 for
+/// dx/dt = A x + w, z = C x + v with spectral densities Q and R
+pub fn kalman_gain(a: &Matrix, c: &Matrix, q: &Matrix, r: &Matrix) -> Option<(Matrix, Matrix)> {
+    let p = care(&transpose(a), &transpose(c), q, r)?;
+    let k = mat_mul(&mat_mul(&p, &transpose(c)), &mat_inv(r)?);
+    Some((k, p))
+}
+
+/// Steady-state discrete Kalman filter gain K = M H'(H M H' + R)^-1 with the
+/// a priori covariance M, for x(k+1) = PHI x(k) + w, z = H x + v
+pub fn discrete_kalman_gain(phi: &Matrix, h: &Matrix, q: &Matrix, r: &Matrix) -> Option<(Matrix, Matrix)> {
+    let ht = transpose(h);
+    let m = dare(&transpose(phi), &ht, q, r)?;
+    let mht = mat_mul(&m, &ht);
+    let k = mat_mul(&mht, &mat_inv(&mat_add(&mat_mul(h, &mht), r))?);
+    Some((k, m))
+}
+
+/// Finite-horizon Riccati solution sampled in time-to-go
+#[derive(Debug, Clone)]
+pub struct RiccatiHistory {
+    pub tgo: Vec<f64>,
+    pub s: Vec<Matrix>,
+    /// Euler predictor stage of each sampled step, before the RK2 averaging.
+    /// GENERATEGAINS.m stores its gains from this S.
+    pub s_predicted: Vec<Matrix>,
+}
+
+/// Integrate dS/dtgo = SA + A'S - SBR^-1B'S + Q from the terminal weight
+/// `s0` at TGO = 0 out to `tf` with the second-order Runge-Kutta step `h`,
+/// sampling every `ts`. The control gain at each sample is R^-1 B'S.
+#[allow(clippy::too_many_arguments)]
+pub fn integrate_riccati(a: &Matrix, b: &Matrix, q: &Matrix, r: &Matrix, s0: &Matrix, tf: f64, h: f64, ts: f64) -> RiccatiHistory {
+    let g = mat_mul(&mat_mul(b, &mat_inv(r).expect("singular control weighting")), &transpose(b));
+    let deriv = |s: &Matrix| {
+        let sa = mat_mul(s, a);
+        mat_add(&mat_sub(&mat_add(&sa, &transpose(&sa)), &mat_mul(&mat_mul(s, &g), s)), q)
+    };
+
+    let mut s = s0.clone();
+    let mut t = 0.0;
+    let mut s1 = 0.0;
+    let mut history = RiccatiHistory { tgo: Vec::new(), s: Vec::new(), s_predicted: Vec::new() };
+    while t < tf - 0.0001 {
+        s1 += h;
+        let sold = s.clone();
+        let sd = deriv(&s);
+        s = mat_add(&s, &mat_scale(&sd, h));
+        t += h;
+        let predicted = s.clone();
+        let sd = deriv(&s);
+        s = mat_add(&mat_scale(&mat_add(&sold, &s), 0.5), &mat_scale(&sd, 0.5 * h));
+
+        if s1 >= ts - 0.0001 {
+            s1 = 0.0;
+            history.tgo.push(t);
+            history.s.push(s.clone());
+            history.s_predicted.push(predicted);
+        }
+    }
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::matrix::diag;
+
+    fn residual_care(a: &Matrix, b: &Matrix, q: &Matrix, r: &Matrix, x: &Matrix) -> f64 {
+        let xa = mat_mul(x, a);
+        let g = mat_mul(&mat_mul(b, &mat_inv(r).unwrap()), &transpose(b));
+        frobenius(&mat_add(&mat_sub(&mat_add(&xa, &transpose(&xa)), &mat_mul(&mat_mul(x, &g), x)), q))
+    }
+
+    #[test]
+    fn test_care_double_integrator() {
+        // Known solution for q = diag(1, 0), r = 1: X = [[sqrt2, 1], [1, sqrt2]]
+        let a = vec![vec![0.0, 1.0], vec![0.0, 0.0]];
+        let b = vec![vec![0.0], vec![1.0]];
+        let q = diag(&[1.0, 0.0]);
+        let r = diag(&[1.0]);
+        let (k, x) = lqr(&a, &b, &q, &r).unwrap();
+        let s2 = 2.0_f64.sqrt();
+        assert!((x[0][0] - s2).abs() < 1e-9 && (x[0][1] - 1.0).abs() < 1e-9 && (x[1][1] - s2).abs() < 1e-9);
+        assert!((k[0][0] - 1.0).abs() < 1e-9 && (k[0][1] - s2).abs() < 1e-9);
+
+        // Unstable, coupled third-order system
+        let a = vec![vec![0.5, 1.0, 0.0], vec![0.0, -1.0, 2.0], vec![-1.0, 0.0, 0.3]];
+        let b = vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![0.0, 0.5]];
+        let q = diag(&[2.0, 1.0, 0.5]);
+        let r = diag(&[1.0, 0.3]);
+        let x = care(&a, &b, &q, &r).unwrap();
+        assert!(residual_care(&a, &b, &q, &r, &x) < 1e-9);
+    }
+
+    #[test]
+    fn test_dare_scalar() {
+        // x = a^2 x - a^2 x^2 b^2 / (r + b^2 x) + q has a closed-form root
+        let (a, b, q, r) = (1.2_f64, 0.5_f64, 1.0_f64, 2.0_f64);
+        let x = dare(&diag(&[a]), &diag(&[b]), &diag(&[q]), &diag(&[r])).unwrap()[0][0];
+        // b^2 x^2 + (r (1 - a^2) - q b^2) x - q r = 0
+        let (qa, qb, qc) = (b * b, r * (1.0 - a * a) - q * b * b, -q * r);
+        let root = (-qb + (qb * qb - 4.0 * qa * qc).sqrt()) / (2.0 * qa);
+        assert!((x - root).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_discrete_kalman_matches_recursion() {
+        // Position-velocity filter: iterate the covariance recursion to steady state
+        let ts: f64 = 0.1;
+        let phi = vec![vec![1.0, ts], vec![0.0, 1.0]];
+        let h = vec![vec![1.0, 0.0]];
+        let q = vec![vec![ts.powi(3) / 3.0, ts * ts / 2.0], vec![ts * ts / 2.0, ts]];
+        let r = diag(&[4.0]);
+        let (k, _) = discrete_kalman_gain(&phi, &h, &q, &r).unwrap();
+
+        let mut p = diag(&[100.0, 100.0]);
+        let mut gain = zeros(2, 1);
+        for _ in 0..2000 {
+            let m = mat_add(&mat_mul(&mat_mul(&phi, &p), &transpose(&phi)), &q);
+            gain = vec![vec![m[0][0] / (m[0][0] + 4.0)], vec![m[1][0] / (m[0][0] + 4.0)]];
+            let ikh = mat_sub(&identity(2), &mat_mul(&gain, &h));
+            p = mat_mul(&ikh, &m);
+        }
+        assert!((k[0][0] - gain[0][0]).abs() < 1e-9 && (k[1][0] - gain[1][0]).abs() < 1e-9);
+
+        let (kc, _) = kalman_gain(&vec![vec![0.0, 1.0], vec![0.0, 0.0]], &h, &diag(&[0.0, 1.0]), &diag(&[1.0])).unwrap();
+        // Continuous position-velocity filter: K = [sqrt(2) q^1/4 r^-1/4, sqrt(q/r)]
+        assert!((kc[0][0] - 2.0_f64.sqrt()).abs() < 1e-9 && (kc[1][0] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_differential_riccati_converges_to_care() {
+        let a = vec![vec![0.0, 1.0], vec![0.0, 0.0]];
+        let b = vec![vec![0.0], vec![1.0]];
+        let q = diag(&[1.0, 0.0]);
+        let r = diag(&[1.0]);
+        let history = integrate_riccati(&a, &b, &q, &r, &zeros(2, 2), 20.0, 0.001, 1.0);
+        let x = care(&a, &b, &q, &r).unwrap();
+        let last = history.s.last().unwrap();
+        assert!(frobenius(&mat_sub(last, &x)) < 1e-6);
+        assert_eq!(history.tgo.len(), 20);
+    }
+}
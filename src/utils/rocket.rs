@@ -0,0 +1,388 @@
+//! Multi-stage rocket model
+//!
+//! Generalizes the two-stage sizing of C16L1 to any number of stages.
+//! Each stage is sized from the top down with the book's relations: the
+//! stage delivers its share of the velocity increment carrying everything
+//! above it, structure weight follows from the mass fraction, and thrust
+//! is set by an acceleration limit, a burn time or directly. Propellant
+//! flow is constant, so weight falls linearly during each burn. Weights are
+//! in pounds, velocities in ft/s.
+
+use super::constants::{EARTH_RADIUS_FT, G_ACCEL, GM_FT};
+use super::rk2::Vec3;
+use super::steering::SteeringLaw;
+
+/// How a stage's thrust is chosen during sizing
+#[derive(Debug, Clone, Copy)]
+pub enum ThrustSpec {
+    /// Thrust (lb)
+    Thrust(f64),
+    /// Axial acceleration at burnout (G), as in the book
+    MaxAcceleration(f64),
+    /// Burn time (s)
+    BurnTime(f64),
+}
+
+/// Technology and thrust level of a stage before sizing
+#[derive(Debug, Clone, Copy)]
+pub struct StageDesign {
+    /// Specific impulse (s)
+    pub isp: f64,
+    /// Propellant weight over propellant plus structure weight
+    pub mass_fraction: f64,
+    pub thrust: ThrustSpec,
+    /// Unpowered coast after burnout before the next stage ignites (s)
+    pub coast: f64,
+}
+
+impl StageDesign {
+    pub fn new(isp: f64, mass_fraction: f64, thrust: ThrustSpec) -> Self {
+        Self { isp, mass_fraction, thrust, coast: 0.0 }
+    }
+
+    pub fn with_coast(mut self, coast: f64) -> Self {
+        self.coast = coast;
+        self
+    }
+}
+
+/// A sized stage
+#[derive(Debug, Clone, Copy)]
+pub struct Stage {
+    pub isp: f64,
+    pub mass_fraction: f64,
+    /// Propellant weight (lb)
+    pub propellant: f64,
+    /// Structure weight (lb)
+    pub structure: f64,
+    /// Thrust (lb)
+    pub thrust: f64,
+    /// Burn time (s)
+    pub burn_time: f64,
+    /// Coast after burnout (s)
+    pub coast: f64,
+}
+
+impl Stage {
+    /// Sizes a stage to add `delv` to a stack of weight `upper` above it,
+    /// or `None` if the mass fraction cannot deliver it
+    pub fn size(design: &StageDesign, delv: f64, upper: f64) -> Option<Self> {
+        let (isp, xmf) = (design.isp, design.mass_fraction);
+        let top = upper * ((delv / (isp * G_ACCEL)).exp() - 1.0);
+        let bot = 1.0 / xmf - ((1.0 - xmf) / xmf) * (delv / (isp * G_ACCEL)).exp();
+        if bot <= 0.0 {
+            return None;
+        }
+        let wp = top / bot;
+        let ws = wp * (1.0 - xmf) / xmf;
+        let (thrust, burn_time) = match design.thrust {
+            ThrustSpec::Thrust(trst) => (trst, isp * wp / trst),
+            ThrustSpec::MaxAcceleration(amax) => {
+                let trst = amax * (upper + ws);
+                (trst, isp * wp / trst)
+            }
+            ThrustSpec::BurnTime(tb) => (isp * wp / tb, tb),
+        };
+        Some(Self { isp, mass_fraction: xmf, propellant: wp, structure: ws, thrust, burn_time, coast: design.coast })
+    }
+
+    /// Ideal velocity increment carrying `upper` (ft/s)
+    pub fn delta_v(&self, upper: f64) -> f64 {
+        let w0 = upper + self.structure + self.propellant;
+        self.isp * G_ACCEL * (w0 / (upper + self.structure)).ln()
+    }
+}
+
+/// Vehicle state handed to a steering law
+#[derive(Debug, Clone, Copy)]
+pub struct FlightState {
+    pub t: f64,
+    pub r: Vec3,
+    pub v: Vec3,
+    /// Thrust acceleration (ft/s^2)
+    pub thrust_accel: f64,
+    /// Index of the burning stage, `None` when coasting
+    pub stage: Option<usize>,
+}
+
+/// Sampled trajectory of a vehicle
+#[derive(Debug, Clone, Default)]
+pub struct Trajectory {
+    pub time: Vec<f64>,
+    pub position: Vec<Vec3>,
+    pub velocity: Vec<Vec3>,
+    /// Thrust acceleration (G)
+    pub accel_g: Vec<f64>,
+    /// Time, position and velocity at final burnout
+    pub burnout: Option<(f64, Vec3, Vec3)>,
+    /// Time, position and velocity when the steering law shut the engine
+    /// down with thrust left
+    pub cutoff: Option<(f64, Vec3, Vec3)>,
+}
+
+/// An N-stage rocket, first stage first
+#[derive(Debug, Clone)]
+pub struct Vehicle {
+    pub stages: Vec<Stage>,
+    /// Payload weight (lb)
+    pub payload: f64,
+}
+
+impl Vehicle {
+    /// Sizes the stages from the top down for the given per-stage velocity
+    /// increments, listed first stage first
+    pub fn size(payload: f64, designs: &[StageDesign], delv: &[f64]) -> Option<Self> {
+        assert_eq!(designs.len(), delv.len(), "one velocity increment per stage");
+        let mut stages = Vec::with_capacity(designs.len());
+        let mut upper = payload;
+        for (design, &dv) in designs.iter().zip(delv).rev() {
+            let stage = Stage::size(design, dv, upper)?;
+            upper += stage.propellant + stage.structure;
+            stages.push(stage);
+        }
+        stages.reverse();
+        Some(Self { stages, payload })
+    }
+
+    /// Sizes the vehicle with the split of `delv` that minimizes liftoff
+    /// weight. With structure proportional to propellant the optimum has a
+    /// closed form in a Lagrange multiplier, found here by bisection.
+    pub fn optimal(payload: f64, designs: &[StageDesign], delv: f64) -> Option<Self> {
+        Self::size(payload, designs, &optimal_split(designs, delv)?)
+    }
+
+    pub fn liftoff_weight(&self) -> f64 {
+        self.ignition_weight(0)
+    }
+
+    /// Weight at ignition of stage `i`
+    pub fn ignition_weight(&self, i: usize) -> f64 {
+        self.stages[i..].iter().rev().fold(self.payload, |w, s| s.propellant + s.structure + w)
+    }
+
+    /// Ideal velocity increment of stage `i`
+    pub fn stage_delta_v(&self, i: usize) -> f64 {
+        let upper = self.stages.get(i + 1).map_or(self.payload, |_| self.ignition_weight(i + 1));
+        self.stages[i].delta_v(upper)
+    }
+
+    /// Total ideal velocity increment
+    pub fn delta_v(&self) -> f64 {
+        (0..self.stages.len()).map(|i| self.stage_delta_v(i)).sum()
+    }
+
+    /// Ignition time of each stage
+    pub fn ignition_times(&self) -> Vec<f64> {
+        let mut t = 0.0;
+        self.stages.iter().map(|s| {
+            let start = t;
+            t += s.burn_time + s.coast;
+            start
+        }).collect()
+    }
+
+    /// Burnout time of the last stage
+    pub fn burnout_time(&self) -> f64 {
+        let last = self.stages.len() - 1;
+        self.ignition_times()[last] + self.stages[last].burn_time
+    }
+
+    /// Weight (lb) and thrust (lb) at time `t`. A spent stage is dropped
+    /// when the next one ignites.
+    pub fn weight_and_thrust(&self, t: f64) -> (f64, f64) {
+        let starts = self.ignition_times();
+        for (i, (stage, &start)) in self.stages.iter().zip(&starts).enumerate() {
+            let w0 = self.ignition_weight(i);
+            if t < start + stage.burn_time {
+                return (w0 - stage.propellant * (t - start) / stage.burn_time, stage.thrust);
+            }
+            if t < start + stage.burn_time + stage.coast {
+                return (w0 - stage.propellant, 0.0);
+            }
+        }
+        (self.payload, 0.0)
+    }
+
+    /// Index of the stage burning at time `t`
+    pub fn burning_stage(&self, t: f64) -> Option<usize> {
+        let starts = self.ignition_times();
+        self.stages.iter().zip(&starts).position(|(s, &start)| t >= start && t < start + s.burn_time)
+    }
+
+    /// Flies the vehicle over a spherical, non-rotating Earth with the
+    /// book's second-order Runge-Kutta integration. `steering` is asked for
+    /// a `Command` at the start of each step, as in the C17 and C43 boost
+    /// loops, and its thrust direction is held over the step. Stops when the
+    /// vehicle returns to the ground or at `t_max`; `burnout` stays `None` if
+    /// it hits the ground first. Samples every `sample` seconds.
+    pub fn fly<S: SteeringLaw + ?Sized>(
+        &self,
+        r0: Vec3, v0: Vec3,
+        h: f64, sample: f64, t_max: f64,
+        steering: &mut S,
+    ) -> Trajectory {
+        let mut traj = Trajectory::default();
+        let tbo = self.burnout_time();
+        let (mut r, mut v) = (r0, v0);
+        let mut t = 0.0;
+        let mut s = 0.0;
+
+        let thrust_accel = |t: f64| {
+            let (wgt, trst) = self.weight_and_thrust(t);
+            G_ACCEL * trst / wgt
+        };
+        let accel = |t: f64, r: Vec3, direction: Vec3| {
+            let rmag = r.magnitude();
+            r * (-GM_FT / (rmag * rmag * rmag)) + direction * thrust_accel(t)
+        };
+
+        while t < t_max {
+            let at = thrust_accel(t);
+            let state = FlightState { t, r, v, thrust_accel: at, stage: self.burning_stage(t) };
+            let command = steering.command(&state);
+            if let Some(vc) = command.velocity {
+                v = vc;
+            }
+            if at > 0.0 && command.is_coast() && traj.cutoff.is_none() {
+                traj.cutoff = Some((t, r, v));
+            }
+            let direction = command.direction;
+
+            let (rold, vold) = (r, v);
+            let a0 = accel(t, r, direction);
+            r = r + v * h;
+            v = v + a0 * h;
+            t += h;
+            let a1 = accel(t, r, direction);
+            r = (rold + r + v * h) * 0.5;
+            v = (vold + v + a1 * h) * 0.5;
+
+            if traj.burnout.is_none() && t >= tbo {
+                traj.burnout = Some((t, r, v));
+            }
+            s += h;
+            if s >= sample - 1e-5 {
+                s = 0.0;
+                traj.time.push(t);
+                traj.position.push(r);
+                traj.velocity.push(v);
+                traj.accel_g.push(if command.is_coast() { 0.0 } else { at / G_ACCEL });
+            }
+            if r.magnitude() < EARTH_RADIUS_FT && r.dot(&v) < 0.0 {
+                break;
+            }
+        }
+        traj
+    }
+}
+
+/// Velocity increments, first stage first, that minimize liftoff weight
+/// for a total of `delv`, or `None` if the stages cannot reach it
+pub fn optimal_split(designs: &[StageDesign], delv: f64) -> Option<Vec<f64>> {
+    let c: Vec<f64> = designs.iter().map(|d| d.isp * G_ACCEL).collect();
+    let eps: Vec<f64> = designs.iter().map(|d| 1.0 - d.mass_fraction).collect();
+    // Upper limit as each stage approaches an infinite mass ratio
+    let dv_max: f64 = c.iter().zip(&eps).map(|(c, e)| -c * e.ln()).sum();
+    if delv >= dv_max {
+        return None;
+    }
+
+    // Stage mass ratio n_i = (c_i eta - 1) / (c_i eps_i eta)
+    let ratio = |i: usize, eta: f64| ((c[i] * eta - 1.0) / (c[i] * eps[i] * eta)).max(1.0);
+    let total = |eta: f64| (0..c.len()).map(|i| c[i] * ratio(i, eta).ln()).sum::<f64>();
+
+    let mut lo = c.iter().zip(&eps).map(|(c, e)| 1.0 / (c * (1.0 - e))).fold(0.0, f64::max);
+    let mut hi = 2.0 * lo;
+    while total(hi) < delv {
+        hi *= 2.0;
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if total(mid) < delv {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let eta = 0.5 * (lo + hi);
+    Some((0..c.len()).map(|i| c[i] * ratio(i, eta).ln()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::steering::Command;
+
+    fn book_designs() -> Vec<StageDesign> {
+        vec![
+            StageDesign::new(250.0, 0.85, ThrustSpec::MaxAcceleration(10.0)),
+            StageDesign::new(250.0, 0.85, ThrustSpec::MaxAcceleration(10.0)),
+        ]
+    }
+
+    #[test]
+    fn test_rocket_matches_book_sizing() {
+        // C16L1 stage 2 sizing
+        let (wpay, delv2): (f64, f64) = (100.0, 0.6667 * 20000.0);
+        let e = (delv2 / (250.0 * 32.2)).exp();
+        let wp2 = wpay * (e - 1.0) / (1.0 / 0.85 - (0.15 / 0.85) * e);
+        let v = Vehicle::size(wpay, &book_designs(), &[0.3333 * 20000.0, delv2]).unwrap();
+        assert!((v.stages[1].propellant - wp2).abs() < 1e-9);
+        assert!((v.stage_delta_v(1) - delv2).abs() < 1e-6);
+        assert!((v.delta_v() - 20000.0).abs() < 1e-6);
+        // Burnout acceleration of each stage is the limit
+        let (w, trst) = v.weight_and_thrust(v.stages[0].burn_time - 1e-9);
+        assert!((trst / w - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rocket_optimal_split_beats_grid() {
+        let designs = vec![
+            StageDesign::new(280.0, 0.9, ThrustSpec::MaxAcceleration(10.0)),
+            StageDesign::new(250.0, 0.85, ThrustSpec::MaxAcceleration(10.0)),
+        ];
+        let best = Vehicle::optimal(100.0, &designs, 20000.0).unwrap();
+        assert!((best.delta_v() - 20000.0).abs() < 1e-6);
+        for k in 1..100 {
+            let f = k as f64 / 100.0;
+            if let Some(v) = Vehicle::size(100.0, &designs, &[f * 20000.0, (1.0 - f) * 20000.0]) {
+                assert!(best.liftoff_weight() <= v.liftoff_weight() + 1e-6, "split {}", f);
+            }
+        }
+        // Identical stages split the velocity evenly
+        let split = optimal_split(&book_designs(), 20000.0).unwrap();
+        assert!((split[0] - split[1]).abs() < 1e-6);
+        // Beyond the technology limit
+        assert!(optimal_split(&book_designs(), 60000.0).is_none());
+    }
+
+    #[test]
+    fn test_rocket_timeline_with_coast() {
+        let designs = vec![
+            StageDesign::new(250.0, 0.85, ThrustSpec::BurnTime(60.0)).with_coast(20.0),
+            StageDesign::new(250.0, 0.85, ThrustSpec::Thrust(2000.0)),
+        ];
+        let v = Vehicle::size(100.0, &designs, &[8000.0, 8000.0]).unwrap();
+        assert!((v.stages[0].burn_time - 60.0).abs() < 1e-12);
+        assert_eq!(v.ignition_times(), vec![0.0, 80.0]);
+        assert_eq!(v.burning_stage(70.0), None);
+        assert_eq!(v.burning_stage(81.0), Some(1));
+        let (w, trst) = v.weight_and_thrust(70.0);
+        assert_eq!(trst, 0.0);
+        assert!((w - v.ignition_weight(1) - v.stages[0].structure).abs() < 1e-9);
+        assert!((v.weight_and_thrust(v.burnout_time() + 1.0).0 - 100.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rocket_fly_vertical() {
+        // Vertical flight with thrust along the radius reaches close to the
+        // ideal velocity less gravity losses
+        let v = Vehicle::size(100.0, &book_designs(), &[10000.0, 10000.0]).unwrap();
+        let r0 = Vec3::new(EARTH_RADIUS_FT, 0.0, 0.0);
+        let traj = v.fly(r0, Vec3::new(1.0, 0.0, 0.0), 0.01, 1.0, 2000.0, &mut |s: &FlightState| Command::thrust(s.r));
+        let (tbo, _, vbo) = traj.burnout.unwrap();
+        let loss = 20000.0 - vbo.magnitude();
+        assert!(loss > 0.0 && loss < 1.1 * G_ACCEL * tbo, "loss {}", loss);
+        assert!(traj.position.last().unwrap().magnitude() < EARTH_RADIUS_FT + 1000.0);
+    }
+}
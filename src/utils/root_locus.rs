@@ -0,0 +1,175 @@
+//! Root locus and closed-loop pole analysis
+//!
+//! Traces the roots of 1 + K L(s) = 0 as the loop gain K varies, keeping each
+//! closed-loop pole on a continuous branch, and reports natural frequency and
+//! damping for each pole (MATLAB `rlocus` and `damp`).
+
+use super::complex::Complex;
+use super::lti::TransferFunction;
+use super::polynomial::{poly_add, poly_roots, poly_scale};
+
+/// Closed-loop pole branches over a range of loop gains
+#[derive(Debug, Clone)]
+pub struct RootLocus {
+    pub gains: Vec<f64>,
+    /// `branches[b][k]` is the pole on branch `b` at `gains[k]`
+    pub branches: Vec<Vec<Complex>>,
+    pub open_loop_poles: Vec<Complex>,
+    pub open_loop_zeros: Vec<Complex>,
+}
+
+impl RootLocus {
+    /// Real parts along branch `b`
+    pub fn branch_re(&self, b: usize) -> Vec<f64> {
+        self.branches[b].iter().map(|p| p.re).collect()
+    }
+
+    /// Imaginary parts along branch `b`
+    pub fn branch_im(&self, b: usize) -> Vec<f64> {
+        self.branches[b].iter().map(|p| p.im).collect()
+    }
+
+    /// First gain at which any closed-loop pole enters the right half plane
+    pub fn critical_gain(&self) -> Option<f64> {
+        (0..self.gains.len())
+            .find(|&k| self.branches.iter().any(|b| b[k].re > 0.0))
+            .map(|k| self.gains[k])
+    }
+}
+
+/// Natural frequency and damping of a single pole
+#[derive(Debug, Clone, Copy)]
+pub struct PoleDamping {
+    pub pole: Complex,
+    /// Natural frequency |p| (rad/sec)
+    pub wn: f64,
+    /// Damping ratio -Re(p)/|p|
+    pub zeta: f64,
+}
+
+/// Natural frequency and damping for each pole (MATLAB `damp`)
+pub fn damping(poles: &[Complex]) -> Vec<PoleDamping> {
+    poles
+        .iter()
+        .map(|&p| {
+            let wn = p.abs();
+            let zeta = if wn > 0.0 { -p.re / wn } else { -1.0 };
+            PoleDamping { pole: p, wn, zeta }
+        })
+        .collect()
+}
+
+/// Closed-loop characteristic polynomial den(s) + K num(s)
+pub fn closed_loop_polynomial(open_loop: &TransferFunction, k: f64) -> Vec<f64> {
+    poly_add(&open_loop.den, &poly_scale(&open_loop.num, k))
+}
+
+/// Closed-loop poles of 1 + K L(s) = 0 at a single gain
+pub fn closed_loop_poles(open_loop: &TransferFunction, k: f64) -> Vec<Complex> {
+    poly_roots(&closed_loop_polynomial(open_loop, k))
+}
+
+/// Root locus of the open loop `L(s)` over the given gains
+///
+/// Roots at each gain are assigned to the branch whose previous pole is
+/// nearest, so plotted branches do not jump between loci.
+pub fn root_locus(open_loop: &TransferFunction, gains: &[f64]) -> RootLocus {
+    let mut branches: Vec<Vec<Complex>> = Vec::new();
+    for (k, &gain) in gains.iter().enumerate() {
+        let mut roots = closed_loop_poles(open_loop, gain);
+        if k == 0 {
+            branches = roots.iter().map(|&r| vec![r]).collect();
+            continue;
+        }
+        for branch in branches.iter_mut() {
+            let last = branch[branch.len() - 1];
+            let nearest = roots
+                .iter()
+                .enumerate()
+                .min_by(|a, b| {
+                    (*a.1 - last).abs()
+                        .partial_cmp(&(*b.1 - last).abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .map(|(i, _)| i);
+            match nearest {
+                Some(i) => branch.push(roots.swap_remove(i)),
+                None => branch.push(last),
+            }
+        }
+    }
+
+    RootLocus {
+        gains: gains.to_vec(),
+        branches,
+        open_loop_poles: open_loop.poles(),
+        open_loop_zeros: open_loop.zeros(),
+    }
+}
+
+/// Gain range from 0 to `k_max` with `n` points, denser near zero where the
+/// poles leave their open-loop locations
+pub fn gain_range(k_max: f64, n: usize) -> Vec<f64> {
+    (0..n)
+        .map(|i| {
+            let f = i as f64 / (n.max(2) - 1) as f64;
+            k_max * f * f
+        })
+        .collect()
+}
+
+/// Save a root locus plot with the closed-loop poles at `design_gain` marked
+/// and annotated with their natural frequency and damping
+pub fn plot_root_locus(
+    filename: &str,
+    title: &str,
+    open_loop: &TransferFunction,
+    locus: &RootLocus,
+    design_gain: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::plotting::{root_locus_plot, PlotConfig};
+
+    let config = PlotConfig::new(title).with_labels("Real (1/Sec)", "Imaginary (Rad/Sec)");
+    let branches: Vec<(Vec<f64>, Vec<f64>)> = (0..locus.branches.len())
+        .map(|b| (locus.branch_re(b), locus.branch_im(b)))
+        .collect();
+    let poles: Vec<(f64, f64)> = locus.open_loop_poles.iter().map(|p| (p.re, p.im)).collect();
+    let zeros: Vec<(f64, f64)> = locus.open_loop_zeros.iter().map(|z| (z.re, z.im)).collect();
+    let marks: Vec<(f64, f64, String)> = damping(&closed_loop_poles(open_loop, design_gain))
+        .iter()
+        .filter(|d| d.pole.im >= 0.0)
+        .map(|d| (d.pole.re, d.pole.im, format!("wn={:.1} z={:.2}", d.wn, d.zeta)))
+        .collect();
+    root_locus_plot(filename, &config, &branches, &poles, &zeros, &marks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_locus_second_order() {
+        // L = 1/(s(s+2)): poles meet at -1 for K = 1 then move vertically
+        let l = TransferFunction::new(&[1.0], &[1.0, 2.0, 0.0]);
+        let locus = root_locus(&l, &gain_range(10.0, 201));
+        assert_eq!(locus.branches.len(), 2);
+        for b in 0..2 {
+            let last = locus.branches[b][200];
+            assert!((last.re + 1.0).abs() < 1e-6);
+            assert!((last.im.abs() - 3.0).abs() < 1e-6);
+        }
+        assert!(locus.critical_gain().is_none());
+
+        let d = damping(&closed_loop_poles(&l, 4.0));
+        assert!((d[0].wn - 2.0).abs() < 1e-9 && (d[0].zeta - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_critical_gain_third_order() {
+        // L = 1/(s(s+1)(s+2)) crosses the imaginary axis at K = 6
+        let l = TransferFunction::new(&[1.0], &[1.0, 3.0, 2.0, 0.0]);
+        let gains: Vec<f64> = (0..=1000).map(|i| 0.01 * i as f64).collect();
+        let k = root_locus(&l, &gains).critical_gain().unwrap();
+        assert!((k - 6.0).abs() < 0.011);
+    }
+}
@@ -0,0 +1,487 @@
+//! Homing seeker models
+//!
+//! The engagement lessons feed guidance the true line-of-sight rate, or the
+//! true angle plus white noise as in C14L1. A `Seeker` sits between the
+//! geometry and the guidance law: a gimballed seeker closes a track loop on
+//! the boresight error and reports the commanded inertial gimbal rate, a
+//! strapdown seeker measures the look angle against the body and
+//! differentiates the gyro-referenced line of sight. Both have a field of
+//! view, range-dependent signal-to-noise ratio and lock-on/loss-of-lock
+//! logic, and imperfect stabilization lets missile body rate leak into the
+//! LOS rate estimate (the parasitic loop).
+//!
+//! `Seeker` works in a single plane (pitch or yaw channel); `Seeker3D` is
+//! the vector form for the three-dimensional engagements. Both are updated
+//! once per integration step after the RK2 averaging and hold their output
+//! for the next step. An optional `Radome` adds refraction error that grows
+//! with look angle relative to the body.
+
+use super::radome::Radome;
+use super::rk2::Vec3;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand_distr::StandardNormal;
+
+/// Seeker mechanization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekerKind {
+    /// Inertially stabilized gimbal driven by a track loop
+    Gimballed,
+    /// Body-fixed detector with gyro-referenced LOS differentiation
+    Strapdown,
+}
+
+/// Seeker parameters
+#[derive(Debug, Clone, Copy)]
+pub struct SeekerConfig {
+    pub kind: SeekerKind,
+    /// Track loop (gimballed) or rate filter (strapdown) bandwidth (rad/sec)
+    pub bandwidth: f64,
+    /// Maximum gimbal angle from the body axis (rad)
+    pub gimbal_limit: Option<f64>,
+    /// Maximum gimbal rate relative to the body (rad/sec)
+    pub gimbal_rate_limit: Option<f64>,
+    /// Full field of view (rad)
+    pub fov: f64,
+    /// Fraction of body rate added to the LOS rate estimate by imperfect
+    /// stabilization or gyro scale factor error
+    pub body_rate_coupling: f64,
+    /// Signal-to-noise ratio at `range_ref` (dB)
+    pub snr_ref_db: f64,
+    /// Reference range (ft)
+    pub range_ref: f64,
+    /// SNR range exponent, 4 for a radar and 2 for a passive seeker
+    pub snr_exponent: f64,
+    /// One-sigma angle noise per update at the reference SNR (rad)
+    pub noise_ref: f64,
+    /// SNR needed to acquire and hold track (dB)
+    pub lock_threshold_db: f64,
+}
+
+impl Default for SeekerConfig {
+    fn default() -> Self {
+        Self {
+            kind: SeekerKind::Gimballed,
+            bandwidth: 10.0,
+            gimbal_limit: None,
+            gimbal_rate_limit: None,
+            fov: 10.0 / 57.3,
+            body_rate_coupling: 0.0,
+            snr_ref_db: 20.0,
+            range_ref: 60000.0,
+            snr_exponent: 4.0,
+            noise_ref: 0.0,
+            lock_threshold_db: 10.0,
+        }
+    }
+}
+
+impl SeekerConfig {
+    /// Signal-to-noise ratio at range `r` (dB)
+    pub fn snr_db(&self, r: f64) -> f64 {
+        self.snr_ref_db + 10.0 * self.snr_exponent * (self.range_ref / r.max(1e-3)).log10()
+    }
+
+    /// One-sigma angle noise at range `r`, inversely proportional to sqrt(SNR)
+    pub fn noise_sigma(&self, r: f64) -> f64 {
+        self.noise_ref * 10.0_f64.powf((self.snr_ref_db - self.snr_db(r)) / 20.0)
+    }
+}
+
+/// Why track was lost
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LossReason {
+    FieldOfView,
+    LowSnr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekerEventKind {
+    LockOn,
+    LossOfLock(LossReason),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeekerEvent {
+    pub t: f64,
+    pub kind: SeekerEventKind,
+}
+
+/// Lock state machine shared by the planar and 3D seekers
+#[derive(Debug, Clone, Default)]
+struct Track {
+    locked: bool,
+    events: Vec<SeekerEvent>,
+}
+
+impl Track {
+    fn update(&mut self, config: &SeekerConfig, t: f64, error: f64, range: f64) {
+        let reason = if error.abs() > 0.5 * config.fov {
+            Some(LossReason::FieldOfView)
+        } else if config.snr_db(range) < config.lock_threshold_db {
+            Some(LossReason::LowSnr)
+        } else {
+            None
+        };
+        match (self.locked, reason) {
+            (false, None) => {
+                self.locked = true;
+                self.events.push(SeekerEvent { t, kind: SeekerEventKind::LockOn });
+            }
+            (true, Some(r)) => {
+                self.locked = false;
+                self.events.push(SeekerEvent { t, kind: SeekerEventKind::LossOfLock(r) });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Geometry and body motion seen by a planar seeker
+#[derive(Debug, Clone, Copy)]
+pub struct SeekerInput {
+    /// Inertial line-of-sight angle (rad)
+    pub los: f64,
+    /// Missile-target range (ft)
+    pub range: f64,
+    /// Missile body attitude (rad)
+    pub body_att: f64,
+    /// Missile body rate (rad/sec)
+    pub body_rate: f64,
+}
+
+/// Single-plane seeker
+#[derive(Debug, Clone)]
+pub struct Seeker {
+    pub config: SeekerConfig,
+    /// Inertial boresight angle (gimballed) (rad)
+    pub boresight: f64,
+    /// Filtered gyro-referenced LOS angle (strapdown) (rad)
+    pub los_filtered: f64,
+    /// LOS rate estimate handed to guidance (rad/sec)
+    pub los_rate: f64,
+    pub radome: Option<Radome>,
+    track: Track,
+    rng: Option<StdRng>,
+}
+
+impl Seeker {
+    /// Seeker cued to the initial line of sight
+    pub fn new(config: SeekerConfig, los: f64, body_att: f64) -> Self {
+        let boresight = match config.kind {
+            SeekerKind::Gimballed => los,
+            SeekerKind::Strapdown => body_att,
+        };
+        Self {
+            config,
+            boresight,
+            los_filtered: los,
+            los_rate: 0.0,
+            radome: None,
+            track: Track::default(),
+            rng: None,
+        }
+    }
+
+    /// Enable angle noise drawn from a seeded generator
+    pub fn with_noise_seed(mut self, seed: u64) -> Self {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Look through a refracting radome
+    pub fn with_radome(mut self, radome: Radome) -> Self {
+        self.radome = Some(radome);
+        self
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.track.locked
+    }
+
+    pub fn events(&self) -> &[SeekerEvent] {
+        &self.track.events
+    }
+
+    /// Gimbal angle relative to the body (rad)
+    pub fn gimbal_angle(&self, body_att: f64) -> f64 {
+        self.boresight - body_att
+    }
+
+    fn noise(&mut self, range: f64) -> f64 {
+        let sigma = self.config.noise_sigma(range);
+        match self.rng.as_mut() {
+            Some(rng) if sigma > 0.0 => sigma * rng.sample::<f64, _>(StandardNormal),
+            _ => 0.0,
+        }
+    }
+
+    /// Advance the seeker by `h` and return the LOS rate estimate
+    pub fn update(&mut self, t: f64, h: f64, inp: &SeekerInput) -> f64 {
+        let cfg = self.config;
+        let look = match cfg.kind {
+            SeekerKind::Gimballed => inp.los - self.boresight,
+            SeekerKind::Strapdown => inp.los - inp.body_att,
+        };
+        self.track.update(&cfg, t, look, inp.range);
+        let refraction = self.radome.as_ref().map_or(0.0, |r| r.error(inp.los - inp.body_att));
+        let measured = look + refraction + self.noise(inp.range);
+
+        match cfg.kind {
+            SeekerKind::Gimballed => {
+                let cmd = if self.track.locked { cfg.bandwidth * measured } else { 0.0 };
+                let mut rel = cmd - (1.0 + cfg.body_rate_coupling) * inp.body_rate;
+                if let Some(rmax) = cfg.gimbal_rate_limit {
+                    rel = rel.clamp(-rmax, rmax);
+                }
+                let mut gimbal = self.boresight - inp.body_att + h * rel;
+                if let Some(gmax) = cfg.gimbal_limit {
+                    gimbal = gimbal.clamp(-gmax, gmax);
+                }
+                self.boresight = inp.body_att + h * inp.body_rate + gimbal;
+                if self.track.locked {
+                    self.los_rate = cmd;
+                }
+            }
+            SeekerKind::Strapdown => {
+                let los_m = measured + (1.0 + cfg.body_rate_coupling) * inp.body_att;
+                self.boresight = inp.body_att;
+                if self.track.locked {
+                    self.los_rate = cfg.bandwidth * (los_m - self.los_filtered);
+                    self.los_filtered += h * self.los_rate;
+                } else {
+                    self.los_filtered = los_m;
+                }
+            }
+        }
+        self.los_rate
+    }
+}
+
+/// Geometry and body motion seen by a three-dimensional seeker
+#[derive(Debug, Clone, Copy)]
+pub struct SeekerInput3D {
+    /// Missile-to-target relative position (ft)
+    pub rtm: Vec3,
+    /// Missile body (velocity) axis
+    pub body_axis: Vec3,
+    /// Missile body angular rate (rad/sec)
+    pub body_rate: Vec3,
+}
+
+/// Vector seeker for the three-dimensional engagements
+#[derive(Debug, Clone)]
+pub struct Seeker3D {
+    pub config: SeekerConfig,
+    /// Boresight unit vector (gimballed)
+    pub boresight: Vec3,
+    /// Filtered gyro-referenced LOS unit vector (strapdown)
+    pub los_filtered: Vec3,
+    /// LOS rate vector estimate (rad/sec)
+    pub los_rate: Vec3,
+    pub radome: Option<Radome>,
+    track: Track,
+    rng: Option<StdRng>,
+}
+
+/// Angle between two unit vectors
+fn angle_between(a: &Vec3, b: &Vec3) -> f64 {
+    a.cross(b).magnitude().atan2(a.dot(b))
+}
+
+/// Component of `v` perpendicular to unit vector `u`
+fn perpendicular(v: &Vec3, u: &Vec3) -> Vec3 {
+    *v - *u * v.dot(u)
+}
+
+impl Seeker3D {
+    pub fn new(config: SeekerConfig, rtm: Vec3, body_axis: Vec3) -> Self {
+        let u = rtm.normalize();
+        let boresight = match config.kind {
+            SeekerKind::Gimballed => u,
+            SeekerKind::Strapdown => body_axis.normalize(),
+        };
+        Self {
+            config,
+            boresight,
+            los_filtered: u,
+            los_rate: Vec3::default(),
+            radome: None,
+            track: Track::default(),
+            rng: None,
+        }
+    }
+
+    pub fn with_noise_seed(mut self, seed: u64) -> Self {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    pub fn with_radome(mut self, radome: Radome) -> Self {
+        self.radome = Some(radome);
+        self
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.track.locked
+    }
+
+    pub fn events(&self) -> &[SeekerEvent] {
+        &self.track.events
+    }
+
+    /// LOS unit vector bent away from the body axis by radome refraction
+    fn refract(&self, u: Vec3, xb: &Vec3) -> Vec3 {
+        let Some(radome) = self.radome.as_ref() else {
+            return u;
+        };
+        let off = perpendicular(&u, xb);
+        if off.magnitude() < 1e-12 {
+            return u;
+        }
+        (u + off.normalize() * radome.error(angle_between(xb, &u))).normalize()
+    }
+
+    /// LOS unit vector perturbed by angle noise about two axes normal to it
+    fn measure(&mut self, u: Vec3, range: f64) -> Vec3 {
+        let sigma = self.config.noise_sigma(range);
+        let Some(rng) = self.rng.as_mut().filter(|_| sigma > 0.0) else {
+            return u;
+        };
+        let trial = if u.x.abs() < 0.9 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+        let e1 = u.cross(&trial).normalize();
+        let e2 = u.cross(&e1);
+        let n1: f64 = rng.sample(StandardNormal);
+        let n2: f64 = rng.sample(StandardNormal);
+        (u + e1 * (sigma * n1) + e2 * (sigma * n2)).normalize()
+    }
+
+    /// Advance the seeker by `h` and return the LOS rate vector estimate
+    pub fn update(&mut self, t: f64, h: f64, inp: &SeekerInput3D) -> Vec3 {
+        let cfg = self.config;
+        let u = inp.rtm.normalize();
+        let xb = inp.body_axis.normalize();
+        let reference = match cfg.kind {
+            SeekerKind::Gimballed => self.boresight,
+            SeekerKind::Strapdown => xb,
+        };
+        self.track.update(&cfg, t, angle_between(&reference, &u), inp.rtm.magnitude());
+        let um = self.measure(self.refract(u, &xb), inp.rtm.magnitude());
+
+        match cfg.kind {
+            SeekerKind::Gimballed => {
+                let b = self.boresight;
+                let cmd = if self.track.locked { b.cross(&um) * cfg.bandwidth } else { Vec3::default() };
+                let mut rel = cmd - inp.body_rate * (1.0 + cfg.body_rate_coupling);
+                if let Some(rmax) = cfg.gimbal_rate_limit {
+                    let m = rel.magnitude();
+                    if m > rmax {
+                        rel = rel * (rmax / m);
+                    }
+                }
+                let mut bn = (b + (rel + inp.body_rate).cross(&b) * h).normalize();
+                if let Some(gmax) = cfg.gimbal_limit {
+                    let g = angle_between(&xb, &bn);
+                    if g > gmax {
+                        let off = perpendicular(&bn, &xb).normalize();
+                        bn = (xb * gmax.cos() + off * gmax.sin()).normalize();
+                    }
+                }
+                self.boresight = bn;
+                if self.track.locked {
+                    self.los_rate = cmd;
+                }
+            }
+            SeekerKind::Strapdown => {
+                self.boresight = xb;
+                if self.track.locked {
+                    let ud = (um - self.los_filtered) * cfg.bandwidth;
+                    let uf = self.los_filtered;
+                    self.los_rate = uf.cross(&ud) + perpendicular(&inp.body_rate, &uf) * cfg.body_rate_coupling;
+                    self.los_filtered = (uf + ud * h).normalize();
+                } else {
+                    self.los_filtered = um;
+                }
+            }
+        }
+        self.los_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// LOS rotating at a constant rate seen from a non-rotating body
+    fn track_ramp(config: SeekerConfig, rate: f64) -> Seeker {
+        let mut seeker = Seeker::new(config, 0.0, 0.0);
+        let h = 0.001;
+        for i in 1..=2000 {
+            let t = i as f64 * h;
+            seeker.update(t, h, &SeekerInput { los: rate * t, range: 20000.0, body_att: 0.0, body_rate: 0.0 });
+        }
+        seeker
+    }
+
+    #[test]
+    fn test_track_loop_settles_to_los_rate() {
+        for kind in [SeekerKind::Gimballed, SeekerKind::Strapdown] {
+            let s = track_ramp(SeekerConfig { kind, ..Default::default() }, 0.05);
+            assert!((s.los_rate - 0.05).abs() < 1e-4);
+            assert_eq!(s.events()[0].kind, SeekerEventKind::LockOn);
+        }
+    }
+
+    #[test]
+    fn test_gimbal_limit_loses_lock() {
+        let config = SeekerConfig { gimbal_limit: Some(0.02), fov: 0.02, ..Default::default() };
+        let s = track_ramp(config, 0.05);
+        assert!(!s.is_locked());
+        assert_eq!(s.events()[1].kind, SeekerEventKind::LossOfLock(LossReason::FieldOfView));
+    }
+
+    #[test]
+    fn test_body_rate_coupling() {
+        let config = SeekerConfig { body_rate_coupling: 0.02, ..Default::default() };
+        let mut s = Seeker::new(config, 0.0, 0.0);
+        let h = 0.001;
+        for i in 1..=2000 {
+            let t = i as f64 * h;
+            s.update(t, h, &SeekerInput { los: 0.0, range: 20000.0, body_att: 1.0 * t, body_rate: 1.0 });
+        }
+        assert!((s.los_rate - 0.02).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_radome_slope_couples_body_rate() {
+        // Fixed LOS, body turning at 1 rad/sec: apparent LOS rate is -R
+        let mut s = Seeker::new(SeekerConfig::default(), 0.0, 0.0).with_radome(Radome::constant(0.03));
+        let h = 0.001;
+        for i in 1..=2000 {
+            let t = i as f64 * h;
+            s.update(t, h, &SeekerInput { los: 0.0, range: 20000.0, body_att: 1.0 * t, body_rate: 1.0 });
+        }
+        assert!((s.los_rate + 0.03).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_snr_and_noise_scale_with_range() {
+        let cfg = SeekerConfig { noise_ref: 0.001, ..Default::default() };
+        assert!((cfg.snr_db(30000.0) - (20.0 + 40.0 * 2.0_f64.log10())).abs() < 1e-9);
+        assert!((cfg.noise_sigma(30000.0) - 0.00025).abs() < 1e-12);
+        assert!(cfg.snr_db(200000.0) < cfg.lock_threshold_db);
+    }
+
+    #[test]
+    fn test_seeker3d_matches_planar() {
+        let cfg = SeekerConfig::default();
+        let mut s = Seeker3D::new(cfg, Vec3::new(20000.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let h = 0.001;
+        for i in 1..=2000 {
+            let th: f64 = 0.05 * i as f64 * h;
+            let rtm = Vec3::new(20000.0 * th.cos(), 20000.0 * th.sin(), 0.0);
+            s.update(i as f64 * h, h, &SeekerInput3D { rtm, body_axis: Vec3::new(1.0, 0.0, 0.0), body_rate: Vec3::default() });
+        }
+        assert!((s.los_rate.z - 0.05).abs() < 1e-4);
+    }
+}
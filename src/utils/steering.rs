@@ -0,0 +1,420 @@
+//! Boost steering laws
+//!
+//! C17 and C43 steer their boosters inline: a vertical rise and pitch kick
+//! into a gravity turn, then Lambert guidance along the velocity to be
+//! gained, cut off when it drops below 500 ft/s or when the energy left
+//! runs out. A `SteeringLaw` turns the current `FlightState` into a thrust
+//! `Command` so `Vehicle::fly` can compare a gravity turn, linear tangent
+//! steering, a tabulated pitch program and Lambert or velocity-to-be-gained
+//! guidance on the same vehicle.
+//!
+//! Laws that need a trajectory plane take it from the launch position and
+//! the target; positions are inertial and angles are in radians.
+
+use super::constants::GM_FT;
+use super::lambert::{lambert, Branch, Direction};
+use super::rk2::Vec3;
+use super::rocket::FlightState;
+
+/// Thrust direction held over one integration step
+#[derive(Debug, Clone, Copy)]
+pub struct Command {
+    /// Unit thrust direction; zero shuts the engine down
+    pub direction: Vec3,
+    /// Velocity imposed at once, the book's idealized pitch kick and the
+    /// trim of the last few hundred ft/s at cutoff
+    pub velocity: Option<Vec3>,
+}
+
+impl Command {
+    pub fn thrust(direction: Vec3) -> Self {
+        Self { direction: direction.normalize(), velocity: None }
+    }
+
+    pub fn coast() -> Self {
+        Self { direction: Vec3::default(), velocity: None }
+    }
+
+    /// Engine off with the velocity set to `velocity`
+    pub fn trim(velocity: Vec3) -> Self {
+        Self { direction: Vec3::default(), velocity: Some(velocity) }
+    }
+
+    pub fn is_coast(&self) -> bool {
+        self.direction.magnitude() == 0.0
+    }
+}
+
+/// Thrust direction as a function of the vehicle state
+pub trait SteeringLaw {
+    fn command(&mut self, state: &FlightState) -> Command;
+}
+
+impl<F: FnMut(&FlightState) -> Command> SteeringLaw for F {
+    fn command(&mut self, state: &FlightState) -> Command {
+        self(state)
+    }
+}
+
+/// Unit normal of the plane through the Earth's center, `r0` and `target`
+fn plane_normal(r0: &Vec3, target: &Vec3) -> Vec3 {
+    r0.cross(target).normalize()
+}
+
+/// Direction `pitch` above the local horizontal at `r`, downrange in the
+/// plane with unit normal `normal`
+fn pitched(r: &Vec3, normal: &Vec3, pitch: f64) -> Vec3 {
+    let up = r.normalize();
+    up * pitch.sin() + normal.cross(&up) * pitch.cos()
+}
+
+/// Thrust along the velocity, optionally after a vertical rise and a pitch
+/// kick that turns the velocity to flight path angle `gamma` (C43L1)
+#[derive(Debug, Clone, Copy)]
+pub struct GravityTurn {
+    /// Vertical rise before the kick (s)
+    pub t_vertical: f64,
+    /// Flight path angle after the kick and the trajectory plane normal
+    pub kick: Option<(f64, Vec3)>,
+    kicked: bool,
+}
+
+impl GravityTurn {
+    /// Thrust along the velocity from liftoff (C16L2)
+    pub fn new() -> Self {
+        Self { t_vertical: 0.0, kick: None, kicked: false }
+    }
+
+    pub fn with_kick(t_vertical: f64, gamma: f64, r0: &Vec3, target: &Vec3) -> Self {
+        Self { t_vertical, kick: Some((gamma, plane_normal(r0, target))), kicked: false }
+    }
+}
+
+impl Default for GravityTurn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SteeringLaw for GravityTurn {
+    fn command(&mut self, state: &FlightState) -> Command {
+        if state.t < self.t_vertical {
+            return Command::thrust(state.r);
+        }
+        match self.kick {
+            Some((gamma, normal)) if !self.kicked => {
+                self.kicked = true;
+                let v = pitched(&state.r, &normal, gamma) * state.v.magnitude();
+                Command { direction: v.normalize(), velocity: Some(v) }
+            }
+            _ => Command::thrust(state.v),
+        }
+    }
+}
+
+/// Linear tangent law: tan(pitch) = tan0 + rate t, with pitch measured
+/// from the launch point's horizontal in a fixed inertial frame
+#[derive(Debug, Clone, Copy)]
+pub struct LinearTangent {
+    pub tan0: f64,
+    /// Rate of change of the pitch tangent (1/s)
+    pub rate: f64,
+    up: Vec3,
+    downrange: Vec3,
+}
+
+impl LinearTangent {
+    pub fn new(tan0: f64, rate: f64, r0: &Vec3, target: &Vec3) -> Self {
+        let up = r0.normalize();
+        Self { tan0, rate, up, downrange: plane_normal(r0, target).cross(&up) }
+    }
+
+    /// Pitch falling from `pitch0` at liftoff to `pitchf` at `tb`
+    pub fn between(pitch0: f64, pitchf: f64, tb: f64, r0: &Vec3, target: &Vec3) -> Self {
+        Self::new(pitch0.tan(), (pitchf.tan() - pitch0.tan()) / tb, r0, target)
+    }
+
+    pub fn pitch(&self, t: f64) -> f64 {
+        (self.tan0 + self.rate * t).atan()
+    }
+}
+
+impl SteeringLaw for LinearTangent {
+    fn command(&mut self, state: &FlightState) -> Command {
+        let pitch = self.pitch(state.t);
+        Command::thrust(self.up * pitch.sin() + self.downrange * pitch.cos())
+    }
+}
+
+/// Pitch above the local horizontal interpolated from a table, held at the
+/// end values outside it
+#[derive(Debug, Clone)]
+pub struct PitchProgram {
+    pub time: Vec<f64>,
+    pub pitch: Vec<f64>,
+    normal: Vec3,
+}
+
+impl PitchProgram {
+    pub fn new(time: Vec<f64>, pitch: Vec<f64>, r0: &Vec3, target: &Vec3) -> Self {
+        assert!(!time.is_empty() && time.len() == pitch.len(), "pitch table needs matching time and pitch");
+        Self { time, pitch, normal: plane_normal(r0, target) }
+    }
+
+    pub fn pitch(&self, t: f64) -> f64 {
+        let n = self.time.len();
+        if t <= self.time[0] {
+            return self.pitch[0];
+        }
+        if t >= self.time[n - 1] {
+            return self.pitch[n - 1];
+        }
+        let i = self.time.partition_point(|&ti| ti <= t) - 1;
+        let f = (t - self.time[i]) / (self.time[i + 1] - self.time[i]);
+        self.pitch[i] + f * (self.pitch[i + 1] - self.pitch[i])
+    }
+}
+
+impl SteeringLaw for PitchProgram {
+    fn command(&mut self, state: &FlightState) -> Command {
+        Command::thrust(pitched(&state.r, &self.normal, self.pitch(state.t)))
+    }
+}
+
+/// Lambert guidance (C17L3, C43L1): thrust along the velocity to be gained
+/// toward the Lambert velocity that reaches `target` at time `tf`. Once the
+/// velocity to be gained is below `tolerance` the engine is shut down and
+/// the velocity trimmed to the Lambert solution; running out of thrust
+/// first leaves the vehicle coasting short.
+#[derive(Debug, Clone, Copy)]
+pub struct LambertSteering {
+    pub target: Vec3,
+    /// Time of arrival at the target (s)
+    pub tf: f64,
+    /// Velocity to be gained at cutoff (ft/s)
+    pub tolerance: f64,
+    pub direction: Direction,
+    done: bool,
+}
+
+impl LambertSteering {
+    pub fn new(target: Vec3, tf: f64) -> Self {
+        Self { target, tf, tolerance: 500.0, direction: Direction::ShortWay, done: false }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Velocity needed now to reach the target at `tf`, or `None` when the
+    /// Lambert solver fails or does not converge, which the steering laws
+    /// treat as cutoff
+    pub fn required_velocity(&self, state: &FlightState) -> Option<Vec3> {
+        lambert(state.r, self.target, self.tf - state.t, GM_FT, self.direction, 0, Branch::Left)
+            .filter(|sol| sol.converged)
+            .map(|sol| sol.v1)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl SteeringLaw for LambertSteering {
+    fn command(&mut self, state: &FlightState) -> Command {
+        if self.done {
+            return Command::coast();
+        }
+        let Some(vr) = self.required_velocity(state) else {
+            self.done = true;
+            return Command::coast();
+        };
+        let vg = vr - state.v;
+        if vg.magnitude() < self.tolerance {
+            self.done = true;
+            Command::trim(vr)
+        } else if state.thrust_accel > 0.0 {
+            Command::thrust(vg)
+        } else {
+            self.done = true;
+            Command::coast()
+        }
+    }
+}
+
+/// Velocity-to-be-gained guidance with energy management (C17L4) for
+/// motors that cannot be shut down. While the velocity capability left
+/// exceeds the velocity to be gained the thrust is turned off the
+/// velocity-to-be-gained direction by sqrt(6 (1 - VG/DVCAP)) to waste the
+/// excess; the velocity is trimmed to the Lambert solution when less than
+/// `dv_cutoff` of capability remains.
+#[derive(Debug, Clone, Copy)]
+pub struct VelocityToBeGained {
+    pub lambert: LambertSteering,
+    /// Velocity capability left (ft/s)
+    pub dvcap: f64,
+    /// Capability at which the velocity is trimmed (ft/s)
+    pub dv_cutoff: f64,
+    last: Option<(f64, f64)>,
+    aligned: bool,
+}
+
+impl VelocityToBeGained {
+    /// `dvcap` is the velocity capability left when the law takes over,
+    /// the vehicle's total velocity increment when it steers from liftoff
+    pub fn new(target: Vec3, tf: f64, dvcap: f64) -> Self {
+        Self {
+            lambert: LambertSteering::new(target, tf),
+            dvcap,
+            dv_cutoff: 50.0,
+            last: None,
+            aligned: false,
+        }
+    }
+}
+
+impl SteeringLaw for VelocityToBeGained {
+    fn command(&mut self, state: &FlightState) -> Command {
+        if let Some((t, at)) = self.last {
+            self.dvcap -= (state.t - t) * at;
+        }
+        self.last = Some((state.t, state.thrust_accel));
+        if self.lambert.done {
+            return Command::coast();
+        }
+        let Some(vr) = self.lambert.required_velocity(state) else {
+            self.lambert.done = true;
+            return Command::coast();
+        };
+        if self.dvcap <= self.dv_cutoff {
+            self.lambert.done = true;
+            return Command::trim(vr);
+        }
+
+        let vg = vr - state.v;
+        let del = vg.magnitude();
+        let mut thet = 0.0;
+        if !self.aligned && self.dvcap > del {
+            thet = (6.0 * (1.0 - del / self.dvcap)).sqrt();
+        } else {
+            self.aligned = true;
+        }
+        // Rotated in the trajectory plane, clockwise seen from its normal
+        let normal = plane_normal(&state.r, &self.lambert.target);
+        let u = vg.normalize();
+        Command::thrust(u * thet.cos() - normal.cross(&u) * thet.sin())
+    }
+}
+
+/// One law until `t_switch`, another after (the C43L1 gravity turn into
+/// Lambert guidance)
+#[derive(Debug, Clone, Copy)]
+pub struct Handover<A, B> {
+    pub first: A,
+    pub second: B,
+    pub t_switch: f64,
+}
+
+impl<A: SteeringLaw, B: SteeringLaw> SteeringLaw for Handover<A, B> {
+    fn command(&mut self, state: &FlightState) -> Command {
+        if state.t <= self.t_switch {
+            self.first.command(state)
+        } else {
+            self.second.command(state)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::EARTH_RADIUS_FT;
+    use crate::utils::kepler::StateVector;
+    use crate::utils::rocket::{StageDesign, ThrustSpec, Vehicle};
+    use std::f64::consts::PI;
+
+    fn state(t: f64, r: Vec3, v: Vec3) -> FlightState {
+        FlightState { t, r, v, thrust_accel: 100.0, stage: Some(0) }
+    }
+
+    fn launch_and_target(range_deg: f64) -> (Vec3, Vec3) {
+        let a = EARTH_RADIUS_FT;
+        let lon = range_deg.to_radians();
+        (Vec3::new(a, 0.0, 0.0), Vec3::new(a * lon.cos(), a * lon.sin(), 0.0))
+    }
+
+    #[test]
+    fn test_steering_open_loop_laws() {
+        let (r0, target) = launch_and_target(30.0);
+        let up = Vec3::new(1.0, 0.0, 0.0);
+        let downrange = Vec3::new(0.0, 1.0, 0.0);
+
+        let mut turn = GravityTurn::with_kick(10.0, 80f64.to_radians(), &r0, &target);
+        let rising = turn.command(&state(5.0, r0, up * 300.0));
+        assert!((rising.direction - up).magnitude() < 1e-12);
+        let kick = turn.command(&state(10.0, r0, up * 300.0));
+        let v = kick.velocity.unwrap();
+        assert!((v.magnitude() - 300.0).abs() < 1e-9);
+        assert!((v.y / v.magnitude() - 10f64.to_radians().sin()).abs() < 1e-12);
+        let follow = turn.command(&state(11.0, r0, v));
+        assert!(follow.velocity.is_none() && (follow.direction - v.normalize()).magnitude() < 1e-12);
+
+        let lt = LinearTangent::between(80f64.to_radians(), 10f64.to_radians(), 100.0, &r0, &target);
+        assert!((lt.pitch(100.0) - 10f64.to_radians()).abs() < 1e-12);
+        let c = LinearTangent::new(1.0, 0.0, &r0, &target).command(&state(0.0, r0, up));
+        assert!((c.direction - (up + downrange).normalize()).magnitude() < 1e-12);
+
+        let mut table = PitchProgram::new(vec![0.0, 10.0, 20.0], vec![PI / 2.0, PI / 4.0, 0.0], &r0, &target);
+        assert!((table.pitch(5.0) - 3.0 * PI / 8.0).abs() < 1e-12);
+        assert!((table.pitch(50.0)).abs() < 1e-12);
+        let c = table.command(&state(20.0, r0, up));
+        assert!((c.direction - downrange).magnitude() < 1e-12);
+    }
+
+    #[test]
+    fn test_steering_lambert_cutoff() {
+        let (r0, target) = launch_and_target(20.0);
+        let mut law = LambertSteering::new(target, 1000.0);
+        let st = state(0.0, r0, Vec3::default());
+        let vr = law.required_velocity(&st).unwrap();
+        let c = law.command(&st);
+        assert!((c.direction - vr.normalize()).magnitude() < 1e-12);
+        // Within tolerance: trim to the Lambert velocity, then coast
+        let c = law.command(&state(0.0, r0, vr * 0.999));
+        assert!(c.is_coast() && (c.velocity.unwrap() - vr).magnitude() < 1e-6);
+        assert!(law.is_done() && law.command(&st).is_coast());
+    }
+
+    #[test]
+    fn test_steering_guided_flyout_hits_target() {
+        let designs = [StageDesign::new(300.0, 0.9, ThrustSpec::MaxAcceleration(20.0)); 2];
+        let vehicle = Vehicle::size(100.0, &designs, &[6667.0, 13333.0]).unwrap();
+        let (r0, target) = launch_and_target(15.0);
+        let up = Vec3::new(1.0, 0.0, 0.0);
+        let tf = 800.0;
+
+        let laws: Vec<Box<dyn SteeringLaw>> = vec![
+            Box::new(Handover {
+                first: GravityTurn::with_kick(5.0, 85f64.to_radians(), &r0, &target),
+                second: LambertSteering::new(target, tf),
+                t_switch: 20.0,
+            }),
+            Box::new(VelocityToBeGained::new(target, tf, vehicle.delta_v())),
+        ];
+        for mut law in laws {
+            let traj = vehicle.fly(r0, up, 0.01, 1.0, 3000.0, law.as_mut());
+            let (tc, rc, vc) = traj.cutoff.expect("guidance cuts the engine off");
+            // Coasting from cutoff arrives at the target at the planned time
+            let sv = StateVector::from_vectors(rc, vc);
+            let arrival = crate::utils::elements::propagate_state(&sv, tf - tc, GM_FT);
+            let miss = (arrival.position() - target).magnitude();
+            assert!(miss < 1.0, "miss {} ft", miss);
+        }
+    }
+}
@@ -0,0 +1,421 @@
+//! Target maneuver models
+//!
+//! Each lesson writes its target acceleration inline: a constant XNT step,
+//! XNT sin(W t) weaves, the trapezoidal weave of C38, the C20 jink and the
+//! Singer model used to size the C30 filters. A `TargetManeuver` gives the
+//! acceleration perpendicular to the line of sight at any time, so a
+//! nonlinear engagement can swap models without touching its loop. Models
+//! that have a white-noise equivalent (random start step, random phase weave,
+//! Singer, random telegraph) also report a `ShapingFilter` for the adjoint
+//! and covariance analyses.
+//!
+//! Random variants draw their start time, phase or switching sequence from a
+//! caller supplied RNG so Monte Carlo runs stay reproducible with a seed.
+
+use super::lti::TransferFunction;
+use rand::Rng;
+use rand_distr::{Distribution, Exp, StandardNormal};
+
+/// Shaping filter driven by white noise of spectral density `phis`
+///
+/// A step of magnitude XNT starting uniformly in [0, TF] is 1/s driven by
+/// PHIS = XNT^2/TF; a Singer maneuver is 1/(s + 1/TAU) driven by 2 SIG^2/TAU.
+#[derive(Debug, Clone)]
+pub struct ShapingFilter {
+    pub tf: TransferFunction,
+    pub phis: f64,
+}
+
+/// Target acceleration perpendicular to the line of sight
+pub trait TargetManeuver {
+    /// Target acceleration at time `t` (ft/sec^2)
+    fn accel(&self, t: f64) -> f64;
+
+    /// Target jerk at time `t`; central difference unless overridden
+    fn jerk(&self, t: f64) -> f64 {
+        let dt = 1e-6;
+        (self.accel(t + dt) - self.accel(t - dt)) / (2.0 * dt)
+    }
+
+    /// Instantaneous displacement added to the target position by time `t`
+    /// (the C20 jink); zero for acceleration-only maneuvers
+    fn offset(&self, _t: f64) -> f64 {
+        0.0
+    }
+
+    /// White-noise equivalent over a flight time `tf`, when one exists
+    fn shaping_filter(&self, _tf: f64) -> Option<ShapingFilter> {
+        None
+    }
+}
+
+/// Constant XNT starting at TSTART (C2L1, C30L2 with MVR = 0)
+#[derive(Debug, Clone, Copy)]
+pub struct Step {
+    pub xnt: f64,
+    pub tstart: f64,
+}
+
+impl Step {
+    pub fn new(xnt: f64) -> Self {
+        Self { xnt, tstart: 0.0 }
+    }
+
+    pub fn starting_at(mut self, tstart: f64) -> Self {
+        self.tstart = tstart;
+        self
+    }
+
+    /// Start time uniform in [0, tf] and random sign
+    pub fn random_start<R: Rng + ?Sized>(xnt: f64, tf: f64, rng: &mut R) -> Self {
+        let sign = if rng.gen::<f64>() < 0.5 { -1.0 } else { 1.0 };
+        Self { xnt: sign * xnt, tstart: tf * rng.gen::<f64>() }
+    }
+}
+
+impl TargetManeuver for Step {
+    fn accel(&self, t: f64) -> f64 {
+        if t < self.tstart {
+            0.0
+        } else {
+            self.xnt
+        }
+    }
+
+    fn jerk(&self, _t: f64) -> f64 {
+        0.0
+    }
+
+    fn shaping_filter(&self, tf: f64) -> Option<ShapingFilter> {
+        Some(ShapingFilter { tf: TransferFunction::integrator(), phis: self.xnt * self.xnt / tf })
+    }
+}
+
+/// Sinusoidal weave XNT sin(W (t - TSTART) + PHASE) (C29L1, C30L2 with MVR = 1)
+#[derive(Debug, Clone, Copy)]
+pub struct Weave {
+    pub xnt: f64,
+    /// Weave frequency (rad/sec)
+    pub w: f64,
+    /// Phase (rad)
+    pub phase: f64,
+    pub tstart: f64,
+}
+
+impl Weave {
+    pub fn new(xnt: f64, w: f64) -> Self {
+        Self { xnt, w, phase: 0.0, tstart: 0.0 }
+    }
+
+    pub fn with_phase(mut self, phase: f64) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    pub fn starting_at(mut self, tstart: f64) -> Self {
+        self.tstart = tstart;
+        self
+    }
+
+    /// Phase uniform in [0, 2 pi)
+    pub fn random_phase<R: Rng + ?Sized>(xnt: f64, w: f64, rng: &mut R) -> Self {
+        Self::new(xnt, w).with_phase(2.0 * std::f64::consts::PI * rng.gen::<f64>())
+    }
+
+    /// Start time uniform in [0, tf]
+    pub fn random_start<R: Rng + ?Sized>(xnt: f64, w: f64, tf: f64, rng: &mut R) -> Self {
+        Self::new(xnt, w).starting_at(tf * rng.gen::<f64>())
+    }
+}
+
+impl TargetManeuver for Weave {
+    fn accel(&self, t: f64) -> f64 {
+        if t < self.tstart {
+            0.0
+        } else {
+            self.xnt * (self.w * (t - self.tstart) + self.phase).sin()
+        }
+    }
+
+    fn jerk(&self, t: f64) -> f64 {
+        if t < self.tstart {
+            0.0
+        } else {
+            self.xnt * self.w * (self.w * (t - self.tstart) + self.phase).cos()
+        }
+    }
+
+    /// 1 / (s^2 + W^2) driven by PHIS = W^2 XNT^2 / TF as in C30L2
+    fn shaping_filter(&self, tf: f64) -> Option<ShapingFilter> {
+        let w2 = self.w * self.w;
+        Some(ShapingFilter {
+            tf: TransferFunction::new(&[1.0], &[1.0, 0.0, w2]),
+            phis: w2 * self.xnt * self.xnt / tf,
+        })
+    }
+}
+
+/// Trapezoidal weave of period PZ with rise time TR (C38)
+#[derive(Debug, Clone, Copy)]
+pub struct TrapezoidalWeave {
+    pub xnt: f64,
+    /// Period (sec)
+    pub pz: f64,
+    /// Time to slew between -XNT and +XNT (sec)
+    pub tr: f64,
+    pub tstart: f64,
+}
+
+impl TrapezoidalWeave {
+    pub fn new(xnt: f64, pz: f64, tr: f64) -> Self {
+        Self { xnt, pz, tr, tstart: 0.0 }
+    }
+
+    pub fn starting_at(mut self, tstart: f64) -> Self {
+        self.tstart = tstart;
+        self
+    }
+
+    /// Start time uniform in [0, tf]
+    pub fn random_start<R: Rng + ?Sized>(xnt: f64, pz: f64, tr: f64, tf: f64, rng: &mut R) -> Self {
+        Self::new(xnt, pz, tr).starting_at(tf * rng.gen::<f64>())
+    }
+
+    /// Time spent at +/-XNT each half period
+    pub fn hold(&self) -> f64 {
+        self.pz / 2.0 - self.tr
+    }
+}
+
+impl TargetManeuver for TrapezoidalWeave {
+    fn accel(&self, t: f64) -> f64 {
+        if t < self.tstart {
+            return 0.0;
+        }
+
+        let (xnt, pz, tr, x) = (self.xnt, self.pz, self.tr, self.hold());
+        let t_rel = t - self.tstart;
+        let period_num = (t_rel / pz).floor() as i32;
+        let tstar = t_rel - (period_num as f64) * pz;
+
+        if tstar < tr / 2.0 {
+            2.0 * xnt * tstar / tr
+        } else if tstar < tr / 2.0 + x {
+            xnt
+        } else if tstar < 3.0 * tr / 2.0 + x {
+            -2.0 * xnt * tstar / tr + 2.0 * xnt + 2.0 * xnt * x / tr
+        } else if tstar < 3.0 * tr / 2.0 + 2.0 * x {
+            -xnt
+        } else {
+            2.0 * xnt * tstar / tr - 4.0 * xnt - 4.0 * xnt * x / tr
+        }
+    }
+}
+
+/// Sudden lateral displacement at time TJINK (C20L1)
+#[derive(Debug, Clone, Copy)]
+pub struct Jink {
+    /// Displacement perpendicular to the line of sight (ft)
+    pub displace: f64,
+    pub tjink: f64,
+}
+
+impl Jink {
+    pub fn new(displace: f64, tjink: f64) -> Self {
+        Self { displace, tjink }
+    }
+
+    /// Jink time uniform in [0, tf]
+    pub fn random_start<R: Rng + ?Sized>(displace: f64, tf: f64, rng: &mut R) -> Self {
+        Self::new(displace, tf * rng.gen::<f64>())
+    }
+}
+
+impl TargetManeuver for Jink {
+    fn accel(&self, _t: f64) -> f64 {
+        0.0
+    }
+
+    fn offset(&self, t: f64) -> f64 {
+        if t < self.tjink {
+            0.0
+        } else {
+            self.displace
+        }
+    }
+}
+
+/// +/-XNT with Poisson distributed sign reversals
+#[derive(Debug, Clone)]
+pub struct RandomTelegraph {
+    pub xnt: f64,
+    /// Mean number of reversals per second
+    pub rate: f64,
+    /// Reversal times in increasing order
+    pub switch_times: Vec<f64>,
+}
+
+impl RandomTelegraph {
+    /// Draw the initial sign and the reversal times over [0, tf]
+    pub fn sample<R: Rng + ?Sized>(xnt: f64, rate: f64, tf: f64, rng: &mut R) -> Self {
+        let sign = if rng.gen::<f64>() < 0.5 { -1.0 } else { 1.0 };
+        let gap = Exp::new(rate).expect("telegraph rate must be positive");
+        let mut switch_times = Vec::new();
+        let mut t = gap.sample(rng);
+        while t < tf {
+            switch_times.push(t);
+            t += gap.sample(rng);
+        }
+        Self { xnt: sign * xnt, rate, switch_times }
+    }
+
+    /// Gauss-Markov process with the same autocorrelation XNT^2 exp(-2 RATE |t|)
+    pub fn equivalent_singer(&self) -> Singer {
+        Singer::new(self.xnt.abs(), 1.0 / (2.0 * self.rate))
+    }
+}
+
+impl TargetManeuver for RandomTelegraph {
+    fn accel(&self, t: f64) -> f64 {
+        let n = self.switch_times.partition_point(|&ts| ts <= t);
+        if n % 2 == 0 {
+            self.xnt
+        } else {
+            -self.xnt
+        }
+    }
+
+    fn jerk(&self, _t: f64) -> f64 {
+        0.0
+    }
+
+    fn shaping_filter(&self, tf: f64) -> Option<ShapingFilter> {
+        self.equivalent_singer().shaping_filter(tf)
+    }
+}
+
+/// Singer model: first-order Gauss-Markov acceleration with RMS SIG and
+/// correlation time TAU
+#[derive(Debug, Clone, Copy)]
+pub struct Singer {
+    pub sig: f64,
+    pub tau: f64,
+}
+
+impl Singer {
+    pub fn new(sig: f64, tau: f64) -> Self {
+        Self { sig, tau }
+    }
+
+    /// Singer RMS for a target that holds +/-XNT_MAX with probability PMAX,
+    /// flies straight with probability P0 and is uniform in between
+    pub fn from_probabilities(xnt_max: f64, pmax: f64, p0: f64, tau: f64) -> Self {
+        Self::new(xnt_max * ((1.0 + 4.0 * pmax - p0) / 3.0).sqrt(), tau)
+    }
+
+    /// White-noise spectral density 2 SIG^2 / TAU
+    pub fn phis(&self) -> f64 {
+        2.0 * self.sig * self.sig / self.tau
+    }
+
+    /// Sample an acceleration history on a grid of spacing `h` over [0, tf]
+    pub fn sample<R: Rng + ?Sized>(&self, tf: f64, h: f64, rng: &mut R) -> SampledManeuver {
+        let phi = (-h / self.tau).exp();
+        let q = self.sig * (1.0 - phi * phi).sqrt();
+        let n = (tf / h).ceil() as usize + 1;
+        let mut values = Vec::with_capacity(n);
+        let mut a = self.sig * rng.sample::<f64, _>(StandardNormal);
+        for _ in 0..n {
+            values.push(a);
+            a = phi * a + q * rng.sample::<f64, _>(StandardNormal);
+        }
+        SampledManeuver { h, values }
+    }
+}
+
+impl TargetManeuver for Singer {
+    /// The Singer model has zero mean; use `sample` for a realization
+    fn accel(&self, _t: f64) -> f64 {
+        0.0
+    }
+
+    fn shaping_filter(&self, _tf: f64) -> Option<ShapingFilter> {
+        Some(ShapingFilter { tf: TransferFunction::lag(self.tau), phis: self.phis() * self.tau * self.tau })
+    }
+}
+
+/// Acceleration history on a uniform grid, linearly interpolated
+#[derive(Debug, Clone)]
+pub struct SampledManeuver {
+    pub h: f64,
+    pub values: Vec<f64>,
+}
+
+impl TargetManeuver for SampledManeuver {
+    fn accel(&self, t: f64) -> f64 {
+        let n = self.values.len();
+        if n == 0 {
+            return 0.0;
+        }
+        let s = (t / self.h).max(0.0);
+        let i = s.floor() as usize;
+        if i + 1 >= n {
+            return self.values[n - 1];
+        }
+        let f = s - i as f64;
+        self.values[i] + f * (self.values[i + 1] - self.values[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_trapezoidal_weave_shape() {
+        let m = TrapezoidalWeave::new(161.0, 3.0, 1.0);
+        assert_eq!(m.accel(0.0), 0.0);
+        assert!((m.accel(0.25) - 80.5).abs() < 1e-9);
+        assert_eq!(m.accel(1.0), 161.0);
+        assert_eq!(m.accel(2.5), -161.0);
+        assert!((m.accel(3.0) - m.accel(0.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_weave_jerk_and_filter() {
+        let m = Weave::new(193.2, 2.0);
+        assert!((m.jerk(0.3) - 193.2 * 2.0 * (0.6_f64).cos()).abs() < 1e-9);
+        let s = m.shaping_filter(10.0).unwrap();
+        assert!((s.phis - 4.0 * 193.2 * 193.2 / 10.0).abs() < 1e-9);
+        assert_eq!(s.tf.order(), 2);
+    }
+
+    #[test]
+    fn test_random_telegraph_switches() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let m = RandomTelegraph::sample(96.6, 1.0, 100.0, &mut rng);
+        let n = m.switch_times.len() as f64;
+        assert!(n > 60.0 && n < 140.0);
+        let t = m.switch_times[0];
+        assert_eq!(m.accel(t - 1e-9), -m.accel(t + 1e-9));
+    }
+
+    #[test]
+    fn test_singer_sample_statistics() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let m = Singer::new(100.0, 1.0);
+        let r = m.sample(2000.0, 0.1, &mut rng);
+        let var = r.values.iter().map(|a| a * a).sum::<f64>() / r.values.len() as f64;
+        assert!((var.sqrt() - 100.0).abs() < 10.0);
+        assert!((r.accel(0.05) - 0.5 * (r.values[0] + r.values[1])).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_jink_offset() {
+        let m = Jink::new(200.0, 2.0);
+        assert_eq!(m.offset(1.9), 0.0);
+        assert_eq!(m.offset(2.0), 200.0);
+        assert_eq!(m.accel(2.0), 0.0);
+    }
+}
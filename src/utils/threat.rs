@@ -0,0 +1,497 @@
+//! Threat trajectories
+//!
+//! Ballistic missile flights stored as sampled state histories over a
+//! non-rotating spherical Earth, so a trajectory can be generated once and
+//! queried at any time by defense engagement and footprint calculations.
+//!
+//! C45 flies its IRBM and ICBM inline, switching boosters on `itgt`. Here
+//! each `RangeClass` carries its booster as a `Vehicle`: the book's IRBM
+//! and two-stage ICBM and a short-range single stage. A `ThreatSpec` flies
+//! one from a launch point to an aim point with the book's vertical rise
+//! and Lambert guidance, the flight time set by C44's minimum-energy fit
+//! plus `tloft` (positive lofted, negative depressed). Threats can also be
+//! started from given burnout conditions. A `ThreatLibrary` collects
+//! families of them.
+
+use std::f64::consts::PI;
+use super::constants::{EARTH_RADIUS_FT, GM_FT};
+use super::earth::{EarthModel, Geodetic};
+use super::elements::propagate_state;
+use super::footprint::ThreatTrack;
+use super::geometry::{great_circle_destination, great_circle_range, LocalFrame};
+use super::kepler::StateVector;
+use super::lambert::{lambert, Branch, Direction};
+use super::rk2::Vec3;
+use super::rocket::{FlightState, Stage, Vehicle};
+use super::steering::{Command, Handover, LambertSteering};
+
+/// Longest coast followed before giving up on a return to the ground (s)
+const MAX_COAST: f64 = 20000.0;
+
+/// Bisection steps locating the ground impact within a sample interval
+const IMPACT_ITERATIONS: usize = 50;
+
+/// Azimuth due east (rad)
+pub const EAST: f64 = 0.5 * PI;
+
+/// Sampled threat state history (ECI ft, ft/s; time from threat launch)
+#[derive(Debug, Clone, Default)]
+pub struct ThreatTrajectory {
+    pub time: Vec<f64>,
+    pub position: Vec<Vec3>,
+    pub velocity: Vec<Vec3>,
+    /// Time, position and velocity at the end of powered flight
+    pub burnout: Option<(f64, Vec3, Vec3)>,
+    /// Whether the history ends at ground impact
+    pub impacted: bool,
+}
+
+impl ThreatTrajectory {
+    /// Coasting flight from `launch` reaching `aim` after `tf`, sampled
+    /// every `sample` seconds and at impact
+    ///
+    /// Returns `None` when no Lambert solution connects the two points.
+    pub fn ballistic(launch: Vec3, aim: Vec3, tf: f64, sample: f64) -> Option<Self> {
+        let solution = lambert(launch, aim, tf, GM_FT, Direction::ShortWay, 0, Branch::Left)?;
+        let x0 = StateVector::from_vectors(launch, solution.v1);
+
+        let mut trajectory = Self { burnout: Some((0.0, launch, solution.v1)), ..Self::default() };
+        trajectory.push(0.0, launch, solution.v1);
+        let mut t = sample;
+        while t < tf - 1e-6 {
+            let x = propagate_state(&x0, t, GM_FT);
+            trajectory.push(t, x.position(), x.velocity());
+            t += sample;
+        }
+        trajectory.push(tf, aim, solution.v2);
+        trajectory.impacted = true;
+        Some(trajectory)
+    }
+
+    /// Coasting flight from the given burnout conditions to impact, or
+    /// `None` if it does not return to the ground within `MAX_COAST`
+    pub fn from_burnout(burnout: &BurnoutConditions, sample: f64) -> Option<Self> {
+        let earth = EarthModel::spherical();
+        let r = earth.geodetic_to_ecef(&burnout.position);
+        let (sg, cg) = burnout.gamma.sin_cos();
+        let (sa, ca) = burnout.azimuth.sin_cos();
+        let enu = Vec3::new(cg * sa, cg * ca, sg) * burnout.speed;
+        let v = LocalFrame::new(&earth, &burnout.position).rotate_from_enu(&enu);
+
+        let mut trajectory = Self { burnout: Some((burnout.time, r, v)), ..Self::default() };
+        trajectory.coast(burnout.time, &StateVector::from_vectors(r, v), sample);
+        trajectory.impacted.then_some(trajectory)
+    }
+
+    /// Appends a sample; times must increase
+    pub fn push(&mut self, t: f64, r: Vec3, v: Vec3) {
+        self.time.push(t);
+        self.position.push(r);
+        self.velocity.push(v);
+    }
+
+    /// Appends the Kepler coast from `x0` at `t0`, sampled every `sample`
+    /// seconds, ending with the ground impact found by bisection. A coast
+    /// still aloft after `MAX_COAST` is left without an impact.
+    fn coast(&mut self, t0: f64, x0: &StateVector, sample: f64) {
+        let below = |dt: f64| propagate_state(x0, dt, GM_FT).position().magnitude() < EARTH_RADIUS_FT;
+        if self.time.last().is_none_or(|&t| t < t0) {
+            self.push(t0, x0.position(), x0.velocity());
+        }
+        let mut dt = 0.0;
+        while !below(dt + sample) {
+            dt += sample;
+            if dt > MAX_COAST {
+                return;
+            }
+            let x = propagate_state(x0, dt, GM_FT);
+            self.push(t0 + dt, x.position(), x.velocity());
+        }
+        let (mut lo, mut hi) = (dt, dt + sample);
+        for _ in 0..IMPACT_ITERATIONS {
+            let mid = 0.5 * (lo + hi);
+            if below(mid) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        let x = propagate_state(x0, hi, GM_FT);
+        self.push(t0 + hi, x.position(), x.velocity());
+        self.impacted = true;
+    }
+
+    /// Time of the last sample (impact)
+    pub fn flight_time(&self) -> f64 {
+        self.time.last().copied().unwrap_or(0.0)
+    }
+
+    /// Position and velocity at `t`, interpolated linearly between samples,
+    /// or `None` outside the recorded flight
+    pub fn state_at(&self, t: f64) -> Option<(Vec3, Vec3)> {
+        let n = self.time.len();
+        if n == 0 || t < self.time[0] || t > self.time[n - 1] {
+            return None;
+        }
+        if n == 1 {
+            return Some((self.position[0], self.velocity[0]));
+        }
+        let i = self.time.partition_point(|&ti| ti <= t).clamp(1, n - 1);
+        let (t0, t1) = (self.time[i - 1], self.time[i]);
+        let f = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
+        let lerp = |a: Vec3, b: Vec3| a + (b - a) * f;
+        Some((lerp(self.position[i - 1], self.position[i]), lerp(self.velocity[i - 1], self.velocity[i])))
+    }
+
+    /// Highest altitude above the spherical Earth (ft)
+    pub fn apogee(&self) -> f64 {
+        self.position.iter().map(|r| r.magnitude() - EARTH_RADIUS_FT).fold(f64::MIN, f64::max)
+    }
+
+    /// Ground impact point, `None` unless the history ends at impact
+    pub fn impact_point(&self) -> Option<Geodetic> {
+        if !self.impacted {
+            return None;
+        }
+        self.position.last().map(|r| EarthModel::spherical().ecef_to_geodetic(r))
+    }
+}
+
+impl ThreatTrack for ThreatTrajectory {
+    fn flight_time(&self) -> f64 {
+        ThreatTrajectory::flight_time(self)
+    }
+
+    fn state_at(&self, t: f64) -> Option<(Vec3, Vec3)> {
+        ThreatTrajectory::state_at(self, t)
+    }
+}
+
+/// State at the end of powered flight
+#[derive(Debug, Clone, Copy)]
+pub struct BurnoutConditions {
+    /// Time from launch (s)
+    pub time: f64,
+    pub position: Geodetic,
+    /// Speed (ft/s)
+    pub speed: f64,
+    /// Flight path angle above the local horizontal (rad)
+    pub gamma: f64,
+    /// Heading clockwise from north (rad)
+    pub azimuth: f64,
+}
+
+/// Ballistic missile range class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeClass {
+    /// Short range, under 1000 km
+    Srbm,
+    /// Intermediate range, 1000 to 5500 km (C45 `itgt == 1`)
+    Irbm,
+    /// Intercontinental, beyond 5500 km (C45 `itgt == 2`)
+    Icbm,
+}
+
+impl RangeClass {
+    pub const ALL: [RangeClass; 3] = [RangeClass::Srbm, RangeClass::Irbm, RangeClass::Icbm];
+
+    /// Class of a missile with the given ground range (km)
+    pub fn for_range(range_km: f64) -> Self {
+        Self::ALL.into_iter().find(|c| range_km < c.range_limits().1).unwrap_or(RangeClass::Icbm)
+    }
+
+    /// Ground range spanned by the class (km)
+    pub fn range_limits(self) -> (f64, f64) {
+        match self {
+            RangeClass::Srbm => (0.0, 1000.0),
+            RangeClass::Irbm => (1000.0, 5500.0),
+            RangeClass::Icbm => (5500.0, f64::INFINITY),
+        }
+    }
+
+    /// Vertical rise before Lambert guidance takes over (s). C45 rises
+    /// for 15-20 s before its lofted flights; the ICBM rises a little
+    /// longer so depressed trajectories clear the ground.
+    pub fn vertical_rise(self) -> f64 {
+        match self {
+            RangeClass::Srbm => 5.0,
+            RangeClass::Irbm => 20.0,
+            RangeClass::Icbm => 30.0,
+        }
+    }
+
+    /// Booster, matching the C45 weight and thrust histories for the IRBM
+    /// and ICBM; the reentry vehicle separates at burnout. The book's IRBM
+    /// reaches about 2000 km on a minimum-energy trajectory.
+    pub fn vehicle(self) -> Vehicle {
+        // Thrust (lb), burn time (s), propellant and structure weight (lb)
+        let stage = |thrust: f64, burn_time: f64, propellant: f64, structure: f64| Stage {
+            isp: thrust * burn_time / propellant,
+            mass_fraction: propellant / (propellant + structure),
+            propellant,
+            structure,
+            thrust,
+            burn_time,
+            coast: 0.0,
+        };
+        match self {
+            RangeClass::Srbm => Vehicle {
+                stages: vec![stage(45000.0, 60.0, 9000.0, 3000.0)],
+                payload: 2000.0,
+            },
+            RangeClass::Irbm => Vehicle {
+                stages: vec![stage(54100.0, 180.0, 38160.0, 2540.0)],
+                payload: 3300.0,
+            },
+            RangeClass::Icbm => Vehicle {
+                stages: vec![
+                    stage(725850.0, 120.0, 314640.0, 34940.0),
+                    stage(182250.0, 120.0, 77040.0, 8540.0),
+                ],
+                payload: 5500.0,
+            },
+        }
+    }
+}
+
+/// One threat: booster, launch and aim points and trajectory shaping
+#[derive(Debug, Clone, Copy)]
+pub struct ThreatSpec {
+    pub class: RangeClass,
+    pub launch: Geodetic,
+    pub aim: Geodetic,
+    /// Flight time added to the minimum-energy one (s): positive lofts the
+    /// trajectory, negative depresses it
+    pub tloft: f64,
+    /// Vertical rise before Lambert guidance (s)
+    pub vertical_rise: f64,
+}
+
+impl ThreatSpec {
+    pub fn new(class: RangeClass, launch: Geodetic, aim: Geodetic) -> Self {
+        Self { class, launch, aim, tloft: 0.0, vertical_rise: class.vertical_rise() }
+    }
+
+    pub fn with_loft(mut self, tloft: f64) -> Self {
+        self.tloft = tloft;
+        self
+    }
+
+    pub fn with_vertical_rise(mut self, vertical_rise: f64) -> Self {
+        self.vertical_rise = vertical_rise;
+        self
+    }
+
+    /// Great-circle ground range (km)
+    pub fn range_km(&self) -> f64 {
+        great_circle_range(&self.launch, &self.aim, EARTH_RADIUS_FT) / 3280.0
+    }
+
+    /// Desired flight time: C44's minimum-energy fit plus the loft (s)
+    pub fn flight_time(&self) -> f64 {
+        let rdeskm = self.range_km();
+        252.0 + 0.223 * rdeskm - 5.44e-6 * rdeskm * rdeskm + self.tloft
+    }
+
+    /// Flies the threat with integration step `h`, sampling every
+    /// `sample` seconds. The boost is integrated as in C45: straight up for
+    /// the vertical rise, then along the velocity to be gained toward the
+    /// Lambert solution for the flight time, trimmed and shut down within
+    /// 500 ft/s. The coast to impact is Keplerian. If the booster runs out
+    /// first the threat falls short; if it returns to the ground before
+    /// shutting down, or coasts without coming back down, there is no
+    /// threat and `None` is returned.
+    pub fn fly(&self, h: f64, sample: f64) -> Option<ThreatTrajectory> {
+        let earth = EarthModel::spherical();
+        let r0 = earth.geodetic_to_ecef(&self.launch);
+        let target = earth.geodetic_to_ecef(&self.aim);
+        let vehicle = self.class.vehicle();
+        let tbo = vehicle.burnout_time();
+
+        let mut steering = Handover {
+            first: |s: &FlightState| Command::thrust(s.r),
+            second: LambertSteering::new(target, self.flight_time()),
+            t_switch: self.vertical_rise,
+        };
+        let boost = vehicle.fly(r0, r0.normalize(), h, sample, tbo + h, &mut steering);
+        let (tb, rb, vb) = boost.cutoff.or(boost.burnout)?;
+
+        let mut trajectory = ThreatTrajectory { burnout: Some((tb, rb, vb)), ..ThreatTrajectory::default() };
+        trajectory.push(0.0, r0, r0.normalize());
+        for k in 0..boost.time.len() {
+            if boost.time[k] < tb {
+                trajectory.push(boost.time[k], boost.position[k], boost.velocity[k]);
+            }
+        }
+        trajectory.coast(tb, &StateVector::from_vectors(rb, vb), sample);
+        trajectory.impacted.then_some(trajectory)
+    }
+}
+
+/// Flown threats, kept alongside their specifications
+#[derive(Debug, Clone, Default)]
+pub struct ThreatLibrary {
+    pub specs: Vec<ThreatSpec>,
+    pub trajectories: Vec<ThreatTrajectory>,
+}
+
+impl ThreatLibrary {
+    /// Integration step and sample interval used to fly threats (s)
+    pub const STEP: f64 = 0.01;
+    pub const SAMPLE: f64 = 1.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flies `spec` and adds it, unless the booster fails to leave the ground
+    pub fn add(&mut self, spec: ThreatSpec) -> Option<&ThreatTrajectory> {
+        let trajectory = spec.fly(Self::STEP, Self::SAMPLE)?;
+        self.specs.push(spec);
+        self.trajectories.push(trajectory);
+        self.trajectories.last()
+    }
+
+    /// Threats of `class` from `launch` against every aim point at every
+    /// loft, leaving out those the booster cannot fly
+    pub fn family(class: RangeClass, launch: Geodetic, aims: &[Geodetic], tlofts: &[f64]) -> Self {
+        let mut library = Self::new();
+        for aim in aims {
+            for &tloft in tlofts {
+                library.add(ThreatSpec::new(class, launch, *aim).with_loft(tloft));
+            }
+        }
+        library
+    }
+
+    /// Aim points `ranges_km` down the great circle from `launch` at `azimuth`
+    pub fn aim_points(launch: &Geodetic, azimuth: f64, ranges_km: &[f64]) -> Vec<Geodetic> {
+        ranges_km.iter()
+            .map(|&km| great_circle_destination(launch, km * 3280.0, azimuth, EARTH_RADIUS_FT))
+            .collect()
+    }
+
+    pub fn extend(&mut self, other: ThreatLibrary) {
+        self.specs.extend(other.specs);
+        self.trajectories.extend(other.trajectories);
+    }
+
+    pub fn len(&self) -> usize {
+        self.specs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+
+    /// Specifications and trajectories of one class
+    pub fn of_class(&self, class: RangeClass) -> impl Iterator<Item = (&ThreatSpec, &ThreatTrajectory)> {
+        self.specs.iter().zip(&self.trajectories).filter(move |(s, _)| s.class == class)
+    }
+
+    /// Ground distance from each threat's impact to its aim point (ft)
+    pub fn misses(&self) -> Vec<f64> {
+        self.specs.iter().zip(&self.trajectories).map(|(spec, traj)| {
+            traj.impact_point().map_or(f64::INFINITY, |p| great_circle_range(&p, &spec.aim, EARTH_RADIUS_FT))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ballistic_threat_reaches_aim_point() {
+        let a = EARTH_RADIUS_FT;
+        let launch = Vec3::new(a, 0.0, 0.0);
+        let ang: f64 = 0.25;
+        let aim = Vec3::new(a * ang.cos(), a * ang.sin(), 0.0);
+        let threat = ThreatTrajectory::ballistic(launch, aim, 1200.0, 10.0).unwrap();
+
+        assert_eq!(threat.flight_time(), 1200.0);
+        assert!((threat.position.last().unwrap().magnitude() - a).abs() < 1.0);
+        // Interior samples stay above ground and match an interpolated query
+        assert!(threat.position[1..threat.time.len() - 1].iter().all(|r| r.magnitude() > a));
+        let (r, _) = threat.state_at(605.0).unwrap();
+        let (r0, _) = threat.state_at(600.0).unwrap();
+        let (r1, _) = threat.state_at(610.0).unwrap();
+        assert!((r - (r0 + r1) * 0.5).magnitude() < 1e-6);
+        assert!(threat.state_at(1300.0).is_none());
+        assert!(threat.apogee() > 1.0e6);
+    }
+
+    #[test]
+    fn test_book_boosters() {
+        // C45 weight and thrust histories
+        let irbm = RangeClass::Irbm.vehicle();
+        let (w, trst) = irbm.weight_and_thrust(100.0);
+        assert!((w - (-212.0 * 100.0 + 44000.0)).abs() < 1e-6 && trst == 54100.0);
+        assert_eq!(irbm.weight_and_thrust(200.0), (3300.0, 0.0));
+        let icbm = RangeClass::Icbm.vehicle();
+        let (w, trst) = icbm.weight_and_thrust(150.0);
+        assert!((w - (-642.0 * 150.0 + 168120.0)).abs() < 1e-6 && trst == 182250.0);
+        assert_eq!(icbm.burnout_time(), 240.0);
+        assert_eq!(RangeClass::for_range(300.0), RangeClass::Srbm);
+        assert_eq!(RangeClass::for_range(2000.0), RangeClass::Irbm);
+        assert_eq!(RangeClass::for_range(10000.0), RangeClass::Icbm);
+    }
+
+    #[test]
+    fn test_lofted_threat_hits_aim_point() {
+        let launch = Geodetic::from_degrees(0.0, 0.0, 0.0);
+        let aims = ThreatLibrary::aim_points(&launch, EAST, &[1500.0]);
+        let library = ThreatLibrary::family(RangeClass::Irbm, launch, &aims, &[-50.0, 0.0, 200.0]);
+        assert_eq!(library.len(), 3);
+        for (miss, traj) in library.misses().iter().zip(&library.trajectories) {
+            assert!(*miss < 3280.0, "miss {} ft", miss);
+            // Guidance shuts the motor down before it burns out
+            assert!(traj.burnout.unwrap().0 < 180.0);
+        }
+        // Lofting raises the apogee and lengthens the flight
+        let apogee: Vec<f64> = library.trajectories.iter().map(|t| t.apogee()).collect();
+        assert!(apogee[0] < apogee[1] && apogee[1] < apogee[2]);
+        let tf = library.trajectories[2].flight_time();
+        assert!((tf - library.specs[2].flight_time()).abs() < 2.0, "{}", tf);
+
+        // Too flat for the IRBM's low thrust to weight: it never climbs out
+        assert!(ThreatSpec::new(RangeClass::Irbm, launch, aims[0]).with_loft(-100.0).fly(0.01, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_threat_from_burnout_conditions() {
+        let burnout = BurnoutConditions {
+            time: 180.0,
+            position: Geodetic::from_degrees(0.0, 1.0, 300000.0),
+            speed: 15000.0,
+            gamma: 30f64.to_radians(),
+            azimuth: EAST,
+        };
+        let threat = ThreatTrajectory::from_burnout(&burnout, 5.0).expect("suborbital burnout impacts");
+        assert_eq!(threat.time[0], 180.0);
+        assert!((threat.position.last().unwrap().magnitude() - EARTH_RADIUS_FT).abs() < 1.0);
+        // Flying east along the equator
+        let impact = threat.impact_point().unwrap();
+        assert!(impact.lat.abs() < 1e-9 && impact.lon.to_degrees() > 10.0);
+        // Energy is conserved on the coast
+        let energy = |k: usize| 0.5 * threat.velocity[k].magnitude().powi(2) - GM_FT / threat.position[k].magnitude();
+        assert!((energy(threat.time.len() - 1) / energy(0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_threat_that_never_impacts() {
+        // Beyond escape speed the coast never comes back down
+        let burnout = BurnoutConditions {
+            time: 180.0,
+            position: Geodetic::from_degrees(0.0, 1.0, 300000.0),
+            speed: 40000.0,
+            gamma: 30f64.to_radians(),
+            azimuth: EAST,
+        };
+        assert!(ThreatTrajectory::from_burnout(&burnout, 100.0).is_none());
+
+        let mut track = ThreatTrajectory::default();
+        track.push(0.0, Vec3::new(EARTH_RADIUS_FT, 0.0, 0.0), Vec3::default());
+        assert!(track.impact_point().is_none());
+    }
+}